@@ -0,0 +1,452 @@
+//! 精灵动画
+//!
+//! 动画剪辑 (帧序列 + 每帧时长) 与播放器分开存储：
+//! 同一剪辑可被多个精灵共享，每个动画精灵拥有独立的播放进度。
+//! 帧切换时替换精灵图原始数据，并保留精灵当前的变换。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::image::decode_gif;
+
+/// 单帧最短时长 (秒)，防止零时长帧导致死循环
+const MIN_FRAME_DURATION: f32 = 0.001;
+
+/// 动画剪辑存储 - 各属性分离为独立数组
+pub struct ClipStore {
+    /// 帧像素数据 (RGBA)
+    pub(super) frames: Vec<Vec<Vec<u8>>>,
+    /// 帧时长 (秒)
+    pub(super) durations: Vec<Vec<f32>>,
+    /// 帧宽度
+    pub(super) widths: Vec<u32>,
+    /// 帧高度
+    pub(super) heights: Vec<u32>,
+    /// 播放次数 (0 表示无限循环)
+    pub(super) loop_counts: Vec<u32>,
+}
+
+impl ClipStore {
+    pub(super) fn new() -> Self {
+        Self {
+            frames: Vec::new(),
+            durations: Vec::new(),
+            widths: Vec::new(),
+            heights: Vec::new(),
+            loop_counts: Vec::new(),
+        }
+    }
+
+    /// 添加空剪辑，返回ID (索引)
+    pub(super) fn add(&mut self, width: u32, height: u32, loop_count: u32) -> u32 {
+        let id = self.frames.len() as u32;
+        self.frames.push(Vec::new());
+        self.durations.push(Vec::new());
+        self.widths.push(width);
+        self.heights.push(height);
+        self.loop_counts.push(loop_count);
+        id
+    }
+
+    /// 剪辑帧数 (剪辑不存在时为 0)
+    pub(super) fn frame_count(&self, clip_id: u32) -> usize {
+        self.frames.get(clip_id as usize).map_or(0, |f| f.len())
+    }
+}
+
+/// 动画播放器存储 - 每个动画精灵一项
+pub struct AnimatorStore {
+    /// 所属精灵图ID
+    pub(super) sprite_ids: Vec<u32>,
+    /// 播放的剪辑ID
+    pub(super) clip_ids: Vec<u32>,
+    /// 当前帧
+    pub(super) frames: Vec<u32>,
+    /// 当前帧已播放时间 (秒)
+    pub(super) elapsed: Vec<f32>,
+    /// 播放速度倍率
+    pub(super) speeds: Vec<f32>,
+    /// 是否正在播放
+    pub(super) playing: Vec<bool>,
+    /// 已完成的播放次数
+    pub(super) plays_done: Vec<u32>,
+}
+
+impl AnimatorStore {
+    pub(super) fn new() -> Self {
+        Self {
+            sprite_ids: Vec::new(),
+            clip_ids: Vec::new(),
+            frames: Vec::new(),
+            elapsed: Vec::new(),
+            speeds: Vec::new(),
+            playing: Vec::new(),
+            plays_done: Vec::new(),
+        }
+    }
+
+    /// 查找精灵图对应的播放器索引
+    pub(super) fn find(&self, sprite_id: u32) -> Option<usize> {
+        self.sprite_ids.iter().position(|&id| id == sprite_id)
+    }
+
+    /// 为精灵图绑定剪辑 (已存在则复用并重置进度)，返回播放器索引
+    fn bind(&mut self, sprite_id: u32, clip_id: u32) -> usize {
+        let i = match self.find(sprite_id) {
+            Some(i) => i,
+            None => {
+                self.sprite_ids.push(sprite_id);
+                self.clip_ids.push(0);
+                self.frames.push(0);
+                self.elapsed.push(0.0);
+                self.speeds.push(1.0);
+                self.playing.push(false);
+                self.plays_done.push(0);
+                self.sprite_ids.len() - 1
+            }
+        };
+        self.clip_ids[i] = clip_id;
+        self.frames[i] = 0;
+        self.elapsed[i] = 0.0;
+        self.playing[i] = true;
+        self.plays_done[i] = 0;
+        i
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 动画剪辑 ==========
+
+    /// 创建空动画剪辑
+    ///
+    /// `loop_count` 为播放次数，0 表示无限循环。
+    pub fn create_animation_clip(&mut self, width: u32, height: u32, loop_count: u32) -> u32 {
+        self.clips.add(width, height, loop_count)
+    }
+
+    /// 向剪辑追加一帧
+    ///
+    /// `data` 为 RGBA 像素，尺寸必须与剪辑一致；`duration` 为帧时长 (秒)。
+    pub fn add_animation_frame(&mut self, clip_id: u32, data: &[u8], duration: f32) {
+        let idx = clip_id as usize;
+        if idx >= self.clips.frames.len() {
+            return;
+        }
+        let expected = (self.clips.widths[idx] * self.clips.heights[idx] * 4) as usize;
+        if data.len() != expected {
+            return;
+        }
+        self.clips.frames[idx].push(data.to_vec());
+        self.clips.durations[idx].push(duration.max(MIN_FRAME_DURATION));
+    }
+
+    /// 从 GIF 文件创建动画剪辑
+    ///
+    /// 解码失败时返回 None。
+    pub fn create_animation_clip_from_gif(&mut self, bytes: &[u8]) -> Option<u32> {
+        let gif = decode_gif(bytes)?;
+        let clip_id = self.clips.add(gif.width, gif.height, gif.loop_count);
+        let idx = clip_id as usize;
+        for frame in gif.frames {
+            self.clips.frames[idx].push(frame.data);
+            self.clips.durations[idx]
+                .push((frame.delay_ms as f32 / 1000.0).max(MIN_FRAME_DURATION));
+        }
+        Some(clip_id)
+    }
+
+    /// 获取剪辑帧数
+    pub fn get_animation_frame_count(&self, clip_id: u32) -> u32 {
+        self.clips.frame_count(clip_id) as u32
+    }
+
+    // ========== 动画精灵 ==========
+
+    /// 以剪辑创建动画精灵图 (自动开始播放)
+    ///
+    /// 剪辑不存在或没有帧时返回 None。
+    pub fn create_animated_sprite(&mut self, clip_id: u32) -> Option<u32> {
+        if self.clips.frame_count(clip_id) == 0 {
+            return None;
+        }
+        let idx = clip_id as usize;
+        let id = self.sprites.add(
+            self.clips.frames[idx][0].clone(),
+            self.clips.widths[idx],
+            self.clips.heights[idx],
+        );
+        self.animators.bind(id, clip_id);
+        Some(id)
+    }
+
+    /// 从 GIF 文件直接创建动画精灵图
+    ///
+    /// 解码失败时返回 None。
+    pub fn create_sprite_from_gif(&mut self, bytes: &[u8]) -> Option<u32> {
+        let clip_id = self.create_animation_clip_from_gif(bytes)?;
+        self.create_animated_sprite(clip_id)
+    }
+
+    /// 为已有精灵图设置动画剪辑 (从第一帧开始播放)
+    pub fn set_sprite_animation(&mut self, sprite_id: u32, clip_id: u32) {
+        if !self.sprites.is_active(sprite_id) || self.clips.frame_count(clip_id) == 0 {
+            return;
+        }
+        self.animators.bind(sprite_id, clip_id);
+        self.show_animation_frame(sprite_id, clip_id, 0);
+    }
+
+    /// 播放精灵动画 (已播放完毕时从头开始)
+    pub fn play_sprite_animation(&mut self, sprite_id: u32) {
+        if let Some(i) = self.animators.find(sprite_id) {
+            let clip = self.animators.clip_ids[i] as usize;
+            let loops = self.clips.loop_counts[clip];
+            if loops != 0 && self.animators.plays_done[i] >= loops {
+                self.animators.plays_done[i] = 0;
+                self.animators.frames[i] = 0;
+                self.animators.elapsed[i] = 0.0;
+                self.show_animation_frame(sprite_id, clip as u32, 0);
+            }
+            self.animators.playing[i] = true;
+        }
+    }
+
+    /// 暂停精灵动画
+    pub fn pause_sprite_animation(&mut self, sprite_id: u32) {
+        if let Some(i) = self.animators.find(sprite_id) {
+            self.animators.playing[i] = false;
+        }
+    }
+
+    /// 精灵动画是否正在播放
+    pub fn is_sprite_animation_playing(&self, sprite_id: u32) -> bool {
+        self.animators
+            .find(sprite_id)
+            .is_some_and(|i| self.animators.playing[i])
+    }
+
+    /// 跳转到指定帧
+    pub fn set_sprite_animation_frame(&mut self, sprite_id: u32, frame: u32) {
+        if let Some(i) = self.animators.find(sprite_id) {
+            let clip = self.animators.clip_ids[i];
+            if (frame as usize) < self.clips.frame_count(clip) {
+                self.animators.frames[i] = frame;
+                self.animators.elapsed[i] = 0.0;
+                self.show_animation_frame(sprite_id, clip, frame as usize);
+            }
+        }
+    }
+
+    /// 获取当前帧 (非动画精灵返回 0)
+    pub fn get_sprite_animation_frame(&self, sprite_id: u32) -> u32 {
+        self.animators
+            .find(sprite_id)
+            .map_or(0, |i| self.animators.frames[i])
+    }
+
+    /// 设置播放速度倍率 (1.0 为原速，非有限值被忽略)
+    pub fn set_sprite_animation_speed(&mut self, sprite_id: u32, speed: f32) {
+        if let Some(i) = self.animators.find(sprite_id) {
+            if speed.is_finite() {
+                self.animators.speeds[i] = speed.max(0.0);
+            }
+        }
+    }
+}
+
+impl World {
+    /// 将剪辑的某一帧设置为精灵图的原始数据
+    fn show_animation_frame(&mut self, sprite_id: u32, clip_id: u32, frame: usize) {
+        let clip = clip_id as usize;
+        let data = self.clips.frames[clip][frame].clone();
+        self.set_sprite_source(
+            sprite_id,
+            data,
+            self.clips.widths[clip],
+            self.clips.heights[clip],
+        );
+    }
+
+    /// 推进所有动画播放器
+    pub(super) fn update_animations(&mut self, dt: f32) {
+        let mut changed = Vec::new();
+
+        for i in 0..self.animators.sprite_ids.len() {
+            let sprite_id = self.animators.sprite_ids[i];
+            if !self.animators.playing[i] || !self.sprites.is_active(sprite_id) {
+                continue;
+            }
+
+            let clip = self.animators.clip_ids[i] as usize;
+            let durations = &self.clips.durations[clip];
+            let loops = self.clips.loop_counts[clip];
+            let start_frame = self.animators.frames[i];
+            let total: f32 = durations.iter().sum();
+            if total.is_nan() || total <= 0.0 {
+                continue;
+            }
+
+            // 本轮播放位置，整轮直接取模而不逐帧步进
+            let mut position = durations[..start_frame as usize].iter().sum::<f32>()
+                + self.animators.elapsed[i]
+                + dt * self.animators.speeds[i];
+            let passes = (position / total).floor();
+            let mut frame = 0;
+            let mut elapsed = 0.0;
+            if passes >= 1.0 {
+                let done = &mut self.animators.plays_done[i];
+                *done = done.saturating_add(passes.min(u32::MAX as f32) as u32);
+                position = position.rem_euclid(total);
+            }
+            if loops != 0 && self.animators.plays_done[i] >= loops {
+                // 播放完毕，停留在最后一帧
+                frame = durations.len() - 1;
+                self.animators.playing[i] = false;
+            } else {
+                while frame + 1 < durations.len() && position >= durations[frame] {
+                    position -= durations[frame];
+                    frame += 1;
+                }
+                elapsed = position;
+            }
+
+            self.animators.frames[i] = frame as u32;
+            self.animators.elapsed[i] = elapsed;
+            if frame as u32 != start_frame {
+                changed.push((sprite_id, clip as u32, frame));
+            }
+        }
+
+        for (sprite_id, clip_id, frame) in changed {
+            self.show_animation_frame(sprite_id, clip_id, frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 创建 1x1 三帧剪辑: 红、绿、蓝，每帧 0.1 秒
+    fn rgb_clip(world: &mut World, loop_count: u32) -> u32 {
+        let clip = world.create_animation_clip(1, 1, loop_count);
+        world.add_animation_frame(clip, &[255, 0, 0, 255], 0.1);
+        world.add_animation_frame(clip, &[0, 255, 0, 255], 0.1);
+        world.add_animation_frame(clip, &[0, 0, 255, 255], 0.1);
+        clip
+    }
+
+    #[test]
+    fn test_animation_advances_and_loops() {
+        let mut world = World::new(10, 10);
+        let clip = rgb_clip(&mut world, 0);
+        let id = world.create_animated_sprite(clip).unwrap();
+        assert_eq!(
+            world.sprites.original_data[id as usize],
+            vec![255, 0, 0, 255]
+        );
+
+        world.update(0.15);
+        assert_eq!(world.get_sprite_animation_frame(id), 1);
+        assert_eq!(
+            world.sprites.display_data[id as usize],
+            vec![0, 255, 0, 255]
+        );
+
+        // 跨越末帧回到开头
+        world.update(0.2);
+        assert_eq!(world.get_sprite_animation_frame(id), 0);
+        assert!(world.is_sprite_animation_playing(id));
+    }
+
+    #[test]
+    fn test_finite_loop_stops_on_last_frame() {
+        let mut world = World::new(10, 10);
+        let clip = rgb_clip(&mut world, 1);
+        let id = world.create_animated_sprite(clip).unwrap();
+
+        world.update(1.0);
+        assert_eq!(world.get_sprite_animation_frame(id), 2);
+        assert!(!world.is_sprite_animation_playing(id));
+
+        // 再次播放从头开始
+        world.play_sprite_animation(id);
+        assert_eq!(world.get_sprite_animation_frame(id), 0);
+        assert!(world.is_sprite_animation_playing(id));
+    }
+
+    #[test]
+    fn test_pause_speed_and_seek() {
+        let mut world = World::new(10, 10);
+        let clip = rgb_clip(&mut world, 0);
+        let id = world.create_animated_sprite(clip).unwrap();
+
+        world.pause_sprite_animation(id);
+        world.update(0.5);
+        assert_eq!(world.get_sprite_animation_frame(id), 0);
+
+        world.play_sprite_animation(id);
+        world.set_sprite_animation_speed(id, 2.0);
+        world.update(0.06);
+        assert_eq!(world.get_sprite_animation_frame(id), 1);
+
+        world.set_sprite_animation_frame(id, 2);
+        assert_eq!(
+            world.sprites.original_data[id as usize],
+            vec![0, 0, 255, 255]
+        );
+        world.set_sprite_animation_frame(id, 9);
+        assert_eq!(world.get_sprite_animation_frame(id), 2);
+    }
+
+    #[test]
+    fn test_large_and_non_finite_steps() {
+        let mut world = World::new(10, 10);
+        let clip = rgb_clip(&mut world, 0);
+        let id = world.create_animated_sprite(clip).unwrap();
+
+        // 非有限值被忽略，不会卡死
+        world.set_sprite_animation_speed(id, f32::INFINITY);
+        world.update(f32::INFINITY);
+        world.update(f32::NAN);
+        assert_eq!(world.get_sprite_animation_frame(id), 0);
+
+        // 大步长按整轮取模: 3000.15 秒落在第 1 帧
+        world.update(3000.15);
+        assert_eq!(world.get_sprite_animation_frame(id), 1);
+
+        let clip = rgb_clip(&mut world, 2);
+        let id = world.create_animated_sprite(clip).unwrap();
+        world.update(1e9);
+        assert_eq!(world.get_sprite_animation_frame(id), 2);
+        assert!(!world.is_sprite_animation_playing(id));
+    }
+
+    #[test]
+    fn test_frame_change_keeps_transform() {
+        let mut world = World::new(10, 10);
+        let clip = rgb_clip(&mut world, 0);
+        let id = world.create_animated_sprite(clip).unwrap();
+        world.apply_sprite_scale(id, 3.0, 3.0);
+
+        world.update(0.1);
+        assert_eq!(world.sprites.display_widths[id as usize], 3);
+        // 中心像素正好对应源像素
+        assert_eq!(
+            &world.sprites.display_data[id as usize][16..20],
+            &[0, 255, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_invalid_clips() {
+        let mut world = World::new(10, 10);
+        let clip = world.create_animation_clip(2, 2, 0);
+        // 尺寸不匹配的帧被忽略
+        world.add_animation_frame(clip, &[0, 0, 0, 0], 0.1);
+        assert_eq!(world.get_animation_frame_count(clip), 0);
+        assert!(world.create_animated_sprite(clip).is_none());
+        assert!(world.create_sprite_from_gif(b"GIF89a").is_none());
+    }
+}
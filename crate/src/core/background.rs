@@ -0,0 +1,480 @@
+//! 场景背景图像
+//!
+//! 场景背景由底色 (纯色或渐变) 与可选的背景图像组成，
+//! 图像按拉伸 / 适应 / 填充 / 居中 / 平铺模式绘制在底色之上，并可设置滚动偏移实现视差。
+//! 背景整帧缓存在 `bg_rows` 中，只有背景参数变化时才重新生成。
+
+use wasm_bindgen::prelude::*;
+
+use super::linear::{blend_linear, interpolate_linear, to_linear, to_srgb};
+use super::sampling::{sample_bilinear, sample_bilinear_linear, SamplingMethod};
+use super::world::{SceneStore, World};
+use crate::image::{blend_over, decode_image, pixel_len, Image};
+
+/// 背景图像绘制模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackgroundMode {
+    /// 拉伸铺满场景 (不保持宽高比)
+    #[default]
+    Stretch,
+    /// 等比缩放至完整显示 (居中，可能留边)
+    Fit,
+    /// 等比缩放至铺满场景 (居中，可能裁切)
+    Fill,
+    /// 原始尺寸居中
+    Center,
+    /// 原始尺寸从左上角平铺
+    Tile,
+}
+
+impl BackgroundMode {
+    /// 从 u8 值创建绘制模式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => BackgroundMode::Stretch,
+            1 => BackgroundMode::Fit,
+            2 => BackgroundMode::Fill,
+            3 => BackgroundMode::Center,
+            4 => BackgroundMode::Tile,
+            _ => BackgroundMode::Stretch,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            BackgroundMode::Stretch => 0,
+            BackgroundMode::Fit => 1,
+            BackgroundMode::Fill => 2,
+            BackgroundMode::Center => 3,
+            BackgroundMode::Tile => 4,
+        }
+    }
+}
+
+/// 平铺采样 (坐标按图像尺寸循环)，`linear` 时颜色通道在线性光空间中插值
+fn sample_wrapped(image: &Image, px: f32, py: f32, bilinear: bool, linear: bool) -> [u8; 4] {
+    let (w, h) = (image.width as i32, image.height as i32);
+    let at = |x: i32, y: i32| {
+        let i = ((y.rem_euclid(h) * w + x.rem_euclid(w)) * 4) as usize;
+        [
+            image.data[i],
+            image.data[i + 1],
+            image.data[i + 2],
+            image.data[i + 3],
+        ]
+    };
+    if !bilinear {
+        return at(px.floor() as i32, py.floor() as i32);
+    }
+    let (px, py) = (px - 0.5, py - 0.5);
+    let (x0, y0) = (px.floor() as i32, py.floor() as i32);
+    let (fx, fy) = (px - px.floor(), py - py.floor());
+    let (c00, c10, c01, c11) = (
+        at(x0, y0),
+        at(x0 + 1, y0),
+        at(x0, y0 + 1),
+        at(x0 + 1, y0 + 1),
+    );
+    let decode = |v: u8, i: usize| {
+        if linear && i < 3 {
+            to_linear(v)
+        } else {
+            v as f32 / 255.0
+        }
+    };
+    let mut out = [0u8; 4];
+    for i in 0..4 {
+        let top = decode(c00[i], i) * (1.0 - fx) + decode(c10[i], i) * fx;
+        let bottom = decode(c01[i], i) * (1.0 - fx) + decode(c11[i], i) * fx;
+        let v = top * (1.0 - fy) + bottom * fy;
+        out[i] = if linear && i < 3 {
+            to_srgb(v)
+        } else {
+            (v * 255.0 + 0.5) as u8
+        };
+    }
+    out
+}
+
+impl SceneStore {
+    /// 背景是否需要缓存整帧 (渐变或图像)，否则只缓存一行纯色
+    pub(super) fn has_full_background(&self, idx: usize) -> bool {
+        self.background_gradients[idx].is_some() || self.background_images[idx].is_some()
+    }
+
+    /// 生成背景缓存 (纯色时为单行，否则为整帧)
+    pub(super) fn build_background(&self, idx: usize) -> Vec<u8> {
+        let width = self.widths[idx];
+        let height = self.heights[idx];
+        let row_size = (width * 4) as usize;
+        let rows = if self.has_full_background(idx) {
+            height as usize
+        } else {
+            1
+        };
+        let mut bg = vec![0u8; row_size * rows];
+
+        match &self.background_gradients[idx] {
+            Some(gradient) if self.linear_blending[idx] => {
+                gradient.fill_into_with(&mut bg, width, interpolate_linear)
+            }
+            Some(gradient) => gradient.fill_into(&mut bg, width),
+            None => {
+                for px in bg.chunks_exact_mut(4) {
+                    px.copy_from_slice(&self.background_colors[idx]);
+                }
+            }
+        }
+        if let Some(image) = &self.background_images[idx] {
+            self.draw_background_image(idx, image, &mut bg);
+        }
+        bg
+    }
+
+    /// 按绘制模式与滚动偏移将背景图像混合到整帧背景上
+    fn draw_background_image(&self, idx: usize, image: &Image, bg: &mut [u8]) {
+        let width = self.widths[idx];
+        let height = self.heights[idx];
+        if width == 0 || image.width == 0 || image.height == 0 {
+            return;
+        }
+        let (iw, ih) = (image.width as f32, image.height as f32);
+        let (sw, sh) = (width as f32, height as f32);
+        let mode = self.background_modes[idx];
+        let [scroll_x, scroll_y] = self.background_scrolls[idx];
+        let bilinear = self.sampling_methods[idx] != SamplingMethod::Nearest;
+        let linear = self.linear_blending[idx];
+        let sample = if linear {
+            sample_bilinear_linear
+        } else {
+            sample_bilinear
+        };
+
+        // 图像在场景中的缩放与左上角位置
+        let (scale_x, scale_y) = match mode {
+            BackgroundMode::Stretch => (sw / iw, sh / ih),
+            BackgroundMode::Fit => {
+                let s = (sw / iw).min(sh / ih);
+                (s, s)
+            }
+            BackgroundMode::Fill => {
+                let s = (sw / iw).max(sh / ih);
+                (s, s)
+            }
+            BackgroundMode::Center | BackgroundMode::Tile => (1.0, 1.0),
+        };
+        let (origin_x, origin_y) = match mode {
+            BackgroundMode::Stretch | BackgroundMode::Tile => (0.0, 0.0),
+            _ => ((sw - iw * scale_x) / 2.0, (sh - ih * scale_y) / 2.0),
+        };
+        let origin_x = origin_x - scroll_x;
+        let origin_y = origin_y - scroll_y;
+
+        for (ty, row) in bg.chunks_exact_mut((width * 4) as usize).enumerate() {
+            let py = (ty as f32 + 0.5 - origin_y) / scale_y;
+            if mode != BackgroundMode::Tile && (py < 0.0 || py >= ih) {
+                continue;
+            }
+            for (tx, px) in row.chunks_exact_mut(4).enumerate() {
+                let sx = (tx as f32 + 0.5 - origin_x) / scale_x;
+                let color = if mode == BackgroundMode::Tile {
+                    sample_wrapped(image, sx, py, bilinear, linear)
+                } else if sx < 0.0 || sx >= iw {
+                    continue;
+                } else if bilinear {
+                    // 图像边缘按钳制处理，避免与透明像素混合出半透明边
+                    let cx = sx.clamp(0.5, iw - 0.5);
+                    let cy = py.clamp(0.5, ih - 0.5);
+                    match sample(&image.data, image.width, image.height, cx, cy) {
+                        Some(color) => color,
+                        None => continue,
+                    }
+                } else {
+                    let i = ((py as u32 * image.width + sx as u32) * 4) as usize;
+                    [
+                        image.data[i],
+                        image.data[i + 1],
+                        image.data[i + 2],
+                        image.data[i + 3],
+                    ]
+                };
+                if linear {
+                    blend_linear(px, color);
+                } else {
+                    blend_over(px, color);
+                }
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 场景背景图像 ==========
+
+    /// 从图像文件设置默认场景的背景图像
+    ///
+    /// 支持的格式同 `create_sprite_from_image`。解码失败时返回 false，原背景保持不变。
+    pub fn set_background_image(&mut self, bytes: &[u8]) -> bool {
+        self.set_scene_background_image(self.default_scene, bytes)
+    }
+
+    /// 从 RGBA 像素数据设置默认场景的背景图像
+    ///
+    /// 数据长度与尺寸不符时返回 false。
+    pub fn set_background_image_rgba(&mut self, data: &[u8], width: u32, height: u32) -> bool {
+        self.set_scene_background_image_rgba(self.default_scene, data, width, height)
+    }
+
+    /// 移除默认场景的背景图像 (保留底色)
+    pub fn clear_background_image(&mut self) {
+        self.clear_scene_background_image(self.default_scene);
+    }
+
+    /// 设置默认场景的背景图像绘制模式
+    ///
+    /// `mode`: 0 = 拉伸, 1 = 适应, 2 = 填充, 3 = 居中, 4 = 平铺。
+    pub fn set_background_mode(&mut self, mode: u8) {
+        self.set_scene_background_mode(self.default_scene, mode);
+    }
+
+    /// 获取默认场景的背景图像绘制模式
+    pub fn get_background_mode(&self) -> u8 {
+        self.get_scene_background_mode(self.default_scene)
+    }
+
+    /// 设置默认场景的背景滚动偏移 (像素)
+    ///
+    /// 偏移为视口在背景图像上的位置：增大 x 使背景向左移动。平铺模式下无缝循环，
+    /// 其余模式下移出的区域显示底色。按摄像机位置乘以系数设置即可实现视差。
+    pub fn set_background_scroll(&mut self, x: f32, y: f32) {
+        self.set_scene_background_scroll(self.default_scene, x, y);
+    }
+
+    /// 获取默认场景的背景滚动偏移 [x, y]
+    pub fn get_background_scroll(&self) -> Option<Vec<f32>> {
+        self.get_scene_background_scroll(self.default_scene)
+    }
+
+    /// 设置指定场景的背景色 (离屏场景常用透明背景)
+    pub fn set_scene_background_color(&mut self, scene_id: u32, r: u8, g: u8, b: u8, a: u8) {
+        if !self.scenes.is_active(scene_id) {
+            return;
+        }
+        let idx = scene_id as usize;
+        self.scenes.background_colors[idx] = [r, g, b, a];
+        self.scenes.background_gradients[idx] = None;
+        self.scenes.bg_dirty[idx] = true;
+        self.scenes.cache_valid[idx] = false;
+    }
+
+    /// 从图像文件设置指定场景的背景图像，场景不存在或解码失败时返回 false
+    pub fn set_scene_background_image(&mut self, scene_id: u32, bytes: &[u8]) -> bool {
+        match decode_image(bytes) {
+            Some(image) => self.replace_background_image(scene_id, Some(image)),
+            None => false,
+        }
+    }
+
+    /// 从 RGBA 像素数据设置指定场景的背景图像
+    ///
+    /// 场景不存在或数据长度与尺寸不符时返回 false。
+    pub fn set_scene_background_image_rgba(
+        &mut self,
+        scene_id: u32,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> bool {
+        if pixel_len(width, height) != Some(data.len()) {
+            return false;
+        }
+        self.replace_background_image(
+            scene_id,
+            Some(Image {
+                width,
+                height,
+                data: data.to_vec(),
+            }),
+        )
+    }
+
+    /// 移除指定场景的背景图像 (保留底色)
+    pub fn clear_scene_background_image(&mut self, scene_id: u32) {
+        self.replace_background_image(scene_id, None);
+    }
+
+    /// 设置指定场景的背景图像绘制模式 (取值同 `set_background_mode`)
+    pub fn set_scene_background_mode(&mut self, scene_id: u32, mode: u8) {
+        if !self.scenes.is_active(scene_id) {
+            return;
+        }
+        let idx = scene_id as usize;
+        self.scenes.background_modes[idx] = BackgroundMode::from_u8(mode);
+        self.scenes.bg_dirty[idx] = true;
+        self.scenes.cache_valid[idx] = false;
+    }
+
+    /// 获取指定场景的背景图像绘制模式，场景不存在时返回 0
+    pub fn get_scene_background_mode(&self, scene_id: u32) -> u8 {
+        if self.scenes.is_active(scene_id) {
+            self.scenes.background_modes[scene_id as usize].to_u8()
+        } else {
+            0
+        }
+    }
+
+    /// 设置指定场景的背景滚动偏移 (含义同 `set_background_scroll`)
+    pub fn set_scene_background_scroll(&mut self, scene_id: u32, x: f32, y: f32) {
+        if !self.scenes.is_active(scene_id) || !x.is_finite() || !y.is_finite() {
+            return;
+        }
+        let idx = scene_id as usize;
+        if self.scenes.background_scrolls[idx] != [x, y] {
+            self.scenes.background_scrolls[idx] = [x, y];
+            self.scenes.bg_dirty[idx] = true;
+            self.scenes.cache_valid[idx] = false;
+        }
+    }
+
+    /// 获取指定场景的背景滚动偏移 [x, y]，场景不存在时返回 None
+    pub fn get_scene_background_scroll(&self, scene_id: u32) -> Option<Vec<f32>> {
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+        Some(self.scenes.background_scrolls[scene_id as usize].to_vec())
+    }
+}
+
+impl World {
+    /// 替换指定场景的背景图像，场景不存在时返回 false
+    fn replace_background_image(&mut self, scene_id: u32, image: Option<Image>) -> bool {
+        if !self.scenes.is_active(scene_id) {
+            return false;
+        }
+        let idx = scene_id as usize;
+        self.scenes.background_images[idx] = image;
+        self.scenes.bg_dirty[idx] = true;
+        self.scenes.cache_valid[idx] = false;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    /// 2x1 图像: 左红右蓝
+    const RED_BLUE: [u8; 8] = [255, 0, 0, 255, 0, 0, 255, 255];
+
+    #[test]
+    fn test_stretch_and_fit() {
+        let mut world = World::new(8, 4);
+        world.set_background_color(0, 255, 0, 255);
+        assert!(world.set_background_image_rgba(&RED_BLUE, 2, 1));
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 7, 3), [0, 0, 255, 255]);
+
+        // 适应: 2x1 → 8x4，等比缩放为 8x4，无留边
+        world.set_background_mode(1);
+        world.resize_scene(8, 8);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 255, 0, 255]);
+        assert_eq!(scene_pixel(&world, 0, 4), [255, 0, 0, 255]);
+        assert_eq!(world.get_background_mode(), 1);
+    }
+
+    #[test]
+    fn test_center_and_fill() {
+        let mut world = World::new(4, 4);
+        world.set_background_color(0, 0, 0, 255);
+        world.set_background_image_rgba(&RED_BLUE, 2, 1);
+        world.set_background_mode(3);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 2, 1), [0, 0, 255, 255]);
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 0, 0, 255]);
+
+        // 填充: 2x1 → 8x4 (左右各裁掉 2 像素)
+        world.set_background_mode(2);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 3, 3), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_tile_with_scroll() {
+        let mut world = World::new(5, 2);
+        world.set_background_image_rgba(&RED_BLUE, 2, 1);
+        world.set_background_mode(4);
+        world.render();
+        assert_eq!(scene_pixel(&world, 4, 1), [255, 0, 0, 255]);
+
+        world.set_background_scroll(1.0, 0.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 0, 255, 255]);
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 0, 0, 255]);
+        assert_eq!(world.get_background_scroll(), Some(vec![1.0, 0.0]));
+
+        world.clear_background_image();
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_linear_scaling() {
+        // 2x1 → 4x1 拉伸，中间像素在红蓝之间插值
+        let mut world = World::new(4, 1);
+        world.set_sampling_method(1);
+        world.set_background_image_rgba(&RED_BLUE, 2, 1);
+        world.render();
+        let srgb = scene_pixel(&world, 1, 0);
+
+        world.set_linear_blending(true);
+        world.render();
+        let linear = scene_pixel(&world, 1, 0);
+        assert!(
+            linear[0] > srgb[0] && linear[2] > srgb[2],
+            "{linear:?} vs {srgb:?}"
+        );
+        assert_eq!(linear[3], 255);
+    }
+
+    #[test]
+    fn test_scene_background_setters() {
+        let mut world = World::new(4, 4);
+        let scene = world.create_scene(4, 2);
+        assert!(world.set_scene_background_image_rgba(scene, &RED_BLUE, 2, 1));
+        world.set_scene_background_mode(scene, 4);
+        world.set_scene_background_scroll(scene, 1.0, 0.0);
+        assert_eq!(world.get_scene_background_mode(scene), 4);
+        assert_eq!(
+            world.get_scene_background_scroll(scene),
+            Some(vec![1.0, 0.0])
+        );
+        // 默认场景不受影响
+        assert_eq!(world.get_background_mode(), 0);
+        assert!(world.scenes.background_images[0].is_none());
+
+        let view = world.create_scene_sprite(scene).unwrap();
+        world.add_to_scene(view);
+        world.render();
+        assert_eq!(&world.scenes.data[scene as usize][..4], &[0, 0, 255, 255]);
+
+        world.clear_scene_background_image(scene);
+        assert!(world.scenes.background_images[scene as usize].is_none());
+        assert!(!world.set_scene_background_image_rgba(42, &RED_BLUE, 2, 1));
+        assert!(world.get_scene_background_scroll(42).is_none());
+    }
+
+    #[test]
+    fn test_invalid_image_keeps_background() {
+        let mut world = World::new(4, 4);
+        assert!(!world.set_background_image(b"not an image"));
+        assert!(!world.set_background_image_rgba(&[0; 4], 2, 2));
+        assert!(world.scenes.background_images[0].is_none());
+    }
+}
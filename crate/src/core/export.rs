@@ -0,0 +1,109 @@
+//! 图像导出
+//!
+//! 将场景渲染结果与精灵图显示数据编码为图像文件字节，
+//! 用于问题反馈截图、缩略图与图像比对测试。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::image::{encode_image, ImageFormat, DEFAULT_COMPRESSION};
+
+#[wasm_bindgen]
+impl World {
+    /// 导出场景为 PNG
+    ///
+    /// 编码的是最近一次 `render` 的结果。
+    /// `level` 为压缩等级 (0-10)，省略时使用默认等级。
+    pub fn export_scene_png(&self, level: Option<u8>) -> Vec<u8> {
+        self.export_scene_image(ImageFormat::Png.to_u8(), level)
+    }
+
+    /// 导出精灵图为 PNG
+    ///
+    /// 编码的是精灵图的显示数据 (包含已应用的变换)。
+    /// 精灵图不存在时返回 None。
+    pub fn export_sprite_png(&self, id: u32, level: Option<u8>) -> Option<Vec<u8>> {
+        self.export_sprite_image(id, ImageFormat::Png.to_u8(), level)
+    }
+
+    /// 按指定格式导出场景
+    ///
+    /// `format`: 0 = PNG, 1 = QOI, 2 = BMP, 3 = TGA。
+    /// `level` 对 PNG 为压缩等级，对 TGA 大于 0 时启用 RLE，省略时使用默认等级。
+    pub fn export_scene_image(&self, format: u8, level: Option<u8>) -> Vec<u8> {
+        let idx = self.default_scene as usize;
+        if idx >= self.scenes.data.len() {
+            return Vec::new();
+        }
+
+        encode_image(
+            ImageFormat::from_u8(format),
+            &self.scenes.data[idx],
+            self.scenes.widths[idx],
+            self.scenes.heights[idx],
+            level.unwrap_or(DEFAULT_COMPRESSION),
+        )
+    }
+
+    /// 按指定格式导出精灵图
+    ///
+    /// 参数含义同 `export_scene_image`，精灵图不存在时返回 None。
+    pub fn export_sprite_image(&self, id: u32, format: u8, level: Option<u8>) -> Option<Vec<u8>> {
+        if !self.sprites.is_active(id) {
+            return None;
+        }
+
+        let idx = id as usize;
+        Some(encode_image(
+            ImageFormat::from_u8(format),
+            &self.sprites.display_data[idx],
+            self.sprites.display_widths[idx],
+            self.sprites.display_heights[idx],
+            level.unwrap_or(DEFAULT_COMPRESSION),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::decode_image;
+
+    #[test]
+    fn test_export_scene_png() {
+        let mut world = World::new(4, 3);
+        world.render();
+        let png = world.export_scene_png(None);
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(&png[16..20], &4u32.to_be_bytes());
+        assert_eq!(&png[20..24], &3u32.to_be_bytes());
+    }
+
+    #[test]
+    fn test_export_sprite_png() {
+        let mut world = World::new(10, 10);
+        let id = world.create_rect_sprite(2, 5, 255, 0, 0, 255);
+        let png = world.export_sprite_png(id, Some(0)).unwrap();
+        assert_eq!(&png[16..20], &2u32.to_be_bytes());
+        assert_eq!(&png[20..24], &5u32.to_be_bytes());
+
+        world.remove_sprite(id);
+        assert!(world.export_sprite_png(id, None).is_none());
+    }
+
+    #[test]
+    fn test_export_scene_roundtrip_formats() {
+        let mut world = World::new(6, 4);
+        world.set_background_color(10, 20, 30, 255);
+        let id = world.create_rect_sprite(2, 2, 200, 100, 50, 255);
+        world.add_to_scene(id);
+        world.render();
+
+        for format in 0..4 {
+            let bytes = world.export_scene_image(format, None);
+            let image = decode_image(&bytes).unwrap();
+            assert_eq!((image.width, image.height), (6, 4));
+            assert_eq!(image.data, world.scenes.data[0]);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! 精灵图滤镜
+//!
+//! 每个精灵图保存一个有序的滤镜链 (模糊、投影、描边、外发光等)，作用于变换后的显示数据。
+//! 滤镜结果四周按各滤镜的边距之和扩展，精灵图中心位置不变，渲染时按扩展后的尺寸计算边界。
+//! 结果缓存到显示数据或滤镜链变化为止，静止的精灵图每帧不再重复计算。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::filter::Filter;
+use crate::image::Image;
+use crate::palette::Palette;
+
+#[wasm_bindgen]
+impl World {
+    // ========== 精灵图滤镜 ==========
+
+    /// 在精灵图滤镜链末尾添加滤镜 (默认启用)，返回其序号；精灵图不存在时返回 None
+    pub fn add_sprite_filter(&mut self, sprite_id: u32, filter: &Filter) -> Option<u32> {
+        if !self.sprites.is_active(sprite_id) {
+            return None;
+        }
+        let idx = sprite_id as usize;
+        self.sprites.filters[idx].push((filter.clone(), true));
+        self.sprites.filter_caches[idx] = None;
+        Some(self.sprites.filters[idx].len() as u32 - 1)
+    }
+
+    /// 替换精灵图中指定序号的滤镜 (保留启用状态)
+    pub fn set_sprite_filter(&mut self, sprite_id: u32, index: u32, filter: &Filter) {
+        if let Some(entry) = self.sprite_filter_mut(sprite_id, index) {
+            entry.0 = filter.clone();
+        }
+    }
+
+    /// 启用或停用精灵图中指定序号的滤镜
+    pub fn set_sprite_filter_enabled(&mut self, sprite_id: u32, index: u32, enabled: bool) {
+        if let Some(entry) = self.sprite_filter_mut(sprite_id, index) {
+            entry.1 = enabled;
+        }
+    }
+
+    /// 检查精灵图中指定序号的滤镜是否启用 (不存在时返回 false)
+    pub fn is_sprite_filter_enabled(&self, sprite_id: u32, index: u32) -> bool {
+        if !self.sprites.is_active(sprite_id) {
+            return false;
+        }
+        self.sprites.filters[sprite_id as usize]
+            .get(index as usize)
+            .is_some_and(|(_, enabled)| *enabled)
+    }
+
+    /// 移除精灵图中指定序号的滤镜 (之后的滤镜序号减一)
+    pub fn remove_sprite_filter(&mut self, sprite_id: u32, index: u32) {
+        if !self.sprites.is_active(sprite_id) {
+            return;
+        }
+        let idx = sprite_id as usize;
+        if (index as usize) < self.sprites.filters[idx].len() {
+            self.sprites.filters[idx].remove(index as usize);
+            self.sprites.filter_caches[idx] = None;
+        }
+    }
+
+    /// 清除精灵图的全部滤镜
+    pub fn clear_sprite_filters(&mut self, sprite_id: u32) {
+        if self.sprites.is_active(sprite_id) {
+            self.sprites.filters[sprite_id as usize].clear();
+            self.sprites.filter_caches[sprite_id as usize] = None;
+        }
+    }
+
+    /// 由精灵图的显示数据生成最多 `count` 个颜色的调色板
+    ///
+    /// `method`: 0 = 中位切分, 1 = 八叉树。精灵图不存在时返回 None。
+    pub fn compute_sprite_palette(
+        &self,
+        sprite_id: u32,
+        count: u32,
+        method: u8,
+    ) -> Option<Palette> {
+        if !self.sprites.is_active(sprite_id) {
+            return None;
+        }
+        Some(Palette::from_pixels(
+            &self.sprites.display_data[sprite_id as usize],
+            count,
+            method,
+        ))
+    }
+
+    /// 获取精灵图的滤镜数量
+    pub fn get_sprite_filter_count(&self, sprite_id: u32) -> u32 {
+        if !self.sprites.is_active(sprite_id) {
+            return 0;
+        }
+        self.sprites.filters[sprite_id as usize].len() as u32
+    }
+
+    /// 获取精灵图渲染时的边界 [左, 上, 宽, 高] (场景坐标，含滤镜扩展的边距)
+    pub fn get_sprite_bounds(&self, sprite_id: u32) -> Option<Vec<f32>> {
+        if !self.sprites.is_active(sprite_id) {
+            return None;
+        }
+        let idx = sprite_id as usize;
+        let pad = self.sprite_filter_padding(idx) as f32 * 2.0;
+        let width = self.sprites.display_widths[idx] as f32 + pad;
+        let height = self.sprites.display_heights[idx] as f32 + pad;
+        Some(vec![
+            self.sprites.positions_x[idx] - width / 2.0,
+            self.sprites.positions_y[idx] - height / 2.0,
+            width,
+            height,
+        ])
+    }
+}
+
+impl World {
+    fn sprite_filter_mut(&mut self, sprite_id: u32, index: u32) -> Option<&mut (Filter, bool)> {
+        if !self.sprites.is_active(sprite_id) {
+            return None;
+        }
+        let idx = sprite_id as usize;
+        self.sprites.filter_caches[idx] = None;
+        self.sprites.filters[idx].get_mut(index as usize)
+    }
+
+    /// 已启用滤镜的边距之和
+    fn sprite_filter_padding(&self, idx: usize) -> u32 {
+        self.sprites.filters[idx]
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(filter, _)| filter.padding())
+            .sum()
+    }
+
+    /// 为带滤镜的精灵图生成缺失的滤镜缓存
+    pub(super) fn update_sprite_filters(&mut self, sprite_ids: &[u32]) {
+        for &id in sprite_ids {
+            let idx = id as usize;
+            if !self.sprites.is_active(id) || self.sprites.filter_caches[idx].is_some() {
+                continue;
+            }
+            if self.sprites.filters[idx].iter().all(|(_, on)| !*on) {
+                continue;
+            }
+            self.sprites.filter_caches[idx] = self.filtered_sprite(idx);
+        }
+    }
+
+    /// 在扩展边距后的显示数据副本上依次应用已启用的滤镜
+    fn filtered_sprite(&self, idx: usize) -> Option<Image> {
+        let pad = self.sprite_filter_padding(idx);
+        let (width, height) = (
+            self.sprites.display_widths[idx],
+            self.sprites.display_heights[idx],
+        );
+        let mut image = Image::new(width + pad * 2, height + pad * 2)?;
+        let src = &self.sprites.display_data[idx];
+        let (row, padded_row) = (width as usize * 4, image.width as usize * 4);
+        for y in 0..height as usize {
+            let dst = (y + pad as usize) * padded_row + pad as usize * 4;
+            image.data[dst..dst + row].copy_from_slice(&src[y * row..(y + 1) * row]);
+        }
+        for (filter, _) in self.sprites.filters[idx].iter().filter(|(_, on)| *on) {
+            filter.apply(&mut image.data, image.width, image.height);
+        }
+        Some(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    #[test]
+    fn test_outline_expands_bounds() {
+        let mut world = World::new(8, 8);
+        world.set_background_color(0, 0, 0, 255);
+        let id = world.create_rect_sprite(2, 2, 255, 255, 255, 255);
+        world.add_to_scene(id);
+        assert_eq!(
+            world.get_sprite_bounds(id),
+            Some(vec![-1.0, -1.0, 2.0, 2.0])
+        );
+
+        let outline = world
+            .add_sprite_filter(id, &Filter::outline(1.0, 255, 0, 0, 255))
+            .unwrap();
+        assert_eq!(
+            world.get_sprite_bounds(id),
+            Some(vec![-2.0, -2.0, 4.0, 4.0])
+        );
+        world.render();
+        assert_eq!(scene_pixel(&world, 4, 4), [255, 255, 255, 255]);
+        assert_eq!(scene_pixel(&world, 2, 4), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 1, 4), [0, 0, 0, 255]);
+
+        world.set_sprite_filter_enabled(id, outline, false);
+        world.render();
+        assert_eq!(scene_pixel(&world, 2, 4), [0, 0, 0, 255]);
+        assert_eq!(
+            world.get_sprite_bounds(id),
+            Some(vec![-1.0, -1.0, 2.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn test_cache_invalidation() {
+        let mut world = World::new(8, 8);
+        let id = world.create_rect_sprite(2, 2, 255, 255, 255, 255);
+        world.add_to_scene(id);
+        world.add_sprite_filter(id, &Filter::drop_shadow(1.0, 1.0, 0.0, 0, 0, 0, 255));
+        world.render();
+        assert_eq!(
+            world.sprites.filter_caches[id as usize]
+                .as_ref()
+                .unwrap()
+                .width,
+            4
+        );
+
+        // 显示数据变化时缓存失效并按新尺寸重建
+        world.apply_sprite_rotation(id, std::f32::consts::FRAC_PI_4);
+        assert!(world.sprites.filter_caches[id as usize].is_none());
+        world.render();
+        let size = world.sprites.display_widths[id as usize] + 2;
+        assert_eq!(
+            world.sprites.filter_caches[id as usize]
+                .as_ref()
+                .unwrap()
+                .width,
+            size
+        );
+
+        world.set_sprite_filter(id, 0, &Filter::blur(1.0));
+        assert!(world.sprites.filter_caches[id as usize].is_none());
+        world.clear_sprite_filters(id);
+        world.render();
+        assert!(world.sprites.filter_caches[id as usize].is_none());
+        assert_eq!(world.get_sprite_filter_count(id), 0);
+        assert_eq!(world.add_sprite_filter(99, &Filter::blur(1.0)), None);
+    }
+
+    #[test]
+    fn test_sprite_quantization() {
+        let mut world = World::new(2, 1);
+        let data = [250, 250, 250, 255, 20, 20, 20, 255];
+        let id = world.create_sprite(&data, 2, 1);
+        world.add_to_scene(id);
+        let palette = world.compute_sprite_palette(id, 1, 1).unwrap();
+        assert_eq!(palette.colors(), vec![135, 135, 135, 255]);
+        world.add_sprite_filter(id, &Filter::quantize(&palette, 0));
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [135, 135, 135, 255]);
+        assert_eq!(scene_pixel(&world, 1, 0), [135, 135, 135, 255]);
+    }
+}
@@ -0,0 +1,341 @@
+//! HDR 浮点累积缓冲
+//!
+//! 开启后场景先以线性光 f32 缓冲合成 (背景、精灵图、瓦片地图、粒子)，
+//! 多次半透明叠加也不会累积 8 位取整误差。渲染结束时乘以曝光度，经色调映射后
+//! 编码为 sRGB 写入供 JS 读取的 RGBA8 像素缓冲，后期处理滤镜作用于映射后的结果。
+//!
+//! 普通的 source-over 混合不会使颜色超过 1；精灵图与粒子可设置发光强度
+//! (线性光乘数) 或叠加模式，其结果在 HDR 缓冲中不截断，由色调映射压缩高光。
+//! 未开启 HDR 时这些参数同样生效，但结果截断到 8 位。
+
+use wasm_bindgen::prelude::*;
+
+use super::linear::{blend_linear, to_linear, to_srgb};
+use super::world::{SceneStore, World};
+use crate::image::blend_over;
+
+/// 色调映射方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tonemap {
+    /// 直接截断到 [0, 1]
+    #[default]
+    Clamp,
+    /// Reinhard: x / (1 + x)
+    Reinhard,
+    /// ACES 电影曲线 (Narkowicz 拟合)
+    Aces,
+}
+
+impl Tonemap {
+    /// 从 u8 值创建色调映射方式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Tonemap::Clamp,
+            1 => Tonemap::Reinhard,
+            2 => Tonemap::Aces,
+            _ => Tonemap::Clamp,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Tonemap::Clamp => 0,
+            Tonemap::Reinhard => 1,
+            Tonemap::Aces => 2,
+        }
+    }
+
+    /// 将线性 HDR 值映射到 [0, 1]
+    fn map(self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        let y = match self {
+            Tonemap::Clamp => x,
+            Tonemap::Reinhard => x / (1.0 + x),
+            Tonemap::Aces => (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14),
+        };
+        y.clamp(0.0, 1.0)
+    }
+}
+
+/// 精灵图与粒子写入场景的方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Emission {
+    /// 线性光颜色乘数 (HDR 中可超过 1)
+    pub(super) intensity: f32,
+    /// 是否叠加到目标上 (否则为 source-over)
+    pub(super) additive: bool,
+}
+
+impl Default for Emission {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            additive: false,
+        }
+    }
+}
+
+impl Emission {
+    /// 是否为普通的 source-over 混合 (可走 8 位快速路径)
+    pub(super) fn is_plain(self) -> bool {
+        self == Self::default()
+    }
+
+    /// 8 位 sRGB 颜色 → 乘以强度后的线性光颜色
+    pub(super) fn linear_rgb(self, src: [u8; 4]) -> [f32; 3] {
+        std::array::from_fn(|c| to_linear(src[c]) * self.intensity)
+    }
+}
+
+/// 将线性光颜色 (可超过 1) 混合到 HDR 像素
+pub(super) fn blend_hdr(dst: &mut [f32], rgb: [f32; 3], a: f32, additive: bool) {
+    let inv = 1.0 - a;
+    for i in 0..3 {
+        dst[i] = if additive {
+            dst[i] + rgb[i] * a
+        } else {
+            rgb[i] * a + dst[i] * inv
+        };
+    }
+    dst[3] = a + dst[3] * inv;
+}
+
+/// 将线性光颜色混合到 8 位 sRGB 像素 (结果截断到 0..1)
+pub(super) fn blend_clamped(dst: &mut [u8], rgb: [f32; 3], a: f32, additive: bool) {
+    let mut px = [
+        to_linear(dst[0]),
+        to_linear(dst[1]),
+        to_linear(dst[2]),
+        dst[3] as f32 / 255.0,
+    ];
+    blend_hdr(&mut px, rgb, a, additive);
+    for i in 0..3 {
+        dst[i] = to_srgb(px[i]);
+    }
+    dst[3] = (px[3].clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+}
+
+/// 混合到场景缓冲中第 `dst` 字节处的像素
+///
+/// 开启 HDR 时写入浮点缓冲；`linear` 为场景的线性光混合设置。
+pub(super) fn blend_into(
+    data: &mut [u8],
+    hdr: Option<&mut [f32]>,
+    dst: usize,
+    src: [u8; 4],
+    emission: Emission,
+    linear: bool,
+) {
+    let a = src[3] as f32 / 255.0;
+    match hdr {
+        Some(hdr) => blend_hdr(
+            &mut hdr[dst..dst + 4],
+            emission.linear_rgb(src),
+            a,
+            emission.additive,
+        ),
+        None if emission.is_plain() && linear => blend_linear(&mut data[dst..dst + 4], src),
+        None if emission.is_plain() => blend_over(&mut data[dst..dst + 4], src),
+        None => blend_clamped(
+            &mut data[dst..dst + 4],
+            emission.linear_rgb(src),
+            a,
+            emission.additive,
+        ),
+    }
+}
+
+impl SceneStore {
+    /// 以已清空为背景的像素缓冲初始化 HDR 缓冲
+    pub(super) fn load_hdr(&mut self, idx: usize) {
+        let data = &self.data[idx];
+        let Some(hdr) = self.hdr_buffers[idx].as_mut() else {
+            return;
+        };
+        hdr.resize(data.len(), 0.0);
+        for (h, px) in hdr.chunks_exact_mut(4).zip(data.chunks_exact(4)) {
+            h[0] = to_linear(px[0]);
+            h[1] = to_linear(px[1]);
+            h[2] = to_linear(px[2]);
+            h[3] = px[3] as f32 / 255.0;
+        }
+    }
+
+    /// 曝光、色调映射并量化到 RGBA8 像素缓冲
+    pub(super) fn resolve_hdr(&mut self, idx: usize) {
+        let Some(hdr) = self.hdr_buffers[idx].as_ref() else {
+            return;
+        };
+        let (tonemap, exposure) = (self.tonemaps[idx], self.exposures[idx]);
+        for (px, h) in self.data[idx].chunks_exact_mut(4).zip(hdr.chunks_exact(4)) {
+            for c in 0..3 {
+                px[c] = to_srgb(tonemap.map(h[c] * exposure));
+            }
+            px[3] = (h[3].clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== HDR ==========
+
+    /// 开启或关闭场景的 HDR 浮点累积缓冲
+    pub fn set_scene_hdr(&mut self, scene_id: u32, enabled: bool) {
+        if !self.scenes.is_active(scene_id) {
+            return;
+        }
+        let idx = scene_id as usize;
+        if enabled != self.scenes.hdr_buffers[idx].is_some() {
+            self.scenes.hdr_buffers[idx] = enabled.then(Vec::new);
+            self.scenes.cache_valid[idx] = false;
+        }
+    }
+
+    /// 检查场景是否开启 HDR
+    pub fn is_scene_hdr(&self, scene_id: u32) -> bool {
+        self.scenes.is_active(scene_id) && self.scenes.hdr_buffers[scene_id as usize].is_some()
+    }
+
+    /// 设置场景的色调映射方式
+    ///
+    /// `mode`: 0 = 截断 (clamp), 1 = Reinhard, 2 = ACES。
+    pub fn set_scene_tonemap(&mut self, scene_id: u32, mode: u8) {
+        if self.scenes.is_active(scene_id) {
+            self.scenes.tonemaps[scene_id as usize] = Tonemap::from_u8(mode);
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 获取场景的色调映射方式
+    pub fn get_scene_tonemap(&self, scene_id: u32) -> u8 {
+        if !self.scenes.is_active(scene_id) {
+            return 0;
+        }
+        self.scenes.tonemaps[scene_id as usize].to_u8()
+    }
+
+    /// 设置场景的曝光度 (色调映射前的线性乘数，默认为 1)
+    pub fn set_scene_exposure(&mut self, scene_id: u32, exposure: f32) {
+        if self.scenes.is_active(scene_id) && exposure.is_finite() && exposure >= 0.0 {
+            self.scenes.exposures[scene_id as usize] = exposure;
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 设置精灵图的发光强度 (线性光乘数，默认为 1，HDR 场景中可超过 1)
+    pub fn set_sprite_intensity(&mut self, id: u32, intensity: f32) {
+        if self.sprites.is_active(id) && intensity.is_finite() && intensity >= 0.0 {
+            self.sprites.emissions[id as usize].intensity = intensity;
+        }
+    }
+
+    /// 设置精灵图是否以叠加模式绘制 (颜色加到目标上，适合光效)
+    pub fn set_sprite_additive(&mut self, id: u32, additive: bool) {
+        if self.sprites.is_active(id) {
+            self.sprites.emissions[id as usize].additive = additive;
+        }
+    }
+
+    /// 设置发射器粒子的发光强度 (线性光乘数，默认为 1)
+    pub fn set_emitter_intensity(&mut self, emitter_id: u32, intensity: f32) {
+        if self.emitters.is_active(emitter_id) && intensity.is_finite() && intensity >= 0.0 {
+            self.emitters.emissions[emitter_id as usize].intensity = intensity;
+        }
+    }
+
+    /// 设置发射器粒子是否以叠加模式绘制
+    pub fn set_emitter_additive(&mut self, emitter_id: u32, additive: bool) {
+        if self.emitters.is_active(emitter_id) {
+            self.emitters.emissions[emitter_id as usize].additive = additive;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tonemap_curves() {
+        assert_eq!(Tonemap::from_u8(2), Tonemap::Aces);
+        assert_eq!(Tonemap::from_u8(9), Tonemap::Clamp);
+        assert_eq!(Tonemap::Reinhard.to_u8(), 1);
+        assert_eq!(Tonemap::Clamp.map(3.0), 1.0);
+        assert_eq!(Tonemap::Reinhard.map(1.0), 0.5);
+        assert!(Tonemap::Aces.map(100.0) > 0.99);
+        assert_eq!(Tonemap::Aces.map(-1.0), 0.0);
+    }
+
+    #[test]
+    fn test_repeated_blending_without_rounding_drift() {
+        let mut world = World::new(1, 1);
+        world.set_background_color(0, 0, 0, 255);
+        world.set_scene_hdr(0, true);
+        assert!(world.is_scene_hdr(0));
+        // 多个极淡的白色精灵叠加: 8 位缓冲中第一层之后的贡献都被取整丢弃
+        for _ in 0..20 {
+            let id = world.create_rect_sprite(1, 1, 255, 255, 255, 1);
+            world.add_to_scene(id);
+        }
+        world.render();
+        // 覆盖率 1 - (254/255)^20 ≈ 0.0755 (线性)，编码后约为 78
+        assert!((world.scenes.data[0][0] as i32 - 78).abs() <= 2);
+
+        world.set_scene_hdr(0, false);
+        world.render();
+        assert_eq!(world.scenes.data[0][0], 1);
+    }
+
+    #[test]
+    fn test_exposure_and_tonemap() {
+        let mut world = World::new(1, 1);
+        world.set_background_color(255, 255, 255, 255);
+        world.set_scene_hdr(0, true);
+        world.render();
+        assert_eq!(&world.scenes.data[0][..4], &[255, 255, 255, 255]);
+
+        world.set_scene_tonemap(0, 1);
+        assert_eq!(world.get_scene_tonemap(0), 1);
+        world.render();
+        // Reinhard 将线性 1.0 映射为 0.5
+        assert!((world.scenes.data[0][0] as i32 - 188).abs() <= 1);
+        world.set_scene_exposure(0, 1000.0);
+        world.render();
+        assert_eq!(world.scenes.data[0][0], 255);
+    }
+
+    #[test]
+    fn test_additive_accumulates_above_one() {
+        let mut world = World::new(1, 1);
+        world.set_background_color(255, 255, 255, 255);
+        world.set_scene_hdr(0, true);
+        world.set_scene_tonemap(0, 1);
+        for _ in 0..3 {
+            let id = world.create_rect_sprite(1, 1, 255, 255, 255, 255);
+            world.set_sprite_additive(id, true);
+            world.add_to_scene(id);
+        }
+        world.render();
+        // 线性 4.0 经 Reinhard 映射为 0.8，而不是截断后的 0.5
+        assert!((world.scenes.data[0][0] as i32 - 231).abs() <= 1);
+
+        // 未开启 HDR 时截断为白色
+        world.set_scene_hdr(0, false);
+        world.set_background_color(0, 0, 0, 255);
+        world.render();
+        assert_eq!(world.scenes.data[0][0], 255);
+
+        let mut world = World::new(1, 1);
+        world.set_background_color(0, 0, 0, 255);
+        world.set_scene_hdr(0, true);
+        world.set_scene_tonemap(0, 1);
+        let id = world.create_rect_sprite(1, 1, 255, 255, 255, 255);
+        world.set_sprite_intensity(id, 3.0);
+        world.add_to_scene(id);
+        world.render();
+        // 线性 3.0 → 0.75
+        assert!((world.scenes.data[0][0] as i32 - 225).abs() <= 1);
+    }
+}
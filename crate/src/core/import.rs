@@ -0,0 +1,80 @@
+//! 图像导入
+//!
+//! 从图像文件字节或像素缓冲区创建精灵图，图像格式自动识别。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::image::decode_image;
+use crate::PixelBuffer;
+
+#[wasm_bindgen]
+impl World {
+    /// 从图像文件创建精灵图
+    ///
+    /// 支持 PNG / QOI / BMP / TGA，格式根据文件头自动识别。
+    /// 解码失败时返回 None。
+    pub fn create_sprite_from_image(&mut self, bytes: &[u8]) -> Option<u32> {
+        let image = decode_image(bytes)?;
+        Some(self.sprites.add(image.data, image.width, image.height))
+    }
+
+    /// 从像素缓冲区创建精灵图 (拷贝当前内容)
+    ///
+    /// 可先在缓冲区上绘制矢量图形，再作为精灵图加入场景。
+    pub fn create_sprite_from_buffer(&mut self, buffer: &PixelBuffer) -> u32 {
+        self.sprites
+            .add(buffer.data.clone(), buffer.width, buffer.height)
+    }
+
+    /// 用像素缓冲区的内容替换精灵图图像 (保留已应用的变换)
+    pub fn set_sprite_from_buffer(&mut self, id: u32, buffer: &PixelBuffer) {
+        self.set_sprite_source(id, buffer.data.clone(), buffer.width, buffer.height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{encode_image, ImageFormat};
+
+    #[test]
+    fn test_create_sprite_from_each_format() {
+        let data: Vec<u8> = (0..3 * 2 * 4).map(|v| (v * 10) as u8).collect();
+        let mut world = World::new(10, 10);
+        for format in [
+            ImageFormat::Png,
+            ImageFormat::Qoi,
+            ImageFormat::Bmp,
+            ImageFormat::Tga,
+        ] {
+            let bytes = encode_image(format, &data, 3, 2, 6);
+            let id = world.create_sprite_from_image(&bytes).unwrap();
+            assert_eq!(world.sprites.original_widths[id as usize], 3);
+            assert_eq!(world.sprites.original_heights[id as usize], 2);
+            assert_eq!(world.sprites.original_data[id as usize], data);
+        }
+    }
+
+    #[test]
+    fn test_sprite_from_buffer() {
+        let mut world = World::new(10, 10);
+        let mut buffer = PixelBuffer::new(4, 4);
+        buffer.fill_circle(2.0, 2.0, 2.0, 255, 0, 0, 255);
+        let id = world.create_sprite_from_buffer(&buffer);
+        assert_eq!(world.sprites.original_data[id as usize], buffer.data);
+
+        let mut bigger = PixelBuffer::new(6, 2);
+        bigger.clear(0, 255, 0, 255);
+        world.set_sprite_from_buffer(id, &bigger);
+        assert_eq!(world.sprites.display_widths[id as usize], 6);
+        assert_eq!(world.sprites.original_data[id as usize], bigger.data);
+    }
+
+    #[test]
+    fn test_invalid_image_creates_no_sprite() {
+        let mut world = World::new(10, 10);
+        assert!(world.create_sprite_from_image(b"garbage").is_none());
+        assert_eq!(world.create_rect_sprite(1, 1, 0, 0, 0, 255), 0);
+    }
+}
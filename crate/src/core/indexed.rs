@@ -0,0 +1,487 @@
+//! 索引色精灵图
+//!
+//! 精灵图以 8 位调色板索引保存源数据，引用世界中共享的调色板。
+//! 调色板在渲染前解析: 替换调色板颜色会重新着色所有引用它的精灵图 (角色换肤)，
+//! 调色板循环按时间轮转一段索引范围内的颜色 (水面、岩浆等效果)。
+//! 变换按最近邻作用于索引，结果不会出现调色板之外的混合色。
+//!
+//! 内存: 除源索引 (每像素 1 字节) 外，还常驻变换后的索引 (每像素 2 字节)
+//! 与解析后的 RGBA 显示数据 (每像素 4 字节)，因此并不比普通精灵图省内存。
+//! 调色板变化时只对变换后的索引重新查表，不重新变换。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::{SpriteTransform, World};
+use crate::image::pixel_len;
+use crate::math::Matrix3x3;
+use crate::palette::Palette;
+
+/// 调色板循环: 索引 [start, end] 内的颜色每秒轮转 `rate` 步 (负数反向)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct PaletteCycle {
+    start: usize,
+    end: usize,
+    rate: f32,
+    /// 累计时间 (秒)
+    elapsed: f32,
+}
+
+impl PaletteCycle {
+    /// 当前轮转步数
+    fn offset(&self) -> usize {
+        let len = (self.end - self.start + 1) as i64;
+        ((self.elapsed * self.rate).floor() as i64).rem_euclid(len) as usize
+    }
+}
+
+/// 共享调色板存储 - 各属性分离为独立数组
+pub struct PaletteStore {
+    /// 基础颜色
+    pub(super) colors: Vec<Vec<[u8; 4]>>,
+    /// 颜色循环
+    pub(super) cycles: Vec<Vec<PaletteCycle>>,
+    /// 版本 (颜色或循环步数变化时加一)
+    pub(super) versions: Vec<u32>,
+}
+
+impl PaletteStore {
+    pub(super) fn new() -> Self {
+        Self {
+            colors: Vec::new(),
+            cycles: Vec::new(),
+            versions: Vec::new(),
+        }
+    }
+
+    fn contains(&self, id: u32) -> bool {
+        (id as usize) < self.colors.len()
+    }
+
+    /// 应用颜色循环后的调色板
+    fn resolved(&self, id: usize) -> Vec<[u8; 4]> {
+        let base = &self.colors[id];
+        let mut colors = base.clone();
+        for cycle in &self.cycles[id] {
+            let (start, end) = (cycle.start, cycle.end.min(base.len().saturating_sub(1)));
+            if start >= end {
+                continue;
+            }
+            let (len, k) = (end - start + 1, cycle.offset());
+            for i in start..=end {
+                colors[i] = base[start + (i - start + len - k % len) % len];
+            }
+        }
+        colors
+    }
+}
+
+/// 变换后索引中表示透明 (落在源图像之外) 的值
+const TRANSPARENT: u16 = u16::MAX;
+
+/// 索引色精灵图存储 - 各属性分离为独立数组
+pub struct IndexedStore {
+    /// 对应的精灵图ID
+    pub(super) sprite_ids: Vec<u32>,
+    /// 调色板索引
+    pub(super) indices: Vec<Vec<u8>>,
+    /// 变换后的调色板索引 (与显示数据同尺寸)
+    pub(super) display_indices: Vec<Vec<u16>>,
+    /// 引用的调色板ID
+    pub(super) palette_ids: Vec<u32>,
+    /// 上次解析时调色板的版本
+    pub(super) versions: Vec<u32>,
+}
+
+impl IndexedStore {
+    pub(super) fn new() -> Self {
+        Self {
+            sprite_ids: Vec::new(),
+            indices: Vec::new(),
+            display_indices: Vec::new(),
+            palette_ids: Vec::new(),
+            versions: Vec::new(),
+        }
+    }
+
+    /// 查找精灵图对应的索引色状态
+    pub(super) fn find(&self, sprite_id: u32) -> Option<usize> {
+        self.sprite_ids.iter().position(|&id| id == sprite_id)
+    }
+
+    /// 移除索引色状态 (精灵图改为普通 RGBA 精灵图)
+    pub(super) fn remove(&mut self, i: usize) {
+        self.sprite_ids.swap_remove(i);
+        self.indices.swap_remove(i);
+        self.display_indices.swap_remove(i);
+        self.palette_ids.swap_remove(i);
+        self.versions.swap_remove(i);
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 索引色精灵图 ==========
+
+    /// 创建共享调色板，返回调色板ID
+    pub fn create_palette(&mut self, palette: &Palette) -> u32 {
+        let id = self.palettes.colors.len() as u32;
+        self.palettes.colors.push(palette.entries().to_vec());
+        self.palettes.cycles.push(Vec::new());
+        self.palettes.versions.push(0);
+        id
+    }
+
+    /// 替换调色板的全部颜色 (引用它的精灵图在下次渲染时重新着色)
+    pub fn set_palette(&mut self, palette_id: u32, palette: &Palette) {
+        if self.palettes.contains(palette_id) {
+            self.palettes.colors[palette_id as usize] = palette.entries().to_vec();
+            self.bump_palette(palette_id as usize);
+        }
+    }
+
+    /// 设置调色板中单个颜色
+    pub fn set_palette_color(&mut self, palette_id: u32, index: u8, r: u8, g: u8, b: u8, a: u8) {
+        if !self.palettes.contains(palette_id) {
+            return;
+        }
+        let p = palette_id as usize;
+        if let Some(color) = self.palettes.colors[p].get_mut(index as usize) {
+            *color = [r, g, b, a];
+            self.bump_palette(p);
+        }
+    }
+
+    /// 添加调色板循环: 索引 [start, end] 内的颜色每秒轮转 `rate` 步 (负数反向)
+    ///
+    /// 调色板不存在、范围为空或 `rate` 非有限值时返回 false。
+    pub fn add_palette_cycle(&mut self, palette_id: u32, start: u8, end: u8, rate: f32) -> bool {
+        if !self.palettes.contains(palette_id) || start >= end || !rate.is_finite() {
+            return false;
+        }
+        self.palettes.cycles[palette_id as usize].push(PaletteCycle {
+            start: start as usize,
+            end: end as usize,
+            rate,
+            elapsed: 0.0,
+        });
+        true
+    }
+
+    /// 清除调色板的全部循环 (恢复基础颜色)
+    pub fn clear_palette_cycles(&mut self, palette_id: u32) {
+        if self.palettes.contains(palette_id) {
+            self.palettes.cycles[palette_id as usize].clear();
+            self.bump_palette(palette_id as usize);
+        }
+    }
+
+    /// 创建索引色精灵图
+    ///
+    /// `indices` 每字节一个像素，超出调色板范围的索引视为透明。
+    /// 数据长度与尺寸不符或调色板不存在时返回 None。
+    pub fn create_indexed_sprite(
+        &mut self,
+        indices: &[u8],
+        width: u32,
+        height: u32,
+        palette_id: u32,
+    ) -> Option<u32> {
+        let len = pixel_len(width, height)? / 4;
+        if indices.len() != len || !self.palettes.contains(palette_id) {
+            return None;
+        }
+        let id = self.sprites.add(Vec::new(), width, height);
+        self.indexed.sprite_ids.push(id);
+        self.indexed.indices.push(indices.to_vec());
+        self.indexed.display_indices.push(Vec::new());
+        self.indexed.palette_ids.push(palette_id);
+        self.indexed.versions.push(0);
+        let i = self.indexed.sprite_ids.len() - 1;
+        self.refresh_indexed_sprite(i);
+        Some(id)
+    }
+
+    /// 更换索引色精灵图引用的调色板，精灵图或调色板不存在时返回 false
+    pub fn set_sprite_palette(&mut self, sprite_id: u32, palette_id: u32) -> bool {
+        let Some(i) = self.indexed.find(sprite_id) else {
+            return false;
+        };
+        if !self.sprites.is_active(sprite_id) || !self.palettes.contains(palette_id) {
+            return false;
+        }
+        self.indexed.palette_ids[i] = palette_id;
+        self.recolor_indexed_sprite(i);
+        true
+    }
+
+    /// 获取索引色精灵图引用的调色板ID
+    pub fn get_sprite_palette(&self, sprite_id: u32) -> Option<u32> {
+        if !self.sprites.is_active(sprite_id) {
+            return None;
+        }
+        Some(self.indexed.palette_ids[self.indexed.find(sprite_id)?])
+    }
+}
+
+impl World {
+    fn bump_palette(&mut self, p: usize) {
+        self.palettes.versions[p] = self.palettes.versions[p].wrapping_add(1);
+    }
+
+    /// 推进调色板循环，步数变化的调色板版本加一
+    pub(super) fn update_palettes(&mut self, dt: f32) {
+        for p in 0..self.palettes.cycles.len() {
+            let mut changed = false;
+            for cycle in self.palettes.cycles[p].iter_mut() {
+                let before = cycle.offset();
+                cycle.elapsed += dt;
+                changed |= cycle.offset() != before;
+            }
+            if changed {
+                self.bump_palette(p);
+            }
+        }
+    }
+
+    /// 重新着色调色板已变化的索引色精灵图
+    pub(super) fn sync_indexed_sprites(&mut self) {
+        for i in 0..self.indexed.sprite_ids.len() {
+            let p = self.indexed.palette_ids[i] as usize;
+            if self.indexed.versions[i] != self.palettes.versions[p] {
+                self.recolor_indexed_sprite(i);
+            }
+        }
+    }
+
+    /// 用当前调色板重新解析变换后的索引 (尺寸不变)
+    fn recolor_indexed_sprite(&mut self, i: usize) {
+        let id = self.indexed.sprite_ids[i];
+        if !self.sprites.is_active(id) {
+            return;
+        }
+        let idx = id as usize;
+        let (width, height) = (
+            self.sprites.display_widths[idx],
+            self.sprites.display_heights[idx],
+        );
+        if self.indexed.display_indices[i].len() != width as usize * height as usize {
+            return;
+        }
+        let data = self.resolve_indices(i);
+        self.sprites.set_display(idx, data, width, height);
+    }
+
+    /// 按调色板查表生成 RGBA 数据，并记录解析时的调色板版本
+    fn resolve_indices(&mut self, i: usize) -> Vec<u8> {
+        let p = self.indexed.palette_ids[i] as usize;
+        let colors = self.palettes.resolved(p);
+        let indices = &self.indexed.display_indices[i];
+        let mut data = vec![0u8; indices.len() * 4];
+        for (px, &index) in data.chunks_exact_mut(4).zip(indices) {
+            if let Some(color) = colors.get(index as usize) {
+                px.copy_from_slice(color);
+            }
+        }
+        self.indexed.versions[i] = self.palettes.versions[p];
+        data
+    }
+
+    /// 按当前调色板与变换重新生成显示数据
+    fn refresh_indexed_sprite(&mut self, i: usize) {
+        let id = self.indexed.sprite_ids[i];
+        if !self.sprites.is_active(id) {
+            return;
+        }
+        let transform = self.sprites.transforms[id as usize];
+        self.apply_indexed_transform(id, transform);
+    }
+
+    /// 若精灵图是索引色精灵图，则按最近邻变换索引并用调色板着色
+    ///
+    /// 透明像素记为 `TRANSPARENT`，不会与调色板中的任何索引冲突。
+    ///
+    /// 返回 false 表示不是索引色精灵图，由调用者走普通的像素重采样。
+    pub(super) fn apply_indexed_transform(&mut self, id: u32, transform: SpriteTransform) -> bool {
+        let Some(i) = self.indexed.find(id) else {
+            return false;
+        };
+        let idx = id as usize;
+        let (angle, sx, sy) = transform.parts();
+        let (width, height) = (
+            self.sprites.original_widths[idx],
+            self.sprites.original_heights[idx],
+        );
+        let (w, h) = (width as f32 * sx.abs(), height as f32 * sy.abs());
+        let (cos_a, sin_a) = (angle.cos().abs(), angle.sin().abs());
+        let new_width = (w * cos_a + h * sin_a).round().max(1.0) as u32;
+        let new_height = (w * sin_a + h * cos_a).round().max(1.0) as u32;
+        let Some(len) = pixel_len(new_width, new_height) else {
+            return true;
+        };
+        if self.indexed.indices[i].len() != width as usize * height as usize {
+            return true;
+        }
+
+        // 目标像素中心 → 源像素坐标
+        let inverse = Matrix3x3::rotation(-angle)
+            .inverse()
+            .unwrap_or_else(Matrix3x3::identity);
+        let indices = &self.indexed.indices[i];
+        let mut transformed = vec![TRANSPARENT; len / 4];
+        for ty in 0..new_height {
+            for tx in 0..new_width {
+                let (rx, ry) = inverse.transform_point(
+                    tx as f32 + 0.5 - new_width as f32 / 2.0,
+                    ty as f32 + 0.5 - new_height as f32 / 2.0,
+                );
+                let src_x = (rx / sx + width as f32 / 2.0).floor();
+                let src_y = (ry / sy + height as f32 / 2.0).floor();
+                if src_x < 0.0 || src_y < 0.0 || src_x >= width as f32 || src_y >= height as f32 {
+                    continue;
+                }
+                transformed[(ty * new_width + tx) as usize] =
+                    indices[src_y as usize * width as usize + src_x as usize] as u16;
+            }
+        }
+
+        self.indexed.display_indices[i] = transformed;
+        let data = self.resolve_indices(i);
+        self.sprites.set_display(idx, data, new_width, new_height);
+        self.sprites.transforms[idx] = transform;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const GREEN: [u8; 4] = [0, 255, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    fn rgb_palette() -> Palette {
+        Palette::from_colors(&[RED, GREEN, BLUE].concat())
+    }
+
+    fn display_pixel(world: &World, id: u32, x: u32, y: u32) -> [u8; 4] {
+        let idx = id as usize;
+        let i = ((y * world.sprites.display_widths[idx] + x) * 4) as usize;
+        world.sprites.display_data[idx][i..i + 4]
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_palette_swap_recolors_all_users() {
+        let mut world = World::new(4, 4);
+        let palette = world.create_palette(&rgb_palette());
+        let a = world
+            .create_indexed_sprite(&[0, 1, 2, 9], 2, 2, palette)
+            .unwrap();
+        let b = world.create_indexed_sprite(&[1], 1, 1, palette).unwrap();
+        assert_eq!(display_pixel(&world, a, 1, 0), GREEN);
+        assert_eq!(display_pixel(&world, a, 1, 1), [0, 0, 0, 0]);
+        // 源数据只保存索引
+        assert!(world.sprites.original_data[a as usize].is_empty());
+
+        world.set_palette_color(palette, 1, 255, 255, 0, 255);
+        world.render();
+        assert_eq!(display_pixel(&world, a, 1, 0), [255, 255, 0, 255]);
+        assert_eq!(display_pixel(&world, b, 0, 0), [255, 255, 0, 255]);
+
+        let other = world.create_palette(&Palette::from_colors(&[BLUE, RED].concat()));
+        assert!(world.set_sprite_palette(b, other));
+        assert_eq!(world.get_sprite_palette(b), Some(other));
+        assert_eq!(display_pixel(&world, b, 0, 0), RED);
+        assert!(world
+            .create_indexed_sprite(&[0, 1], 1, 1, palette)
+            .is_none());
+        assert!(world.create_indexed_sprite(&[0], 1, 1, 42).is_none());
+    }
+
+    #[test]
+    fn test_palette_cycling() {
+        let mut world = World::new(4, 4);
+        let palette = world.create_palette(&rgb_palette());
+        assert!(world.add_palette_cycle(palette, 0, 2, 2.0));
+        assert!(!world.add_palette_cycle(palette, 2, 2, 1.0));
+        let id = world
+            .create_indexed_sprite(&[0, 1, 2], 3, 1, palette)
+            .unwrap();
+
+        world.update(0.25);
+        world.render();
+        assert_eq!(display_pixel(&world, id, 0, 0), RED);
+        world.update(0.25);
+        world.render();
+        // 轮转一步: 每个索引显示前一个索引的颜色
+        assert_eq!(display_pixel(&world, id, 0, 0), BLUE);
+        assert_eq!(display_pixel(&world, id, 1, 0), RED);
+        assert_eq!(display_pixel(&world, id, 2, 0), GREEN);
+
+        world.clear_palette_cycles(palette);
+        world.render();
+        assert_eq!(display_pixel(&world, id, 0, 0), RED);
+    }
+
+    #[test]
+    fn test_indexed_transform_uses_nearest() {
+        let mut world = World::new(4, 4);
+        let palette = world.create_palette(&rgb_palette());
+        let id = world.create_indexed_sprite(&[0, 1], 2, 1, palette).unwrap();
+        world.apply_sprite_scale(id, 2.0, 2.0);
+        assert_eq!(world.sprites.display_widths[id as usize], 4);
+        assert_eq!(world.sprites.display_heights[id as usize], 2);
+        assert_eq!(display_pixel(&world, id, 1, 1), RED);
+        assert_eq!(display_pixel(&world, id, 2, 0), GREEN);
+
+        // 换色只重新查表，保留变换后的尺寸与布局
+        world.set_palette_color(palette, 1, 255, 255, 0, 255);
+        world.render();
+        assert_eq!(world.sprites.display_widths[id as usize], 4);
+        assert_eq!(world.indexed.display_indices[0], [0, 0, 1, 1, 0, 0, 1, 1]);
+        assert_eq!(display_pixel(&world, id, 3, 1), [255, 255, 0, 255]);
+
+        world.apply_sprite_rotation(id, std::f32::consts::FRAC_PI_2);
+        assert_eq!(world.sprites.display_widths[id as usize], 1);
+        assert_eq!(world.sprites.display_heights[id as usize], 2);
+        world.reset_sprite_transform(id);
+        assert_eq!(world.sprites.display_widths[id as usize], 2);
+        assert_eq!(display_pixel(&world, id, 0, 0), RED);
+    }
+
+    #[test]
+    fn test_replace_source_drops_indices() {
+        let mut world = World::new(4, 4);
+        let palette = world.create_palette(&rgb_palette());
+        let id = world.create_indexed_sprite(&[0], 1, 1, palette).unwrap();
+        world.apply_sprite_scale(id, 2.0, 2.0);
+        world.set_sprite_from_buffer(id, &crate::PixelBuffer::new(4, 4));
+        assert_eq!(world.get_sprite_palette(id), None);
+        assert_eq!(world.sprites.display_widths[id as usize], 8);
+
+        // 以场景为纹理时同样改为普通精灵图
+        let other = world.create_indexed_sprite(&[1], 1, 1, palette).unwrap();
+        world.add_to_scene(other);
+        let scene = world.create_scene(3, 3);
+        assert!(world.set_sprite_scene_source(other, scene));
+        world.render();
+        assert_eq!(world.get_sprite_palette(other), None);
+        assert_eq!(world.sprites.display_widths[other as usize], 3);
+    }
+
+    #[test]
+    fn test_remove_sprite_drops_indices() {
+        let mut world = World::new(4, 4);
+        let palette = world.create_palette(&rgb_palette());
+        let id = world.create_indexed_sprite(&[0], 1, 1, palette).unwrap();
+        let kept = world.create_indexed_sprite(&[1], 1, 1, palette).unwrap();
+        world.remove_sprite(id);
+        assert_eq!(world.indexed.sprite_ids, [kept]);
+
+        world.set_palette_color(palette, 1, 255, 255, 0, 255);
+        world.render();
+        assert_eq!(display_pixel(&world, kept, 0, 0), [255, 255, 0, 255]);
+    }
+}
@@ -0,0 +1,627 @@
+//! 2D 光照
+//!
+//! 开启光照的场景在 `render` 中逐像素为精灵图着色: 环境光加上场景中各点光源、
+//! 聚光灯的贡献，在线性光空间中与精灵图颜色相乘。HDR 场景中结果不截断，
+//! 由色调映射压缩高光；8 位场景截断到白色。光源按半径与衰减指数衰减，
+//! 聚光灯在内外锥角之间平滑过渡。
+//!
+//! 精灵图可附加法线贴图 (与原始纹理对齐，RGB 编码 [-1, 1]，绿色通道向上)，
+//! 漫反射按法线与指向光源 (位于 `height` 高度) 的方向计算，随精灵图的旋转与缩放变换。
+//! 未附加法线贴图时视为正对光源。标记为不受光照的精灵图 (UI 等) 保持原色。
+//! 背景、瓦片地图与粒子不参与光照。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::{SpriteStore, World};
+use crate::image::{decode_image, pixel_len, Image};
+use crate::math::Matrix3x3;
+
+/// 光源存储 - 各属性分离为独立数组
+pub struct LightStore {
+    /// X 坐标
+    pub(super) positions_x: Vec<f32>,
+    /// Y 坐标
+    pub(super) positions_y: Vec<f32>,
+    /// 距离精灵图平面的高度 (影响法线贴图的明暗)
+    pub(super) heights: Vec<f32>,
+    /// 颜色 (0~1，已乘以强度)
+    pub(super) colors: Vec<[f32; 3]>,
+    /// 影响半径 (像素)
+    pub(super) radii: Vec<f32>,
+    /// 衰减指数
+    pub(super) falloffs: Vec<f32>,
+    /// 聚光灯锥体 (方向, 内半角, 外半角)，None 表示点光源
+    pub(super) cones: Vec<Option<(f32, f32, f32)>>,
+    /// 是否启用
+    pub(super) enabled: Vec<bool>,
+    /// 是否活跃 (用于删除标记)
+    pub(super) active: Vec<bool>,
+}
+
+impl LightStore {
+    pub(super) fn new() -> Self {
+        Self {
+            positions_x: Vec::new(),
+            positions_y: Vec::new(),
+            heights: Vec::new(),
+            colors: Vec::new(),
+            radii: Vec::new(),
+            falloffs: Vec::new(),
+            cones: Vec::new(),
+            enabled: Vec::new(),
+            active: Vec::new(),
+        }
+    }
+
+    /// 添加白色光源，返回ID (索引)
+    fn add(&mut self, x: f32, y: f32, radius: f32, cone: Option<(f32, f32, f32)>) -> u32 {
+        let id = self.positions_x.len() as u32;
+        self.positions_x.push(x);
+        self.positions_y.push(y);
+        self.heights.push(DEFAULT_HEIGHT);
+        self.colors.push([1.0; 3]);
+        self.radii.push(radius);
+        self.falloffs.push(DEFAULT_FALLOFF);
+        self.cones.push(cone);
+        self.enabled.push(true);
+        self.active.push(true);
+        id
+    }
+
+    /// 检查光源是否存在且活跃
+    pub(super) fn is_active(&self, id: u32) -> bool {
+        let idx = id as usize;
+        idx < self.active.len() && self.active[idx]
+    }
+
+    fn light(&self, idx: usize) -> Light {
+        Light {
+            x: self.positions_x[idx],
+            y: self.positions_y[idx],
+            height: self.heights[idx],
+            color: self.colors[idx],
+            radius: self.radii[idx],
+            falloff: self.falloffs[idx],
+            cone: self.cones[idx],
+        }
+    }
+}
+
+/// 默认光源高度 (像素)
+const DEFAULT_HEIGHT: f32 = 50.0;
+/// 默认衰减指数
+const DEFAULT_FALLOFF: f32 = 2.0;
+
+/// 渲染时使用的光源参数快照
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Light {
+    x: f32,
+    y: f32,
+    height: f32,
+    color: [f32; 3],
+    radius: f32,
+    falloff: f32,
+    cone: Option<(f32, f32, f32)>,
+}
+
+impl Light {
+    /// 光源照亮的矩形是否与 [左, 上, 右, 下] 相交
+    fn reaches(&self, bounds: [f32; 4]) -> bool {
+        self.x + self.radius > bounds[0]
+            && self.x - self.radius < bounds[2]
+            && self.y + self.radius > bounds[1]
+            && self.y - self.radius < bounds[3]
+    }
+
+    /// 场景坐标 (x, y) 处、法线为 `normal` 时的光照强度 (0~1)
+    fn intensity(&self, x: f32, y: f32, normal: Option<[f32; 3]>) -> f32 {
+        let (dx, dy) = (x - self.x, y - self.y);
+        let dist = (dx * dx + dy * dy).sqrt();
+        if dist >= self.radius {
+            return 0.0;
+        }
+        let mut value = (1.0 - dist / self.radius).powf(self.falloff);
+        if let Some((direction, inner, outer)) = self.cone {
+            let delta = (dy.atan2(dx) - direction + std::f32::consts::PI)
+                .rem_euclid(std::f32::consts::TAU)
+                - std::f32::consts::PI;
+            value *= 1.0 - smoothstep(inner, outer, delta.abs());
+        }
+        if let Some(n) = normal {
+            let len = (dist * dist + self.height * self.height).sqrt().max(1e-6);
+            value *= ((-dx * n[0] - dy * n[1] + self.height * n[2]) / len).max(0.0);
+        }
+        value
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// 法线贴图采样器 (场景像素 → 原始纹理坐标 → 变换后的法线)
+struct NormalSampler<'a> {
+    map: &'a Image,
+    /// 显示空间偏移到原始纹理偏移
+    inverse: Matrix3x3,
+    /// 原始纹理空间法线到显示空间 (逆转置)
+    normal_matrix: Matrix3x3,
+    offset_x: f32,
+    offset_y: f32,
+    half_w: f32,
+    half_h: f32,
+    scale_x: f32,
+    scale_y: f32,
+}
+
+impl NormalSampler<'_> {
+    fn normal(&self, tx: u32, ty: u32) -> [f32; 3] {
+        let (sx, sy) = self
+            .inverse
+            .transform_point(tx as f32 + self.offset_x, ty as f32 + self.offset_y);
+        let mx = (((sx + self.half_w) * self.scale_x) as i32).clamp(0, self.map.width as i32 - 1);
+        let my = (((sy + self.half_h) * self.scale_y) as i32).clamp(0, self.map.height as i32 - 1);
+        let i = ((my as u32 * self.map.width + mx as u32) * 4) as usize;
+        let px = &self.map.data[i..i + 3];
+        let decode = |v: u8| v as f32 / 127.5 - 1.0;
+        // 绿色通道向上，场景 Y 轴向下
+        let (nx, ny) = self
+            .normal_matrix
+            .transform_point(decode(px[0]), -decode(px[1]));
+        let nz = decode(px[2]);
+        let len = (nx * nx + ny * ny + nz * nz).sqrt().max(1e-6);
+        [nx / len, ny / len, nz / len]
+    }
+}
+
+/// 单个精灵图的逐像素光照计算
+pub(super) struct LightSampler<'a> {
+    ambient: [f32; 3],
+    lights: Vec<&'a Light>,
+    normal: Option<NormalSampler<'a>>,
+    center_x: f32,
+    center_y: f32,
+}
+
+impl LightSampler<'_> {
+    /// 对场景像素处的线性光颜色应用光照 (结果可超过 1)
+    pub(super) fn shade(&self, rgb: [f32; 3], tx: u32, ty: u32) -> [f32; 3] {
+        let (x, y) = (tx as f32 - self.center_x, ty as f32 - self.center_y);
+        let normal = self.normal.as_ref().map(|n| n.normal(tx, ty));
+        let mut light = self.ambient;
+        for l in &self.lights {
+            let value = l.intensity(x, y, normal);
+            if value > 0.0 {
+                for (sum, c) in light.iter_mut().zip(l.color) {
+                    *sum += c * value;
+                }
+            }
+        }
+        std::array::from_fn(|c| rgb[c] * light[c])
+    }
+}
+
+impl World {
+    /// 场景开启光照时收集其启用的光源
+    pub(super) fn scene_lights(&self, scene_idx: usize) -> Option<Vec<Light>> {
+        if !self.scenes.lighting[scene_idx] {
+            return None;
+        }
+        let lights = self.scenes.light_ids[scene_idx]
+            .iter()
+            .map(|&id| id as usize)
+            .filter(|&idx| self.lights.active[idx] && self.lights.enabled[idx])
+            .map(|idx| self.lights.light(idx))
+            .collect();
+        Some(lights)
+    }
+
+    /// 使包含该光源的场景缓存失效
+    fn invalidate_light_scenes(&mut self, light_id: u32) {
+        for (light_ids, valid) in self
+            .scenes
+            .light_ids
+            .iter()
+            .zip(self.scenes.cache_valid.iter_mut())
+        {
+            if light_ids.contains(&light_id) {
+                *valid = false;
+            }
+        }
+    }
+
+    /// 使包含该精灵图的场景缓存失效
+    fn invalidate_sprite_scenes(&mut self, id: u32) {
+        for (sprite_ids, valid) in self
+            .scenes
+            .sprite_ids
+            .iter()
+            .zip(self.scenes.cache_valid.iter_mut())
+        {
+            if sprite_ids.contains(&id) {
+                *valid = false;
+            }
+        }
+    }
+}
+
+impl SpriteStore {
+    /// 为精灵图创建光照采样器 (`bounds` 为精灵图在场景坐标中的 [左, 上, 右, 下])
+    pub(super) fn light_sampler<'a>(
+        &'a self,
+        idx: usize,
+        lights: &'a [Light],
+        ambient: [f32; 3],
+        bounds: [f32; 4],
+        center_x: f32,
+        center_y: f32,
+    ) -> Option<LightSampler<'a>> {
+        if self.unlit[idx] {
+            return None;
+        }
+        let normal = self.normal_maps[idx].as_ref().map(|map| {
+            let (angle, sx, sy) = self.transforms[idx].parts();
+            let (width, height) = (self.original_widths[idx], self.original_heights[idx]);
+            let transform = Matrix3x3::rotation(-angle).multiply(&Matrix3x3::scale(sx, sy));
+            let normal_matrix =
+                Matrix3x3::rotation(-angle).multiply(&Matrix3x3::scale(1.0 / sx, 1.0 / sy));
+            NormalSampler {
+                map,
+                inverse: transform.inverse().unwrap_or_else(Matrix3x3::identity),
+                normal_matrix,
+                offset_x: -center_x - self.positions_x[idx],
+                offset_y: -center_y - self.positions_y[idx],
+                half_w: width as f32 / 2.0,
+                half_h: height as f32 / 2.0,
+                scale_x: map.width as f32 / width.max(1) as f32,
+                scale_y: map.height as f32 / height.max(1) as f32,
+            }
+        });
+        Some(LightSampler {
+            ambient,
+            lights: lights.iter().filter(|l| l.reaches(bounds)).collect(),
+            normal,
+            center_x,
+            center_y,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 2D 光照 ==========
+
+    /// 设置场景是否开启光照 (开启后精灵图颜色乘以环境光与光源的贡献)
+    pub fn set_scene_lighting(&mut self, scene_id: u32, enabled: bool) {
+        if self.scenes.is_active(scene_id) {
+            self.scenes.lighting[scene_id as usize] = enabled;
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 获取场景是否开启光照
+    pub fn is_scene_lighting(&self, scene_id: u32) -> bool {
+        self.scenes.is_active(scene_id) && self.scenes.lighting[scene_id as usize]
+    }
+
+    /// 设置场景环境光 (默认黑色，即只有光源照亮的区域可见)
+    pub fn set_scene_ambient_light(&mut self, scene_id: u32, r: u8, g: u8, b: u8, intensity: f32) {
+        if !self.scenes.is_active(scene_id) || !intensity.is_finite() {
+            return;
+        }
+        let idx = scene_id as usize;
+        self.scenes.ambients[idx] = scale_color(r, g, b, intensity);
+        self.scenes.cache_valid[idx] = false;
+    }
+
+    /// 创建点光源 (白色，强度 1)，需加入场景后才会生效
+    pub fn create_point_light(&mut self, x: f32, y: f32, radius: f32) -> u32 {
+        self.lights.add(x, y, radius.max(0.0), None)
+    }
+
+    /// 创建聚光灯，`direction` 为朝向 (弧度，0 指向 +X，顺时针)，`angle` 为锥体半角
+    ///
+    /// 锥体边缘从 `angle` 的 3/4 处开始平滑衰减，可用 `set_light_cone` 调整。
+    pub fn create_spot_light(
+        &mut self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        direction: f32,
+        angle: f32,
+    ) -> u32 {
+        let angle = angle.abs();
+        self.lights.add(
+            x,
+            y,
+            radius.max(0.0),
+            Some((direction, angle * 0.75, angle)),
+        )
+    }
+
+    /// 移除光源 (同时从所有场景移除)
+    pub fn remove_light(&mut self, light_id: u32) {
+        if !self.lights.is_active(light_id) {
+            return;
+        }
+        self.lights.active[light_id as usize] = false;
+        self.invalidate_light_scenes(light_id);
+        for light_ids in self.scenes.light_ids.iter_mut() {
+            light_ids.retain(|&id| id != light_id);
+        }
+    }
+
+    /// 添加光源到指定场景
+    pub fn add_light_to_scene(&mut self, light_id: u32, scene_id: u32) {
+        let scene_idx = scene_id as usize;
+        if self.scenes.is_active(scene_id)
+            && self.lights.is_active(light_id)
+            && !self.scenes.light_ids[scene_idx].contains(&light_id)
+        {
+            self.scenes.light_ids[scene_idx].push(light_id);
+            self.scenes.cache_valid[scene_idx] = false;
+        }
+    }
+
+    /// 从指定场景移除光源
+    pub fn remove_light_from_scene(&mut self, light_id: u32, scene_id: u32) {
+        if let Some(light_ids) = self.scenes.light_ids.get_mut(scene_id as usize) {
+            light_ids.retain(|&id| id != light_id);
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 设置光源位置 (非有限值被忽略)
+    pub fn set_light_position(&mut self, light_id: u32, x: f32, y: f32) {
+        if self.lights.is_active(light_id) && x.is_finite() && y.is_finite() {
+            self.lights.positions_x[light_id as usize] = x;
+            self.lights.positions_y[light_id as usize] = y;
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 设置光源颜色与强度
+    pub fn set_light_color(&mut self, light_id: u32, r: u8, g: u8, b: u8, intensity: f32) {
+        if self.lights.is_active(light_id) && intensity.is_finite() {
+            self.lights.colors[light_id as usize] = scale_color(r, g, b, intensity);
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 设置光源影响半径
+    pub fn set_light_radius(&mut self, light_id: u32, radius: f32) {
+        if self.lights.is_active(light_id) && radius.is_finite() {
+            self.lights.radii[light_id as usize] = radius.max(0.0);
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 设置衰减指数 (1 为线性，越大边缘越暗)
+    pub fn set_light_falloff(&mut self, light_id: u32, exponent: f32) {
+        if self.lights.is_active(light_id) && exponent.is_finite() {
+            self.lights.falloffs[light_id as usize] = exponent.max(0.0);
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 设置光源高度 (越低法线贴图的明暗越强烈)
+    pub fn set_light_height(&mut self, light_id: u32, height: f32) {
+        if self.lights.is_active(light_id) && height.is_finite() {
+            self.lights.heights[light_id as usize] = height.max(0.0);
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 将光源设为聚光灯: 内半角以内全亮，内外半角之间平滑衰减
+    pub fn set_light_cone(&mut self, light_id: u32, direction: f32, inner: f32, outer: f32) {
+        let finite = direction.is_finite() && inner.is_finite() && outer.is_finite();
+        if self.lights.is_active(light_id) && finite {
+            let (inner, outer) = (inner.abs(), outer.abs());
+            self.lights.cones[light_id as usize] = Some((direction, inner.min(outer), outer));
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 将光源恢复为点光源
+    pub fn clear_light_cone(&mut self, light_id: u32) {
+        if self.lights.is_active(light_id) {
+            self.lights.cones[light_id as usize] = None;
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 设置光源是否启用
+    pub fn set_light_enabled(&mut self, light_id: u32, enabled: bool) {
+        if self.lights.is_active(light_id) {
+            self.lights.enabled[light_id as usize] = enabled;
+            self.invalidate_light_scenes(light_id);
+        }
+    }
+
+    /// 为精灵图附加法线贴图 (RGBA，与原始纹理对齐，尺寸不同时按比例采样)
+    ///
+    /// 数据长度与尺寸不符时返回 false。
+    pub fn set_sprite_normal_map(&mut self, id: u32, data: &[u8], width: u32, height: u32) -> bool {
+        if !self.sprites.is_active(id) || pixel_len(width, height) != Some(data.len()) {
+            return false;
+        }
+        self.sprites.normal_maps[id as usize] = Some(Image {
+            width,
+            height,
+            data: data.to_vec(),
+        });
+        self.invalidate_sprite_scenes(id);
+        true
+    }
+
+    /// 从 PNG/BMP/TGA/QOI 文件为精灵图附加法线贴图，解码失败时返回 false
+    pub fn set_sprite_normal_map_image(&mut self, id: u32, bytes: &[u8]) -> bool {
+        if !self.sprites.is_active(id) {
+            return false;
+        }
+        let Some(image) = decode_image(bytes) else {
+            return false;
+        };
+        self.sprites.normal_maps[id as usize] = Some(image);
+        self.invalidate_sprite_scenes(id);
+        true
+    }
+
+    /// 移除精灵图的法线贴图
+    pub fn clear_sprite_normal_map(&mut self, id: u32) {
+        if self.sprites.is_active(id) {
+            self.sprites.normal_maps[id as usize] = None;
+            self.invalidate_sprite_scenes(id);
+        }
+    }
+
+    /// 设置精灵图是否不受光照 (UI 等保持原色)
+    pub fn set_sprite_unlit(&mut self, id: u32, unlit: bool) {
+        if self.sprites.is_active(id) {
+            self.sprites.unlit[id as usize] = unlit;
+            self.invalidate_sprite_scenes(id);
+        }
+    }
+
+    /// 获取精灵图是否不受光照
+    pub fn is_sprite_unlit(&self, id: u32) -> bool {
+        self.sprites.is_active(id) && self.sprites.unlit[id as usize]
+    }
+}
+
+fn scale_color(r: u8, g: u8, b: u8, intensity: f32) -> [f32; 3] {
+    let k = intensity.max(0.0) / 255.0;
+    [r as f32 * k, g as f32 * k, b as f32 * k]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::linear::{to_linear, to_srgb};
+    use crate::core::scene_pixel;
+
+    /// 亮度 200 的精灵图在 0.5 环境光下的结果
+    fn dim() -> u8 {
+        to_srgb(to_linear(200) * 0.5)
+    }
+
+    fn lit_world() -> (World, u32) {
+        let mut world = World::new(20, 20);
+        let id = world.create_rect_sprite(20, 20, 200, 200, 200, 255);
+        world.add_to_scene(id);
+        world.set_scene_lighting(0, true);
+        world.set_scene_ambient_light(0, 255, 255, 255, 0.5);
+        (world, id)
+    }
+
+    #[test]
+    fn test_ambient_and_unlit() {
+        let (mut world, id) = lit_world();
+        world.render();
+        assert_eq!(scene_pixel(&world, 5, 5), [dim(), dim(), dim(), 255]);
+
+        world.set_sprite_unlit(id, true);
+        assert!(world.is_sprite_unlit(id));
+        world.render();
+        assert_eq!(scene_pixel(&world, 5, 5), [200, 200, 200, 255]);
+
+        world.set_sprite_unlit(id, false);
+        world.set_scene_lighting(0, false);
+        world.render();
+        assert_eq!(scene_pixel(&world, 5, 5), [200, 200, 200, 255]);
+    }
+
+    #[test]
+    fn test_point_and_spot_lights() {
+        let (mut world, _) = lit_world();
+        let light = world.create_point_light(-5.0, 0.0, 8.0);
+        world.set_light_color(light, 255, 0, 0, 1.0);
+        world.add_light_to_scene(light, 0);
+        world.render();
+        // 光源中心附近红色增强，半径之外只有环境光
+        assert!(scene_pixel(&world, 5, 10)[0] > 180);
+        assert_eq!(scene_pixel(&world, 5, 10)[1], dim());
+        assert_eq!(scene_pixel(&world, 18, 10), [dim(), dim(), dim(), 255]);
+        world.remove_light(light);
+
+        // 朝 +X 的聚光灯只照亮右侧
+        let spot = world.create_spot_light(0.0, 0.0, 20.0, 0.0, 0.5);
+        world.add_light_to_scene(spot, 0);
+        world.render();
+        assert!(scene_pixel(&world, 15, 10)[1] > dim());
+        assert_eq!(scene_pixel(&world, 4, 10), [dim(), dim(), dim(), 255]);
+    }
+
+    #[test]
+    fn test_bright_light_in_hdr() {
+        let mut world = World::new(4, 4);
+        let id = world.create_rect_sprite(4, 4, 255, 255, 255, 255);
+        world.add_to_scene(id);
+        world.set_scene_lighting(0, true);
+        world.set_scene_ambient_light(0, 255, 255, 255, 3.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1)[0], 255);
+
+        // HDR 中光照结果为线性 3.0，Reinhard 映射为 0.75 而不是截断后的 0.5
+        world.set_scene_hdr(0, true);
+        world.set_scene_tonemap(0, 1);
+        world.render();
+        assert!((scene_pixel(&world, 1, 1)[0] as i32 - 225).abs() <= 1);
+    }
+
+    #[test]
+    fn test_setters_invalidate_cached_scene() {
+        let mut world = World::new(20, 20);
+        let lit = world.create_scene(20, 20);
+        let id = world.create_rect_sprite(20, 20, 200, 200, 200, 255);
+        world.add_sprite_to_scene(id, lit);
+        world.set_scene_lighting(lit, true);
+        let light = world.create_point_light(10.0, 10.0, 30.0);
+        world.add_light_to_scene(light, lit);
+        let view = world.create_scene_sprite(lit).unwrap();
+        world.add_to_scene(view);
+        world.set_scene_cached(lit, true);
+        world.render();
+        let bright = scene_pixel(&world, 10, 10);
+        assert!(bright[0] > 100);
+
+        // 非有限的位置被忽略
+        world.set_light_position(light, f32::NAN, 0.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 10, 10), bright);
+
+        world.set_light_position(light, 500.0, 500.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 10, 10), [0, 0, 0, 255]);
+
+        world.set_light_position(light, 10.0, 10.0);
+        world.set_sprite_unlit(id, true);
+        world.render();
+        assert_eq!(scene_pixel(&world, 10, 10), [200, 200, 200, 255]);
+    }
+
+    #[test]
+    fn test_normal_map_facing() {
+        let (mut world, id) = lit_world();
+        world.set_scene_ambient_light(0, 0, 0, 0, 0.0);
+        // 法线整体朝右
+        let normals = [255, 128, 128, 255].repeat(400);
+        assert!(world.set_sprite_normal_map(id, &normals, 20, 20));
+        assert!(!world.set_sprite_normal_map(id, &normals, 10, 10));
+        let light = world.create_point_light(5.0, 0.0, 30.0);
+        world.set_light_height(light, 5.0);
+        world.add_light_to_scene(light, 0);
+        world.render();
+        // 光源在右侧时朝右的一面被照亮
+        assert!(scene_pixel(&world, 10, 10)[0] > 40);
+
+        // 水平翻转后法线朝左，背向光源
+        world.apply_sprite_scale(id, -1.0, 1.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 10, 10)[0], 0);
+    }
+}
@@ -0,0 +1,112 @@
+//! 线性光颜色空间
+//!
+//! sRGB 字节是经过伽马编码的，直接对其插值或混合会使渐变与半透明叠加偏暗。
+//! 线性光模式下颜色先解码为线性值，插值与混合完成后再编码回 sRGB。
+//! 解码使用 256 项查找表，编码使用 4096 项查找表 (12 位精度)，避免逐像素计算幂函数。
+
+use std::sync::OnceLock;
+
+/// 编码查找表级数
+const ENCODE_SIZE: usize = 4096;
+
+/// sRGB 传递函数: 编码值 (0..1) → 线性值
+fn decode(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// sRGB 传递函数: 线性值 (0..1) → 编码值
+fn encode(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn decode_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| std::array::from_fn(|i| decode(i as f32 / 255.0)))
+}
+
+fn encode_table() -> &'static [u8; ENCODE_SIZE] {
+    static TABLE: OnceLock<[u8; ENCODE_SIZE]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|i| (encode(i as f32 / (ENCODE_SIZE - 1) as f32) * 255.0 + 0.5) as u8)
+    })
+}
+
+/// sRGB 字节 → 线性值 (0..1)
+pub(crate) fn to_linear(v: u8) -> f32 {
+    decode_table()[v as usize]
+}
+
+/// 线性值 → sRGB 字节 (超出 0..1 时截断)
+pub(crate) fn to_srgb(v: f32) -> u8 {
+    let i = (v.clamp(0.0, 1.0) * (ENCODE_SIZE - 1) as f32 + 0.5) as usize;
+    encode_table()[i]
+}
+
+/// 在线性光空间中将颜色混合到目标像素 (与渲染循环的 sRGB 混合语义相同)
+pub(crate) fn blend_linear(dst: &mut [u8], src: [u8; 4]) {
+    let src_a = src[3] as u32;
+    let (a, inv) = (src_a as f32 / 255.0, (255 - src_a) as f32 / 255.0);
+    for i in 0..3 {
+        dst[i] = to_srgb(to_linear(src[i]) * a + to_linear(dst[i]) * inv);
+    }
+    dst[3] = ((src_a * 255 + dst[3] as u32 * (255 - src_a)) / 255) as u8;
+}
+
+/// 在线性光空间中插值两种颜色 (预乘 Alpha)，返回非预乘的 sRGB 结果
+pub(crate) fn interpolate_linear(c0: [u8; 4], c1: [u8; 4], f: f32) -> [u8; 4] {
+    let (a0, a1) = (c0[3] as f32 / 255.0, c1[3] as f32 / 255.0);
+    let alpha = a0 + (a1 - a0) * f;
+    if alpha <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+    let mut out = [0u8; 4];
+    for i in 0..3 {
+        let (v0, v1) = (to_linear(c0[i]) * a0, to_linear(c1[i]) * a1);
+        out[i] = to_srgb((v0 + (v1 - v0) * f) / alpha);
+    }
+    out[3] = (alpha * 255.0 + 0.5) as u8;
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        for v in 0..=255u8 {
+            assert_eq!(to_srgb(to_linear(v)), v);
+        }
+        assert_eq!(to_srgb(2.0), 255);
+        assert_eq!(to_srgb(-1.0), 0);
+    }
+
+    #[test]
+    fn test_blend_is_brighter_than_srgb() {
+        // 白色以 50% 覆盖黑色: 线性光结果约为 188 而不是 127
+        let mut dst = [0, 0, 0, 255];
+        blend_linear(&mut dst, [255, 255, 255, 128]);
+        assert!((dst[0] as i32 - 188).abs() <= 1);
+        assert_eq!(dst[3], 255);
+    }
+
+    #[test]
+    fn test_interpolate_linear() {
+        // 红到绿的中点在线性光中两个通道都约为 188
+        let mid = interpolate_linear([255, 0, 0, 255], [0, 255, 0, 255], 0.5);
+        assert!((mid[0] as i32 - 188).abs() <= 1 && (mid[1] as i32 - 188).abs() <= 1);
+        assert_eq!(mid[3], 255);
+        assert_eq!(
+            interpolate_linear([9, 9, 9, 0], [9, 9, 9, 0], 0.5),
+            [0, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! 裁剪与遮罩
+//!
+//! 裁剪矩形直接收窄 `render` 中精灵图的绘制范围：精灵图可设置自己的裁剪矩形，
+//! 也可加入裁剪组 (多个精灵图共享、可整体移动的矩形，适合滚动列表)，两者同时存在时取交集。
+//! 矩形坐标与精灵图位置使用相同的坐标系 (场景中心为原点)。
+//!
+//! 遮罩以另一个精灵图 (按其位置与当前变换) 的 Alpha 或亮度逐像素控制被遮罩精灵图的不透明度，
+//! 可反相。遮罩精灵图本身是否绘制取决于它是否加入场景。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::{SpriteStore, World};
+
+/// 遮罩模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaskMode {
+    /// 遮罩 Alpha 越高越可见
+    #[default]
+    Alpha,
+    /// 遮罩亮度 (乘以 Alpha) 越高越可见
+    Luminance,
+    /// 反相 Alpha
+    InvertedAlpha,
+    /// 反相亮度
+    InvertedLuminance,
+}
+
+impl MaskMode {
+    /// 从 u8 值创建遮罩模式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => MaskMode::Alpha,
+            1 => MaskMode::Luminance,
+            2 => MaskMode::InvertedAlpha,
+            3 => MaskMode::InvertedLuminance,
+            _ => MaskMode::Alpha,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            MaskMode::Alpha => 0,
+            MaskMode::Luminance => 1,
+            MaskMode::InvertedAlpha => 2,
+            MaskMode::InvertedLuminance => 3,
+        }
+    }
+}
+
+/// 裁剪组存储 - 各属性分离为独立数组
+pub struct ClipGroupStore {
+    /// 裁剪矩形 [左, 上, 右, 下]
+    pub(super) rects: Vec<[f32; 4]>,
+}
+
+impl ClipGroupStore {
+    pub(super) fn new() -> Self {
+        Self { rects: Vec::new() }
+    }
+
+    fn exists(&self, id: u32) -> bool {
+        (id as usize) < self.rects.len()
+    }
+}
+
+/// 矩形参数转换为 [左, 上, 右, 下] (宽高为负或非有限值时返回 None)
+fn rect_edges(x: f32, y: f32, width: f32, height: f32) -> Option<[f32; 4]> {
+    let edges = [x, y, x + width, y + height];
+    (width >= 0.0 && height >= 0.0 && edges.iter().all(|v| v.is_finite())).then_some(edges)
+}
+
+/// 遮罩采样器 (渲染单个精灵图期间使用)
+pub(super) struct MaskSampler<'a> {
+    data: &'a [u8],
+    width: i32,
+    height: i32,
+    /// 场景像素坐标到遮罩像素坐标的偏移
+    offset_x: f32,
+    offset_y: f32,
+    mode: MaskMode,
+}
+
+impl MaskSampler<'_> {
+    /// 场景像素处的覆盖率 (0~255，最近邻采样)
+    pub(super) fn coverage(&self, tx: u32, ty: u32) -> u32 {
+        let mx = (tx as f32 + self.offset_x).round() as i32;
+        let my = (ty as f32 + self.offset_y).round() as i32;
+        let value = if mx >= 0 && mx < self.width && my >= 0 && my < self.height {
+            let i = ((my * self.width + mx) * 4) as usize;
+            let px = &self.data[i..i + 4];
+            match self.mode {
+                MaskMode::Alpha | MaskMode::InvertedAlpha => px[3] as u32,
+                MaskMode::Luminance | MaskMode::InvertedLuminance => {
+                    // Rec. 601 亮度，乘以 Alpha
+                    let luma =
+                        (px[0] as u32 * 299 + px[1] as u32 * 587 + px[2] as u32 * 114) / 1000;
+                    luma * px[3] as u32 / 255
+                }
+            }
+        } else {
+            0
+        };
+        match self.mode {
+            MaskMode::InvertedAlpha | MaskMode::InvertedLuminance => 255 - value,
+            _ => value,
+        }
+    }
+}
+
+impl SpriteStore {
+    /// 精灵图的遮罩采样器 (未设置遮罩或遮罩精灵图已移除时返回 None)
+    pub(super) fn mask_sampler(
+        &self,
+        idx: usize,
+        center_x: f32,
+        center_y: f32,
+    ) -> Option<MaskSampler<'_>> {
+        let (mask_id, mode) = self.masks[idx]?;
+        if !self.is_active(mask_id) {
+            return None;
+        }
+        let m = mask_id as usize;
+        let (width, height) = (self.display_widths[m], self.display_heights[m]);
+        Some(MaskSampler {
+            data: &self.display_data[m],
+            width: width as i32,
+            height: height as i32,
+            offset_x: -center_x - self.positions_x[m] + width as f32 / 2.0,
+            offset_y: -center_y - self.positions_y[m] + height as f32 / 2.0,
+            mode,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 裁剪 ==========
+
+    /// 设置精灵图的裁剪矩形 (`x`, `y` 为左上角，坐标系与精灵图位置相同)
+    pub fn set_sprite_clip_rect(&mut self, id: u32, x: f32, y: f32, width: f32, height: f32) {
+        if !self.sprites.is_active(id) {
+            return;
+        }
+        if let Some(edges) = rect_edges(x, y, width, height) {
+            self.sprites.clip_rects[id as usize] = Some(edges);
+        }
+    }
+
+    /// 清除精灵图的裁剪矩形
+    pub fn clear_sprite_clip_rect(&mut self, id: u32) {
+        if self.sprites.is_active(id) {
+            self.sprites.clip_rects[id as usize] = None;
+        }
+    }
+
+    /// 创建裁剪组，返回ID
+    pub fn create_clip_group(&mut self, x: f32, y: f32, width: f32, height: f32) -> Option<u32> {
+        let edges = rect_edges(x, y, width, height)?;
+        self.clip_groups.rects.push(edges);
+        Some(self.clip_groups.rects.len() as u32 - 1)
+    }
+
+    /// 设置裁剪组矩形 (组内所有精灵图随之更新)
+    pub fn set_clip_group_rect(&mut self, group_id: u32, x: f32, y: f32, width: f32, height: f32) {
+        if !self.clip_groups.exists(group_id) {
+            return;
+        }
+        if let Some(edges) = rect_edges(x, y, width, height) {
+            self.clip_groups.rects[group_id as usize] = edges;
+        }
+    }
+
+    /// 将精灵图加入裁剪组 (一个精灵图只属于一个组)
+    pub fn add_sprite_to_clip_group(&mut self, sprite_id: u32, group_id: u32) {
+        if self.sprites.is_active(sprite_id) && self.clip_groups.exists(group_id) {
+            self.sprites.clip_groups[sprite_id as usize] = Some(group_id);
+        }
+    }
+
+    /// 将精灵图移出裁剪组
+    pub fn remove_sprite_from_clip_group(&mut self, sprite_id: u32) {
+        if self.sprites.is_active(sprite_id) {
+            self.sprites.clip_groups[sprite_id as usize] = None;
+        }
+    }
+
+    // ========== 遮罩 ==========
+
+    /// 设置精灵图的遮罩
+    ///
+    /// `mode`: 0 = Alpha, 1 = 亮度, 2 = 反相 Alpha, 3 = 反相亮度。
+    /// 遮罩不能是精灵图自身。
+    pub fn set_sprite_mask(&mut self, id: u32, mask_id: u32, mode: u8) {
+        if self.sprites.is_active(id) && self.sprites.is_active(mask_id) && id != mask_id {
+            self.sprites.masks[id as usize] = Some((mask_id, MaskMode::from_u8(mode)));
+        }
+    }
+
+    /// 清除精灵图的遮罩
+    pub fn clear_sprite_mask(&mut self, id: u32) {
+        if self.sprites.is_active(id) {
+            self.sprites.masks[id as usize] = None;
+        }
+    }
+
+    /// 获取遮罩 [遮罩精灵图ID, 模式]
+    pub fn get_sprite_mask(&self, id: u32) -> Option<Vec<u32>> {
+        if !self.sprites.is_active(id) {
+            return None;
+        }
+        let (mask_id, mode) = self.sprites.masks[id as usize]?;
+        Some(vec![mask_id, mode.to_u8() as u32])
+    }
+}
+
+impl World {
+    /// 精灵图的有效裁剪范围 (场景像素 [左, 上, 右, 下)，未裁剪时为整个场景)
+    pub(super) fn sprite_clip_bounds(&self, idx: usize, width: u32, height: u32) -> [u32; 4] {
+        let mut bounds = [0.0, 0.0, width as f32, height as f32];
+        let group = self.sprites.clip_groups[idx].map(|g| self.clip_groups.rects[g as usize]);
+        for [left, top, right, bottom] in self.sprites.clip_rects[idx].into_iter().chain(group) {
+            let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+            bounds[0] = bounds[0].max(left + cx);
+            bounds[1] = bounds[1].max(top + cy);
+            bounds[2] = bounds[2].min(right + cx);
+            bounds[3] = bounds[3].min(bottom + cy);
+        }
+        // 像素中心落在矩形内的像素参与绘制
+        bounds.map(|v| v.round().max(0.0) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    #[test]
+    fn test_clip_rect_and_group() {
+        let mut world = World::new(16, 16);
+        world.set_background_color(0, 0, 0, 255);
+        let id = world.create_rect_sprite(16, 16, 255, 0, 0, 255);
+        world.add_to_scene(id);
+        world.set_sprite_clip_rect(id, -4.0, -4.0, 8.0, 8.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 4, 4), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 11, 11), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 3, 8), [0, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 12, 8), [0, 0, 0, 255]);
+
+        // 裁剪组与自身矩形取交集，移动组矩形即可滚动
+        let group = world.create_clip_group(0.0, -8.0, 8.0, 16.0).unwrap();
+        world.add_sprite_to_clip_group(id, group);
+        world.render();
+        assert_eq!(scene_pixel(&world, 7, 8), [0, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 8, 8), [255, 0, 0, 255]);
+        world.clear_sprite_clip_rect(id);
+        world.set_clip_group_rect(group, -8.0, -8.0, 4.0, 16.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 3, 15), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 4, 15), [0, 0, 0, 255]);
+
+        world.remove_sprite_from_clip_group(id);
+        world.render();
+        assert_eq!(scene_pixel(&world, 15, 15), [255, 0, 0, 255]);
+        assert!(world.create_clip_group(0.0, 0.0, -1.0, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_alpha_and_luminance_masks() {
+        let mut world = World::new(8, 8);
+        world.set_background_color(0, 0, 0, 255);
+        let id = world.create_rect_sprite(8, 8, 255, 255, 255, 255);
+        world.add_to_scene(id);
+        // 遮罩: 左半部分不透明白色，右半部分半透明 / 灰色
+        let mut data = Vec::new();
+        for _ in 0..8 {
+            for x in 0..8 {
+                data.extend_from_slice(if x < 4 {
+                    &[255, 255, 255, 255]
+                } else {
+                    &[0, 0, 0, 128]
+                });
+            }
+        }
+        let mask = world.create_sprite(&data, 8, 8);
+
+        world.set_sprite_mask(id, mask, 0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 255, 255, 255]);
+        assert_eq!(scene_pixel(&world, 6, 1)[0], 128);
+
+        // 亮度: 右半部分为黑色，完全不可见
+        world.set_sprite_mask(id, mask, 1);
+        world.render();
+        assert_eq!(scene_pixel(&world, 6, 1), [0, 0, 0, 255]);
+
+        // 反相亮度
+        world.set_sprite_mask(id, mask, 3);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [0, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 6, 1), [255, 255, 255, 255]);
+        assert_eq!(world.get_sprite_mask(id), Some(vec![mask, 3]));
+
+        // 遮罩移开后，反相遮罩外的区域完全可见
+        world.set_sprite_position(mask, 100.0, 0.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 255, 255, 255]);
+        world.clear_sprite_mask(id);
+        assert_eq!(world.get_sprite_mask(id), None);
+    }
+}
@@ -2,8 +2,34 @@
 //!
 //! 提供纯数据导向的 ECS 架构，使用数组存储精灵图和场景数据。
 
+mod animation;
+mod background;
+mod export;
+mod filters;
+mod hdr;
+mod import;
+mod indexed;
+mod lighting;
+mod linear;
+mod masking;
+mod nineslice;
+mod output;
+mod particles;
+mod postprocess;
+mod render_target;
 mod sampling;
+mod svg;
+mod text;
+mod tiled;
+mod tilemap;
 mod world;
 
 pub use sampling::SamplingMethod;
 pub use world::World;
+
+/// 测试辅助: 读取默认场景 (x, y) 处的 RGBA 像素
+#[cfg(test)]
+fn scene_pixel(world: &World, x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * world.scenes.widths[0] + x) * 4) as usize;
+    world.scenes.data[0][i..i + 4].try_into().unwrap()
+}
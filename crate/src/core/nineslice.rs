@@ -0,0 +1,409 @@
+//! 九宫格精灵
+//!
+//! 源图像按四条边距切分为 3×3 区域：四角保持原尺寸，上下边沿水平方向、
+//! 左右边沿垂直方向拉伸或平铺，中心区域在两个方向上填充。
+//! 设置尺寸或缩放九宫格精灵时按目标尺寸重新拼接，而不是整体拉伸，
+//! 因此面板缩放后边角依然清晰。目标尺寸小于两侧边距之和时，四角等比缩小。
+
+use wasm_bindgen::prelude::*;
+
+use super::sampling::sample_bilinear;
+use super::world::{SpriteTransform, World};
+use crate::image::{pixel_len, Image};
+use crate::math::Matrix3x3;
+
+/// 边与中心区域的填充方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SliceMode {
+    /// 拉伸
+    #[default]
+    Stretch,
+    /// 以原始尺寸平铺 (从区域起点开始，末端裁切)
+    Tile,
+}
+
+impl SliceMode {
+    /// 从 u8 值创建填充方式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => SliceMode::Stretch,
+            1 => SliceMode::Tile,
+            _ => SliceMode::Stretch,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            SliceMode::Stretch => 0,
+            SliceMode::Tile => 1,
+        }
+    }
+}
+
+/// 九宫格精灵存储 - 各属性分离为独立数组
+pub struct NineSliceStore {
+    /// 对应的精灵图ID
+    pub(super) sprite_ids: Vec<u32>,
+    /// 源图像
+    pub(super) sources: Vec<Image>,
+    /// 边距 [左, 上, 右, 下] (源图像像素)
+    pub(super) insets: Vec<[u32; 4]>,
+    /// 边的填充方式
+    pub(super) edge_modes: Vec<SliceMode>,
+    /// 中心的填充方式
+    pub(super) center_modes: Vec<SliceMode>,
+}
+
+impl NineSliceStore {
+    pub(super) fn new() -> Self {
+        Self {
+            sprite_ids: Vec::new(),
+            sources: Vec::new(),
+            insets: Vec::new(),
+            edge_modes: Vec::new(),
+            center_modes: Vec::new(),
+        }
+    }
+
+    /// 查找精灵图对应的九宫格状态索引
+    pub(super) fn find(&self, sprite_id: u32) -> Option<usize> {
+        self.sprite_ids.iter().position(|&id| id == sprite_id)
+    }
+
+    /// 按目标尺寸拼接九宫格，返回 RGBA 数据
+    fn render(&self, i: usize, width: u32, height: u32) -> Vec<u8> {
+        let source = &self.sources[i];
+        let [left, top, right, bottom] = self.insets[i];
+        let columns = Segments::new(source.width, left, right, width);
+        let rows = Segments::new(source.height, top, bottom, height);
+        let (edge, center) = (self.edge_modes[i], self.center_modes[i]);
+
+        let mut data = vec![0u8; (width * height * 4) as usize];
+        for y in 0..height {
+            let row = rows.segment(y);
+            // 中间一行: 左右边沿垂直方向填充，中心按中心方式填充
+            for x in 0..width {
+                let column = columns.segment(x);
+                let x_mode = if row == 1 { center } else { edge };
+                let y_mode = if column == 1 { center } else { edge };
+                let (Some(sx), Some(sy)) = (columns.map(x, x_mode), rows.map(y, y_mode)) else {
+                    continue;
+                };
+                let src = ((sy * source.width + sx) * 4) as usize;
+                let dst = ((y * width + x) * 4) as usize;
+                data[dst..dst + 4].copy_from_slice(&source.data[src..src + 4]);
+            }
+        }
+        data
+    }
+}
+
+/// 单个轴向上的三段划分 (源与目标的分界点)
+struct Segments {
+    src: [u32; 4],
+    dst: [u32; 4],
+}
+
+impl Segments {
+    fn new(source: u32, start: u32, end: u32, target: u32) -> Self {
+        // 目标尺寸不足以容纳两侧边距时等比缩小
+        let border = start + end;
+        let (dst_start, dst_end) = if border > target {
+            let dst_start = (start as u64 * target as u64 / border as u64) as u32;
+            (dst_start, target - dst_start)
+        } else {
+            (start, end)
+        };
+        Self {
+            src: [0, start, source - end, source],
+            dst: [0, dst_start, target - dst_end, target],
+        }
+    }
+
+    /// 目标坐标所在的段 (0 = 起始边, 1 = 中间, 2 = 末尾边)
+    fn segment(&self, d: u32) -> usize {
+        if d < self.dst[1] {
+            0
+        } else if d < self.dst[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// 目标坐标映射到源坐标 (最近邻，源区域为空时返回 None)
+    fn map(&self, d: u32, mode: SliceMode) -> Option<u32> {
+        let s = self.segment(d);
+        let (s0, s1) = (self.src[s], self.src[s + 1]);
+        let (d0, d1) = (self.dst[s], self.dst[s + 1]);
+        let (src_len, dst_len) = (s1 - s0, d1 - d0);
+        if src_len == 0 {
+            return None;
+        }
+        let offset = d - d0;
+        // 边角段只在缩小时缩放，中间段按填充方式映射
+        let local = if s == 1 && mode == SliceMode::Tile {
+            offset % src_len
+        } else if src_len == dst_len {
+            offset
+        } else {
+            (((offset as f32 + 0.5) * src_len as f32 / dst_len as f32) as u32).min(src_len - 1)
+        };
+        Some(s0 + local)
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 九宫格精灵 ==========
+
+    /// 从 RGBA 数据创建九宫格精灵
+    ///
+    /// `left` / `top` / `right` / `bottom` 为源图像中四条边距 (像素)，
+    /// 初始尺寸与源图像相同。数据长度不符或边距超出图像时返回 None。
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_nine_slice_sprite(
+        &mut self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        left: u32,
+        top: u32,
+        right: u32,
+        bottom: u32,
+    ) -> Option<u32> {
+        if pixel_len(width, height) != Some(data.len())
+            || left.checked_add(right)? > width
+            || top.checked_add(bottom)? > height
+        {
+            return None;
+        }
+        let id = self.sprites.add(data.to_vec(), width, height);
+        self.nine_slices.sprite_ids.push(id);
+        self.nine_slices.sources.push(Image {
+            width,
+            height,
+            data: data.to_vec(),
+        });
+        self.nine_slices.insets.push([left, top, right, bottom]);
+        self.nine_slices.edge_modes.push(SliceMode::Stretch);
+        self.nine_slices.center_modes.push(SliceMode::Stretch);
+        Some(id)
+    }
+
+    /// 设置九宫格精灵的尺寸 (不含变换)，当前变换会在新尺寸上重新应用
+    pub fn set_nine_slice_size(&mut self, id: u32, width: u32, height: u32) {
+        let Some(i) = self.nine_slices.find(id) else {
+            return;
+        };
+        if !self.sprites.is_active(id) || pixel_len(width, height).is_none() {
+            return;
+        }
+        let data = self.nine_slices.render(i, width, height);
+        self.set_sprite_source(id, data, width, height);
+    }
+
+    /// 设置九宫格边距 [左, 上, 右, 下]，超出源图像时忽略
+    pub fn set_nine_slice_insets(&mut self, id: u32, left: u32, top: u32, right: u32, bottom: u32) {
+        let Some(i) = self.nine_slices.find(id) else {
+            return;
+        };
+        let source = &self.nine_slices.sources[i];
+        if left.saturating_add(right) > source.width || top.saturating_add(bottom) > source.height {
+            return;
+        }
+        self.nine_slices.insets[i] = [left, top, right, bottom];
+        self.refresh_nine_slice(id, i);
+    }
+
+    /// 设置边与中心的填充方式
+    ///
+    /// 0 = 拉伸, 1 = 平铺
+    pub fn set_nine_slice_mode(&mut self, id: u32, edge_mode: u8, center_mode: u8) {
+        let Some(i) = self.nine_slices.find(id) else {
+            return;
+        };
+        self.nine_slices.edge_modes[i] = SliceMode::from_u8(edge_mode);
+        self.nine_slices.center_modes[i] = SliceMode::from_u8(center_mode);
+        self.refresh_nine_slice(id, i);
+    }
+
+    /// 获取填充方式 [边, 中心]
+    pub fn get_nine_slice_mode(&self, id: u32) -> Option<Vec<u8>> {
+        let i = self.nine_slices.find(id)?;
+        Some(vec![
+            self.nine_slices.edge_modes[i].to_u8(),
+            self.nine_slices.center_modes[i].to_u8(),
+        ])
+    }
+}
+
+impl World {
+    /// 按当前尺寸重新拼接九宫格精灵
+    fn refresh_nine_slice(&mut self, id: u32, i: usize) {
+        if !self.sprites.is_active(id) {
+            return;
+        }
+        let width = self.sprites.original_widths[id as usize];
+        let height = self.sprites.original_heights[id as usize];
+        let data = self.nine_slices.render(i, width, height);
+        self.set_sprite_source(id, data, width, height);
+    }
+
+    /// 若精灵图是九宫格精灵，则按缩放后的尺寸重新拼接显示数据
+    ///
+    /// 只处理含缩放的变换；返回 false 表示由调用者走普通的像素重采样。
+    pub(super) fn apply_nine_slice_transform(
+        &mut self,
+        id: u32,
+        transform: SpriteTransform,
+    ) -> bool {
+        let Some(i) = self.nine_slices.find(id) else {
+            return false;
+        };
+        let (angle, sx, sy) = match transform {
+            SpriteTransform::Scale(sx, sy) => (0.0, sx, sy),
+            SpriteTransform::Transform(angle, sx, sy) => (angle, sx, sy),
+            _ => return false,
+        };
+        let idx = id as usize;
+        let width = (self.sprites.original_widths[idx] as f32 * sx.abs()).round() as u32;
+        let height = (self.sprites.original_heights[idx] as f32 * sy.abs()).round() as u32;
+        if width == 0 || height == 0 || pixel_len(width, height).is_none() {
+            return false;
+        }
+        let panel = self.nine_slices.render(i, width, height);
+
+        let (data, new_width, new_height) = if angle == 0.0 {
+            (panel, width, height)
+        } else {
+            // 拼接后的面板再旋转
+            let (cos_a, sin_a) = (angle.cos().abs(), angle.sin().abs());
+            let (w, h) = (width as f32, height as f32);
+            let new_width = (w * cos_a + h * sin_a).ceil() as u32;
+            let new_height = (w * sin_a + h * cos_a).ceil() as u32;
+            let inverse = Matrix3x3::rotation(-angle)
+                .inverse()
+                .unwrap_or_else(Matrix3x3::identity);
+            let mut data = vec![0u8; (new_width * new_height * 4) as usize];
+            for ty in 0..new_height {
+                for tx in 0..new_width {
+                    let (src_x, src_y) = inverse.transform_point(
+                        tx as f32 - new_width as f32 / 2.0,
+                        ty as f32 - new_height as f32 / 2.0,
+                    );
+                    if let Some(color) =
+                        sample_bilinear(&panel, width, height, src_x + w / 2.0, src_y + h / 2.0)
+                    {
+                        let dst = ((ty * new_width + tx) * 4) as usize;
+                        data[dst..dst + 4].copy_from_slice(&color);
+                    }
+                }
+            }
+            (data, new_width, new_height)
+        };
+
+        self.sprites.set_display(idx, data, new_width, new_height);
+        self.sprites.transforms[idx] = transform;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x4 源图像: 边距 1 像素，四角红色，上下边绿色，左右边蓝色，中心白色
+    fn panel(world: &mut World) -> u32 {
+        let mut data = Vec::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                let edge_x = x == 0 || x == 3;
+                let edge_y = y == 0 || y == 3;
+                data.extend_from_slice(match (edge_x, edge_y) {
+                    (true, true) => &[255, 0, 0, 255],
+                    (false, true) => &[0, 255, 0, 255],
+                    (true, false) => &[0, 0, 255, 255],
+                    (false, false) => &[255, 255, 255, 255],
+                });
+            }
+        }
+        world
+            .create_nine_slice_sprite(&data, 4, 4, 1, 1, 1, 1)
+            .unwrap()
+    }
+
+    fn pixel(world: &World, id: u32, x: u32, y: u32) -> [u8; 4] {
+        let idx = id as usize;
+        let i = ((y * world.sprites.display_widths[idx] + x) * 4) as usize;
+        world.sprites.display_data[idx][i..i + 4]
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resize_keeps_corners() {
+        let mut world = World::new(64, 64);
+        let id = panel(&mut world);
+        world.set_nine_slice_size(id, 10, 6);
+        assert_eq!(world.sprites.display_widths[id as usize], 10);
+        // 四角仍为 1 像素
+        assert_eq!(pixel(&world, id, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&world, id, 1, 0), [0, 255, 0, 255]);
+        assert_eq!(pixel(&world, id, 9, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel(&world, id, 8, 5), [0, 255, 0, 255]);
+        assert_eq!(pixel(&world, id, 0, 4), [0, 0, 255, 255]);
+        assert_eq!(pixel(&world, id, 5, 3), [255, 255, 255, 255]);
+
+        assert!(world
+            .create_nine_slice_sprite(&[0; 16], 2, 2, 2, 0, 1, 0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_scale_and_shrink() {
+        let mut world = World::new(64, 64);
+        let id = panel(&mut world);
+        world.apply_sprite_scale(id, 3.0, 2.0);
+        assert_eq!(world.sprites.display_widths[id as usize], 12);
+        assert_eq!(world.sprites.display_heights[id as usize], 8);
+        assert_eq!(pixel(&world, id, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&world, id, 1, 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(&world, id, 11, 7), [255, 0, 0, 255]);
+
+        // 目标小于边距之和时四角等比缩小
+        world.reset_sprite_transform(id);
+        world.set_nine_slice_size(id, 1, 1);
+        assert_eq!(pixel(&world, id, 0, 0), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_tile_mode() {
+        let mut world = World::new(64, 64);
+        // 6x3 源图像: 边距左右 1，中间两列交替红 / 蓝
+        let mut data = Vec::new();
+        for _ in 0..3 {
+            for x in 0..6 {
+                data.extend_from_slice(match x {
+                    0 | 5 => &[0, 0, 0, 255],
+                    1 | 3 => &[255, 0, 0, 255],
+                    _ => &[0, 0, 255, 255],
+                });
+            }
+        }
+        let id = world
+            .create_nine_slice_sprite(&data, 6, 3, 1, 0, 1, 0)
+            .unwrap();
+        world.set_nine_slice_mode(id, 1, 1);
+        assert_eq!(world.get_nine_slice_mode(id), Some(vec![1, 1]));
+        world.set_nine_slice_size(id, 9, 3);
+        // 中间 7 列为 [红 蓝 红 蓝] 重复后裁切
+        let row: Vec<u8> = (0..9).map(|x| pixel(&world, id, x, 1)[0]).collect();
+        assert_eq!(row, vec![0, 255, 0, 255, 0, 255, 0, 255, 0]);
+
+        world.set_nine_slice_mode(id, 0, 0);
+        assert_eq!(pixel(&world, id, 2, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&world, id, 6, 1), [0, 0, 255, 255]);
+    }
+}
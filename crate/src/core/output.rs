@@ -0,0 +1,219 @@
+//! 输出像素格式
+//!
+//! 场景内部始终以 RGBA8 合成 (作为纹理、滤镜与导出都依赖这一布局)。
+//! 设置其他输出格式后，每次渲染结束时额外编码一份目标布局的缓冲，
+//! `scene_data_ptr` / `scene_data_len` 改为指向该缓冲，供原生帧缓冲、
+//! 视频编码器或嵌入式 LCD 直接读取。不含透明度的格式直接丢弃 alpha。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::{SceneStore, World};
+
+/// 输出像素格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelFormat {
+    /// R, G, B, A 各 8 位
+    #[default]
+    Rgba8,
+    /// B, G, R, A 各 8 位
+    Bgra8,
+    /// 16 位 5-6-5，小端序
+    Rgb565,
+    /// R, G, B 各 8 位
+    Rgb888,
+    /// 8 位灰度 (BT.601 亮度)
+    Gray8,
+}
+
+impl PixelFormat {
+    /// 从 u8 值创建像素格式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => PixelFormat::Rgba8,
+            1 => PixelFormat::Bgra8,
+            2 => PixelFormat::Rgb565,
+            3 => PixelFormat::Rgb888,
+            4 => PixelFormat::Gray8,
+            _ => PixelFormat::Rgba8,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            PixelFormat::Rgba8 => 0,
+            PixelFormat::Bgra8 => 1,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Rgb888 => 3,
+            PixelFormat::Gray8 => 4,
+        }
+    }
+
+    /// 每像素字节数
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgba8 | PixelFormat::Bgra8 => 4,
+            PixelFormat::Rgb888 => 3,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+
+    /// 将 RGBA8 像素编码为目标布局，写入 `out` (长度按需调整)
+    fn encode(self, rgba: &[u8], out: &mut Vec<u8>) {
+        let bpp = self.bytes_per_pixel();
+        out.resize(rgba.len() / 4 * bpp, 0);
+        for (src, dst) in rgba.chunks_exact(4).zip(out.chunks_exact_mut(bpp)) {
+            let (r, g, b, a) = (src[0], src[1], src[2], src[3]);
+            match self {
+                PixelFormat::Rgba8 => dst.copy_from_slice(src),
+                PixelFormat::Bgra8 => dst.copy_from_slice(&[b, g, r, a]),
+                PixelFormat::Rgb565 => {
+                    let v = ((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3);
+                    dst.copy_from_slice(&v.to_le_bytes());
+                }
+                PixelFormat::Rgb888 => dst.copy_from_slice(&[r, g, b]),
+                PixelFormat::Gray8 => {
+                    dst[0] = ((77 * r as u32 + 150 * g as u32 + 29 * b as u32 + 128) >> 8) as u8;
+                }
+            }
+        }
+    }
+}
+
+impl SceneStore {
+    /// 按场景的输出格式编码输出缓冲 (RGBA8 时不需要额外缓冲)
+    pub(super) fn encode_output(&mut self, idx: usize) {
+        let format = self.output_formats[idx];
+        if format == PixelFormat::Rgba8 {
+            self.outputs[idx] = Vec::new();
+        } else {
+            format.encode(&self.data[idx], &mut self.outputs[idx]);
+        }
+    }
+
+    /// 场景对外输出的像素缓冲
+    pub(super) fn output(&self, idx: usize) -> &[u8] {
+        match self.output_formats[idx] {
+            PixelFormat::Rgba8 => &self.data[idx],
+            _ => &self.outputs[idx],
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 输出像素格式 ==========
+
+    /// 设置默认场景的输出像素格式
+    ///
+    /// 0=RGBA8, 1=BGRA8, 2=RGB565 (小端序), 3=RGB888, 4=灰度，其他值视为 RGBA8。
+    pub fn set_output_format(&mut self, format: u8) {
+        self.set_scene_output_format(self.default_scene, format);
+    }
+
+    /// 获取默认场景的输出像素格式
+    pub fn get_output_format(&self) -> u8 {
+        self.get_scene_output_format(self.default_scene)
+    }
+
+    /// 设置指定场景的输出像素格式 (立即按当前画面编码)
+    pub fn set_scene_output_format(&mut self, scene_id: u32, format: u8) {
+        if !self.scenes.is_active(scene_id) {
+            return;
+        }
+        let idx = scene_id as usize;
+        self.scenes.output_formats[idx] = PixelFormat::from_u8(format);
+        self.scenes.encode_output(idx);
+    }
+
+    /// 获取指定场景的输出像素格式
+    pub fn get_scene_output_format(&self, scene_id: u32) -> u8 {
+        if !self.scenes.is_active(scene_id) {
+            return 0;
+        }
+        self.scenes.output_formats[scene_id as usize].to_u8()
+    }
+
+    /// 获取默认场景输出格式的每像素字节数
+    pub fn output_bytes_per_pixel(&self) -> u32 {
+        if !self.scenes.is_active(self.default_scene) {
+            return 0;
+        }
+        self.scenes.output_formats[self.default_scene as usize].bytes_per_pixel() as u32
+    }
+
+    /// 获取指定场景按输出格式编码的像素数据副本
+    pub fn get_scene_output(&self, scene_id: u32) -> Option<Vec<u8>> {
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+        Some(self.scenes.output(scene_id as usize).to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(format: PixelFormat, rgba: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        format.encode(rgba, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_encode_formats() {
+        let px = [255, 128, 0, 200, 0, 0, 255, 255];
+        assert_eq!(
+            encode(PixelFormat::Bgra8, &px),
+            [0, 128, 255, 200, 255, 0, 0, 255]
+        );
+        assert_eq!(encode(PixelFormat::Rgb888, &px), [255, 128, 0, 0, 0, 255]);
+        // 0b11111_100000_00000 = 0xFC00, 0b00000_000000_11111 = 0x001F
+        assert_eq!(encode(PixelFormat::Rgb565, &px), [0x00, 0xFC, 0x1F, 0x00]);
+        assert_eq!(
+            encode(PixelFormat::Gray8, &[255, 255, 255, 255, 0, 0, 0, 255]),
+            [255, 0]
+        );
+        for v in 0..=5 {
+            assert_eq!(PixelFormat::from_u8(v).to_u8(), v % 5);
+        }
+    }
+
+    #[test]
+    fn test_scene_output_format() {
+        let mut world = World::new(2, 2);
+        world.set_background_color(10, 20, 30, 255);
+        world.render();
+        assert_eq!(world.scene_data_len(), 16);
+
+        world.set_output_format(1);
+        assert_eq!(world.get_output_format(), 1);
+        assert_eq!(world.output_bytes_per_pixel(), 4);
+        let output = world.get_scene_output(0).unwrap();
+        assert_eq!(&output[..4], &[30, 20, 10, 255]);
+
+        world.set_output_format(3);
+        world.render();
+        assert_eq!(world.scene_data_len(), 12);
+        assert_eq!(world.output_bytes_per_pixel(), 3);
+        // 内部缓冲仍为 RGBA8
+        assert_eq!(&world.scenes.data[0][..4], &[10, 20, 30, 255]);
+
+        world.set_output_format(0);
+        assert_eq!(world.scene_data_len(), 16);
+        assert!(world.get_scene_output(9).is_none());
+    }
+
+    #[test]
+    fn test_resize_keeps_output_in_sync() {
+        let mut world = World::new(2, 2);
+        world.set_output_format(3);
+        world.render();
+        world.resize_scene(4, 3);
+        // 未渲染前输出长度已与新尺寸一致
+        assert_eq!(world.scene_data_len(), 4 * 3 * 3);
+        assert_eq!(world.get_scene_output(0).unwrap().len(), 36);
+    }
+}
@@ -0,0 +1,718 @@
+//! 粒子系统
+//!
+//! 发射器与粒子分开存储：所有发射器的粒子共享一个对象池 (各属性分离为独立数组，
+//! 死亡粒子的槽位进入空闲列表等待复用)，每帧在 `update` 中统一模拟，
+//! 渲染时按发射器批量绘制，与精灵图按 z-index 交错。
+//!
+//! 粒子在出生时取发射器当前位置，之后独立于发射器运动 (坐标系与精灵图相同)。
+//! 颜色与尺寸按生命周期进度 (0~1) 在关键帧之间线性插值。
+
+use wasm_bindgen::prelude::*;
+
+use super::hdr::{blend_into, Emission};
+use super::world::World;
+use crate::image::{pixel_len, Image};
+
+/// 单个发射器同时存活的粒子上限
+pub const MAX_PARTICLES: u32 = 1 << 16;
+
+/// 粒子对象池 - 各属性分离为独立数组
+pub struct ParticleStore {
+    /// 所属发射器ID
+    pub(super) emitter_ids: Vec<u32>,
+    /// X 坐标
+    pub(super) positions_x: Vec<f32>,
+    /// Y 坐标
+    pub(super) positions_y: Vec<f32>,
+    /// X 速度 (像素/秒)
+    pub(super) velocities_x: Vec<f32>,
+    /// Y 速度 (像素/秒)
+    pub(super) velocities_y: Vec<f32>,
+    /// 旋转角度 (弧度，顺时针)
+    pub(super) rotations: Vec<f32>,
+    /// 角速度 (弧度/秒)
+    pub(super) angular_velocities: Vec<f32>,
+    /// 已存活时间 (秒)
+    pub(super) ages: Vec<f32>,
+    /// 寿命 (秒)
+    pub(super) lifetimes: Vec<f32>,
+    /// 是否存活
+    pub(super) alive: Vec<bool>,
+    /// 空闲槽位
+    pub(super) free: Vec<u32>,
+}
+
+impl ParticleStore {
+    pub(super) fn new() -> Self {
+        Self {
+            emitter_ids: Vec::new(),
+            positions_x: Vec::new(),
+            positions_y: Vec::new(),
+            velocities_x: Vec::new(),
+            velocities_y: Vec::new(),
+            rotations: Vec::new(),
+            angular_velocities: Vec::new(),
+            ages: Vec::new(),
+            lifetimes: Vec::new(),
+            alive: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// 分配一个粒子槽位 (优先复用空闲槽位)
+    fn alloc(&mut self) -> usize {
+        if let Some(i) = self.free.pop() {
+            return i as usize;
+        }
+        self.emitter_ids.push(0);
+        self.positions_x.push(0.0);
+        self.positions_y.push(0.0);
+        self.velocities_x.push(0.0);
+        self.velocities_y.push(0.0);
+        self.rotations.push(0.0);
+        self.angular_velocities.push(0.0);
+        self.ages.push(0.0);
+        self.lifetimes.push(0.0);
+        self.alive.push(false);
+        self.emitter_ids.len() - 1
+    }
+
+    /// 回收粒子
+    fn release(&mut self, i: usize) {
+        self.alive[i] = false;
+        self.free.push(i as u32);
+    }
+}
+
+/// 发射器存储 - 各属性分离为独立数组
+pub struct EmitterStore {
+    /// X 坐标
+    pub(super) positions_x: Vec<f32>,
+    /// Y 坐标
+    pub(super) positions_y: Vec<f32>,
+    /// Z 层级
+    pub(super) zindexes: Vec<i32>,
+    /// 每秒发射数量
+    pub(super) rates: Vec<f32>,
+    /// 未满一个粒子的发射累计
+    pub(super) accumulators: Vec<f32>,
+    /// 是否持续发射
+    pub(super) emitting: Vec<bool>,
+    /// 同时存活的粒子上限
+    pub(super) capacities: Vec<u32>,
+    /// 当前存活的粒子数
+    pub(super) counts: Vec<u32>,
+    /// 寿命范围 [最小, 最大] (秒)
+    pub(super) lifetimes: Vec<[f32; 2]>,
+    /// 发射方向 (弧度，0 为 +X，Y 轴向下)
+    pub(super) directions: Vec<f32>,
+    /// 方向散布角 (弧度，方向两侧各一半)
+    pub(super) spreads: Vec<f32>,
+    /// 初速度范围 [最小, 最大] (像素/秒)
+    pub(super) speeds: Vec<[f32; 2]>,
+    /// 加速度 [x, y] (像素/秒²)
+    pub(super) accelerations: Vec<[f32; 2]>,
+    /// 重力 (沿 +Y，像素/秒²)
+    pub(super) gravities: Vec<f32>,
+    /// 角速度范围 [最小, 最大] (弧度/秒)
+    pub(super) angular_velocities: Vec<[f32; 2]>,
+    /// 颜色关键帧 (生命周期进度, RGBA)
+    pub(super) color_curves: Vec<Vec<(f32, [f32; 4])>>,
+    /// 尺寸关键帧 (生命周期进度, 像素宽度)
+    pub(super) size_curves: Vec<Vec<(f32, [f32; 1])>>,
+    /// 粒子纹理 (None 时绘制纯色方块)
+    pub(super) textures: Vec<Option<Image>>,
+    /// 发光强度与叠加模式
+    pub(super) emissions: Vec<Emission>,
+    /// 随机数状态
+    pub(super) seeds: Vec<u32>,
+    /// 是否活跃 (用于删除标记)
+    pub(super) active: Vec<bool>,
+}
+
+impl EmitterStore {
+    pub(super) fn new() -> Self {
+        Self {
+            positions_x: Vec::new(),
+            positions_y: Vec::new(),
+            zindexes: Vec::new(),
+            rates: Vec::new(),
+            accumulators: Vec::new(),
+            emitting: Vec::new(),
+            capacities: Vec::new(),
+            counts: Vec::new(),
+            lifetimes: Vec::new(),
+            directions: Vec::new(),
+            spreads: Vec::new(),
+            speeds: Vec::new(),
+            accelerations: Vec::new(),
+            gravities: Vec::new(),
+            angular_velocities: Vec::new(),
+            color_curves: Vec::new(),
+            size_curves: Vec::new(),
+            textures: Vec::new(),
+            emissions: Vec::new(),
+            seeds: Vec::new(),
+            active: Vec::new(),
+        }
+    }
+
+    /// 添加发射器，返回ID (索引)
+    fn add(&mut self, capacity: u32) -> u32 {
+        let id = self.positions_x.len() as u32;
+        self.positions_x.push(0.0);
+        self.positions_y.push(0.0);
+        self.zindexes.push(0);
+        self.rates.push(10.0);
+        self.accumulators.push(0.0);
+        self.emitting.push(true);
+        self.capacities.push(capacity);
+        self.counts.push(0);
+        self.lifetimes.push([1.0, 1.0]);
+        self.directions.push(-std::f32::consts::FRAC_PI_2);
+        self.spreads.push(0.0);
+        self.speeds.push([50.0, 50.0]);
+        self.accelerations.push([0.0, 0.0]);
+        self.gravities.push(0.0);
+        self.angular_velocities.push([0.0, 0.0]);
+        self.color_curves.push(vec![(0.0, [255.0; 4])]);
+        self.size_curves.push(vec![(0.0, [4.0])]);
+        self.textures.push(None);
+        self.emissions.push(Emission::default());
+        self.seeds.push(0x9E37_79B9 ^ id.wrapping_mul(0x85EB_CA6B));
+        self.active.push(true);
+        id
+    }
+
+    /// 检查发射器是否存在且活跃
+    pub(super) fn is_active(&self, id: u32) -> bool {
+        let idx = id as usize;
+        idx < self.active.len() && self.active[idx]
+    }
+
+    /// 取 [0, 1) 均匀分布的随机数 (xorshift32)
+    fn random(&mut self, idx: usize) -> f32 {
+        let mut x = self.seeds[idx];
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.seeds[idx] = x;
+        (x >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// 在 [min, max] 范围内取随机值
+    fn random_range(&mut self, idx: usize, [min, max]: [f32; 2]) -> f32 {
+        min + (max - min) * self.random(idx)
+    }
+}
+
+/// 在关键帧之间线性插值 (进度超出首尾关键帧时取端点值)
+fn sample_curve<const N: usize>(keys: &[(f32, [f32; N])], t: f32) -> [f32; N] {
+    let Some(next) = keys.iter().position(|(offset, _)| *offset > t) else {
+        return keys[keys.len() - 1].1;
+    };
+    if next == 0 {
+        return keys[0].1;
+    }
+    let (t0, a) = keys[next - 1];
+    let (t1, b) = keys[next];
+    let f = (t - t0) / (t1 - t0);
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * f)
+}
+
+/// 校验关键帧并按进度排序 (进度限制在 0~1)
+fn build_curve<const N: usize>(offsets: &[f32], values: &[f32]) -> Option<Vec<(f32, [f32; N])>> {
+    if offsets.is_empty() || values.len() != offsets.len() * N {
+        return None;
+    }
+    if offsets.iter().chain(values).any(|v| !v.is_finite()) {
+        return None;
+    }
+    let mut keys: Vec<(f32, [f32; N])> = offsets
+        .iter()
+        .zip(values.chunks_exact(N))
+        .map(|(&t, v)| (t.clamp(0.0, 1.0), std::array::from_fn(|i| v[i])))
+        .collect();
+    keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Some(keys)
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 粒子发射器 ==========
+
+    /// 创建粒子发射器，`max_particles` 为同时存活的粒子上限 (不超过 `MAX_PARTICLES`)
+    ///
+    /// 默认每秒发射 10 个白色 4 像素粒子，寿命 1 秒，以 50 像素/秒向上运动。
+    /// 发射器需加入场景后才会绘制。
+    pub fn create_particle_emitter(&mut self, max_particles: u32) -> u32 {
+        self.emitters.add(max_particles.min(MAX_PARTICLES))
+    }
+
+    /// 移除发射器 (同时回收其粒子并从所有场景移除)
+    pub fn remove_particle_emitter(&mut self, emitter_id: u32) {
+        if !self.emitters.is_active(emitter_id) {
+            return;
+        }
+        let idx = emitter_id as usize;
+        self.emitters.active[idx] = false;
+        self.emitters.textures[idx] = None;
+        self.clear_particles(emitter_id);
+        for emitter_ids in self.scenes.emitter_ids.iter_mut() {
+            emitter_ids.retain(|&id| id != emitter_id);
+        }
+    }
+
+    /// 添加发射器到指定场景
+    pub fn add_emitter_to_scene(&mut self, emitter_id: u32, scene_id: u32) {
+        let scene_idx = scene_id as usize;
+        if self.scenes.is_active(scene_id)
+            && self.emitters.is_active(emitter_id)
+            && !self.scenes.emitter_ids[scene_idx].contains(&emitter_id)
+        {
+            self.scenes.emitter_ids[scene_idx].push(emitter_id);
+        }
+    }
+
+    /// 从指定场景移除发射器
+    pub fn remove_emitter_from_scene(&mut self, emitter_id: u32, scene_id: u32) {
+        if let Some(emitter_ids) = self.scenes.emitter_ids.get_mut(scene_id as usize) {
+            emitter_ids.retain(|&id| id != emitter_id);
+        }
+    }
+
+    /// 设置发射器位置 (只影响之后发射的粒子)
+    pub fn set_emitter_position(&mut self, emitter_id: u32, x: f32, y: f32) {
+        if self.emitters.is_active(emitter_id) && x.is_finite() && y.is_finite() {
+            self.emitters.positions_x[emitter_id as usize] = x;
+            self.emitters.positions_y[emitter_id as usize] = y;
+        }
+    }
+
+    /// 设置发射器 z-index
+    pub fn set_emitter_zindex(&mut self, emitter_id: u32, zindex: i32) {
+        if self.emitters.is_active(emitter_id) {
+            self.emitters.zindexes[emitter_id as usize] = zindex;
+        }
+    }
+
+    /// 设置每秒发射数量
+    pub fn set_emitter_rate(&mut self, emitter_id: u32, per_second: f32) {
+        if self.emitters.is_active(emitter_id) && per_second.is_finite() {
+            self.emitters.rates[emitter_id as usize] = per_second.max(0.0);
+        }
+    }
+
+    /// 开始 / 停止持续发射 (已存在的粒子继续运动直至寿命结束)
+    pub fn set_emitter_emitting(&mut self, emitter_id: u32, emitting: bool) {
+        if self.emitters.is_active(emitter_id) {
+            self.emitters.emitting[emitter_id as usize] = emitting;
+            self.emitters.accumulators[emitter_id as usize] = 0.0;
+        }
+    }
+
+    /// 立即发射一批粒子 (受粒子上限限制)，返回实际发射数量
+    pub fn emit_particle_burst(&mut self, emitter_id: u32, count: u32) -> u32 {
+        if !self.emitters.is_active(emitter_id) {
+            return 0;
+        }
+        let idx = emitter_id as usize;
+        let count = count.min(self.emitters.capacities[idx] - self.emitters.counts[idx]);
+        for _ in 0..count {
+            self.spawn_particle(idx);
+        }
+        count
+    }
+
+    /// 设置粒子寿命范围 (秒)
+    pub fn set_emitter_lifetime(&mut self, emitter_id: u32, min: f32, max: f32) {
+        if self.emitters.is_active(emitter_id) && min > 0.0 && max >= min && max.is_finite() {
+            self.emitters.lifetimes[emitter_id as usize] = [min, max];
+        }
+    }
+
+    /// 设置初速度：`direction` 为发射方向 (弧度，0 为 +X，Y 轴向下)，
+    /// `spread` 为散布角，速度在 [min, max] 之间随机
+    pub fn set_emitter_velocity(
+        &mut self,
+        emitter_id: u32,
+        direction: f32,
+        spread: f32,
+        min_speed: f32,
+        max_speed: f32,
+    ) {
+        let values = [direction, spread, min_speed, max_speed];
+        if self.emitters.is_active(emitter_id) && values.iter().all(|v| v.is_finite()) {
+            let idx = emitter_id as usize;
+            self.emitters.directions[idx] = direction;
+            self.emitters.spreads[idx] = spread.abs();
+            self.emitters.speeds[idx] = [min_speed, max_speed];
+        }
+    }
+
+    /// 设置加速度 (像素/秒²)
+    pub fn set_emitter_acceleration(&mut self, emitter_id: u32, ax: f32, ay: f32) {
+        if self.emitters.is_active(emitter_id) && ax.is_finite() && ay.is_finite() {
+            self.emitters.accelerations[emitter_id as usize] = [ax, ay];
+        }
+    }
+
+    /// 设置重力 (沿 +Y 方向，像素/秒²)
+    pub fn set_emitter_gravity(&mut self, emitter_id: u32, gravity: f32) {
+        if self.emitters.is_active(emitter_id) && gravity.is_finite() {
+            self.emitters.gravities[emitter_id as usize] = gravity;
+        }
+    }
+
+    /// 设置角速度范围 (弧度/秒，正值为顺时针)
+    pub fn set_emitter_angular_velocity(&mut self, emitter_id: u32, min: f32, max: f32) {
+        if self.emitters.is_active(emitter_id) && min.is_finite() && max.is_finite() {
+            self.emitters.angular_velocities[emitter_id as usize] = [min, max];
+        }
+    }
+
+    /// 设置颜色随生命周期变化的关键帧
+    ///
+    /// `offsets` 为生命周期进度 (0~1)，`colors` 为对应的 RGBA (每项 4 字节)。
+    /// 长度不匹配时忽略。
+    pub fn set_emitter_color_curve(&mut self, emitter_id: u32, offsets: &[f32], colors: &[u8]) {
+        if !self.emitters.is_active(emitter_id) {
+            return;
+        }
+        let values: Vec<f32> = colors.iter().map(|&c| c as f32).collect();
+        if let Some(curve) = build_curve::<4>(offsets, &values) {
+            self.emitters.color_curves[emitter_id as usize] = curve;
+        }
+    }
+
+    /// 设置尺寸 (像素宽度) 随生命周期变化的关键帧，长度不匹配时忽略
+    pub fn set_emitter_size_curve(&mut self, emitter_id: u32, offsets: &[f32], sizes: &[f32]) {
+        if !self.emitters.is_active(emitter_id) {
+            return;
+        }
+        if let Some(curve) = build_curve::<1>(offsets, sizes) {
+            let curve = curve
+                .into_iter()
+                .map(|(t, [s])| (t, [s.max(0.0)]))
+                .collect();
+            self.emitters.size_curves[emitter_id as usize] = curve;
+        }
+    }
+
+    /// 设置粒子纹理 (RGBA)，绘制时缩放到粒子尺寸 (保持宽高比) 并与粒子颜色相乘
+    pub fn set_emitter_texture(&mut self, emitter_id: u32, data: &[u8], width: u32, height: u32) {
+        if self.emitters.is_active(emitter_id)
+            && width > 0
+            && pixel_len(width, height) == Some(data.len())
+        {
+            self.emitters.textures[emitter_id as usize] = Some(Image {
+                width,
+                height,
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    /// 清除粒子纹理 (恢复为纯色方块)
+    pub fn clear_emitter_texture(&mut self, emitter_id: u32) {
+        if self.emitters.is_active(emitter_id) {
+            self.emitters.textures[emitter_id as usize] = None;
+        }
+    }
+
+    /// 设置随机数种子 (相同种子产生相同的粒子序列)
+    pub fn set_emitter_seed(&mut self, emitter_id: u32, seed: u32) {
+        if self.emitters.is_active(emitter_id) {
+            // xorshift 状态不能为 0
+            self.emitters.seeds[emitter_id as usize] = seed.max(1);
+        }
+    }
+
+    /// 获取当前存活的粒子数
+    pub fn get_emitter_particle_count(&self, emitter_id: u32) -> u32 {
+        if self.emitters.is_active(emitter_id) {
+            self.emitters.counts[emitter_id as usize]
+        } else {
+            0
+        }
+    }
+
+    /// 立即清除发射器的全部粒子
+    pub fn clear_particles(&mut self, emitter_id: u32) {
+        if (emitter_id as usize) >= self.emitters.counts.len() {
+            return;
+        }
+        for i in 0..self.particles.alive.len() {
+            if self.particles.alive[i] && self.particles.emitter_ids[i] == emitter_id {
+                self.particles.release(i);
+            }
+        }
+        self.emitters.counts[emitter_id as usize] = 0;
+    }
+}
+
+impl World {
+    /// 在发射器位置生成一个粒子
+    fn spawn_particle(&mut self, idx: usize) {
+        let emitters = &mut self.emitters;
+        let lifetime = emitters.random_range(idx, emitters.lifetimes[idx]);
+        let spread = emitters.spreads[idx];
+        let angle = emitters.directions[idx] + spread * (emitters.random(idx) - 0.5);
+        let speed = emitters.random_range(idx, emitters.speeds[idx]);
+        let angular_velocity = emitters.random_range(idx, emitters.angular_velocities[idx]);
+        emitters.counts[idx] += 1;
+
+        let particles = &mut self.particles;
+        let i = particles.alloc();
+        particles.emitter_ids[i] = idx as u32;
+        particles.positions_x[i] = emitters.positions_x[idx];
+        particles.positions_y[i] = emitters.positions_y[idx];
+        particles.velocities_x[i] = angle.cos() * speed;
+        particles.velocities_y[i] = angle.sin() * speed;
+        particles.rotations[i] = 0.0;
+        particles.angular_velocities[i] = angular_velocity;
+        particles.ages[i] = 0.0;
+        particles.lifetimes[i] = lifetime;
+        particles.alive[i] = true;
+    }
+
+    /// 模拟粒子并按发射速率生成新粒子
+    pub(super) fn update_particles(&mut self, dt: f32) {
+        let particles = &mut self.particles;
+        for i in 0..particles.alive.len() {
+            if !particles.alive[i] {
+                continue;
+            }
+            particles.ages[i] += dt;
+            let e = particles.emitter_ids[i] as usize;
+            if particles.ages[i] >= particles.lifetimes[i] {
+                particles.release(i);
+                self.emitters.counts[e] -= 1;
+                continue;
+            }
+            let [ax, ay] = self.emitters.accelerations[e];
+            particles.velocities_x[i] += ax * dt;
+            particles.velocities_y[i] += (ay + self.emitters.gravities[e]) * dt;
+            particles.positions_x[i] += particles.velocities_x[i] * dt;
+            particles.positions_y[i] += particles.velocities_y[i] * dt;
+            particles.rotations[i] += particles.angular_velocities[i] * dt;
+        }
+
+        for idx in 0..self.emitters.active.len() {
+            if !self.emitters.active[idx] || !self.emitters.emitting[idx] {
+                continue;
+            }
+            let pending = self.emitters.accumulators[idx] + self.emitters.rates[idx] * dt;
+            let count = pending.floor();
+            self.emitters.accumulators[idx] = pending - count;
+            let room = self.emitters.capacities[idx] - self.emitters.counts[idx];
+            for _ in 0..(count as u32).min(room) {
+                self.spawn_particle(idx);
+            }
+        }
+    }
+
+    /// 场景中需要绘制的发射器，按 z-index 排序
+    pub(super) fn sorted_emitters(&self, scene_idx: usize) -> Vec<u32> {
+        let mut emitters: Vec<u32> = self.scenes.emitter_ids[scene_idx]
+            .iter()
+            .copied()
+            .filter(|&id| self.emitters.is_active(id) && self.emitters.counts[id as usize] > 0)
+            .collect();
+        emitters.sort_by_key(|&id| self.emitters.zindexes[id as usize]);
+        emitters
+    }
+
+    /// 批量绘制发射器的全部粒子
+    pub(super) fn draw_emitter(&mut self, scene_idx: usize, emitter_id: u32) {
+        let e = emitter_id as usize;
+        let scene_w = self.scenes.widths[scene_idx] as i32;
+        let scene_h = self.scenes.heights[scene_idx] as i32;
+        let texture = self.emitters.textures[e].as_ref();
+        let emission = self.emitters.emissions[e];
+        let linear = self.scenes.linear_blending[scene_idx];
+        let aspect = texture.map_or(1.0, |t| t.height as f32 / t.width as f32);
+        let colors = &self.emitters.color_curves[e];
+        let sizes = &self.emitters.size_curves[e];
+        let particles = &self.particles;
+        let scene_data = &mut self.scenes.data[scene_idx];
+        let mut hdr = self.scenes.hdr_buffers[scene_idx].as_deref_mut();
+
+        for i in 0..particles.alive.len() {
+            if !particles.alive[i] || particles.emitter_ids[i] != emitter_id {
+                continue;
+            }
+            let t = particles.ages[i] / particles.lifetimes[i];
+            let color = sample_curve(colors, t);
+            let [size] = sample_curve(sizes, t);
+            if color[3] < 0.5 || size <= 0.0 {
+                continue;
+            }
+            let (half_w, half_h) = (size / 2.0, size * aspect / 2.0);
+            let (sin, cos) = particles.rotations[i].sin_cos();
+            let extent_x = (half_w * cos).abs() + (half_h * sin).abs();
+            let extent_y = (half_w * sin).abs() + (half_h * cos).abs();
+            let cx = particles.positions_x[i] + scene_w as f32 / 2.0;
+            let cy = particles.positions_y[i] + scene_h as f32 / 2.0;
+
+            let x0 = ((cx - extent_x).floor() as i32).max(0);
+            let x1 = ((cx + extent_x).ceil() as i32).min(scene_w);
+            let y0 = ((cy - extent_y).floor() as i32).max(0);
+            let y1 = ((cy + extent_y).ceil() as i32).min(scene_h);
+            for py in y0..y1 {
+                let dy = py as f32 + 0.5 - cy;
+                for px in x0..x1 {
+                    let dx = px as f32 + 0.5 - cx;
+                    // 逆旋转到粒子局部坐标
+                    let u = dx * cos + dy * sin;
+                    let v = dy * cos - dx * sin;
+                    if u.abs() > half_w || v.abs() > half_h {
+                        continue;
+                    }
+                    let src = match texture {
+                        Some(tex) => {
+                            let tx = ((u + half_w) / size * tex.width as f32) as u32;
+                            let ty = ((v + half_h) / (size * aspect) * tex.height as f32) as u32;
+                            let s = ((ty.min(tex.height - 1) * tex.width + tx.min(tex.width - 1))
+                                * 4) as usize;
+                            [
+                                tex.data[s] as f32 / 255.0,
+                                tex.data[s + 1] as f32 / 255.0,
+                                tex.data[s + 2] as f32 / 255.0,
+                                tex.data[s + 3] as f32 / 255.0,
+                            ]
+                        }
+                        None => [1.0; 4],
+                    };
+                    let rgba: [u8; 4] = std::array::from_fn(|c| (color[c] * src[c] + 0.5) as u8);
+                    let dst = ((py * scene_w + px) * 4) as usize;
+                    blend_into(scene_data, hdr.as_deref_mut(), dst, rgba, emission, linear);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    #[test]
+    fn test_spawn_rate_and_lifetime() {
+        let mut world = World::new(64, 64);
+        let id = world.create_particle_emitter(100);
+        world.set_emitter_rate(id, 20.0);
+        world.set_emitter_lifetime(id, 0.5, 0.5);
+        world.update(0.26);
+        assert_eq!(world.get_emitter_particle_count(id), 5);
+        world.update(0.26);
+        assert_eq!(world.get_emitter_particle_count(id), 10);
+        // 最早的一批粒子寿命结束
+        world.update(0.26);
+        assert_eq!(world.get_emitter_particle_count(id), 10);
+        // 池中的槽位被复用
+        assert!(world.particles.alive.len() <= 16);
+
+        world.set_emitter_emitting(id, false);
+        world.update(1.0);
+        assert_eq!(world.get_emitter_particle_count(id), 0);
+        assert_eq!(world.emit_particle_burst(id, 500), 100);
+        world.clear_particles(id);
+        assert_eq!(world.get_emitter_particle_count(id), 0);
+    }
+
+    #[test]
+    fn test_motion_and_gravity() {
+        let mut world = World::new(64, 64);
+        let id = world.create_particle_emitter(10);
+        world.set_emitter_emitting(id, false);
+        world.set_emitter_position(id, 5.0, 0.0);
+        world.set_emitter_velocity(id, 0.0, 0.0, 10.0, 10.0);
+        world.set_emitter_gravity(id, 20.0);
+        world.set_emitter_angular_velocity(id, 2.0, 2.0);
+        world.emit_particle_burst(id, 1);
+        world.update(0.5);
+        let p = &world.particles;
+        assert!((p.positions_x[0] - 10.0).abs() < 1e-4);
+        // 半隐式欧拉: v = 10, y = 10 * 0.5
+        assert!((p.velocities_y[0] - 10.0).abs() < 1e-4);
+        assert!((p.positions_y[0] - 5.0).abs() < 1e-4);
+        assert!((p.rotations[0] - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rejects_invalid_parameters() {
+        let mut world = World::new(64, 64);
+        let id = world.create_particle_emitter(u32::MAX);
+        world.set_emitter_emitting(id, false);
+        assert_eq!(world.emit_particle_burst(id, u32::MAX), MAX_PARTICLES);
+        world.clear_particles(id);
+
+        world.set_emitter_position(id, f32::NAN, 0.0);
+        world.set_emitter_velocity(id, 0.0, f32::INFINITY, 1.0, 1.0);
+        world.set_emitter_acceleration(id, f32::NAN, 0.0);
+        world.set_emitter_gravity(id, f32::INFINITY);
+        world.set_emitter_angular_velocity(id, f32::NEG_INFINITY, 0.0);
+        let idx = id as usize;
+        assert_eq!(world.emitters.positions_x[idx], 0.0);
+        assert_eq!(world.emitters.spreads[idx], 0.0);
+        assert_eq!(world.emitters.speeds[idx], [50.0, 50.0]);
+        assert_eq!(world.emitters.accelerations[idx], [0.0, 0.0]);
+        assert_eq!(world.emitters.gravities[idx], 0.0);
+        assert_eq!(world.emitters.angular_velocities[idx], [0.0, 0.0]);
+
+        world.emit_particle_burst(id, 1);
+        world.update(0.5);
+        assert!(world.particles.positions_y[0].is_finite());
+    }
+
+    #[test]
+    fn test_curves_and_render() {
+        let mut world = World::new(16, 16);
+        world.set_background_color(0, 0, 0, 255);
+        let id = world.create_particle_emitter(10);
+        world.add_emitter_to_scene(id, 0);
+        world.set_emitter_emitting(id, false);
+        world.set_emitter_velocity(id, 0.0, 0.0, 0.0, 0.0);
+        world.set_emitter_lifetime(id, 1.0, 1.0);
+        world.set_emitter_color_curve(id, &[0.0, 1.0], &[255, 0, 0, 255, 0, 0, 255, 255]);
+        world.set_emitter_size_curve(id, &[0.0, 1.0], &[4.0, 8.0]);
+        world.emit_particle_burst(id, 1);
+        world.render();
+        assert_eq!(scene_pixel(&world, 8, 8), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 5, 8), [0, 0, 0, 255]);
+
+        world.update(0.5);
+        world.render();
+        // 进度 0.5: 颜色居中插值，尺寸 6
+        assert_eq!(scene_pixel(&world, 8, 8), [128, 0, 128, 255]);
+        assert_eq!(scene_pixel(&world, 5, 8), [128, 0, 128, 255]);
+        assert_eq!(scene_pixel(&world, 4, 8), [0, 0, 0, 255]);
+
+        // 无效关键帧被忽略
+        world.set_emitter_size_curve(id, &[0.0], &[1.0, 2.0]);
+        assert_eq!(world.emitters.size_curves[id as usize].len(), 2);
+
+        world.remove_particle_emitter(id);
+        world.render();
+        assert_eq!(scene_pixel(&world, 8, 8), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_zorder_with_sprites() {
+        let mut world = World::new(16, 16);
+        let sprite = world.create_rect_sprite(16, 16, 0, 255, 0, 255);
+        world.add_to_scene(sprite);
+        world.set_sprite_zindex(sprite, 1);
+        let id = world.create_particle_emitter(1);
+        world.add_emitter_to_scene(id, 0);
+        world.set_emitter_velocity(id, 0.0, 0.0, 0.0, 0.0);
+        world.emit_particle_burst(id, 1);
+        world.render();
+        assert_eq!(scene_pixel(&world, 8, 8), [0, 255, 0, 255]);
+        world.set_emitter_zindex(id, 2);
+        world.render();
+        assert_eq!(scene_pixel(&world, 8, 8), [255, 255, 255, 255]);
+    }
+}
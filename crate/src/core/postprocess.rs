@@ -0,0 +1,183 @@
+//! 场景后期处理
+//!
+//! 每个场景保存一个有序的滤镜列表，场景渲染完成后依次应用到其像素缓冲，
+//! 因此作为纹理被引用的离屏场景同样带有滤镜效果。
+//! 滤镜按添加顺序编号，可单独替换参数或启用 / 停用而不改变顺序。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::filter::Filter;
+use crate::palette::Palette;
+
+#[wasm_bindgen]
+impl World {
+    // ========== 后期处理 ==========
+
+    /// 在场景滤镜列表末尾添加滤镜 (默认启用)，返回其序号；场景不存在时返回 None
+    pub fn add_scene_filter(&mut self, scene_id: u32, filter: &Filter) -> Option<u32> {
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+        let filters = &mut self.scenes.filters[scene_id as usize];
+        filters.push((filter.clone(), true));
+        self.scenes.cache_valid[scene_id as usize] = false;
+        Some(filters.len() as u32 - 1)
+    }
+
+    /// 替换场景中指定序号的滤镜 (保留启用状态)
+    pub fn set_scene_filter(&mut self, scene_id: u32, index: u32, filter: &Filter) {
+        if let Some(entry) = self.scene_filter_mut(scene_id, index) {
+            entry.0 = filter.clone();
+        }
+    }
+
+    /// 启用或停用场景中指定序号的滤镜
+    pub fn set_scene_filter_enabled(&mut self, scene_id: u32, index: u32, enabled: bool) {
+        if let Some(entry) = self.scene_filter_mut(scene_id, index) {
+            entry.1 = enabled;
+        }
+    }
+
+    /// 检查场景中指定序号的滤镜是否启用 (不存在时返回 false)
+    pub fn is_scene_filter_enabled(&self, scene_id: u32, index: u32) -> bool {
+        if !self.scenes.is_active(scene_id) {
+            return false;
+        }
+        self.scenes.filters[scene_id as usize]
+            .get(index as usize)
+            .is_some_and(|(_, enabled)| *enabled)
+    }
+
+    /// 移除场景中指定序号的滤镜 (之后的滤镜序号减一)
+    pub fn remove_scene_filter(&mut self, scene_id: u32, index: u32) {
+        if !self.scenes.is_active(scene_id) {
+            return;
+        }
+        let idx = scene_id as usize;
+        if (index as usize) < self.scenes.filters[idx].len() {
+            self.scenes.filters[idx].remove(index as usize);
+            self.scenes.cache_valid[idx] = false;
+        }
+    }
+
+    /// 清除场景的全部滤镜
+    pub fn clear_scene_filters(&mut self, scene_id: u32) {
+        if self.scenes.is_active(scene_id) {
+            self.scenes.filters[scene_id as usize].clear();
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 由场景当前的像素缓冲生成最多 `count` 个颜色的调色板
+    ///
+    /// `method`: 0 = 中位切分, 1 = 八叉树。场景不存在时返回 None。
+    pub fn compute_scene_palette(&self, scene_id: u32, count: u32, method: u8) -> Option<Palette> {
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+        Some(Palette::from_pixels(
+            &self.scenes.data[scene_id as usize],
+            count,
+            method,
+        ))
+    }
+
+    /// 获取场景的滤镜数量
+    pub fn get_scene_filter_count(&self, scene_id: u32) -> u32 {
+        if !self.scenes.is_active(scene_id) {
+            return 0;
+        }
+        self.scenes.filters[scene_id as usize].len() as u32
+    }
+}
+
+impl World {
+    fn scene_filter_mut(&mut self, scene_id: u32, index: u32) -> Option<&mut (Filter, bool)> {
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+        let idx = scene_id as usize;
+        self.scenes.cache_valid[idx] = false;
+        self.scenes.filters[idx].get_mut(index as usize)
+    }
+
+    /// 按顺序将场景已启用的滤镜应用到其像素缓冲
+    pub(super) fn apply_scene_filters(&mut self, scene_idx: usize) {
+        let (width, height) = (
+            self.scenes.widths[scene_idx],
+            self.scenes.heights[scene_idx],
+        );
+        let data = &mut self.scenes.data[scene_idx];
+        for (filter, _) in self.scenes.filters[scene_idx].iter().filter(|(_, on)| *on) {
+            filter.apply(data, width, height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    #[test]
+    fn test_filters_apply_in_order() {
+        let mut world = World::new(4, 4);
+        world.set_background_color(200, 100, 0, 255);
+        let gray = world.add_scene_filter(0, &Filter::grayscale(1.0)).unwrap();
+        let pixelate = world.add_scene_filter(0, &Filter::pixelate(2)).unwrap();
+        assert_eq!((gray, pixelate), (0, 1));
+        assert_eq!(world.get_scene_filter_count(0), 2);
+        world.render();
+        let p = scene_pixel(&world, 1, 1);
+        assert!(p[0] == p[1] && p[1] == p[2]);
+
+        world.set_scene_filter_enabled(0, gray, false);
+        assert!(!world.is_scene_filter_enabled(0, gray));
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [200, 100, 0, 255]);
+
+        world.set_scene_filter(0, gray, &Filter::sepia(1.0));
+        world.set_scene_filter_enabled(0, gray, true);
+        world.render();
+        let p = scene_pixel(&world, 1, 1);
+        assert!(p[0] > p[1] && p[1] > p[2]);
+
+        world.remove_scene_filter(0, gray);
+        assert_eq!(world.get_scene_filter_count(0), 1);
+        world.clear_scene_filters(0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [200, 100, 0, 255]);
+        assert_eq!(world.add_scene_filter(9, &Filter::blur(1.0)), None);
+    }
+
+    #[test]
+    fn test_offscreen_scene_filters() {
+        let mut world = World::new(4, 4);
+        let inner = world.create_scene(4, 4);
+        world.set_scene_background_color(inner, 255, 0, 0, 255);
+        world.add_scene_filter(inner, &Filter::grayscale(1.0));
+        let view = world.create_scene_sprite(inner).unwrap();
+        world.add_to_scene(view);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [54, 54, 54, 255]);
+    }
+
+    #[test]
+    fn test_scene_palette_quantization() {
+        let mut world = World::new(4, 4);
+        world.set_background_color(10, 20, 200, 255);
+        let id = world.create_rect_sprite(2, 2, 240, 30, 20, 255);
+        world.add_to_scene(id);
+        world.render();
+        let palette = world.compute_scene_palette(0, 2, 0).unwrap();
+        assert_eq!(palette.len(), 2);
+
+        world.add_scene_filter(0, &Filter::brightness(0.9));
+        world.add_scene_filter(0, &Filter::quantize(&palette, 4));
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [10, 20, 200, 255]);
+        assert_eq!(scene_pixel(&world, 2, 2), [240, 30, 20, 255]);
+        assert!(world.compute_scene_palette(7, 2, 0).is_none());
+    }
+}
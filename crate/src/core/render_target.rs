@@ -0,0 +1,257 @@
+//! 渲染到纹理
+//!
+//! 精灵图可以引用另一个场景的渲染结果作为纹理 (小地图、画中画、缓存的组合精灵)。
+//! `render` 从默认场景出发按深度优先收集依赖的场景，依赖先于引用者渲染，
+//! 每个场景每帧最多渲染一次；精灵图在所属场景渲染前同步源场景的最新输出，
+//! 并保留自身的变换。
+//!
+//! 依赖成环时 (A 引用 B，B 又引用 A)，环上的回边被跳过，
+//! 对应精灵图沿用上一次同步的画面，而不会无限递归。
+//! 缓存场景只在首次或失效后重新渲染，适合内容很少变化的组合。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+
+/// 深度优先遍历状态
+const UNVISITED: u8 = 0;
+const VISITING: u8 = 1;
+const DONE: u8 = 2;
+
+#[wasm_bindgen]
+impl World {
+    // ========== 渲染到纹理 ==========
+
+    /// 创建以场景渲染结果为纹理的精灵图 (尺寸与场景相同)
+    ///
+    /// 场景不存在时返回 None。精灵图初始内容为场景当前的像素缓冲。
+    pub fn create_scene_sprite(&mut self, scene_id: u32) -> Option<u32> {
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+        let idx = scene_id as usize;
+        let id = self.sprites.add(
+            self.scenes.data[idx].clone(),
+            self.scenes.widths[idx],
+            self.scenes.heights[idx],
+        );
+        self.sprites.source_scenes[id as usize] = Some(scene_id);
+        self.sprites.source_versions[id as usize] = self.scenes.versions[idx];
+        Some(id)
+    }
+
+    /// 将已有精灵图的纹理改为引用场景 (下次渲染时同步)，场景或精灵图不存在时返回 false
+    pub fn set_sprite_scene_source(&mut self, sprite_id: u32, scene_id: u32) -> bool {
+        if !self.sprites.is_active(sprite_id) || !self.scenes.is_active(scene_id) {
+            return false;
+        }
+        let idx = sprite_id as usize;
+        self.sprites.source_scenes[idx] = Some(scene_id);
+        // 保证下次渲染时一定同步
+        self.sprites.source_versions[idx] = self.scenes.versions[scene_id as usize].wrapping_sub(1);
+        true
+    }
+
+    /// 解除精灵图与场景的引用 (保留最后一次同步的画面)
+    pub fn clear_sprite_scene_source(&mut self, sprite_id: u32) {
+        if self.sprites.is_active(sprite_id) {
+            self.sprites.source_scenes[sprite_id as usize] = None;
+        }
+    }
+
+    /// 获取精灵图引用的场景ID
+    pub fn get_sprite_scene_source(&self, sprite_id: u32) -> Option<u32> {
+        if !self.sprites.is_active(sprite_id) {
+            return None;
+        }
+        self.sprites.source_scenes[sprite_id as usize]
+    }
+
+    /// 设置场景是否缓存 (缓存场景作为纹理时只在首次或 `invalidate_scene` 后重新渲染)
+    pub fn set_scene_cached(&mut self, scene_id: u32, cached: bool) {
+        if self.scenes.is_active(scene_id) {
+            self.scenes.cached[scene_id as usize] = cached;
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 使缓存场景失效，下次渲染时重新绘制
+    pub fn invalidate_scene(&mut self, scene_id: u32) {
+        if self.scenes.is_active(scene_id) {
+            self.scenes.cache_valid[scene_id as usize] = false;
+        }
+    }
+
+    /// 检查从场景出发的纹理引用是否成环
+    pub fn has_scene_cycle(&self, scene_id: u32) -> bool {
+        if !self.scenes.is_active(scene_id) {
+            return false;
+        }
+        let mut state = vec![UNVISITED; self.scenes.active.len()];
+        let mut order = Vec::new();
+        let mut cycle = false;
+        self.collect_scene_order(scene_id as usize, false, &mut state, &mut order, &mut cycle);
+        cycle
+    }
+}
+
+impl World {
+    /// 按依赖顺序渲染场景及其引用的场景
+    pub(super) fn render_scene_tree(&mut self, root: usize) {
+        let mut state = vec![UNVISITED; self.scenes.active.len()];
+        let mut order = Vec::new();
+        let mut cycle = false;
+        self.collect_scene_order(root, true, &mut state, &mut order, &mut cycle);
+        self.sync_indexed_sprites();
+
+        for scene in order {
+            if scene != root && self.scenes.cached[scene] && self.scenes.cache_valid[scene] {
+                continue;
+            }
+            self.sync_scene_sprites(scene);
+            self.render_scene(scene);
+            self.apply_scene_filters(scene);
+            self.scenes.encode_output(scene);
+            self.scenes.versions[scene] = self.scenes.versions[scene].wrapping_add(1);
+            self.scenes.cache_valid[scene] = true;
+        }
+    }
+
+    /// 深度优先收集场景渲染顺序 (依赖在前)，遇到回边时记录成环并跳过
+    ///
+    /// `skip_cached` 为 true 时不进入仍然有效的缓存场景的依赖。
+    fn collect_scene_order(
+        &self,
+        scene: usize,
+        skip_cached: bool,
+        state: &mut [u8],
+        order: &mut Vec<usize>,
+        cycle: &mut bool,
+    ) {
+        state[scene] = VISITING;
+        let cached = skip_cached && self.scenes.cached[scene] && self.scenes.cache_valid[scene];
+        if !cached {
+            for &id in &self.scenes.sprite_ids[scene] {
+                if !self.sprites.is_active(id) {
+                    continue;
+                }
+                let Some(source) = self.sprites.source_scenes[id as usize] else {
+                    continue;
+                };
+                if !self.scenes.is_active(source) {
+                    continue;
+                }
+                match state[source as usize] {
+                    UNVISITED => {
+                        self.collect_scene_order(source as usize, skip_cached, state, order, cycle)
+                    }
+                    VISITING => *cycle = true,
+                    _ => {}
+                }
+            }
+        }
+        state[scene] = DONE;
+        order.push(scene);
+    }
+
+    /// 将场景中引用其他场景的精灵图同步为源场景的最新输出
+    fn sync_scene_sprites(&mut self, scene: usize) {
+        for i in 0..self.scenes.sprite_ids[scene].len() {
+            let id = self.scenes.sprite_ids[scene][i];
+            if !self.sprites.is_active(id) {
+                continue;
+            }
+            let idx = id as usize;
+            let Some(source) = self.sprites.source_scenes[idx] else {
+                continue;
+            };
+            let s = source as usize;
+            if !self.scenes.is_active(source)
+                || self.sprites.source_versions[idx] == self.scenes.versions[s]
+            {
+                continue;
+            }
+            self.sprites.source_versions[idx] = self.scenes.versions[s];
+            let data = self.scenes.data[s].clone();
+            let (width, height) = (self.scenes.widths[s], self.scenes.heights[s]);
+            self.set_sprite_source(id, data, width, height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    #[test]
+    fn test_scene_as_sprite() {
+        let mut world = World::new(16, 16);
+        world.set_background_color(0, 0, 0, 255);
+        let mini = world.create_scene(4, 4);
+        world.set_scene_background_color(mini, 0, 0, 255, 255);
+        let dot = world.create_rect_sprite(2, 2, 255, 0, 0, 255);
+        world.add_sprite_to_scene(dot, mini);
+
+        let view = world.create_scene_sprite(mini).unwrap();
+        assert_eq!(world.get_sprite_scene_source(view), Some(mini));
+        world.add_to_scene(view);
+        world.render();
+        // 4x4 场景居中绘制: 红点位于中间 2x2
+        assert_eq!(scene_pixel(&world, 6, 6), [0, 0, 255, 255]);
+        assert_eq!(scene_pixel(&world, 8, 8), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 2, 2), [0, 0, 0, 255]);
+
+        // 源场景的变化在下一帧同步
+        world.set_sprite_position(dot, 10.0, 0.0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 8, 8), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_nested_order_and_cache() {
+        let mut world = World::new(4, 4);
+        let inner = world.create_scene(4, 4);
+        let middle = world.create_scene(4, 4);
+        world.set_scene_background_color(inner, 255, 0, 0, 255);
+        world.set_scene_background_color(middle, 0, 0, 0, 0);
+        let inner_view = world.create_scene_sprite(inner).unwrap();
+        world.add_sprite_to_scene(inner_view, middle);
+        let middle_view = world.create_scene_sprite(middle).unwrap();
+        world.add_to_scene(middle_view);
+        // 同一帧内按 inner → middle → 默认场景的顺序渲染
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 0, 0, 255]);
+
+        world.set_scene_cached(middle, true);
+        world.render();
+        world.set_scene_background_color(inner, 0, 255, 0, 255);
+        world.render();
+        // 缓存场景未失效，画面不变
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 0, 0, 255]);
+        world.invalidate_scene(middle);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let mut world = World::new(4, 4);
+        let a = world.create_scene(4, 4);
+        let b = world.create_scene(4, 4);
+        let a_view = world.create_scene_sprite(a).unwrap();
+        let b_view = world.create_scene_sprite(b).unwrap();
+        world.add_sprite_to_scene(b_view, a);
+        assert!(!world.has_scene_cycle(a));
+        world.add_sprite_to_scene(a_view, b);
+        assert!(world.has_scene_cycle(a));
+        assert!(world.has_scene_cycle(b));
+        world.add_to_scene(a_view);
+        // 成环时仍能完成渲染
+        world.render();
+
+        world.clear_sprite_scene_source(a_view);
+        assert!(!world.has_scene_cycle(a));
+        assert!(!world.set_sprite_scene_source(a_view, 99));
+    }
+}
@@ -1,6 +1,9 @@
 //! 采样方法模块
 //!
 //! 提供不同的像素采样算法用于精灵图渲染。
+//! 双线性与超采样各有线性光版本，在解码后的线性值上插值。
+
+use super::linear::{to_linear, to_srgb};
 
 /// 采样方法枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -87,6 +90,30 @@ pub fn sample_bilinear(
     height: u32,
     px: f32,
     py: f32,
+) -> Option<[u8; 4]> {
+    bilinear(data, width, height, px, py, false)
+}
+
+/// 线性光双线性插值采样
+///
+/// 颜色通道解码为线性值后插值，结果重新编码为 sRGB；Alpha 直接插值。
+pub fn sample_bilinear_linear(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    px: f32,
+    py: f32,
+) -> Option<[u8; 4]> {
+    bilinear(data, width, height, px, py, true)
+}
+
+fn bilinear(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    px: f32,
+    py: f32,
+    linear: bool,
 ) -> Option<[u8; 4]> {
     // 坐标调整：采样点在像素中心
     let px = px - 0.5;
@@ -105,6 +132,14 @@ pub fn sample_bilinear(
     let get_pixel = |x: i32, y: i32| -> [f32; 4] {
         if x >= 0 && x < width as i32 && y >= 0 && y < height as i32 {
             let idx = ((y as u32 * width + x as u32) * 4) as usize;
+            if linear {
+                return [
+                    to_linear(data[idx]) * 255.0,
+                    to_linear(data[idx + 1]) * 255.0,
+                    to_linear(data[idx + 2]) * 255.0,
+                    data[idx + 3] as f32,
+                ];
+            }
             [
                 data[idx] as f32,
                 data[idx + 1] as f32,
@@ -144,7 +179,11 @@ pub fn sample_bilinear(
     let mut result = [0u8; 4];
     for i in 0..4 {
         let value = w00 * c00[i] + w10 * c10[i] + w01 * c01[i] + w11 * c11[i];
-        result[i] = value.clamp(0.0, 255.0) as u8;
+        result[i] = if linear && i < 3 {
+            to_srgb(value / 255.0)
+        } else {
+            value.clamp(0.0, 255.0) as u8
+        };
     }
 
     Some(result)
@@ -173,6 +212,28 @@ pub fn sample_supersampling(
     height: u32,
     px: f32,
     py: f32,
+) -> Option<[u8; 4]> {
+    supersampling(data, width, height, px, py, false)
+}
+
+/// 线性光超采样抗锯齿 (2x2)，子像素颜色在线性光空间中平均
+pub fn sample_supersampling_linear(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    px: f32,
+    py: f32,
+) -> Option<[u8; 4]> {
+    supersampling(data, width, height, px, py, true)
+}
+
+fn supersampling(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    px: f32,
+    py: f32,
+    linear: bool,
 ) -> Option<[u8; 4]> {
     // 2x2 超采样：在像素内采样 4 个点
     let offsets = [
@@ -193,9 +254,15 @@ pub fn sample_supersampling(
         let sample_y = py + oy;
 
         if let Some(color) = sample_nearest(data, width, height, sample_x, sample_y) {
-            r_sum += color[0] as f32;
-            g_sum += color[1] as f32;
-            b_sum += color[2] as f32;
+            if linear {
+                r_sum += to_linear(color[0]);
+                g_sum += to_linear(color[1]);
+                b_sum += to_linear(color[2]);
+            } else {
+                r_sum += color[0] as f32;
+                g_sum += color[1] as f32;
+                b_sum += color[2] as f32;
+            }
             a_sum += color[3] as f32;
             sample_count += 1;
         }
@@ -206,6 +273,14 @@ pub fn sample_supersampling(
     }
 
     let count = sample_count as f32;
+    if linear {
+        return Some([
+            to_srgb(r_sum / count),
+            to_srgb(g_sum / count),
+            to_srgb(b_sum / count),
+            (a_sum / count) as u8,
+        ]);
+    }
     Some([
         (r_sum / count) as u8,
         (g_sum / count) as u8,
@@ -277,4 +352,19 @@ mod tests {
         let color = sample_supersampling(&data, width, height, 0.0, 0.0).unwrap();
         assert_eq!(color, [255, 0, 0, 255]);
     }
+
+    #[test]
+    fn test_linear_sampling() {
+        let (data, width, height) = create_test_image();
+
+        // 红通道一半为 255、一半为 0: 线性光平均后编码约为 188
+        let srgb = sample_bilinear(&data, width, height, 1.0, 1.0).unwrap();
+        let linear = sample_bilinear_linear(&data, width, height, 1.0, 1.0).unwrap();
+        assert_eq!(srgb[0], 127);
+        assert!((linear[0] as i32 - 188).abs() <= 1);
+        assert_eq!(linear[3], 255);
+
+        let color = sample_supersampling_linear(&data, width, height, 0.5, 0.0).unwrap();
+        assert!((color[0] as i32 - 188).abs() <= 1);
+    }
 }
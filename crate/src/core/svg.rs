@@ -0,0 +1,191 @@
+//! SVG 精灵
+//!
+//! SVG 精灵保留解析后的矢量文档：以请求尺寸栅格化为原始数据，
+//! 应用旋转 / 缩放变换时直接按变换后的尺寸重新栅格化，放大后依然清晰。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::{SpriteTransform, World};
+use crate::math::Matrix3x3;
+use crate::svg::SvgDocument;
+
+/// SVG 精灵存储 - 各属性分离为独立数组
+pub struct SvgStore {
+    /// 对应的精灵图ID
+    pub(super) sprite_ids: Vec<u32>,
+    /// 解析后的文档
+    pub(super) documents: Vec<SvgDocument>,
+}
+
+impl SvgStore {
+    pub(super) fn new() -> Self {
+        Self {
+            sprite_ids: Vec::new(),
+            documents: Vec::new(),
+        }
+    }
+
+    /// 查找精灵图对应的 SVG 状态索引
+    pub(super) fn find(&self, sprite_id: u32) -> Option<usize> {
+        self.sprite_ids.iter().position(|&id| id == sprite_id)
+    }
+}
+
+/// 将文档的固有尺寸换算为像素尺寸 (至少 1 像素)
+fn intrinsic_size(document: &SvgDocument) -> (u32, u32) {
+    (
+        (document.width.ceil() as u32).max(1),
+        (document.height.ceil() as u32).max(1),
+    )
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== SVG 精灵 ==========
+
+    /// 从 SVG 文本创建精灵图
+    ///
+    /// 支持 path、rect、circle、ellipse、line、polyline、polygon 与带变换的分组，
+    /// 纯色填充 / 描边及不透明度。`width` / `height` 为 0 时使用文档的固有尺寸。
+    /// 解析失败时返回 None。
+    pub fn create_svg_sprite(&mut self, svg: &str, width: u32, height: u32) -> Option<u32> {
+        let document = SvgDocument::parse(svg)?;
+        let (iw, ih) = intrinsic_size(&document);
+        let width = if width == 0 { iw } else { width };
+        let height = if height == 0 { ih } else { height };
+        let image = document.render_to_size(width, height)?;
+        let id = self.sprites.add(image.data, width, height);
+        self.svgs.sprite_ids.push(id);
+        self.svgs.documents.push(document);
+        Some(id)
+    }
+
+    /// 设置 SVG 精灵的栅格化尺寸 (不含变换)，当前变换会在新尺寸上重新应用
+    pub fn set_svg_sprite_size(&mut self, id: u32, width: u32, height: u32) {
+        let Some(i) = self.svgs.find(id) else {
+            return;
+        };
+        if !self.sprites.is_active(id) || width == 0 || height == 0 {
+            return;
+        }
+        if let Some(image) = self.svgs.documents[i].render_to_size(width, height) {
+            self.set_sprite_source(id, image.data, width, height);
+        }
+    }
+}
+
+impl World {
+    /// 若精灵图是 SVG 精灵，则按变换直接重新栅格化显示数据
+    ///
+    /// 返回 false 表示不是 SVG 精灵 (或渲染失败)，由调用者走普通的像素重采样。
+    pub(super) fn apply_svg_transform(&mut self, id: u32, transform: SpriteTransform) -> bool {
+        let Some(i) = self.svgs.find(id) else {
+            return false;
+        };
+        let idx = id as usize;
+        let width = self.sprites.original_widths[idx] as f32;
+        let height = self.sprites.original_heights[idx] as f32;
+
+        let (angle, sx, sy) = transform.parts();
+
+        // 与像素重采样路径保持相同的输出尺寸
+        let (new_width, new_height) = match transform {
+            SpriteTransform::Scale(..) | SpriteTransform::None => (
+                (width * sx.abs()).round() as u32,
+                (height * sy.abs()).round() as u32,
+            ),
+            _ => {
+                let (cos_a, sin_a) = (angle.cos().abs(), angle.sin().abs());
+                let (w, h) = (width * sx.abs(), height * sy.abs());
+                (
+                    (w * cos_a + h * sin_a).ceil() as u32,
+                    (w * sin_a + h * cos_a).ceil() as u32,
+                )
+            }
+        };
+        if new_width == 0 || new_height == 0 {
+            return false;
+        }
+
+        // 文档视口 → 原始像素 → (以中心为原点) 缩放、旋转 → 输出像素
+        let document = &self.svgs.documents[i];
+        let matrix = match transform {
+            SpriteTransform::Scale(..) | SpriteTransform::None => Matrix3x3::scale(
+                new_width as f32 / document.width,
+                new_height as f32 / document.height,
+            ),
+            _ => Matrix3x3::translation(new_width as f32 / 2.0, new_height as f32 / 2.0)
+                .multiply(&Matrix3x3::rotation(-angle))
+                .multiply(&Matrix3x3::scale(sx, sy))
+                .multiply(&Matrix3x3::translation(-width / 2.0, -height / 2.0))
+                .multiply(&Matrix3x3::scale(
+                    width / document.width,
+                    height / document.height,
+                )),
+        };
+        let Some(image) = document.render(&matrix, new_width, new_height) else {
+            return false;
+        };
+
+        self.sprites
+            .set_display(idx, image.data, new_width, new_height);
+        self.sprites.transforms[idx] = transform;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICON: &str =
+        r#"<svg viewBox="0 0 8 8"><rect x="2" y="2" width="4" height="4" fill="red"/></svg>"#;
+
+    fn display_pixel(world: &World, id: u32, x: u32, y: u32) -> [u8; 4] {
+        let idx = id as usize;
+        let i = ((y * world.sprites.display_widths[idx] + x) * 4) as usize;
+        world.sprites.display_data[idx][i..i + 4]
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_create_svg_sprite() {
+        let mut world = World::new(64, 64);
+        let id = world.create_svg_sprite(ICON, 0, 0).unwrap();
+        assert_eq!(world.sprites.original_widths[id as usize], 8);
+        let big = world.create_svg_sprite(ICON, 32, 16).unwrap();
+        assert_eq!(world.sprites.display_widths[big as usize], 32);
+        assert_eq!(world.sprites.display_heights[big as usize], 16);
+        assert!(world.create_svg_sprite("not svg", 8, 8).is_none());
+    }
+
+    #[test]
+    fn test_scale_rerasterizes_sharp_edges() {
+        let mut world = World::new(64, 64);
+        let id = world.create_svg_sprite(ICON, 8, 8).unwrap();
+        world.apply_sprite_scale(id, 4.0, 4.0);
+        assert_eq!(world.sprites.display_widths[id as usize], 32);
+        // 边缘像素完全覆盖或完全透明，没有双线性插值产生的模糊过渡
+        assert_eq!(display_pixel(&world, id, 8, 16), [255, 0, 0, 255]);
+        assert_eq!(display_pixel(&world, id, 7, 16)[3], 0);
+        assert_eq!(
+            world.sprites.transforms[id as usize],
+            SpriteTransform::Scale(4.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn test_rotation_and_resize() {
+        let mut world = World::new(64, 64);
+        let id = world.create_svg_sprite(ICON, 8, 8).unwrap();
+        world.apply_sprite_rotation(id, std::f32::consts::FRAC_PI_4);
+        let w = world.sprites.display_widths[id as usize];
+        assert_eq!(w, 12);
+        assert_eq!(display_pixel(&world, id, 6, 6), [255, 0, 0, 255]);
+
+        world.set_svg_sprite_size(id, 16, 16);
+        assert_eq!(world.sprites.original_widths[id as usize], 16);
+        assert_eq!(world.sprites.display_widths[id as usize], 23);
+    }
+}
@@ -0,0 +1,481 @@
+//! 文字精灵
+//!
+//! 文字精灵是普通精灵图的一种：文本、字号、颜色、对齐或最大宽度变化时重新排版，
+//! 并将合成结果写入精灵图原始数据，因此同样参与 z-index 排序与变换。
+//! 字体可以是 BMFont 位图字体，也可以是 TTF / OTF 矢量字体。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::image::{decode_image, Image};
+use crate::text::{clamp_size, layout_text, BitmapFont, OutlineFont, TextAlign};
+
+/// 矢量字体文字精灵的默认字号 (像素)
+const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// 已加载的字体
+pub(super) enum Font {
+    /// BMFont 位图字体
+    Bitmap(BitmapFont),
+    /// TTF / OTF 矢量字体
+    Outline(Box<OutlineFont>),
+}
+
+/// 字体存储 - 各属性分离为独立数组
+pub struct FontStore {
+    /// 字体
+    pub(super) fonts: Vec<Font>,
+    /// 位图字体的页面图像 (未加载的页面为 None，矢量字体为空)
+    pub(super) pages: Vec<Vec<Option<Image>>>,
+}
+
+impl FontStore {
+    pub(super) fn new() -> Self {
+        Self {
+            fonts: Vec::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// 添加字体，返回ID (索引)
+    fn add(&mut self, font: Font) -> u32 {
+        let id = self.fonts.len() as u32;
+        let page_count = match &font {
+            Font::Bitmap(bitmap) => bitmap.pages.len(),
+            Font::Outline(_) => 0,
+        };
+        self.pages.push(vec![None; page_count]);
+        self.fonts.push(font);
+        id
+    }
+
+    /// 获取位图字体
+    fn bitmap(&self, id: u32) -> Option<&BitmapFont> {
+        match self.fonts.get(id as usize)? {
+            Font::Bitmap(bitmap) => Some(bitmap),
+            Font::Outline(_) => None,
+        }
+    }
+
+    /// 检查字体是否存在
+    fn exists(&self, id: u32) -> bool {
+        (id as usize) < self.fonts.len()
+    }
+}
+
+/// 文字状态存储 - 每个文字精灵一项
+pub struct TextStore {
+    /// 所属精灵图ID
+    pub(super) sprite_ids: Vec<u32>,
+    /// 使用的字体ID
+    pub(super) font_ids: Vec<u32>,
+    /// 文本内容
+    pub(super) texts: Vec<String>,
+    /// 文字颜色 (与字形像素相乘)
+    pub(super) colors: Vec<[u8; 4]>,
+    /// 字号 (像素，仅矢量字体使用)
+    pub(super) font_sizes: Vec<f32>,
+    /// 对齐方式
+    pub(super) aligns: Vec<TextAlign>,
+    /// 最大行宽 (0 表示不自动换行)
+    pub(super) max_widths: Vec<f32>,
+    /// 排版后的文本块尺寸
+    pub(super) bounds: Vec<[f32; 2]>,
+}
+
+impl TextStore {
+    pub(super) fn new() -> Self {
+        Self {
+            sprite_ids: Vec::new(),
+            font_ids: Vec::new(),
+            texts: Vec::new(),
+            colors: Vec::new(),
+            font_sizes: Vec::new(),
+            aligns: Vec::new(),
+            max_widths: Vec::new(),
+            bounds: Vec::new(),
+        }
+    }
+
+    /// 查找精灵图对应的文字状态索引
+    pub(super) fn find(&self, sprite_id: u32) -> Option<usize> {
+        self.sprite_ids.iter().position(|&id| id == sprite_id)
+    }
+
+    fn add(&mut self, sprite_id: u32, font_id: u32, text: &str) -> usize {
+        self.sprite_ids.push(sprite_id);
+        self.font_ids.push(font_id);
+        self.texts.push(text.to_string());
+        self.colors.push([255, 255, 255, 255]);
+        self.font_sizes.push(DEFAULT_FONT_SIZE);
+        self.aligns.push(TextAlign::default());
+        self.max_widths.push(0.0);
+        self.bounds.push([0.0, 0.0]);
+        self.sprite_ids.len() - 1
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 字体 ==========
+
+    /// 加载 BMFont 字体描述 (文本或二进制 `.fnt`)
+    ///
+    /// 页面图像需随后通过 `set_font_page` 提供。解析失败时返回 None。
+    pub fn load_bitmap_font(&mut self, fnt: &[u8]) -> Option<u32> {
+        let font = BitmapFont::parse(fnt)?;
+        Some(self.fonts.add(Font::Bitmap(font)))
+    }
+
+    /// 加载 TTF / OTF 矢量字体 (字体集合取第一个字体)
+    ///
+    /// 解析失败时返回 None。
+    pub fn load_font(&mut self, bytes: &[u8]) -> Option<u32> {
+        let font = OutlineFont::parse(bytes)?;
+        Some(self.fonts.add(Font::Outline(Box::new(font))))
+    }
+
+    /// 获取字体页面数 (矢量字体为 0)
+    pub fn get_font_page_count(&self, font_id: u32) -> u32 {
+        self.fonts
+            .bitmap(font_id)
+            .map_or(0, |f| f.pages.len() as u32)
+    }
+
+    /// 获取字体页面图像的文件名 (用于 JS 端加载)
+    pub fn get_font_page_file(&self, font_id: u32, page: u32) -> Option<String> {
+        self.fonts
+            .bitmap(font_id)?
+            .pages
+            .get(page as usize)
+            .cloned()
+    }
+
+    /// 获取矢量字体在指定字号下已缓存的字形数 (用于诊断图集占用)
+    pub fn get_font_cached_glyph_count(&self, font_id: u32, size: f32) -> u32 {
+        match self.fonts.fonts.get(font_id as usize) {
+            Some(Font::Outline(font)) => font.atlas(size).map_or(0, |a| a.glyph_count() as u32),
+            _ => 0,
+        }
+    }
+
+    /// 设置字体页面图像 (PNG / QOI / BMP / TGA 文件字节)
+    ///
+    /// 解码失败或页面不存在时返回 false。
+    pub fn set_font_page(&mut self, font_id: u32, page: u32, bytes: &[u8]) -> bool {
+        match decode_image(bytes) {
+            Some(image) => self.store_font_page(font_id, page, image),
+            None => false,
+        }
+    }
+
+    /// 设置字体页面图像 (RGBA 像素)
+    pub fn set_font_page_rgba(
+        &mut self,
+        font_id: u32,
+        page: u32,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> bool {
+        if data.len() != (width * height * 4) as usize {
+            return false;
+        }
+        let image = Image {
+            width,
+            height,
+            data: data.to_vec(),
+        };
+        self.store_font_page(font_id, page, image)
+    }
+
+    // ========== 文字精灵 ==========
+
+    /// 创建文字精灵图
+    ///
+    /// 默认白色、左对齐、不自动换行，矢量字体默认字号 16。字体不存在时返回 None。
+    pub fn create_text_sprite(&mut self, font_id: u32, text: &str) -> Option<u32> {
+        if !self.fonts.exists(font_id) {
+            return None;
+        }
+        let id = self.sprites.add(vec![0u8; 4], 1, 1);
+        let i = self.texts.add(id, font_id, text);
+        self.refresh_text(i);
+        Some(id)
+    }
+
+    /// 设置文本内容
+    pub fn set_text(&mut self, sprite_id: u32, text: &str) {
+        if let Some(i) = self.texts.find(sprite_id) {
+            if self.texts.texts[i] != text {
+                self.texts.texts[i] = text.to_string();
+                self.refresh_text(i);
+            }
+        }
+    }
+
+    /// 获取文本内容
+    pub fn get_text(&self, sprite_id: u32) -> Option<String> {
+        self.texts
+            .find(sprite_id)
+            .map(|i| self.texts.texts[i].clone())
+    }
+
+    /// 设置字号 (像素，仅对矢量字体生效，范围 1 ~ 512)
+    pub fn set_text_font_size(&mut self, sprite_id: u32, size: f32) {
+        if let Some(i) = self.texts.find(sprite_id) {
+            let size = clamp_size(size);
+            if self.texts.font_sizes[i] != size {
+                self.texts.font_sizes[i] = size;
+                self.refresh_text(i);
+            }
+        }
+    }
+
+    /// 获取字号 (文字精灵不存在时返回 0)
+    pub fn get_text_font_size(&self, sprite_id: u32) -> f32 {
+        self.texts
+            .find(sprite_id)
+            .map_or(0.0, |i| self.texts.font_sizes[i])
+    }
+
+    /// 设置文字颜色 (与字形像素相乘)
+    pub fn set_text_color(&mut self, sprite_id: u32, r: u8, g: u8, b: u8, a: u8) {
+        if let Some(i) = self.texts.find(sprite_id) {
+            if self.texts.colors[i] != [r, g, b, a] {
+                self.texts.colors[i] = [r, g, b, a];
+                self.refresh_text(i);
+            }
+        }
+    }
+
+    /// 设置对齐方式
+    ///
+    /// `align`: 0 = 左对齐, 1 = 居中, 2 = 右对齐。
+    pub fn set_text_align(&mut self, sprite_id: u32, align: u8) {
+        if let Some(i) = self.texts.find(sprite_id) {
+            let align = TextAlign::from_u8(align);
+            if self.texts.aligns[i] != align {
+                self.texts.aligns[i] = align;
+                self.refresh_text(i);
+            }
+        }
+    }
+
+    /// 获取对齐方式 (文字精灵不存在时返回 0)
+    pub fn get_text_align(&self, sprite_id: u32) -> u8 {
+        self.texts
+            .find(sprite_id)
+            .map_or(0, |i| self.texts.aligns[i].to_u8())
+    }
+
+    /// 设置最大行宽 (像素)，超出时自动换行；0 表示不换行，非有限值被忽略
+    pub fn set_text_max_width(&mut self, sprite_id: u32, max_width: f32) {
+        if !max_width.is_finite() {
+            return;
+        }
+        if let Some(i) = self.texts.find(sprite_id) {
+            let max_width = max_width.max(0.0);
+            if self.texts.max_widths[i] != max_width {
+                self.texts.max_widths[i] = max_width;
+                self.refresh_text(i);
+            }
+        }
+    }
+
+    /// 获取排版后的文本块尺寸 [宽, 高]
+    pub fn get_text_size(&self, sprite_id: u32) -> Option<Vec<f32>> {
+        self.texts
+            .find(sprite_id)
+            .map(|i| self.texts.bounds[i].to_vec())
+    }
+}
+
+impl World {
+    /// 保存页面图像，并重新合成使用该字体的文字精灵
+    fn store_font_page(&mut self, font_id: u32, page: u32, image: Image) -> bool {
+        let Some(slot) = self
+            .fonts
+            .pages
+            .get_mut(font_id as usize)
+            .and_then(|pages| pages.get_mut(page as usize))
+        else {
+            return false;
+        };
+        *slot = Some(image);
+
+        for i in 0..self.texts.sprite_ids.len() {
+            if self.texts.font_ids[i] == font_id {
+                self.refresh_text(i);
+            }
+        }
+        true
+    }
+
+    /// 重新排版并合成文字精灵
+    pub(super) fn refresh_text(&mut self, i: usize) {
+        let sprite_id = self.texts.sprite_ids[i];
+        if !self.sprites.is_active(sprite_id) {
+            return;
+        }
+
+        let font_id = self.texts.font_ids[i] as usize;
+        let (text, max_width, align) = (
+            &self.texts.texts[i],
+            self.texts.max_widths[i],
+            self.texts.aligns[i],
+        );
+        let color = self.texts.colors[i];
+
+        let (layout, image) = match &mut self.fonts.fonts[font_id] {
+            Font::Bitmap(font) => {
+                let layout = layout_text(font, text, max_width, align);
+                let image = font.render(&layout, &self.fonts.pages[font_id], color);
+                (layout, image)
+            }
+            Font::Outline(font) => {
+                let size = self.texts.font_sizes[i];
+                let layout = font
+                    .at_size(size)
+                    .map(|metrics| layout_text(&metrics, text, max_width, align))
+                    .unwrap_or_default();
+                let image = font.render(&layout, size, color);
+                (layout, image)
+            }
+        };
+
+        // 超出位图尺寸上限时以 1x1 透明图像代替
+        let image = image.unwrap_or_else(|| Image {
+            width: 1,
+            height: 1,
+            data: vec![0; 4],
+        });
+        self.texts.bounds[i] = [layout.width, layout.height];
+        self.set_sprite_source(sprite_id, image.data, image.width, image.height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &str = "common lineHeight=4 base=3\n\
+        page id=0 file=\"page.png\"\n\
+        char id=65 x=0 y=0 width=2 height=2 xoffset=0 yoffset=0 xadvance=3 page=0 chnl=15\n";
+
+    fn world_with_font() -> (World, u32) {
+        let mut world = World::new(20, 20);
+        let font = world.load_bitmap_font(FONT.as_bytes()).unwrap();
+        assert!(world.set_font_page_rgba(font, 0, &[255u8; 2 * 2 * 4], 2, 2));
+        (world, font)
+    }
+
+    #[test]
+    fn test_font_pages() {
+        let mut world = World::new(20, 20);
+        let font = world.load_bitmap_font(FONT.as_bytes()).unwrap();
+        assert_eq!(world.get_font_page_count(font), 1);
+        assert_eq!(
+            world.get_font_page_file(font, 0),
+            Some("page.png".to_string())
+        );
+        assert!(!world.set_font_page_rgba(font, 1, &[0u8; 4], 1, 1));
+        assert!(!world.set_font_page(font, 0, b"not an image"));
+    }
+
+    #[test]
+    fn test_text_sprite_relayout() {
+        let (mut world, font) = world_with_font();
+        let id = world.create_text_sprite(font, "AA").unwrap();
+        assert_eq!(world.get_text_size(id), Some(vec![6.0, 4.0]));
+        assert_eq!(world.sprites.original_widths[id as usize], 6);
+
+        world.set_text(id, "A\nA");
+        assert_eq!(world.get_text(id), Some("A\nA".to_string()));
+        assert_eq!(world.get_text_size(id), Some(vec![3.0, 8.0]));
+        assert_eq!(world.sprites.display_heights[id as usize], 8);
+    }
+
+    #[test]
+    fn test_text_color_and_render() {
+        let (mut world, font) = world_with_font();
+        let id = world.create_text_sprite(font, "A").unwrap();
+        world.set_text_color(id, 0, 255, 0, 255);
+        assert_eq!(
+            &world.sprites.original_data[id as usize][0..4],
+            &[0, 255, 0, 255]
+        );
+
+        world.set_background_color(0, 0, 0, 255);
+        world.add_to_scene(id);
+        world.render();
+        assert!(world.scenes.data[0]
+            .chunks_exact(4)
+            .any(|px| px == [0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn test_page_loaded_later_updates_text() {
+        let mut world = World::new(20, 20);
+        let font = world.load_bitmap_font(FONT.as_bytes()).unwrap();
+        let id = world.create_text_sprite(font, "A").unwrap();
+        assert_eq!(world.sprites.original_data[id as usize][3], 0);
+
+        world.set_font_page_rgba(font, 0, &[255u8; 2 * 2 * 4], 2, 2);
+        assert_eq!(world.sprites.original_data[id as usize][3], 255);
+    }
+
+    #[test]
+    fn test_outline_text_sprite() {
+        let mut world = World::new(40, 40);
+        assert!(world.load_font(b"not a font").is_none());
+        let font = world.load_font(&crate::text::test_font()).unwrap();
+        assert_eq!(world.get_font_page_count(font), 0);
+
+        let id = world.create_text_sprite(font, "AA").unwrap();
+        assert_eq!(world.get_text_font_size(id), 16.0);
+        world.set_text_font_size(id, 10.0);
+        assert_eq!(world.get_text_size(id), Some(vec![12.0, 10.0]));
+        assert_eq!(
+            world.sprites.original_data[id as usize][(4 * 6 + 2) * 4 + 3],
+            255
+        );
+
+        world.set_text_font_size(id, 20.0);
+        assert_eq!(world.get_text_size(id), Some(vec![24.0, 20.0]));
+        assert_eq!(world.sprites.original_widths[id as usize], 24);
+        assert_eq!(world.get_font_cached_glyph_count(font, 10.0), 1);
+        assert_eq!(world.get_font_cached_glyph_count(font, 20.0), 1);
+        assert_eq!(world.get_font_cached_glyph_count(font, 30.0), 0);
+    }
+
+    #[test]
+    fn test_wrap_and_align() {
+        let (mut world, font) = world_with_font();
+        let id = world.create_text_sprite(font, "AAAA").unwrap();
+        world.set_text_max_width(id, 7.0);
+        assert_eq!(world.get_text_size(id), Some(vec![7.0, 8.0]));
+
+        world.set_text_align(id, 2);
+        assert_eq!(world.get_text_align(id), 2);
+        // 右对齐: 第一行 "AA" 宽 6，从 x=1 开始
+        assert_eq!(world.sprites.original_data[id as usize][3], 0);
+        assert_eq!(world.sprites.original_data[id as usize][7], 255);
+        assert!(world.create_text_sprite(99, "A").is_none());
+    }
+
+    #[test]
+    fn test_huge_max_width() {
+        let (mut world, font) = world_with_font();
+        let id = world.create_text_sprite(font, "AAAA").unwrap();
+        world.set_text_max_width(id, f32::INFINITY);
+        world.set_text_max_width(id, f32::NAN);
+        assert_eq!(world.texts.max_widths[0], 0.0);
+        assert_eq!(world.get_text_size(id), Some(vec![12.0, 4.0]));
+
+        world.set_text_max_width(id, 1e30);
+        let size = world.get_text_size(id).unwrap();
+        assert_eq!(size[0], crate::image::MAX_DIMENSION as f32);
+        assert!(world.sprites.original_widths[id as usize] <= crate::image::MAX_DIMENSION);
+    }
+}
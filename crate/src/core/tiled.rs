@@ -0,0 +1,534 @@
+//! Tiled 地图导入
+//!
+//! 加载后的地图保留解析结果：外部瓦片集文件与纹理图像由调用者按路径读取后补充
+//! (引擎不访问文件系统)，准备完成后可实例化到场景中——每个瓦片图层按所用瓦片集
+//! 拆分为若干瓦片地图。对象图层中的对象以数据形式查询，由调用者决定如何使用。
+
+use wasm_bindgen::prelude::*;
+
+use super::world::World;
+use crate::image::{decode_image, Image};
+use crate::tiled::{TiledMap, TiledObject, TilesetData};
+
+/// Tiled 地图存储 - 各属性分离为独立数组
+pub struct TiledStore {
+    /// 解析后的地图
+    pub(super) maps: Vec<TiledMap>,
+    /// 各瓦片集的纹理
+    pub(super) images: Vec<Vec<Option<Image>>>,
+    /// 各瓦片集已创建的引擎瓦片集ID (多次实例化时共享)
+    pub(super) tileset_ids: Vec<Vec<Option<u32>>>,
+}
+
+impl TiledStore {
+    pub(super) fn new() -> Self {
+        Self {
+            maps: Vec::new(),
+            images: Vec::new(),
+            tileset_ids: Vec::new(),
+        }
+    }
+
+    /// 添加地图，返回ID (索引)
+    fn add(&mut self, map: TiledMap) -> u32 {
+        let id = self.maps.len() as u32;
+        self.images.push(vec![None; map.tilesets.len()]);
+        self.tileset_ids.push(vec![None; map.tilesets.len()]);
+        self.maps.push(map);
+        id
+    }
+
+    fn get(&self, id: u32) -> Option<&TiledMap> {
+        self.maps.get(id as usize)
+    }
+
+    fn object(&self, id: u32, index: u32) -> Option<&TiledObject> {
+        self.get(id)?.objects.get(index as usize)
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== Tiled 地图 ==========
+
+    /// 加载 Tiled 导出的地图 (TMX 或 JSON，自动识别)
+    ///
+    /// 仅支持正交、非无限地图；图层数据可为 CSV、base64 或 base64 + zlib / gzip。
+    /// 解析失败时返回 None。
+    pub fn load_tiled_map(&mut self, text: &str) -> Option<u32> {
+        let map = TiledMap::parse(text)?;
+        Some(self.tiled.add(map))
+    }
+
+    /// 获取地图尺寸 [列数, 行数, 格子宽度, 格子高度]
+    pub fn get_tiled_map_size(&self, map_id: u32) -> Option<Vec<u32>> {
+        let map = self.tiled.get(map_id)?;
+        Some(vec![map.columns, map.rows, map.tile_width, map.tile_height])
+    }
+
+    /// 获取瓦片图层名称 (按绘制顺序)
+    pub fn get_tiled_layer_names(&self, map_id: u32) -> Vec<String> {
+        self.tiled.get(map_id).map_or_else(Vec::new, |map| {
+            map.layers.iter().map(|l| l.name.clone()).collect()
+        })
+    }
+
+    /// 获取地图引用的瓦片集数量
+    pub fn get_tiled_tileset_count(&self, map_id: u32) -> u32 {
+        self.tiled
+            .get(map_id)
+            .map_or(0, |map| map.tilesets.len() as u32)
+    }
+
+    /// 获取外部瓦片集的文件路径 (内嵌瓦片集返回 None)
+    pub fn get_tiled_tileset_source(&self, map_id: u32, index: u32) -> Option<String> {
+        self.tiled
+            .get(map_id)?
+            .tilesets
+            .get(index as usize)?
+            .source
+            .clone()
+    }
+
+    /// 提供外部瓦片集文件内容 (TSX 或 JSON)，解析失败时返回 false
+    pub fn set_tiled_tileset(&mut self, map_id: u32, index: u32, text: &str) -> bool {
+        let (m, i) = (map_id as usize, index as usize);
+        if self
+            .tiled
+            .get(map_id)
+            .is_none_or(|map| i >= map.tilesets.len())
+        {
+            return false;
+        }
+        let Some(data) = TilesetData::parse(text) else {
+            return false;
+        };
+        self.tiled.maps[m].tilesets[i].data = Some(data);
+        self.tiled.tileset_ids[m][i] = None;
+        true
+    }
+
+    /// 获取瓦片集纹理的文件路径 (瓦片集内容尚未提供时返回 None)
+    pub fn get_tiled_tileset_image(&self, map_id: u32, index: u32) -> Option<String> {
+        let tileset = self.tiled.get(map_id)?.tilesets.get(index as usize)?;
+        tileset.data.as_ref()?.image.clone()
+    }
+
+    /// 提供瓦片集纹理 (图像文件字节)，瓦片集设置了透明色时对应像素变为透明
+    ///
+    /// 瓦片集内容尚未提供或解码失败时返回 false。
+    pub fn set_tiled_tileset_image(&mut self, map_id: u32, index: u32, bytes: &[u8]) -> bool {
+        let (m, i) = (map_id as usize, index as usize);
+        let Some(data) = self
+            .tiled
+            .get(map_id)
+            .and_then(|map| map.tilesets.get(i))
+            .and_then(|t| t.data.as_ref())
+        else {
+            return false;
+        };
+        let Some(mut image) = decode_image(bytes) else {
+            return false;
+        };
+        if let Some(key) = data.transparent {
+            for px in image.data.chunks_exact_mut(4) {
+                if px[..3] == key {
+                    px[3] = 0;
+                }
+            }
+        }
+        self.tiled.images[m][i] = Some(image);
+        self.tiled.tileset_ids[m][i] = None;
+        true
+    }
+
+    /// 将地图实例化到场景，返回创建的瓦片地图ID
+    ///
+    /// 每个瓦片图层按所用瓦片集拆分为瓦片地图，第 k 个图层的 z-index 为 `zindex + k`；
+    /// 地图左上角对齐场景左上角 (加上图层偏移)，图层不透明度与可见性一并应用，
+    /// 动画瓦片转换为瓦片集动画。所用瓦片集缺少内容、纹理，
+    /// 或瓦片尺寸与地图格子尺寸不一致时返回 None。
+    pub fn instantiate_tiled_map(
+        &mut self,
+        map_id: u32,
+        scene_id: u32,
+        zindex: i32,
+    ) -> Option<Vec<u32>> {
+        let map = self.tiled.get(map_id)?;
+        if !self.scenes.is_active(scene_id) {
+            return None;
+        }
+
+        // 各图层按瓦片集拆分的格子
+        let mut layers = Vec::new();
+        let mut used = vec![false; map.tilesets.len()];
+        for layer in &map.layers {
+            let mut parts: Vec<(usize, Vec<u32>)> = Vec::new();
+            for (i, &gid) in layer.cells.iter().enumerate() {
+                let Some(t) = map.tileset_for(gid) else {
+                    continue;
+                };
+                used[t] = true;
+                let part = match parts.iter().position(|(pt, _)| *pt == t) {
+                    Some(p) => p,
+                    None => {
+                        parts.push((t, vec![0; layer.cells.len()]));
+                        parts.len() - 1
+                    }
+                };
+                parts[part].1[i] = map.local_cell(gid, t);
+            }
+            parts.sort_by_key(|(t, _)| *t);
+            layers.push(parts);
+        }
+
+        // 为用到的瓦片集创建引擎瓦片集
+        let m = map_id as usize;
+        for t in (0..used.len()).filter(|&t| used[t]) {
+            if self.tiled.tileset_ids[m][t].is_some() {
+                continue;
+            }
+            let map = &self.tiled.maps[m];
+            let data = map.tilesets[t].data.as_ref()?;
+            if data.tile_width != map.tile_width || data.tile_height != map.tile_height {
+                return None;
+            }
+            let image = self.tiled.images[m][t].clone()?;
+            let data = data.clone();
+            let id = self.add_tileset(image, data.tile_width, data.tile_height)?;
+            self.set_tileset_spacing(id, data.margin, data.spacing);
+            for (tile, frames) in &data.animations {
+                let tiles: Vec<u32> = frames.iter().map(|f| f.0).collect();
+                let durations: Vec<f32> = frames.iter().map(|f| f.1 as f32 / 1000.0).collect();
+                self.set_tile_animation(id, *tile, &tiles, &durations);
+            }
+            self.tiled.tileset_ids[m][t] = Some(id);
+        }
+
+        let map = &self.tiled.maps[m];
+        let map_w = (map.columns * map.tile_width) as f32;
+        let map_h = (map.rows * map.tile_height) as f32;
+        let scene_w = self.scenes.widths[scene_id as usize] as f32;
+        let scene_h = self.scenes.heights[scene_id as usize] as f32;
+        let (columns, rows) = (map.columns, map.rows);
+        let settings: Vec<(f32, f32, f32, bool)> = map
+            .layers
+            .iter()
+            .map(|l| (l.offset_x, l.offset_y, l.opacity, l.visible))
+            .collect();
+
+        let mut ids = Vec::new();
+        for (k, parts) in layers.into_iter().enumerate() {
+            let (offset_x, offset_y, opacity, visible) = settings[k];
+            for (t, cells) in parts {
+                let tileset_id = self.tiled.tileset_ids[m][t]?;
+                let id = self.create_tilemap(tileset_id, columns, rows)?;
+                self.set_tiles(id, &cells);
+                self.set_tilemap_position(
+                    id,
+                    map_w / 2.0 - scene_w / 2.0 + offset_x,
+                    map_h / 2.0 - scene_h / 2.0 + offset_y,
+                );
+                self.set_tilemap_zindex(id, zindex.saturating_add(k as i32));
+                self.set_tilemap_opacity(id, opacity);
+                self.set_tilemap_visible(id, visible);
+                self.add_tilemap_to_scene(id, scene_id);
+                ids.push(id);
+            }
+        }
+        Some(ids)
+    }
+
+    // ========== Tiled 对象 ==========
+
+    /// 获取对象数量 (全部对象图层)
+    pub fn get_tiled_object_count(&self, map_id: u32) -> u32 {
+        self.tiled
+            .get(map_id)
+            .map_or(0, |map| map.objects.len() as u32)
+    }
+
+    /// 获取对象名称
+    pub fn get_tiled_object_name(&self, map_id: u32, index: u32) -> Option<String> {
+        Some(self.tiled.object(map_id, index)?.name.clone())
+    }
+
+    /// 获取对象类型 (class)
+    pub fn get_tiled_object_type(&self, map_id: u32, index: u32) -> Option<String> {
+        Some(self.tiled.object(map_id, index)?.class.clone())
+    }
+
+    /// 获取对象所在的对象图层名称
+    pub fn get_tiled_object_layer(&self, map_id: u32, index: u32) -> Option<String> {
+        Some(self.tiled.object(map_id, index)?.layer.clone())
+    }
+
+    /// 获取对象在 Tiled 中的ID
+    pub fn get_tiled_object_id(&self, map_id: u32, index: u32) -> Option<u32> {
+        Some(self.tiled.object(map_id, index)?.id)
+    }
+
+    /// 获取对象形状
+    ///
+    /// 0 = 矩形, 1 = 椭圆, 2 = 点, 3 = 多边形, 4 = 折线, 5 = 瓦片, 6 = 文字
+    pub fn get_tiled_object_shape(&self, map_id: u32, index: u32) -> Option<u8> {
+        Some(self.tiled.object(map_id, index)?.shape.to_u8())
+    }
+
+    /// 获取对象范围 [x, y, 宽度, 高度, 旋转角度(度)]
+    ///
+    /// 坐标为地图像素坐标 (原点在地图左上角)，瓦片对象的 (x, y) 为其左下角。
+    pub fn get_tiled_object_bounds(&self, map_id: u32, index: u32) -> Option<Vec<f32>> {
+        let o = self.tiled.object(map_id, index)?;
+        Some(vec![o.x, o.y, o.width, o.height, o.rotation])
+    }
+
+    /// 获取对象是否可见
+    pub fn get_tiled_object_visible(&self, map_id: u32, index: u32) -> Option<bool> {
+        Some(self.tiled.object(map_id, index)?.visible)
+    }
+
+    /// 获取瓦片对象的全局瓦片ID (含翻转标志，其他对象为 0)
+    pub fn get_tiled_object_gid(&self, map_id: u32, index: u32) -> Option<u32> {
+        Some(self.tiled.object(map_id, index)?.gid)
+    }
+
+    /// 获取多边形 / 折线顶点 [x0, y0, x1, y1, ...] (相对于对象坐标)
+    pub fn get_tiled_object_points(&self, map_id: u32, index: u32) -> Option<Vec<f32>> {
+        let o = self.tiled.object(map_id, index)?;
+        Some(o.points.iter().flatten().copied().collect())
+    }
+
+    /// 获取对象自定义属性名称
+    pub fn get_tiled_object_property_names(&self, map_id: u32, index: u32) -> Vec<String> {
+        self.tiled.object(map_id, index).map_or_else(Vec::new, |o| {
+            o.properties.iter().map(|(k, _)| k.clone()).collect()
+        })
+    }
+
+    /// 获取对象自定义属性值 (统一为字符串)
+    pub fn get_tiled_object_property(&self, map_id: u32, index: u32, name: &str) -> Option<String> {
+        let o = self.tiled.object(map_id, index)?;
+        o.properties
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+    use crate::image::{encode_png, DEFAULT_COMPRESSION};
+
+    /// 2x1 个 2x2 瓦片的纹理: 瓦片 0 为红色，瓦片 1 为洋红色 (透明色键)
+    fn tileset_png() -> Vec<u8> {
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            for x in 0..4 {
+                data.extend_from_slice(if x < 2 {
+                    &[255, 0, 0, 255]
+                } else {
+                    &[255, 0, 255, 255]
+                });
+            }
+        }
+        encode_png(&data, 4, 2, DEFAULT_COMPRESSION)
+    }
+
+    const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="3" height="2"
+     tilewidth="2" tileheight="2" infinite="0">
+ <tileset firstgid="1" source="tiles.tsx"/>
+ <layer id="1" name="ground" width="3" height="2">
+  <data encoding="csv">
+1,0,2,
+0,2147483649,0
+</data>
+ </layer>
+ <group name="upper" offsetx="2" opacity="0.5">
+  <layer id="2" name="deco" width="3" height="2" visible="0">
+   <data encoding="csv">0,0,0,0,0,1</data>
+  </layer>
+ </group>
+ <objectgroup name="spawns" offsety="1">
+  <object id="7" name="player" type="Spawn" x="1" y="2">
+   <properties>
+    <property name="health" type="int" value="3"/>
+    <property name="note">line one
+line two</property>
+   </properties>
+   <point/>
+  </object>
+  <object id="8" x="0" y="0">
+   <polygon points="0,0 4,0 4,2"/>
+  </object>
+ </objectgroup>
+</map>"#;
+
+    const TSX: &str = r#"<tileset name="tiles" tilewidth="2" tileheight="2" tilecount="2" columns="2">
+ <image source="tiles.png" trans="ff00ff" width="4" height="2"/>
+ <tile id="0"><animation><frame tileid="0" duration="100"/><frame tileid="1" duration="100"/></animation></tile>
+</tileset>"#;
+
+    #[test]
+    fn test_load_tmx() {
+        let mut world = World::new(8, 8);
+        let id = world.load_tiled_map(TMX).unwrap();
+        assert_eq!(world.get_tiled_map_size(id), Some(vec![3, 2, 2, 2]));
+        assert_eq!(world.get_tiled_layer_names(id), vec!["ground", "deco"]);
+        let map = &world.tiled.maps[id as usize];
+        assert_eq!(map.layers[0].cells, vec![1, 0, 2, 0, 0x8000_0001, 0]);
+        assert_eq!(map.layers[1].offset_x, 2.0);
+        assert_eq!(map.layers[1].opacity, 0.5);
+        assert!(!map.layers[1].visible);
+
+        assert_eq!(world.get_tiled_object_count(id), 2);
+        assert_eq!(
+            world.get_tiled_object_name(id, 0).as_deref(),
+            Some("player")
+        );
+        assert_eq!(world.get_tiled_object_type(id, 0).as_deref(), Some("Spawn"));
+        assert_eq!(
+            world.get_tiled_object_layer(id, 0).as_deref(),
+            Some("spawns")
+        );
+        assert_eq!(world.get_tiled_object_shape(id, 0), Some(2));
+        assert_eq!(
+            world.get_tiled_object_bounds(id, 0),
+            Some(vec![1.0, 3.0, 0.0, 0.0, 0.0])
+        );
+        assert_eq!(
+            world.get_tiled_object_property(id, 0, "health").as_deref(),
+            Some("3")
+        );
+        assert_eq!(
+            world.get_tiled_object_property(id, 0, "note").as_deref(),
+            Some("line one\nline two")
+        );
+        assert_eq!(world.get_tiled_object_shape(id, 1), Some(3));
+        assert_eq!(
+            world.get_tiled_object_points(id, 1),
+            Some(vec![0.0, 0.0, 4.0, 0.0, 4.0, 2.0])
+        );
+        assert!(world.get_tiled_object_name(id, 2).is_none());
+
+        assert!(world
+            .load_tiled_map("<map orientation=\"isometric\"/>")
+            .is_none());
+    }
+
+    #[test]
+    fn test_external_tileset_and_instantiate() {
+        let mut world = World::new(8, 8);
+        world.set_background_color(0, 0, 0, 255);
+        let id = world.load_tiled_map(TMX).unwrap();
+        assert_eq!(
+            world.get_tiled_tileset_source(id, 0).as_deref(),
+            Some("tiles.tsx")
+        );
+        // 瓦片集未准备好时无法实例化
+        assert!(world.instantiate_tiled_map(id, 0, 0).is_none());
+        assert!(world.get_tiled_tileset_image(id, 0).is_none());
+        assert!(!world.set_tiled_tileset_image(id, 0, &tileset_png()));
+
+        assert!(world.set_tiled_tileset(id, 0, TSX));
+        assert_eq!(
+            world.get_tiled_tileset_image(id, 0).as_deref(),
+            Some("tiles.png")
+        );
+        assert!(world.set_tiled_tileset_image(id, 0, &tileset_png()));
+
+        let maps = world.instantiate_tiled_map(id, 0, 5).unwrap();
+        assert_eq!(maps.len(), 2);
+        assert_eq!(world.tilemaps.zindexes[maps[1] as usize], 6);
+        assert!(!world.tilemaps.visible[maps[1] as usize]);
+        world.render();
+        // 地图左上角对齐场景左上角
+        assert_eq!(scene_pixel(&world, 0, 0), [255, 0, 0, 255]);
+        // 洋红色瓦片按色键变为透明
+        assert_eq!(scene_pixel(&world, 4, 0), [0, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 2, 2), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 6, 0), [0, 0, 0, 255]);
+
+        // 动画瓦片 0 → 1 (透明)
+        world.update(0.15);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_load_json_with_zlib_data() {
+        // 3x2 个格子，数据为 [1, 0, 2, 0, 2147483649, 0] 的 zlib 压缩 base64
+        let raw: Vec<u8> = [1u32, 0, 2, 0, 0x8000_0001, 0]
+            .iter()
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&raw, 6);
+        let json = format!(
+            r##"{{"orientation":"orthogonal","infinite":false,"width":3,"height":2,
+            "tilewidth":2,"tileheight":2,
+            "tilesets":[{{"firstgid":1,"name":"tiles","tilewidth":2,"tileheight":2,
+                "tilecount":2,"columns":2,"image":"tiles.png","transparentcolor":"#ff00ff"}}],
+            "layers":[
+              {{"type":"tilelayer","name":"ground","width":3,"height":2,
+                "encoding":"base64","compression":"zlib","data":"{}"}},
+              {{"type":"group","name":"g","offsety":4,"layers":[
+                {{"type":"objectgroup","name":"items","objects":[
+                  {{"id":3,"name":"coin","type":"","class":"Coin","gid":2,
+                    "x":2,"y":4,"width":2,"height":2,
+                    "properties":[{{"name":"value","type":"float","value":2.5}},
+                                  {{"name":"shiny","type":"bool","value":true}}]}},
+                  {{"id":4,"ellipse":true,"x":0,"y":0,"width":1,"height":1}}]}}]}},
+              {{"type":"imagelayer","name":"sky"}}]}}"##,
+            base64(&compressed)
+        );
+        let mut world = World::new(6, 4);
+        let id = world.load_tiled_map(&json).unwrap();
+        assert_eq!(
+            world.tiled.maps[id as usize].layers[0].cells,
+            vec![1, 0, 2, 0, 0x8000_0001, 0]
+        );
+        assert_eq!(world.get_tiled_tileset_source(id, 0), None);
+        assert_eq!(world.get_tiled_object_type(id, 0).as_deref(), Some("Coin"));
+        assert_eq!(world.get_tiled_object_shape(id, 0), Some(5));
+        assert_eq!(world.get_tiled_object_gid(id, 0), Some(2));
+        assert_eq!(world.get_tiled_object_bounds(id, 0).unwrap()[1], 8.0);
+        assert_eq!(
+            world.get_tiled_object_property_names(id, 0),
+            vec!["value", "shiny"]
+        );
+        assert_eq!(
+            world.get_tiled_object_property(id, 0, "value").as_deref(),
+            Some("2.5")
+        );
+        assert_eq!(world.get_tiled_object_shape(id, 1), Some(1));
+
+        assert!(world.set_tiled_tileset_image(id, 0, &tileset_png()));
+        world.set_background_color(0, 0, 0, 255);
+        assert_eq!(world.instantiate_tiled_map(id, 0, 0).unwrap().len(), 1);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 4, 0), [0, 0, 0, 255]);
+    }
+
+    fn base64(bytes: &[u8]) -> String {
+        const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let mut bits = 0u32;
+            for (i, &b) in chunk.iter().enumerate() {
+                bits |= (b as u32) << (16 - 8 * i);
+            }
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(TABLE[(bits >> (18 - 6 * i) & 63) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,656 @@
+//! 瓦片地图
+//!
+//! 瓦片集 (一张按网格切分的纹理) 与瓦片地图 (引用瓦片集的格子数组) 分开存储：
+//! 同一瓦片集可被多个地图图层共享。地图由 `World::render` 直接绘制，
+//! 只遍历与场景可见区域相交的格子，与精灵图按 z-index 交错绘制
+//! (同一 z-index 时地图先于精灵图绘制)。
+//!
+//! 格子值编码与 Tiled 一致：低 29 位为瓦片索引 + 1 (0 表示空格子)，
+//! 最高三位依次为水平翻转、垂直翻转、对角翻转标志。
+
+use wasm_bindgen::prelude::*;
+
+use super::hdr::{blend_into, Emission};
+use super::world::World;
+use crate::image::{decode_image, pixel_len, Image};
+
+/// 水平翻转标志
+pub const FLIP_HORIZONTAL: u32 = 0x8000_0000;
+/// 垂直翻转标志
+pub const FLIP_VERTICAL: u32 = 0x4000_0000;
+/// 对角翻转标志 (沿左上-右下对角线转置)
+pub const FLIP_DIAGONAL: u32 = 0x2000_0000;
+/// 格子值中的瓦片部分
+const TILE_MASK: u32 = 0x1FFF_FFFF;
+
+/// 动画瓦片帧最短时长 (秒)
+const MIN_TILE_FRAME_DURATION: f32 = 0.001;
+
+/// 动画瓦片: 按帧序列轮换显示的瓦片
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TileAnimation {
+    /// 被替换的瓦片索引
+    pub(super) tile: u32,
+    /// 帧瓦片索引
+    pub(super) frames: Vec<u32>,
+    /// 帧时长 (秒)
+    pub(super) durations: Vec<f32>,
+}
+
+impl TileAnimation {
+    /// 计算时间 `time` 时显示的瓦片
+    fn frame_at(&self, time: f32) -> u32 {
+        let total: f32 = self.durations.iter().sum();
+        let mut t = time.rem_euclid(total);
+        for (frame, duration) in self.frames.iter().zip(&self.durations) {
+            if t < *duration {
+                return *frame;
+            }
+            t -= duration;
+        }
+        self.frames[self.frames.len() - 1]
+    }
+}
+
+/// 瓦片集存储 - 各属性分离为独立数组
+pub struct TilesetStore {
+    /// 纹理
+    pub(super) images: Vec<Image>,
+    /// 瓦片宽度
+    pub(super) tile_widths: Vec<u32>,
+    /// 瓦片高度
+    pub(super) tile_heights: Vec<u32>,
+    /// 纹理四周边距
+    pub(super) margins: Vec<u32>,
+    /// 瓦片间距
+    pub(super) spacings: Vec<u32>,
+    /// 动画瓦片
+    pub(super) animations: Vec<Vec<TileAnimation>>,
+    /// 动画时钟 (秒)
+    pub(super) clocks: Vec<f32>,
+}
+
+impl TilesetStore {
+    pub(super) fn new() -> Self {
+        Self {
+            images: Vec::new(),
+            tile_widths: Vec::new(),
+            tile_heights: Vec::new(),
+            margins: Vec::new(),
+            spacings: Vec::new(),
+            animations: Vec::new(),
+            clocks: Vec::new(),
+        }
+    }
+
+    /// 添加瓦片集，返回ID (索引)
+    pub(super) fn add(&mut self, image: Image, tile_width: u32, tile_height: u32) -> u32 {
+        let id = self.images.len() as u32;
+        self.images.push(image);
+        self.tile_widths.push(tile_width);
+        self.tile_heights.push(tile_height);
+        self.margins.push(0);
+        self.spacings.push(0);
+        self.animations.push(Vec::new());
+        self.clocks.push(0.0);
+        id
+    }
+
+    pub(super) fn exists(&self, id: u32) -> bool {
+        (id as usize) < self.images.len()
+    }
+
+    /// 纹理中的列数
+    pub(super) fn columns(&self, id: u32) -> u32 {
+        let i = id as usize;
+        let (margin, spacing) = (self.margins[i], self.spacings[i]);
+        let usable = (self.images[i].width + spacing).saturating_sub(margin * 2);
+        usable / (self.tile_widths[i] + spacing)
+    }
+
+    /// 瓦片总数
+    pub(super) fn tile_count(&self, id: u32) -> u32 {
+        let i = id as usize;
+        let (margin, spacing) = (self.margins[i], self.spacings[i]);
+        let usable = (self.images[i].height + spacing).saturating_sub(margin * 2);
+        self.columns(id) * (usable / (self.tile_heights[i] + spacing))
+    }
+
+    /// 解析动画后实际显示的瓦片
+    fn resolve(&self, id: u32, tile: u32) -> u32 {
+        let i = id as usize;
+        self.animations[i]
+            .iter()
+            .find(|a| a.tile == tile)
+            .map_or(tile, |a| a.frame_at(self.clocks[i]))
+    }
+}
+
+/// 瓦片地图存储 - 各属性分离为独立数组
+pub struct TilemapStore {
+    /// 引用的瓦片集ID
+    pub(super) tileset_ids: Vec<u32>,
+    /// 列数 (格子)
+    pub(super) columns: Vec<u32>,
+    /// 行数 (格子)
+    pub(super) rows: Vec<u32>,
+    /// 格子值 (行优先)
+    pub(super) cells: Vec<Vec<u32>>,
+    /// X 坐标 (地图中心，与精灵图相同的坐标系)
+    pub(super) positions_x: Vec<f32>,
+    /// Y 坐标
+    pub(super) positions_y: Vec<f32>,
+    /// Z 层级
+    pub(super) zindexes: Vec<i32>,
+    /// 图层不透明度 (0~1)
+    pub(super) opacities: Vec<f32>,
+    /// 是否可见
+    pub(super) visible: Vec<bool>,
+    /// 是否活跃 (用于删除标记)
+    pub(super) active: Vec<bool>,
+}
+
+impl TilemapStore {
+    pub(super) fn new() -> Self {
+        Self {
+            tileset_ids: Vec::new(),
+            columns: Vec::new(),
+            rows: Vec::new(),
+            cells: Vec::new(),
+            positions_x: Vec::new(),
+            positions_y: Vec::new(),
+            zindexes: Vec::new(),
+            opacities: Vec::new(),
+            visible: Vec::new(),
+            active: Vec::new(),
+        }
+    }
+
+    /// 添加空地图，返回ID (索引)
+    pub(super) fn add(&mut self, tileset_id: u32, columns: u32, rows: u32) -> u32 {
+        let id = self.tileset_ids.len() as u32;
+        self.tileset_ids.push(tileset_id);
+        self.columns.push(columns);
+        self.rows.push(rows);
+        self.cells.push(vec![0; (columns * rows) as usize]);
+        self.positions_x.push(0.0);
+        self.positions_y.push(0.0);
+        self.zindexes.push(0);
+        self.opacities.push(1.0);
+        self.visible.push(true);
+        self.active.push(true);
+        id
+    }
+
+    /// 检查地图是否存在且活跃
+    pub(super) fn is_active(&self, id: u32) -> bool {
+        let idx = id as usize;
+        idx < self.active.len() && self.active[idx]
+    }
+
+    /// 格子下标 (越界时返回 None)
+    fn cell_index(&self, id: u32, column: u32, row: u32) -> Option<usize> {
+        let idx = id as usize;
+        (self.is_active(id) && column < self.columns[idx] && row < self.rows[idx])
+            .then(|| (row * self.columns[idx] + column) as usize)
+    }
+}
+
+/// 按翻转标志将瓦片内的目标坐标映射回纹理中的源坐标
+///
+/// 渲染时先对角翻转，再水平、垂直翻转 (与 Tiled 一致)，采样时按相反顺序还原。
+fn unflip(mut u: u32, mut v: u32, flags: u32, tile_w: u32, tile_h: u32) -> (u32, u32) {
+    // 对角翻转后瓦片宽高互换
+    let (w, h) = if flags & FLIP_DIAGONAL != 0 {
+        (tile_h, tile_w)
+    } else {
+        (tile_w, tile_h)
+    };
+    if flags & FLIP_HORIZONTAL != 0 {
+        u = w - 1 - u;
+    }
+    if flags & FLIP_VERTICAL != 0 {
+        v = h - 1 - v;
+    }
+    if flags & FLIP_DIAGONAL != 0 {
+        (v, u)
+    } else {
+        (u, v)
+    }
+}
+
+#[wasm_bindgen]
+impl World {
+    // ========== 瓦片集 ==========
+
+    /// 从图像文件创建瓦片集
+    ///
+    /// 纹理按 `tile_width` × `tile_height` 的网格从左上角开始切分，瓦片索引按行优先编号。
+    /// 解码失败或瓦片尺寸为 0 时返回 None。
+    pub fn create_tileset(
+        &mut self,
+        bytes: &[u8],
+        tile_width: u32,
+        tile_height: u32,
+    ) -> Option<u32> {
+        let image = decode_image(bytes)?;
+        self.add_tileset(image, tile_width, tile_height)
+    }
+
+    /// 从 RGBA 像素数据创建瓦片集
+    pub fn create_tileset_rgba(
+        &mut self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        tile_width: u32,
+        tile_height: u32,
+    ) -> Option<u32> {
+        if pixel_len(width, height) != Some(data.len()) {
+            return None;
+        }
+        let image = Image {
+            width,
+            height,
+            data: data.to_vec(),
+        };
+        self.add_tileset(image, tile_width, tile_height)
+    }
+
+    /// 设置瓦片集纹理的边距与瓦片间距 (像素)
+    pub fn set_tileset_spacing(&mut self, tileset_id: u32, margin: u32, spacing: u32) {
+        if self.tilesets.exists(tileset_id) {
+            self.tilesets.margins[tileset_id as usize] = margin;
+            self.tilesets.spacings[tileset_id as usize] = spacing;
+        }
+    }
+
+    /// 获取瓦片集中的瓦片总数
+    pub fn get_tileset_tile_count(&self, tileset_id: u32) -> u32 {
+        if self.tilesets.exists(tileset_id) {
+            self.tilesets.tile_count(tileset_id)
+        } else {
+            0
+        }
+    }
+
+    /// 设置动画瓦片
+    ///
+    /// 地图中的 `tile` 按 `frames` (瓦片索引) 轮换显示，每帧时长取 `durations` (秒) 对应项。
+    /// 两个数组长度不一致或为空时移除该瓦片的动画。动画时钟由 `update` 推进。
+    pub fn set_tile_animation(
+        &mut self,
+        tileset_id: u32,
+        tile: u32,
+        frames: &[u32],
+        durations: &[f32],
+    ) {
+        if !self.tilesets.exists(tileset_id) {
+            return;
+        }
+        let animations = &mut self.tilesets.animations[tileset_id as usize];
+        animations.retain(|a| a.tile != tile);
+        if frames.is_empty() || frames.len() != durations.len() {
+            return;
+        }
+        animations.push(TileAnimation {
+            tile,
+            frames: frames.to_vec(),
+            durations: durations
+                .iter()
+                .map(|d| d.max(MIN_TILE_FRAME_DURATION))
+                .collect(),
+        });
+    }
+
+    // ========== 瓦片地图 ==========
+
+    /// 创建空瓦片地图 (`columns` × `rows` 个格子)
+    ///
+    /// 瓦片集不存在或尺寸为 0 时返回 None。地图需加入场景后才会绘制。
+    pub fn create_tilemap(&mut self, tileset_id: u32, columns: u32, rows: u32) -> Option<u32> {
+        if !self.tilesets.exists(tileset_id) || columns == 0 || rows == 0 {
+            return None;
+        }
+        columns.checked_mul(rows)?;
+        Some(self.tilemaps.add(tileset_id, columns, rows))
+    }
+
+    /// 移除瓦片地图 (同时从所有场景移除)
+    pub fn remove_tilemap(&mut self, map_id: u32) {
+        if !self.tilemaps.is_active(map_id) {
+            return;
+        }
+        self.tilemaps.active[map_id as usize] = false;
+        self.tilemaps.cells[map_id as usize] = Vec::new();
+        for tilemap_ids in self.scenes.tilemap_ids.iter_mut() {
+            tilemap_ids.retain(|&id| id != map_id);
+        }
+    }
+
+    /// 添加瓦片地图到指定场景
+    pub fn add_tilemap_to_scene(&mut self, map_id: u32, scene_id: u32) {
+        let scene_idx = scene_id as usize;
+        if self.scenes.is_active(scene_id)
+            && self.tilemaps.is_active(map_id)
+            && !self.scenes.tilemap_ids[scene_idx].contains(&map_id)
+        {
+            self.scenes.tilemap_ids[scene_idx].push(map_id);
+        }
+    }
+
+    /// 从指定场景移除瓦片地图
+    pub fn remove_tilemap_from_scene(&mut self, map_id: u32, scene_id: u32) {
+        if let Some(tilemap_ids) = self.scenes.tilemap_ids.get_mut(scene_id as usize) {
+            tilemap_ids.retain(|&id| id != map_id);
+        }
+    }
+
+    /// 设置格子的瓦片
+    ///
+    /// `flags`: 位 0 = 水平翻转, 位 1 = 垂直翻转, 位 2 = 对角翻转。
+    pub fn set_tile(&mut self, map_id: u32, column: u32, row: u32, tile: u32, flags: u8) {
+        let Some(i) = self.tilemaps.cell_index(map_id, column, row) else {
+            return;
+        };
+        if tile >= TILE_MASK {
+            return;
+        }
+        let mut cell = tile + 1;
+        if flags & 1 != 0 {
+            cell |= FLIP_HORIZONTAL;
+        }
+        if flags & 2 != 0 {
+            cell |= FLIP_VERTICAL;
+        }
+        if flags & 4 != 0 {
+            cell |= FLIP_DIAGONAL;
+        }
+        self.tilemaps.cells[map_id as usize][i] = cell;
+    }
+
+    /// 清空格子
+    pub fn clear_tile(&mut self, map_id: u32, column: u32, row: u32) {
+        if let Some(i) = self.tilemaps.cell_index(map_id, column, row) {
+            self.tilemaps.cells[map_id as usize][i] = 0;
+        }
+    }
+
+    /// 获取格子的瓦片索引 (空格子或越界时返回 None)
+    pub fn get_tile(&self, map_id: u32, column: u32, row: u32) -> Option<u32> {
+        let i = self.tilemaps.cell_index(map_id, column, row)?;
+        let cell = self.tilemaps.cells[map_id as usize][i] & TILE_MASK;
+        cell.checked_sub(1)
+    }
+
+    /// 批量设置全部格子 (行优先，编码同 Tiled: 瓦片索引 + 1，0 为空，高三位为翻转标志)
+    ///
+    /// 长度与地图格子数不一致时忽略。
+    pub fn set_tiles(&mut self, map_id: u32, cells: &[u32]) {
+        if !self.tilemaps.is_active(map_id) {
+            return;
+        }
+        let idx = map_id as usize;
+        if self.tilemaps.cells[idx].len() == cells.len() {
+            self.tilemaps.cells[idx].copy_from_slice(cells);
+        }
+    }
+
+    /// 设置瓦片地图位置 (地图中心，坐标系与精灵图相同)
+    pub fn set_tilemap_position(&mut self, map_id: u32, x: f32, y: f32) {
+        if self.tilemaps.is_active(map_id) {
+            self.tilemaps.positions_x[map_id as usize] = x;
+            self.tilemaps.positions_y[map_id as usize] = y;
+        }
+    }
+
+    /// 获取瓦片地图位置 [x, y]
+    pub fn get_tilemap_position(&self, map_id: u32) -> Option<Vec<f32>> {
+        let idx = map_id as usize;
+        self.tilemaps.is_active(map_id).then(|| {
+            vec![
+                self.tilemaps.positions_x[idx],
+                self.tilemaps.positions_y[idx],
+            ]
+        })
+    }
+
+    /// 设置瓦片地图 z-index
+    pub fn set_tilemap_zindex(&mut self, map_id: u32, zindex: i32) {
+        if self.tilemaps.is_active(map_id) {
+            self.tilemaps.zindexes[map_id as usize] = zindex;
+        }
+    }
+
+    /// 设置图层不透明度 (0~1)
+    pub fn set_tilemap_opacity(&mut self, map_id: u32, opacity: f32) {
+        if self.tilemaps.is_active(map_id) && !opacity.is_nan() {
+            self.tilemaps.opacities[map_id as usize] = opacity.clamp(0.0, 1.0);
+        }
+    }
+
+    /// 设置图层是否可见
+    pub fn set_tilemap_visible(&mut self, map_id: u32, visible: bool) {
+        if self.tilemaps.is_active(map_id) {
+            self.tilemaps.visible[map_id as usize] = visible;
+        }
+    }
+}
+
+impl World {
+    /// 添加瓦片集 (校验瓦片尺寸)
+    pub(super) fn add_tileset(
+        &mut self,
+        image: Image,
+        tile_width: u32,
+        tile_height: u32,
+    ) -> Option<u32> {
+        if tile_width == 0 || tile_height == 0 {
+            return None;
+        }
+        Some(self.tilesets.add(image, tile_width, tile_height))
+    }
+
+    /// 推进动画瓦片时钟
+    pub(super) fn update_tilesets(&mut self, dt: f32) {
+        for clock in self.tilesets.clocks.iter_mut() {
+            *clock += dt;
+        }
+    }
+
+    /// 场景中需要绘制的瓦片地图，按 z-index 排序
+    pub(super) fn sorted_tilemaps(&self, scene_idx: usize) -> Vec<u32> {
+        let mut maps: Vec<u32> = self.scenes.tilemap_ids[scene_idx]
+            .iter()
+            .copied()
+            .filter(|&id| {
+                self.tilemaps.is_active(id)
+                    && self.tilemaps.visible[id as usize]
+                    && self.tilemaps.opacities[id as usize] > 0.0
+            })
+            .collect();
+        maps.sort_by_key(|&id| self.tilemaps.zindexes[id as usize]);
+        maps
+    }
+
+    /// 将瓦片地图绘制到场景 (只遍历可见的格子)
+    pub(super) fn draw_tilemap(&mut self, scene_idx: usize, map_id: u32) {
+        let m = map_id as usize;
+        let tileset = self.tilemaps.tileset_ids[m];
+        let t = tileset as usize;
+        let (tile_w, tile_h) = (self.tilesets.tile_widths[t], self.tilesets.tile_heights[t]);
+        let (columns, rows) = (self.tilemaps.columns[m], self.tilemaps.rows[m]);
+        let tileset_columns = self.tilesets.columns(tileset);
+        let tile_count = self.tilesets.tile_count(tileset);
+        if tileset_columns == 0 {
+            return;
+        }
+        let (margin, spacing) = (self.tilesets.margins[t], self.tilesets.spacings[t]);
+        let opacity = self.tilemaps.opacities[m];
+        let linear = self.scenes.linear_blending[scene_idx];
+
+        let scene_w = self.scenes.widths[scene_idx] as i64;
+        let scene_h = self.scenes.heights[scene_idx] as i64;
+        let map_w = (columns * tile_w) as f32;
+        let map_h = (rows * tile_h) as f32;
+        // 地图左上角在场景中的像素位置 (取整，保证瓦片对齐像素)
+        let left =
+            (self.tilemaps.positions_x[m] - map_w / 2.0 + scene_w as f32 / 2.0).round() as i64;
+        let top =
+            (self.tilemaps.positions_y[m] - map_h / 2.0 + scene_h as f32 / 2.0).round() as i64;
+
+        // 与场景相交的格子范围
+        let (tw, th) = (tile_w as i64, tile_h as i64);
+        let col_start = ((-left).max(0) / tw).min(columns as i64);
+        let col_end = ((scene_w - left + tw - 1).max(0) / tw).min(columns as i64);
+        let row_start = ((-top).max(0) / th).min(rows as i64);
+        let row_end = ((scene_h - top + th - 1).max(0) / th).min(rows as i64);
+
+        let image = &self.tilesets.images[t];
+        let cells = &self.tilemaps.cells[m];
+        let scene_data = &mut self.scenes.data[scene_idx];
+        let mut hdr = self.scenes.hdr_buffers[scene_idx].as_deref_mut();
+
+        for row in row_start..row_end {
+            for col in col_start..col_end {
+                let cell = cells[(row * columns as i64 + col) as usize];
+                if cell & TILE_MASK == 0 {
+                    continue;
+                }
+                let tile = self.tilesets.resolve(tileset, (cell & TILE_MASK) - 1);
+                if tile >= tile_count {
+                    continue;
+                }
+                let src_x = margin + (tile % tileset_columns) * (tile_w + spacing);
+                let src_y = margin + (tile / tileset_columns) * (tile_h + spacing);
+                let x0 = left + col * tw;
+                let y0 = top + row * th;
+
+                for v in (-y0).max(0)..th.min(scene_h - y0) {
+                    let dst_row = ((y0 + v) * scene_w) as usize;
+                    for u in (-x0).max(0)..tw.min(scene_w - x0) {
+                        let (su, sv) = unflip(u as u32, v as u32, cell, tile_w, tile_h);
+                        if su >= tile_w || sv >= tile_h {
+                            continue;
+                        }
+                        let src = (((src_y + sv) * image.width + src_x + su) * 4) as usize;
+                        let Some(px) = image.data.get(src..src + 4) else {
+                            continue;
+                        };
+                        let alpha = (px[3] as f32 * opacity + 0.5) as u8;
+                        if alpha == 0 {
+                            continue;
+                        }
+                        let dst = (dst_row + (x0 + u) as usize) * 4;
+                        blend_into(
+                            scene_data,
+                            hdr.as_deref_mut(),
+                            dst,
+                            [px[0], px[1], px[2], alpha],
+                            Emission::default(),
+                            linear,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_pixel;
+
+    /// 2x1 个 2x2 瓦片的纹理: 瓦片 0 为红色 (左上像素为白色)，瓦片 1 为蓝色
+    fn tileset(world: &mut World) -> u32 {
+        let mut data = Vec::new();
+        for y in 0..2 {
+            for x in 0..4 {
+                let px = match (x, y) {
+                    (0, 0) => [255, 255, 255, 255],
+                    (0..=1, _) => [255, 0, 0, 255],
+                    _ => [0, 0, 255, 255],
+                };
+                data.extend_from_slice(&px);
+            }
+        }
+        world.create_tileset_rgba(&data, 4, 2, 2, 2).unwrap()
+    }
+
+    #[test]
+    fn test_render_tilemap() {
+        let mut world = World::new(4, 4);
+        let tileset = tileset(&mut world);
+        assert_eq!(world.get_tileset_tile_count(tileset), 2);
+        let map = world.create_tilemap(tileset, 2, 2).unwrap();
+        world.set_tile(map, 0, 0, 0, 0);
+        world.set_tile(map, 1, 1, 1, 0);
+        world.add_tilemap_to_scene(map, 0);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(scene_pixel(&world, 1, 1), [255, 0, 0, 255]);
+        assert_eq!(scene_pixel(&world, 3, 3), [0, 0, 255, 255]);
+        assert_eq!(scene_pixel(&world, 3, 0), [0, 0, 0, 255]);
+        assert_eq!(world.get_tile(map, 1, 1), Some(1));
+        assert_eq!(world.get_tile(map, 1, 0), None);
+    }
+
+    #[test]
+    fn test_flip_flags() {
+        let mut world = World::new(2, 2);
+        let tileset = tileset(&mut world);
+        let map = world.create_tilemap(tileset, 1, 1).unwrap();
+        world.add_tilemap_to_scene(map, 0);
+        for (flags, white) in [(1, (1, 0)), (2, (0, 1)), (3, (1, 1)), (4, (0, 0))] {
+            world.set_tile(map, 0, 0, 0, flags);
+            world.render();
+            assert_eq!(scene_pixel(&world, white.0, white.1), [255, 255, 255, 255]);
+        }
+        assert_eq!(unflip(1, 0, FLIP_DIAGONAL, 2, 2), (0, 1));
+    }
+
+    #[test]
+    fn test_visible_range_and_opacity() {
+        let mut world = World::new(4, 4);
+        let tileset = tileset(&mut world);
+        let map = world.create_tilemap(tileset, 100, 100).unwrap();
+        world.set_tiles(map, &vec![2; 100 * 100]);
+        world.add_tilemap_to_scene(map, 0);
+        // 地图比场景大得多，只会绘制与场景相交的格子
+        world.set_tilemap_position(map, -50.0, -50.0);
+        world.set_tilemap_opacity(map, 0.5);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 0, 128, 255]);
+
+        world.set_tilemap_visible(map, false);
+        world.render();
+        assert_eq!(scene_pixel(&world, 0, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_animated_tile_and_zorder() {
+        let mut world = World::new(2, 2);
+        let tileset = tileset(&mut world);
+        world.set_tile_animation(tileset, 0, &[0, 1], &[0.5, 0.5]);
+        let map = world.create_tilemap(tileset, 1, 1).unwrap();
+        world.set_tile(map, 0, 0, 0, 0);
+        world.add_tilemap_to_scene(map, 0);
+        world.update(0.6);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [0, 0, 255, 255]);
+
+        // z-index 更高的精灵图绘制在地图之上，更低的被地图覆盖
+        let sprite = world.create_rect_sprite(2, 2, 0, 255, 0, 255);
+        world.add_to_scene(sprite);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [0, 255, 0, 255]);
+        world.set_tilemap_zindex(map, 1);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [0, 0, 255, 255]);
+
+        world.remove_tilemap(map);
+        world.render();
+        assert_eq!(scene_pixel(&world, 1, 1), [0, 255, 0, 255]);
+    }
+}
@@ -4,31 +4,108 @@
 
 use wasm_bindgen::prelude::*;
 
-use super::sampling::{sample_bilinear, sample_supersampling, SamplingMethod};
+use super::animation::{AnimatorStore, ClipStore};
+use super::background::BackgroundMode;
+use super::masking::{ClipGroupStore, MaskMode};
+use super::nineslice::NineSliceStore;
+use super::particles::{EmitterStore, ParticleStore};
+use super::hdr::{blend_clamped, blend_hdr, Emission, Tonemap};
+use super::indexed::{IndexedStore, PaletteStore};
+use super::lighting::LightStore;
+use super::output::PixelFormat;
+use super::linear::blend_linear;
+use super::sampling::{
+    sample_bilinear, sample_bilinear_linear, sample_supersampling, sample_supersampling_linear,
+    SamplingMethod,
+};
+use super::svg::SvgStore;
+use super::text::{FontStore, TextStore};
+use super::tiled::TiledStore;
+use super::tilemap::{TilemapStore, TilesetStore};
+use crate::filter::Filter;
+use crate::image::{pixel_len, Image};
 use crate::math::Matrix3x3;
+use crate::raster::Gradient;
+
+/// 精灵图当前应用的变换 (用于原始数据更新后重新生成显示数据)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SpriteTransform {
+    /// 无变换
+    #[default]
+    None,
+    /// 旋转 (弧度)
+    Rotation(f32),
+    /// 缩放
+    Scale(f32, f32),
+    /// 旋转 + 缩放
+    Transform(f32, f32, f32),
+}
+
+impl SpriteTransform {
+    /// 分解为 (旋转角度, 横向缩放, 纵向缩放)
+    pub(super) fn parts(self) -> (f32, f32, f32) {
+        match self {
+            SpriteTransform::None => (0.0, 1.0, 1.0),
+            SpriteTransform::Rotation(angle) => (angle, 1.0, 1.0),
+            SpriteTransform::Scale(sx, sy) => (0.0, sx, sy),
+            SpriteTransform::Transform(angle, sx, sy) => (angle, sx, sy),
+        }
+    }
+}
+
+/// 与精灵图按 z-index 交错绘制的场景图层
+#[derive(Debug, Clone, Copy)]
+enum SceneLayer {
+    /// 瓦片地图
+    Tilemap(u32),
+    /// 粒子发射器
+    Emitter(u32),
+}
 
 /// 精灵图存储 - 各属性分离为独立数组
 pub struct SpriteStore {
     /// 原始像素数据 (只读，用于变换)
-    original_data: Vec<Vec<u8>>,
+    pub(super) original_data: Vec<Vec<u8>>,
     /// 显示像素数据 (变换结果)
-    display_data: Vec<Vec<u8>>,
+    pub(super) display_data: Vec<Vec<u8>>,
     /// 原始宽度
-    original_widths: Vec<u32>,
+    pub(super) original_widths: Vec<u32>,
     /// 原始高度
-    original_heights: Vec<u32>,
+    pub(super) original_heights: Vec<u32>,
     /// 显示宽度 (变换后)
-    display_widths: Vec<u32>,
+    pub(super) display_widths: Vec<u32>,
     /// 显示高度 (变换后)
-    display_heights: Vec<u32>,
+    pub(super) display_heights: Vec<u32>,
     /// X 坐标
-    positions_x: Vec<f32>,
+    pub(super) positions_x: Vec<f32>,
     /// Y 坐标
-    positions_y: Vec<f32>,
+    pub(super) positions_y: Vec<f32>,
     /// Z 层级
-    zindexes: Vec<i32>,
+    pub(super) zindexes: Vec<i32>,
+    /// 当前应用的变换
+    pub(super) transforms: Vec<SpriteTransform>,
+    /// 裁剪矩形 [左, 上, 右, 下]
+    pub(super) clip_rects: Vec<Option<[f32; 4]>>,
+    /// 所属裁剪组
+    pub(super) clip_groups: Vec<Option<u32>>,
+    /// 遮罩 (遮罩精灵图ID, 模式)
+    pub(super) masks: Vec<Option<(u32, MaskMode)>>,
+    /// 作为纹理来源的场景ID
+    pub(super) source_scenes: Vec<Option<u32>>,
+    /// 上次同步时来源场景的渲染版本
+    pub(super) source_versions: Vec<u32>,
+    /// 滤镜链 (按顺序应用，附带启用标记)
+    pub(super) filters: Vec<Vec<(Filter, bool)>>,
+    /// 滤镜结果缓存 (显示数据或滤镜变化时清空)
+    pub(super) filter_caches: Vec<Option<Image>>,
+    /// 发光强度与叠加模式
+    pub(super) emissions: Vec<Emission>,
+    /// 法线贴图 (与原始纹理对齐)
+    pub(super) normal_maps: Vec<Option<Image>>,
+    /// 是否不受光照
+    pub(super) unlit: Vec<bool>,
     /// 是否活跃 (用于删除标记)
-    active: Vec<bool>,
+    pub(super) active: Vec<bool>,
 }
 
 impl SpriteStore {
@@ -43,12 +120,23 @@ impl SpriteStore {
             positions_x: Vec::new(),
             positions_y: Vec::new(),
             zindexes: Vec::new(),
+            transforms: Vec::new(),
+            clip_rects: Vec::new(),
+            clip_groups: Vec::new(),
+            masks: Vec::new(),
+            source_scenes: Vec::new(),
+            source_versions: Vec::new(),
+            filters: Vec::new(),
+            filter_caches: Vec::new(),
+            emissions: Vec::new(),
+            normal_maps: Vec::new(),
+            unlit: Vec::new(),
             active: Vec::new(),
         }
     }
 
     /// 添加新精灵图，返回ID (索引)
-    fn add(&mut self, data: Vec<u8>, width: u32, height: u32) -> u32 {
+    pub(super) fn add(&mut self, data: Vec<u8>, width: u32, height: u32) -> u32 {
         let id = self.original_data.len() as u32;
         self.original_data.push(data.clone());
         self.display_data.push(data);
@@ -59,12 +147,23 @@ impl SpriteStore {
         self.positions_x.push(0.0);
         self.positions_y.push(0.0);
         self.zindexes.push(0);
+        self.transforms.push(SpriteTransform::None);
+        self.clip_rects.push(None);
+        self.clip_groups.push(None);
+        self.masks.push(None);
+        self.source_scenes.push(None);
+        self.source_versions.push(0);
+        self.filters.push(Vec::new());
+        self.filter_caches.push(None);
+        self.emissions.push(Emission::default());
+        self.normal_maps.push(None);
+        self.unlit.push(false);
         self.active.push(true);
         id
     }
 
     /// 移除精灵图 (标记为非活跃)
-    fn remove(&mut self, id: u32) {
+    pub(super) fn remove(&mut self, id: u32) {
         let idx = id as usize;
         if idx < self.active.len() {
             self.active[idx] = false;
@@ -72,38 +171,84 @@ impl SpriteStore {
     }
 
     /// 检查精灵图是否存在且活跃
-    fn is_active(&self, id: u32) -> bool {
+    pub(super) fn is_active(&self, id: u32) -> bool {
         let idx = id as usize;
         idx < self.active.len() && self.active[idx]
     }
+
+    /// 替换显示数据 (同时使滤镜缓存失效)
+    pub(super) fn set_display(&mut self, idx: usize, data: Vec<u8>, width: u32, height: u32) {
+        self.display_data[idx] = data;
+        self.display_widths[idx] = width;
+        self.display_heights[idx] = height;
+        self.filter_caches[idx] = None;
+    }
 }
 
 /// 场景存储 - 各属性分离为独立数组
 pub struct SceneStore {
     /// 像素缓冲数据
-    data: Vec<Vec<u8>>,
+    pub(super) data: Vec<Vec<u8>>,
     /// 宽度
-    widths: Vec<u32>,
+    pub(super) widths: Vec<u32>,
     /// 高度
-    heights: Vec<u32>,
+    pub(super) heights: Vec<u32>,
     /// Z 层级
-    zindexes: Vec<i32>,
+    pub(super) zindexes: Vec<i32>,
     /// 背景色
-    background_colors: Vec<[u8; 4]>,
+    pub(super) background_colors: Vec<[u8; 4]>,
+    /// 背景渐变 (设置时取代背景色)
+    pub(super) background_gradients: Vec<Option<Gradient>>,
+    /// 背景图像 (绘制在背景色 / 渐变之上)
+    pub(super) background_images: Vec<Option<Image>>,
+    /// 背景图像绘制模式
+    pub(super) background_modes: Vec<BackgroundMode>,
+    /// 背景滚动偏移
+    pub(super) background_scrolls: Vec<[f32; 2]>,
     /// 包含的精灵图ID列表
-    sprite_ids: Vec<Vec<u32>>,
+    pub(super) sprite_ids: Vec<Vec<u32>>,
+    /// 包含的瓦片地图ID列表
+    pub(super) tilemap_ids: Vec<Vec<u32>>,
+    /// 包含的粒子发射器ID列表
+    pub(super) emitter_ids: Vec<Vec<u32>>,
     /// 采样方法
-    sampling_methods: Vec<SamplingMethod>,
+    pub(super) sampling_methods: Vec<SamplingMethod>,
     /// 是否活跃
-    active: Vec<bool>,
+    pub(super) active: Vec<bool>,
     /// 已排序的精灵ID列表（缓存）
-    sorted_sprites: Vec<Vec<u32>>,
+    pub(super) sorted_sprites: Vec<Vec<u32>>,
     /// 排序脏标记
-    sort_dirty: Vec<bool>,
-    /// 预计算的背景行（缓存）
-    bg_rows: Vec<Vec<u8>>,
+    pub(super) sort_dirty: Vec<bool>,
+    /// 预计算的背景（缓存，纯色时为单行，渐变或图像时为整帧）
+    pub(super) bg_rows: Vec<Vec<u8>>,
     /// 背景行脏标记
-    bg_dirty: Vec<bool>,
+    pub(super) bg_dirty: Vec<bool>,
+    /// 作为纹理时是否缓存渲染结果
+    pub(super) cached: Vec<bool>,
+    /// 缓存是否有效
+    pub(super) cache_valid: Vec<bool>,
+    /// 渲染版本 (每渲染一次加一)
+    pub(super) versions: Vec<u32>,
+    /// 后期处理滤镜 (按顺序应用，附带启用标记)
+    pub(super) filters: Vec<Vec<(Filter, bool)>>,
+    /// 是否在线性光空间中采样与混合
+    pub(super) linear_blending: Vec<bool>,
+    /// HDR 浮点累积缓冲 (线性光 RGBA，None 表示未开启)
+    pub(super) hdr_buffers: Vec<Option<Vec<f32>>>,
+    /// 色调映射方式
+    pub(super) tonemaps: Vec<Tonemap>,
+    /// 曝光度
+    pub(super) exposures: Vec<f32>,
+    /// 输出像素格式
+    pub(super) output_formats: Vec<PixelFormat>,
+    /// 按输出格式编码的缓冲 (RGBA8 时为空，直接使用 `data`)
+    pub(super) outputs: Vec<Vec<u8>>,
+    /// 场景中的光源ID
+    pub(super) light_ids: Vec<Vec<u32>>,
+    /// 是否开启光照
+    pub(super) lighting: Vec<bool>,
+    /// 环境光 (0~1，已乘以强度)
+    pub(super) ambients: Vec<[f32; 3]>,
 }
 
 impl SceneStore {
@@ -114,18 +259,37 @@ impl SceneStore {
             heights: Vec::new(),
             zindexes: Vec::new(),
             background_colors: Vec::new(),
+            background_gradients: Vec::new(),
+            background_images: Vec::new(),
+            background_modes: Vec::new(),
+            background_scrolls: Vec::new(),
             sprite_ids: Vec::new(),
+            tilemap_ids: Vec::new(),
+            emitter_ids: Vec::new(),
             sampling_methods: Vec::new(),
             active: Vec::new(),
             sorted_sprites: Vec::new(),
             sort_dirty: Vec::new(),
             bg_rows: Vec::new(),
             bg_dirty: Vec::new(),
+            cached: Vec::new(),
+            cache_valid: Vec::new(),
+            versions: Vec::new(),
+            filters: Vec::new(),
+            linear_blending: Vec::new(),
+            hdr_buffers: Vec::new(),
+            tonemaps: Vec::new(),
+            exposures: Vec::new(),
+            output_formats: Vec::new(),
+            outputs: Vec::new(),
+            light_ids: Vec::new(),
+            lighting: Vec::new(),
+            ambients: Vec::new(),
         }
     }
 
     /// 添加新场景，返回ID (索引)
-    fn add(&mut self, width: u32, height: u32) -> u32 {
+    pub(super) fn add(&mut self, width: u32, height: u32) -> u32 {
         let id = self.data.len() as u32;
         let size = (width * height * 4) as usize;
         self.data.push(vec![0u8; size]);
@@ -133,18 +297,37 @@ impl SceneStore {
         self.heights.push(height);
         self.zindexes.push(0);
         self.background_colors.push([0, 0, 0, 255]);
+        self.background_gradients.push(None);
+        self.background_images.push(None);
+        self.background_modes.push(BackgroundMode::default());
+        self.background_scrolls.push([0.0, 0.0]);
         self.sprite_ids.push(Vec::new());
+        self.tilemap_ids.push(Vec::new());
+        self.emitter_ids.push(Vec::new());
         self.sampling_methods.push(SamplingMethod::default());
         self.active.push(true);
         self.sorted_sprites.push(Vec::new());
         self.sort_dirty.push(true);
         self.bg_rows.push(Vec::new());
         self.bg_dirty.push(true);
+        self.cached.push(false);
+        self.cache_valid.push(false);
+        self.versions.push(0);
+        self.filters.push(Vec::new());
+        self.linear_blending.push(false);
+        self.hdr_buffers.push(None);
+        self.tonemaps.push(Tonemap::default());
+        self.exposures.push(1.0);
+        self.output_formats.push(PixelFormat::default());
+        self.outputs.push(Vec::new());
+        self.light_ids.push(Vec::new());
+        self.lighting.push(false);
+        self.ambients.push([0.0; 3]);
         id
     }
 
     /// 检查场景是否存在且活跃
-    fn is_active(&self, id: u32) -> bool {
+    pub(super) fn is_active(&self, id: u32) -> bool {
         let idx = id as usize;
         idx < self.active.len() && self.active[idx]
     }
@@ -156,11 +339,41 @@ impl SceneStore {
 #[wasm_bindgen]
 pub struct World {
     /// 精灵图存储
-    sprites: SpriteStore,
+    pub(super) sprites: SpriteStore,
     /// 场景存储
-    scenes: SceneStore,
+    pub(super) scenes: SceneStore,
+    /// 动画剪辑存储
+    pub(super) clips: ClipStore,
+    /// 动画播放器存储
+    pub(super) animators: AnimatorStore,
+    /// 字体存储
+    pub(super) fonts: FontStore,
+    /// 文字精灵存储
+    pub(super) texts: TextStore,
+    /// SVG 精灵存储
+    pub(super) svgs: SvgStore,
+    /// 九宫格精灵存储
+    pub(super) nine_slices: NineSliceStore,
+    /// 裁剪组存储
+    pub(super) clip_groups: ClipGroupStore,
+    /// 瓦片集存储
+    pub(super) tilesets: TilesetStore,
+    /// 瓦片地图存储
+    pub(super) tilemaps: TilemapStore,
+    /// Tiled 地图存储
+    pub(super) tiled: TiledStore,
+    /// 粒子发射器存储
+    pub(super) emitters: EmitterStore,
+    /// 粒子对象池
+    pub(super) particles: ParticleStore,
+    /// 共享调色板存储
+    pub(super) palettes: PaletteStore,
+    /// 索引色精灵图存储
+    pub(super) indexed: IndexedStore,
+    /// 光源存储
+    pub(super) lights: LightStore,
     /// 默认场景ID (保持向后兼容)
-    default_scene: u32,
+    pub(super) default_scene: u32,
 }
 
 #[wasm_bindgen]
@@ -171,6 +384,21 @@ impl World {
         let mut world = Self {
             sprites: SpriteStore::new(),
             scenes: SceneStore::new(),
+            clips: ClipStore::new(),
+            animators: AnimatorStore::new(),
+            fonts: FontStore::new(),
+            texts: TextStore::new(),
+            svgs: SvgStore::new(),
+            nine_slices: NineSliceStore::new(),
+            clip_groups: ClipGroupStore::new(),
+            tilesets: TilesetStore::new(),
+            tilemaps: TilemapStore::new(),
+            tiled: TiledStore::new(),
+            emitters: EmitterStore::new(),
+            particles: ParticleStore::new(),
+            palettes: PaletteStore::new(),
+            indexed: IndexedStore::new(),
+            lights: LightStore::new(),
             default_scene: 0,
         };
         // 创建默认场景
@@ -208,9 +436,28 @@ impl World {
         self.sprites.add(data, width, height)
     }
 
+    /// 创建渐变填充的矩形精灵图
+    ///
+    /// 渐变坐标以精灵图左上角为原点 (像素)。
+    /// 尺寸为 0 或超出上限时返回 None。
+    pub fn create_gradient_sprite(
+        &mut self,
+        width: u32,
+        height: u32,
+        gradient: &Gradient,
+    ) -> Option<u32> {
+        let mut data = vec![0u8; pixel_len(width, height)?];
+        gradient.fill_into(&mut data, width);
+        Some(self.sprites.add(data, width, height))
+    }
+
     /// 移除精灵图
     pub fn remove_sprite(&mut self, id: u32) {
         self.sprites.remove(id);
+        // 同时丢弃调色板索引，避免每帧同步已删除的精灵图
+        if let Some(i) = self.indexed.find(id) {
+            self.indexed.remove(i);
+        }
         // 从所有场景中移除
         for (scene_idx, sprite_ids) in self.scenes.sprite_ids.iter_mut().enumerate() {
             if sprite_ids.contains(&id) {
@@ -283,6 +530,12 @@ impl World {
         if !self.sprites.is_active(id) {
             return;
         }
+        if self.apply_svg_transform(id, SpriteTransform::Rotation(angle)) {
+            return;
+        }
+        if self.apply_indexed_transform(id, SpriteTransform::Rotation(angle)) {
+            return;
+        }
 
         let orig_width = self.sprites.original_widths[idx];
         let orig_height = self.sprites.original_heights[idx];
@@ -330,9 +583,8 @@ impl World {
             }
         }
 
-        self.sprites.display_data[idx] = new_data;
-        self.sprites.display_widths[idx] = new_width;
-        self.sprites.display_heights[idx] = new_height;
+        self.sprites.set_display(idx, new_data, new_width, new_height);
+        self.sprites.transforms[idx] = SpriteTransform::Rotation(angle);
     }
 
     /// 应用缩放变换到精灵图
@@ -343,6 +595,15 @@ impl World {
         if !self.sprites.is_active(id) || sx.abs() < 0.001 || sy.abs() < 0.001 {
             return;
         }
+        if self.apply_svg_transform(id, SpriteTransform::Scale(sx, sy)) {
+            return;
+        }
+        if self.apply_nine_slice_transform(id, SpriteTransform::Scale(sx, sy)) {
+            return;
+        }
+        if self.apply_indexed_transform(id, SpriteTransform::Scale(sx, sy)) {
+            return;
+        }
 
         let orig_width = self.sprites.original_widths[idx];
         let orig_height = self.sprites.original_heights[idx];
@@ -376,9 +637,8 @@ impl World {
             }
         }
 
-        self.sprites.display_data[idx] = new_data;
-        self.sprites.display_widths[idx] = new_width;
-        self.sprites.display_heights[idx] = new_height;
+        self.sprites.set_display(idx, new_data, new_width, new_height);
+        self.sprites.transforms[idx] = SpriteTransform::Scale(sx, sy);
     }
 
     /// 应用旋转+缩放组合变换
@@ -389,6 +649,15 @@ impl World {
         if !self.sprites.is_active(id) || sx.abs() < 0.001 || sy.abs() < 0.001 {
             return;
         }
+        if self.apply_svg_transform(id, SpriteTransform::Transform(angle, sx, sy)) {
+            return;
+        }
+        if self.apply_nine_slice_transform(id, SpriteTransform::Transform(angle, sx, sy)) {
+            return;
+        }
+        if self.apply_indexed_transform(id, SpriteTransform::Transform(angle, sx, sy)) {
+            return;
+        }
 
         let orig_width = self.sprites.original_widths[idx];
         let orig_height = self.sprites.original_heights[idx];
@@ -444,9 +713,8 @@ impl World {
             }
         }
 
-        self.sprites.display_data[idx] = new_data;
-        self.sprites.display_widths[idx] = new_width;
-        self.sprites.display_heights[idx] = new_height;
+        self.sprites.set_display(idx, new_data, new_width, new_height);
+        self.sprites.transforms[idx] = SpriteTransform::Transform(angle, sx, sy);
     }
 
     /// 重置精灵图变换 (恢复到原始状态)
@@ -456,9 +724,33 @@ impl World {
             return;
         }
 
-        self.sprites.display_data[idx] = self.sprites.original_data[idx].clone();
-        self.sprites.display_widths[idx] = self.sprites.original_widths[idx];
-        self.sprites.display_heights[idx] = self.sprites.original_heights[idx];
+        if self.apply_indexed_transform(id, SpriteTransform::None) {
+            return;
+        }
+
+        self.sprites.set_display(
+            idx,
+            self.sprites.original_data[idx].clone(),
+            self.sprites.original_widths[idx],
+            self.sprites.original_heights[idx],
+        );
+        self.sprites.transforms[idx] = SpriteTransform::None;
+    }
+
+    // ========== 帧更新 ==========
+
+    /// 推进一帧的时间
+    ///
+    /// `dt` 为距上一帧经过的时间 (秒)，驱动精灵动画等随时间变化的状态。
+    /// 非有限值或非正值被忽略。
+    pub fn update(&mut self, dt: f32) {
+        if !dt.is_finite() || dt <= 0.0 {
+            return;
+        }
+        self.update_animations(dt);
+        self.update_tilesets(dt);
+        self.update_particles(dt);
+        self.update_palettes(dt);
     }
 
     // ========== 场景操作 ==========
@@ -494,22 +786,23 @@ impl World {
     /// 添加精灵图到指定场景
     pub fn add_sprite_to_scene(&mut self, sprite_id: u32, scene_id: u32) {
         let scene_idx = scene_id as usize;
-        if self.scenes.is_active(scene_id) && self.sprites.is_active(sprite_id) {
-            if !self.scenes.sprite_ids[scene_idx].contains(&sprite_id) {
-                self.scenes.sprite_ids[scene_idx].push(sprite_id);
-                self.scenes.sort_dirty[scene_idx] = true;
-            }
+        if self.scenes.is_active(scene_id)
+            && self.sprites.is_active(sprite_id)
+            && !self.scenes.sprite_ids[scene_idx].contains(&sprite_id)
+        {
+            self.scenes.sprite_ids[scene_idx].push(sprite_id);
+            self.scenes.sort_dirty[scene_idx] = true;
         }
     }
 
     /// 从场景移除精灵图
     pub fn remove_from_scene(&mut self, sprite_id: u32) {
         let scene_idx = self.default_scene as usize;
-        if scene_idx < self.scenes.sprite_ids.len() {
-            if self.scenes.sprite_ids[scene_idx].contains(&sprite_id) {
-                self.scenes.sprite_ids[scene_idx].retain(|&id| id != sprite_id);
-                self.scenes.sort_dirty[scene_idx] = true;
-            }
+        if scene_idx < self.scenes.sprite_ids.len()
+            && self.scenes.sprite_ids[scene_idx].contains(&sprite_id)
+        {
+            self.scenes.sprite_ids[scene_idx].retain(|&id| id != sprite_id);
+            self.scenes.sort_dirty[scene_idx] = true;
         }
     }
 
@@ -518,21 +811,59 @@ impl World {
         let idx = self.default_scene as usize;
         if idx < self.scenes.background_colors.len() {
             let new_color = [r, g, b, a];
-            if self.scenes.background_colors[idx] != new_color {
+            if self.scenes.background_colors[idx] != new_color
+                || self.scenes.background_gradients[idx].is_some()
+            {
                 self.scenes.background_colors[idx] = new_color;
+                self.scenes.background_gradients[idx] = None;
                 self.scenes.bg_dirty[idx] = true;
             }
         }
     }
 
+    /// 设置场景背景渐变 (取代背景色，再次调用 `set_background_color` 可恢复纯色)
+    ///
+    /// 渐变坐标以场景左上角为原点 (像素)。
+    pub fn set_background_gradient(&mut self, gradient: &Gradient) {
+        let idx = self.default_scene as usize;
+        if idx < self.scenes.background_gradients.len() {
+            self.scenes.background_gradients[idx] = Some(gradient.clone());
+            self.scenes.bg_dirty[idx] = true;
+        }
+    }
+
     /// 设置采样方法
     pub fn set_sampling_method(&mut self, method: u8) {
         let idx = self.default_scene as usize;
         if idx < self.scenes.sampling_methods.len() {
             self.scenes.sampling_methods[idx] = SamplingMethod::from_u8(method);
+            // 背景图像的缩放同样使用该采样方法
+            self.scenes.bg_dirty[idx] = true;
+        }
+    }
+
+    /// 设置是否在线性光空间中采样与混合 (渐变与半透明叠加更准确，开销略高)
+    pub fn set_linear_blending(&mut self, enabled: bool) {
+        self.set_scene_linear_blending(self.default_scene, enabled);
+    }
+
+    /// 设置指定场景是否在线性光空间中采样与混合
+    pub fn set_scene_linear_blending(&mut self, scene_id: u32, enabled: bool) {
+        if self.scenes.is_active(scene_id) {
+            let idx = scene_id as usize;
+            self.scenes.linear_blending[idx] = enabled;
+            // 渐变与背景图像的插值方式随之改变
+            self.scenes.bg_dirty[idx] = true;
+            self.scenes.cache_valid[idx] = false;
         }
     }
 
+    /// 获取是否在线性光空间中采样与混合
+    pub fn get_linear_blending(&self) -> bool {
+        let idx = self.default_scene as usize;
+        idx < self.scenes.linear_blending.len() && self.scenes.linear_blending[idx]
+    }
+
     /// 获取当前采样方法
     pub fn get_sampling_method(&self) -> u8 {
         let idx = self.default_scene as usize;
@@ -544,39 +875,42 @@ impl World {
     }
 
     /// 渲染一帧
+    ///
+    /// 默认场景中以其他场景为纹理的精灵图所引用的场景会先按依赖顺序渲染。
     pub fn render(&mut self) {
         let scene_idx = self.default_scene as usize;
         if scene_idx >= self.scenes.data.len() {
             return;
         }
+        self.render_scene_tree(scene_idx);
+    }
 
+    /// 将单个场景渲染到其像素缓冲
+    pub(super) fn render_scene(&mut self, scene_idx: usize) {
         let width = self.scenes.widths[scene_idx];
         let height = self.scenes.heights[scene_idx];
-        let bg_color = self.scenes.background_colors[scene_idx];
         let sampling_method = self.scenes.sampling_methods[scene_idx];
+        let linear = self.scenes.linear_blending[scene_idx];
 
-        // 优化1: 使用预计算背景行清空场景
-        if self.scenes.bg_dirty[scene_idx] || self.scenes.bg_rows[scene_idx].len() != (width * 4) as usize {
-            // 重新生成背景行
-            let row_size = (width * 4) as usize;
-            let mut bg_row = vec![0u8; row_size];
-            for i in 0..width as usize {
-                bg_row[i * 4] = bg_color[0];
-                bg_row[i * 4 + 1] = bg_color[1];
-                bg_row[i * 4 + 2] = bg_color[2];
-                bg_row[i * 4 + 3] = bg_color[3];
-            }
-            self.scenes.bg_rows[scene_idx] = bg_row;
+        // 优化1: 使用预计算背景行清空场景 (渐变或图像背景缓存整帧)
+        let row_size = (width * 4) as usize;
+        let bg_size = if self.scenes.has_full_background(scene_idx) {
+            row_size * height as usize
+        } else {
+            row_size
+        };
+        if self.scenes.bg_dirty[scene_idx] || self.scenes.bg_rows[scene_idx].len() != bg_size {
+            self.scenes.bg_rows[scene_idx] = self.scenes.build_background(scene_idx);
             self.scenes.bg_dirty[scene_idx] = false;
         }
 
         // 使用 copy_from_slice 批量填充背景
-        let row_size = (width * 4) as usize;
-        let bg_row = &self.scenes.bg_rows[scene_idx];
+        let bg_rows = self.scenes.bg_rows[scene_idx].chunks_exact(row_size.max(1)).cycle();
         let scene_data = &mut self.scenes.data[scene_idx];
-        for row in scene_data.chunks_exact_mut(row_size) {
+        for (row, bg_row) in scene_data.chunks_exact_mut(row_size.max(1)).zip(bg_rows) {
             row.copy_from_slice(bg_row);
         }
+        self.scenes.load_hdr(scene_idx);
 
         // 优化2: 使用缓存的排序精灵列表
         if self.scenes.sort_dirty[scene_idx] {
@@ -596,6 +930,13 @@ impl World {
 
         // 克隆排序列表以避免借用冲突
         let sprite_ids = self.scenes.sorted_sprites[scene_idx].clone();
+        self.update_sprite_filters(&sprite_ids);
+
+        // 瓦片地图、粒子与精灵图按 z-index 交错绘制 (同层时精灵图在上)
+        let layers = self.sorted_layers(scene_idx);
+        let mut next_layer = 0;
+        let lights = self.scene_lights(scene_idx);
+        let ambient = self.scenes.ambients[scene_idx];
 
         for sprite_id in sprite_ids {
             // 跳过非活跃精灵
@@ -604,24 +945,48 @@ impl World {
             }
             
             let idx = sprite_id as usize;
-            let sprite_data = &self.sprites.display_data[idx];
-            let sprite_w = self.sprites.display_widths[idx];
-            let sprite_h = self.sprites.display_heights[idx];
+            while next_layer < layers.len() && layers[next_layer].0 <= self.sprites.zindexes[idx] {
+                self.draw_layer(scene_idx, layers[next_layer].1);
+                next_layer += 1;
+            }
+
+            let [clip_left, clip_top, clip_right, clip_bottom] =
+                self.sprite_clip_bounds(idx, width, height);
+            // 带滤镜的精灵图使用扩展边距后的缓存结果
+            let (sprite_data, sprite_w, sprite_h) = match &self.sprites.filter_caches[idx] {
+                Some(image) => (&image.data, image.width, image.height),
+                None => (
+                    &self.sprites.display_data[idx],
+                    self.sprites.display_widths[idx],
+                    self.sprites.display_heights[idx],
+                ),
+            };
             let pos_x = self.sprites.positions_x[idx];
             let pos_y = self.sprites.positions_y[idx];
 
             let half_w = sprite_w as f32 / 2.0;
             let half_h = sprite_h as f32 / 2.0;
 
-            // 计算精灵图在场景中的边界
+            // 计算精灵图在场景中的边界 (与裁剪范围求交)
             let start_x = ((pos_x - half_w + center_x).floor() as i32).max(0) as u32;
             let end_x = ((pos_x + half_w + center_x).ceil() as i32).min(width as i32) as u32;
             let start_y = ((pos_y - half_h + center_y).floor() as i32).max(0) as u32;
             let end_y = ((pos_y + half_h + center_y).ceil() as i32).min(height as i32) as u32;
+            let start_x = start_x.max(clip_left);
+            let end_x = end_x.min(clip_right);
+            let start_y = start_y.max(clip_top);
+            let end_y = end_y.min(clip_bottom);
+            let mask = self.sprites.mask_sampler(idx, center_x, center_y);
+            let emission = self.sprites.emissions[idx];
+            let lighting = lights.as_deref().and_then(|lights| {
+                let bounds = [pos_x - half_w, pos_y - half_h, pos_x + half_w, pos_y + half_h];
+                self.sprites.light_sampler(idx, lights, ambient, bounds, center_x, center_y)
+            });
 
             // 优化3: 按行处理，减少索引计算
             let scene_data = &mut self.scenes.data[scene_idx];
-            
+            let mut hdr = self.scenes.hdr_buffers[scene_idx].as_deref_mut();
+
             for ty in start_y..end_y {
                 let dst_row_start = (ty * width) as usize * 4;
                 let local_y = ty as f32 - center_y - pos_y + half_h;
@@ -647,15 +1012,24 @@ impl World {
                                 None
                             }
                         }
+                        SamplingMethod::Bilinear if linear => {
+                            sample_bilinear_linear(sprite_data, sprite_w, sprite_h, local_x, local_y)
+                        }
                         SamplingMethod::Bilinear => {
                             sample_bilinear(sprite_data, sprite_w, sprite_h, local_x, local_y)
                         }
+                        SamplingMethod::Supersampling if linear => {
+                            sample_supersampling_linear(sprite_data, sprite_w, sprite_h, local_x, local_y)
+                        }
                         SamplingMethod::Supersampling => {
                             sample_supersampling(sprite_data, sprite_w, sprite_h, local_x, local_y)
                         }
                     };
 
-                    if let Some(color) = color {
+                    if let Some(mut color) = color {
+                        if let Some(mask) = &mask {
+                            color[3] = ((color[3] as u32 * mask.coverage(tx, ty) + 127) / 255) as u8;
+                        }
                         let dst_idx = dst_row_start + (tx as usize) * 4;
                         let src_a = color[3] as u32;
 
@@ -663,6 +1037,19 @@ impl World {
                         if src_a == 0 {
                             continue;
                         }
+                        // 光照与发光在线性光空间中计算，HDR 缓冲中不截断
+                        if hdr.is_some() || lighting.is_some() || !emission.is_plain() {
+                            let a = src_a as f32 / 255.0;
+                            let mut rgb = emission.linear_rgb(color);
+                            if let Some(lighting) = &lighting {
+                                rgb = lighting.shade(rgb, tx, ty);
+                            }
+                            match hdr.as_deref_mut() {
+                                Some(hdr) => blend_hdr(&mut hdr[dst_idx..dst_idx + 4], rgb, a, emission.additive),
+                                None => blend_clamped(&mut scene_data[dst_idx..dst_idx + 4], rgb, a, emission.additive),
+                            }
+                            continue;
+                        }
 
                         // 优化5: 快速路径 - 全不透明直接覆盖
                         if src_a == 255 {
@@ -673,6 +1060,11 @@ impl World {
                             continue;
                         }
 
+                        if linear {
+                            blend_linear(&mut scene_data[dst_idx..dst_idx + 4], color);
+                            continue;
+                        }
+
                         // 优化6: 定点数Alpha混合 (避免浮点除法)
                         let inv_a = 255 - src_a;
                         scene_data[dst_idx] = ((color[0] as u32 * src_a + scene_data[dst_idx] as u32 * inv_a) / 255) as u8;
@@ -683,23 +1075,29 @@ impl World {
                 }
             }
         }
+
+        // 绘制位于所有精灵图之上的图层
+        for &(_, layer) in &layers[next_layer..] {
+            self.draw_layer(scene_idx, layer);
+        }
+        self.scenes.resolve_hdr(scene_idx);
     }
 
-    /// 获取场景数据指针
+    /// 获取场景数据指针 (按默认场景的输出像素格式编码)
     pub fn scene_data_ptr(&self) -> *const u8 {
         let idx = self.default_scene as usize;
         if idx < self.scenes.data.len() {
-            self.scenes.data[idx].as_ptr()
+            self.scenes.output(idx).as_ptr()
         } else {
             std::ptr::null()
         }
     }
 
-    /// 获取场景数据长度
+    /// 获取场景数据长度 (字节)
     pub fn scene_data_len(&self) -> usize {
         let idx = self.default_scene as usize;
         if idx < self.scenes.data.len() {
-            self.scenes.data[idx].len()
+            self.scenes.output(idx).len()
         } else {
             0
         }
@@ -733,11 +1131,61 @@ impl World {
             self.scenes.heights[idx] = height;
             let new_size = (width * height * 4) as usize;
             self.scenes.data[idx].resize(new_size, 0);
+            // 输出缓冲与新尺寸保持一致
+            self.scenes.encode_output(idx);
             self.scenes.bg_dirty[idx] = true;
         }
     }
 }
 
+impl World {
+    /// 场景中与精灵图交错绘制的图层，按 z-index 排序 (同层时瓦片地图在粒子之下)
+    fn sorted_layers(&self, scene_idx: usize) -> Vec<(i32, SceneLayer)> {
+        let tilemaps = self.sorted_tilemaps(scene_idx).into_iter().map(|id| {
+            (self.tilemaps.zindexes[id as usize], SceneLayer::Tilemap(id))
+        });
+        let emitters = self.sorted_emitters(scene_idx).into_iter().map(|id| {
+            (self.emitters.zindexes[id as usize], SceneLayer::Emitter(id))
+        });
+        let mut layers: Vec<(i32, SceneLayer)> = tilemaps.chain(emitters).collect();
+        layers.sort_by_key(|&(zindex, _)| zindex);
+        layers
+    }
+
+    fn draw_layer(&mut self, scene_idx: usize, layer: SceneLayer) {
+        match layer {
+            SceneLayer::Tilemap(id) => self.draw_tilemap(scene_idx, id),
+            SceneLayer::Emitter(id) => self.draw_emitter(scene_idx, id),
+        }
+    }
+
+    /// 替换精灵图原始数据，并重新应用当前变换生成显示数据
+    pub(super) fn set_sprite_source(&mut self, id: u32, data: Vec<u8>, width: u32, height: u32) {
+        if !self.sprites.is_active(id) {
+            return;
+        }
+
+        // 新数据为 RGBA，不再按调色板索引解析
+        if let Some(i) = self.indexed.find(id) {
+            self.indexed.remove(i);
+        }
+
+        let idx = id as usize;
+        self.sprites.original_data[idx] = data;
+        self.sprites.original_widths[idx] = width;
+        self.sprites.original_heights[idx] = height;
+
+        match self.sprites.transforms[idx] {
+            SpriteTransform::None => self.reset_sprite_transform(id),
+            SpriteTransform::Rotation(angle) => self.apply_sprite_rotation(id, angle),
+            SpriteTransform::Scale(sx, sy) => self.apply_sprite_scale(id, sx, sy),
+            SpriteTransform::Transform(angle, sx, sy) => {
+                self.apply_sprite_transform(id, angle, sx, sy)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -796,4 +1244,84 @@ mod tests {
         world.render();
         assert!(world.scene_data_len() > 0);
     }
+
+    #[test]
+    fn test_gradient_background_and_sprite() {
+        let mut gradient = Gradient::linear(0.0, 0.0, 0.0, 10.0);
+        gradient.add_color_stop(0.0, 255, 0, 0, 255);
+        gradient.add_color_stop(1.0, 0, 0, 255, 255);
+
+        let mut world = World::new(4, 10);
+        world.set_background_gradient(&gradient);
+        world.render();
+        let data = &world.scenes.data[0];
+        assert!(data[0] > 200 && data[2] < 50);
+        let last = data.len() - 4;
+        assert!(data[last] < 50 && data[last + 2] > 200);
+
+        // 恢复纯色背景
+        world.set_background_color(0, 255, 0, 255);
+        world.render();
+        assert_eq!(&world.scenes.data[0][..4], &[0, 255, 0, 255]);
+
+        assert!(world.create_gradient_sprite(0, 10, &gradient).is_none());
+        assert!(world.create_gradient_sprite(1 << 20, 1 << 20, &gradient).is_none());
+        let id = world.create_gradient_sprite(2, 10, &gradient).unwrap();
+        let sprite = &world.sprites.original_data[id as usize];
+        assert!(sprite[0] > 200 && sprite[sprite.len() - 2] > 200);
+    }
+
+    #[test]
+    fn test_set_sprite_source_keeps_transform() {
+        let mut world = World::new(100, 100);
+        let id = world.create_rect_sprite(10, 10, 255, 0, 0, 255);
+        world.apply_sprite_scale(id, 2.0, 2.0);
+        assert_eq!(world.sprites.display_widths[id as usize], 20);
+
+        world.set_sprite_source(id, vec![0u8; 4 * 4 * 4], 4, 4);
+        assert_eq!(world.sprites.display_widths[id as usize], 8);
+        assert_eq!(world.sprites.display_heights[id as usize], 8);
+
+        world.reset_sprite_transform(id);
+        world.set_sprite_source(id, vec![0u8; 3 * 3 * 4], 3, 3);
+        assert_eq!(world.sprites.display_widths[id as usize], 3);
+    }
+
+    #[test]
+    fn test_linear_blending() {
+        let mut world = World::new(2, 2);
+        world.set_background_color(0, 0, 0, 255);
+        let id = world.create_rect_sprite(2, 2, 255, 255, 255, 128);
+        world.add_to_scene(id);
+        world.render();
+        assert_eq!(world.scenes.data[0][0], 128);
+
+        world.set_linear_blending(true);
+        assert!(world.get_linear_blending());
+        world.render();
+        assert!((world.scenes.data[0][0] as i32 - 188).abs() <= 1);
+        assert_eq!(world.scenes.data[0][3], 255);
+
+        // 关闭后无需其它改动即重新渲染
+        world.set_linear_blending(false);
+        world.render();
+        assert_eq!(world.scenes.data[0][0], 128);
+    }
+
+    #[test]
+    fn test_linear_gradient_background() {
+        let mut gradient = Gradient::linear(0.0, 0.0, 2.0, 0.0);
+        gradient.add_color_stop(0.0, 255, 0, 0, 255);
+        gradient.add_color_stop(1.0, 0, 255, 0, 255);
+
+        let mut world = World::new(3, 1);
+        world.set_background_gradient(&gradient);
+        world.render();
+        let srgb_mid = world.scenes.data[0][4];
+
+        world.set_linear_blending(true);
+        world.render();
+        let linear_mid = world.scenes.data[0][4];
+        assert!(linear_mid > srgb_mid + 40, "{linear_mid} vs {srgb_mid}");
+    }
 }
@@ -0,0 +1,158 @@
+//! 高斯模糊
+//!
+//! 以三次盒式模糊逼近高斯核，每次先水平后垂直各一遍，滑动窗口使开销与半径无关。
+//! 模糊在预乘 Alpha 空间进行，透明像素的颜色不会渗入相邻的不透明区域。
+//! 边缘像素向外延伸 (clamp)。
+
+/// 预乘 Alpha 的浮点像素 (各通道 0..1)
+pub(crate) type Pixel = [f32; 4];
+
+/// 非预乘 RGBA8 → 预乘浮点像素
+pub(crate) fn to_premultiplied(data: &[u8]) -> Vec<Pixel> {
+    data.chunks_exact(4)
+        .map(|px| {
+            let a = px[3] as f32 / 255.0;
+            [
+                px[0] as f32 / 255.0 * a,
+                px[1] as f32 / 255.0 * a,
+                px[2] as f32 / 255.0 * a,
+                a,
+            ]
+        })
+        .collect()
+}
+
+/// 预乘浮点像素 → 非预乘 RGBA8
+pub(crate) fn from_premultiplied(pixels: &[Pixel], data: &mut [u8]) {
+    for (px, p) in data.chunks_exact_mut(4).zip(pixels) {
+        let a = p[3].clamp(0.0, 1.0);
+        if a <= 0.0 {
+            px.copy_from_slice(&[0, 0, 0, 0]);
+            continue;
+        }
+        for i in 0..3 {
+            px[i] = (p[i] / a * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+        }
+        px[3] = (a * 255.0 + 0.5) as u8;
+    }
+}
+
+/// 计算逼近标准差 `sigma` 的三次盒式模糊半径
+fn box_radii(sigma: f32) -> [usize; 3] {
+    let n = 3.0;
+    let ideal = (12.0 * sigma * sigma / n + 1.0).sqrt();
+    let mut lower = ideal.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let wl = lower as f32;
+    let m = ((12.0 * sigma * sigma - n * wl * wl - 4.0 * n * wl - 3.0 * n) / (-4.0 * wl - 4.0))
+        .round() as i32;
+    let mut radii = [0; 3];
+    for (i, r) in radii.iter_mut().enumerate() {
+        let size = if (i as i32) < m { lower } else { lower + 2 };
+        *r = ((size - 1) / 2).max(0) as usize;
+    }
+    radii
+}
+
+/// 对预乘像素做高斯模糊 (原地)
+pub(crate) fn gaussian_blur(pixels: &mut [Pixel], width: usize, height: usize, sigma: f32) {
+    if sigma.is_nan() || sigma <= 0.0 || width == 0 || height == 0 || pixels.len() != width * height
+    {
+        return;
+    }
+    let mut tmp = vec![[0.0; 4]; pixels.len()];
+    for r in box_radii(sigma) {
+        if r == 0 {
+            continue;
+        }
+        box_pass(
+            pixels,
+            &mut tmp,
+            height,
+            width,
+            |line, i| line * width + i,
+            r,
+        );
+        box_pass(&tmp, pixels, width, height, |line, i| i * width + line, r);
+    }
+}
+
+/// 沿 `lines` 条长度为 `len` 的直线做一遍盒式模糊，`index(line, i)` 给出像素下标
+fn box_pass(
+    src: &[Pixel],
+    dst: &mut [Pixel],
+    lines: usize,
+    len: usize,
+    index: impl Fn(usize, usize) -> usize,
+    r: usize,
+) {
+    let scale = 1.0 / (2 * r + 1) as f32;
+    for line in 0..lines {
+        let at = |i: isize| src[index(line, i.clamp(0, len as isize - 1) as usize)];
+        let mut sum = [0.0f32; 4];
+        for i in -(r as isize)..=r as isize {
+            let p = at(i);
+            for c in 0..4 {
+                sum[c] += p[c];
+            }
+        }
+        for i in 0..len {
+            let out = &mut dst[index(line, i)];
+            for c in 0..4 {
+                out[c] = sum[c] * scale;
+            }
+            let (add, sub) = (at((i + r + 1) as isize), at(i as isize - r as isize));
+            for c in 0..4 {
+                sum[c] += add[c] - sub[c];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_radii() {
+        assert_eq!(box_radii(1.0), [0, 0, 1]);
+        let r = box_radii(5.0);
+        // 三次盒式模糊的方差之和应接近 sigma²
+        let variance: f32 = r
+            .iter()
+            .map(|&r| {
+                let w = (2 * r + 1) as f32;
+                (w * w - 1.0) / 12.0
+            })
+            .sum();
+        assert!((variance - 25.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn test_blur_spreads_and_preserves_energy() {
+        let (w, h) = (9, 9);
+        let mut pixels = vec![[0.0; 4]; w * h];
+        pixels[4 * w + 4] = [1.0, 1.0, 1.0, 1.0];
+        gaussian_blur(&mut pixels, w, h, 1.5);
+        let total: f32 = pixels.iter().map(|p| p[3]).sum();
+        assert!((total - 1.0).abs() < 1e-3);
+        assert!(pixels[4 * w + 4][3] < 1.0);
+        assert!(pixels[4 * w + 5][3] > 0.0);
+        // 对称
+        assert!((pixels[4 * w + 3][3] - pixels[4 * w + 5][3]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_transparent_does_not_darken() {
+        let mut data = vec![0u8; 4 * 4];
+        data[..4].copy_from_slice(&[255, 0, 0, 255]);
+        let mut pixels = to_premultiplied(&data);
+        gaussian_blur(&mut pixels, 4, 1, 1.0);
+        from_premultiplied(&pixels, &mut data);
+        // 与透明像素混合后仍为纯红，只是 Alpha 降低
+        assert_eq!(&data[4..7], &[255, 0, 0]);
+        assert!(data[7] > 0 && data[7] < 255);
+    }
+}
@@ -0,0 +1,132 @@
+//! 颜色矩阵
+//!
+//! 4x5 矩阵按行主序作用于非预乘的 [R, G, B, A, 1] (各通道 0..1)，
+//! 第 5 列为偏移量，与 SVG `feColorMatrix` / CSS 滤镜的定义一致。
+
+/// 颜色矩阵 (行主序 4x5)
+pub(crate) type ColorMatrix = [f32; 20];
+
+/// 仅作用于 RGB 的 3x3 矩阵与偏移构造颜色矩阵 (Alpha 不变)
+fn rgb(m: [f32; 9], offset: f32) -> ColorMatrix {
+    [
+        m[0], m[1], m[2], 0.0, offset, //
+        m[3], m[4], m[5], 0.0, offset, //
+        m[6], m[7], m[8], 0.0, offset, //
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ]
+}
+
+/// 亮度: RGB 乘以 `amount`
+pub(crate) fn brightness(amount: f32) -> ColorMatrix {
+    rgb([amount, 0.0, 0.0, 0.0, amount, 0.0, 0.0, 0.0, amount], 0.0)
+}
+
+/// 对比度: 以 0.5 为中心缩放
+pub(crate) fn contrast(amount: f32) -> ColorMatrix {
+    let offset = 0.5 - 0.5 * amount;
+    rgb(
+        [amount, 0.0, 0.0, 0.0, amount, 0.0, 0.0, 0.0, amount],
+        offset,
+    )
+}
+
+/// 饱和度: 0 为灰度，1 不变，大于 1 增强
+pub(crate) fn saturation(s: f32) -> ColorMatrix {
+    rgb(
+        [
+            0.213 + 0.787 * s,
+            0.715 - 0.715 * s,
+            0.072 - 0.072 * s,
+            0.213 - 0.213 * s,
+            0.715 + 0.285 * s,
+            0.072 - 0.072 * s,
+            0.213 - 0.213 * s,
+            0.715 - 0.715 * s,
+            0.072 + 0.928 * s,
+        ],
+        0.0,
+    )
+}
+
+/// 色相旋转 (弧度)，保持亮度近似不变
+pub(crate) fn hue_rotate(angle: f32) -> ColorMatrix {
+    let (sin, cos) = angle.sin_cos();
+    rgb(
+        [
+            0.213 + cos * 0.787 - sin * 0.213,
+            0.715 - cos * 0.715 - sin * 0.715,
+            0.072 - cos * 0.072 + sin * 0.928,
+            0.213 - cos * 0.213 + sin * 0.143,
+            0.715 + cos * 0.285 + sin * 0.140,
+            0.072 - cos * 0.072 - sin * 0.283,
+            0.213 - cos * 0.213 - sin * 0.787,
+            0.715 - cos * 0.715 + sin * 0.715,
+            0.072 + cos * 0.928 + sin * 0.072,
+        ],
+        0.0,
+    )
+}
+
+/// 反相: `amount` 为与原色的混合比例
+pub(crate) fn invert(amount: f32) -> ColorMatrix {
+    let k = 1.0 - 2.0 * amount;
+    rgb([k, 0.0, 0.0, 0.0, k, 0.0, 0.0, 0.0, k], amount)
+}
+
+/// 将颜色矩阵应用到 RGBA8 像素
+pub(crate) fn apply_matrix(data: &mut [u8], m: &ColorMatrix) {
+    for px in data.chunks_exact_mut(4) {
+        let v = [
+            px[0] as f32 / 255.0,
+            px[1] as f32 / 255.0,
+            px[2] as f32 / 255.0,
+            px[3] as f32 / 255.0,
+        ];
+        for (i, out) in px.iter_mut().enumerate() {
+            let row = &m[i * 5..i * 5 + 5];
+            let c = row[0] * v[0] + row[1] * v[1] + row[2] * v[2] + row[3] * v[3] + row[4];
+            *out = (c * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 单位矩阵
+    const IDENTITY: ColorMatrix = [
+        1.0, 0.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ];
+
+    fn applied(m: &ColorMatrix, px: [u8; 4]) -> [u8; 4] {
+        let mut data = px;
+        apply_matrix(&mut data, m);
+        data
+    }
+
+    #[test]
+    fn test_basic_matrices() {
+        let px = [200, 100, 50, 128];
+        assert_eq!(applied(&IDENTITY, px), px);
+        assert_eq!(applied(&brightness(0.5), px), [100, 50, 25, 128]);
+        assert_eq!(applied(&invert(1.0), px), [55, 155, 205, 128]);
+        assert_eq!(applied(&contrast(0.0), px), [128, 128, 128, 128]);
+        let gray = applied(&saturation(0.0), px);
+        assert!(gray[0] == gray[1] && gray[1] == gray[2]);
+    }
+
+    #[test]
+    fn test_hue_rotate() {
+        assert_eq!(
+            applied(&hue_rotate(0.0), [10, 20, 30, 255]),
+            [10, 20, 30, 255]
+        );
+        // 旋转 180° 后红色偏向青色
+        let c = applied(&hue_rotate(std::f32::consts::PI), [255, 0, 0, 255]);
+        assert!(c[0] < c[1] && c[0] < c[2]);
+    }
+}
@@ -0,0 +1,269 @@
+//! 全屏效果
+//!
+//! 泛光、暗角、色差、CRT 扫描线、像素化与灰度 / 怀旧色调。
+//! 需要邻域采样的效果从原始像素的副本中读取，按最近邻采样，坐标越界时截断到边缘。
+
+use super::blur::{gaussian_blur, to_premultiplied};
+
+/// Rec.709 亮度 (0..1)
+pub(crate) fn luminance(r: f32, g: f32, b: f32) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// 像素中心的归一化坐标 ([-1, 1]，中心为 0)
+fn normalized(x: u32, y: u32, width: u32, height: u32) -> (f32, f32) {
+    (
+        (x as f32 + 0.5) / width as f32 * 2.0 - 1.0,
+        (y as f32 + 0.5) / height as f32 * 2.0 - 1.0,
+    )
+}
+
+/// 最近邻读取归一化坐标处的像素 (越界截断)
+fn sample(src: &[u8], width: u32, height: u32, u: f32, v: f32) -> [u8; 4] {
+    let x = (((u + 1.0) * 0.5 * width as f32) as i64).clamp(0, width as i64 - 1);
+    let y = (((v + 1.0) * 0.5 * height as f32) as i64).clamp(0, height as i64 - 1);
+    let i = ((y as usize) * width as usize + x as usize) * 4;
+    [src[i], src[i + 1], src[i + 2], src[i + 3]]
+}
+
+/// 泛光: 提取亮度超过阈值的部分，模糊后叠加回原图
+pub(super) fn bloom(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    threshold: f32,
+    intensity: f32,
+    sigma: f32,
+) {
+    let mut bright = to_premultiplied(data);
+    let knee = (1.0 - threshold).max(1e-3);
+    for p in bright.iter_mut() {
+        let a = p[3];
+        let l = if a > 0.0 {
+            luminance(p[0], p[1], p[2]) / a
+        } else {
+            0.0
+        };
+        let k = ((l - threshold) / knee).clamp(0.0, 1.0);
+        for c in p.iter_mut() {
+            *c *= k;
+        }
+    }
+    gaussian_blur(&mut bright, width as usize, height as usize, sigma);
+    for (px, glow) in data.chunks_exact_mut(4).zip(&bright) {
+        for i in 0..3 {
+            let v = px[i] as f32 + glow[i] * intensity * 255.0;
+            px[i] = (v + 0.5).min(255.0) as u8;
+        }
+        let a = px[3] as f32 + glow[3] * intensity * 255.0;
+        px[3] = (a + 0.5).min(255.0) as u8;
+    }
+}
+
+/// 暗角: 从 `radius` 到 `radius + softness` (按半对角线归一化的距离) 逐渐变暗
+pub(super) fn vignette(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    strength: f32,
+    radius: f32,
+    softness: f32,
+) {
+    for y in 0..height {
+        for x in 0..width {
+            let (u, v) = normalized(x, y, width, height);
+            let d = (u * u + v * v).sqrt() / std::f32::consts::SQRT_2;
+            let f = 1.0 - strength * smoothstep(radius, radius + softness, d);
+            let i = ((y * width + x) * 4) as usize;
+            for c in &mut data[i..i + 3] {
+                *c = (*c as f32 * f + 0.5) as u8;
+            }
+        }
+    }
+}
+
+/// 色差: 红、蓝通道沿径向向外 / 向内偏移，边缘偏移 `offset` 像素，中心不偏移
+pub(super) fn chromatic_aberration(data: &mut [u8], width: u32, height: u32, offset: f32) {
+    let src = data.to_vec();
+    let (sx, sy) = (2.0 * offset / width as f32, 2.0 * offset / height as f32);
+    for y in 0..height {
+        for x in 0..width {
+            let (u, v) = normalized(x, y, width, height);
+            let i = ((y * width + x) * 4) as usize;
+            data[i] = sample(&src, width, height, u * (1.0 - sx), v * (1.0 - sy))[0];
+            data[i + 2] = sample(&src, width, height, u * (1.0 + sx), v * (1.0 + sy))[2];
+        }
+    }
+}
+
+/// CRT: 桶形畸变 (屏幕外为黑色) 与扫描线 (奇数行变暗)
+pub(super) fn crt(data: &mut [u8], width: u32, height: u32, scanline: f32, curvature: f32) {
+    let src = (curvature > 0.0).then(|| data.to_vec());
+    for y in 0..height {
+        let line = if y % 2 == 1 { 1.0 - scanline } else { 1.0 };
+        for x in 0..width {
+            let i = ((y * width + x) * 4) as usize;
+            if let Some(src) = &src {
+                let (u, v) = normalized(x, y, width, height);
+                let k = 1.0 + curvature * (u * u + v * v) * 0.25;
+                let (u, v) = (u * k, v * k);
+                let color = if u.abs() > 1.0 || v.abs() > 1.0 {
+                    [0, 0, 0, 255]
+                } else {
+                    sample(src, width, height, u, v)
+                };
+                data[i..i + 4].copy_from_slice(&color);
+            }
+            for c in &mut data[i..i + 3] {
+                *c = (*c as f32 * line + 0.5) as u8;
+            }
+        }
+    }
+}
+
+/// 像素化: 按 `size` × `size` 的块取平均色 (预乘 Alpha 平均)
+pub(super) fn pixelate(data: &mut [u8], width: u32, height: u32, size: u32) {
+    let (w, h, size) = (width as usize, height as usize, size.max(1) as usize);
+    for by in (0..h).step_by(size) {
+        for bx in (0..w).step_by(size) {
+            let (x1, y1) = ((bx + size).min(w), (by + size).min(h));
+            let mut sum = [0u64; 4];
+            for y in by..y1 {
+                for x in bx..x1 {
+                    let px = &data[(y * w + x) * 4..][..4];
+                    let a = px[3] as u64;
+                    for c in 0..3 {
+                        sum[c] += px[c] as u64 * a;
+                    }
+                    sum[3] += a;
+                }
+            }
+            let count = ((x1 - bx) * (y1 - by)) as u64;
+            let color = match sum[3] {
+                0 => [0, 0, 0, 0],
+                a => [
+                    ((sum[0] + a / 2) / a) as u8,
+                    ((sum[1] + a / 2) / a) as u8,
+                    ((sum[2] + a / 2) / a) as u8,
+                    ((a + count / 2) / count) as u8,
+                ],
+            };
+            for y in by..y1 {
+                for x in bx..x1 {
+                    data[(y * w + x) * 4..][..4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
+/// 灰度: 按 `amount` 在原色与亮度之间插值
+pub(super) fn grayscale(data: &mut [u8], amount: f32) {
+    for px in data.chunks_exact_mut(4) {
+        let l = luminance(px[0] as f32, px[1] as f32, px[2] as f32);
+        for c in &mut px[..3] {
+            *c = (*c as f32 + (l - *c as f32) * amount + 0.5).clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// 怀旧色调: 按 `amount` 在原色与经典 sepia 矩阵结果之间插值
+pub(super) fn sepia(data: &mut [u8], amount: f32) {
+    for px in data.chunks_exact_mut(4) {
+        let (r, g, b) = (px[0] as f32, px[1] as f32, px[2] as f32);
+        let toned = [
+            0.393 * r + 0.769 * g + 0.189 * b,
+            0.349 * r + 0.686 * g + 0.168 * b,
+            0.272 * r + 0.534 * g + 0.131 * b,
+        ];
+        for (c, t) in px[..3].iter_mut().zip(toned) {
+            *c = (*c as f32 + (t - *c as f32) * amount + 0.5).clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        color.repeat((width * height) as usize)
+    }
+
+    #[test]
+    fn test_vignette_darkens_corners() {
+        let mut data = solid(9, 9, [200, 200, 200, 255]);
+        vignette(&mut data, 9, 9, 1.0, 0.3, 0.5);
+        assert_eq!(&data[(4 * 9 + 4) * 4..][..4], &[200, 200, 200, 255]);
+        assert!(data[0] < 100);
+        assert_eq!(data[3], 255);
+    }
+
+    #[test]
+    fn test_pixelate_averages_blocks() {
+        let mut data = vec![0u8; 4 * 4 * 4];
+        data[..4].copy_from_slice(&[255, 255, 255, 255]);
+        data[4..8].copy_from_slice(&[0, 0, 0, 255]);
+        pixelate(&mut data, 4, 4, 2);
+        // 左上 2x2 块: 一白一黑两个不透明像素与两个透明像素
+        assert_eq!(&data[..4], &[128, 128, 128, 128]);
+        assert_eq!(&data[(4 + 1) * 4..][..4], &[128, 128, 128, 128]);
+        assert_eq!(&data[2 * 4..][..4], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_grayscale_and_sepia() {
+        let mut data = vec![255, 0, 0, 255];
+        grayscale(&mut data, 1.0);
+        assert_eq!(data, vec![54, 54, 54, 255]);
+        let mut data = vec![255, 0, 0, 255];
+        grayscale(&mut data, 0.0);
+        assert_eq!(data, vec![255, 0, 0, 255]);
+        let mut data = vec![100, 100, 100, 255];
+        sepia(&mut data, 1.0);
+        assert!(data[0] > data[1] && data[1] > data[2]);
+    }
+
+    #[test]
+    fn test_bloom_only_affects_bright_areas() {
+        let mut data = solid(5, 5, [20, 20, 20, 255]);
+        data[(2 * 5 + 2) * 4..][..4].copy_from_slice(&[255, 255, 255, 255]);
+        let mut dark = solid(5, 5, [20, 20, 20, 255]);
+        bloom(&mut data, 5, 5, 0.8, 1.0, 1.0);
+        bloom(&mut dark, 5, 5, 0.8, 1.0, 1.0);
+        assert!(data[(2 * 5 + 3) * 4] > 20);
+        assert!(dark.iter().step_by(4).all(|&c| c == 20));
+    }
+
+    #[test]
+    fn test_crt_scanlines_and_curvature() {
+        let mut data = solid(8, 8, [200, 200, 200, 255]);
+        crt(&mut data, 8, 8, 0.5, 0.0);
+        assert_eq!(data[0], 200);
+        assert_eq!(data[8 * 4], 100);
+        let mut data = solid(8, 8, [200, 200, 200, 255]);
+        crt(&mut data, 8, 8, 0.0, 1.0);
+        // 角落被推出屏幕外
+        assert_eq!(&data[..4], &[0, 0, 0, 255]);
+        assert_eq!(data[(4 * 8 + 4) * 4], 200);
+    }
+
+    #[test]
+    fn test_chromatic_aberration_splits_channels() {
+        let mut data = solid(16, 1, [0, 0, 0, 255]);
+        data[12 * 4..][..4].copy_from_slice(&[255, 255, 255, 255]);
+        chromatic_aberration(&mut data, 16, 1, 2.0);
+        // 红色向外、蓝色向内偏移
+        assert_eq!(data[13 * 4], 255);
+        assert_eq!(data[11 * 4 + 2], 255);
+        assert_eq!(data[12 * 4 + 1], 255);
+    }
+}
@@ -0,0 +1,209 @@
+//! 3D 颜色查找表 (LUT)
+//!
+//! 支持 Adobe / Resolve 的 `.cube` 文本格式 (仅 `LUT_3D_SIZE`)，以及常见的条带图像布局:
+//! N 个 N×N 的切片横向 (宽 N², 高 N) 或纵向 (宽 N, 高 N²) 排列，切片序号对应蓝色，
+//! 切片内 x 对应红色、y 对应绿色。查表使用三线性插值，Alpha 不变。
+
+/// LUT 边长上限
+const MAX_SIZE: usize = 256;
+
+/// 3D 颜色查找表
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Lut3d {
+    /// 边长
+    size: usize,
+    /// 输出颜色 (红色变化最快，其次绿色、蓝色)
+    table: Vec<[f32; 3]>,
+    /// 输入范围下限
+    domain_min: [f32; 3],
+    /// 输入范围上限
+    domain_max: [f32; 3],
+}
+
+/// 解析若干个浮点数
+fn floats<const N: usize>(fields: &[&str]) -> Option<[f32; N]> {
+    if fields.len() != N {
+        return None;
+    }
+    let mut out = [0.0; N];
+    for (o, f) in out.iter_mut().zip(fields) {
+        *o = f.parse().ok()?;
+    }
+    Some(out)
+}
+
+impl Lut3d {
+    /// 解析 `.cube` 文本
+    pub(crate) fn parse_cube(text: &str) -> Option<Self> {
+        let mut size = 0;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[0] {
+                "TITLE" => {}
+                "LUT_3D_SIZE" => {
+                    size = fields.get(1)?.parse().ok()?;
+                    if !(2..=MAX_SIZE).contains(&size) {
+                        return None;
+                    }
+                    table.reserve(size * size * size);
+                }
+                "DOMAIN_MIN" => domain_min = floats(&fields[1..])?,
+                "DOMAIN_MAX" => domain_max = floats(&fields[1..])?,
+                // 一维 LUT 与未知关键字不支持
+                key if key.starts_with(|c: char| c.is_ascii_alphabetic()) => return None,
+                _ => table.push(floats(&fields)?),
+            }
+        }
+        if size == 0 || table.len() != size * size * size {
+            return None;
+        }
+        Some(Self {
+            size,
+            table,
+            domain_min,
+            domain_max,
+        })
+    }
+
+    /// 从 RGBA8 条带图像创建
+    pub(crate) fn from_strip(data: &[u8], width: u32, height: u32) -> Option<Self> {
+        let (w, h) = (width as usize, height as usize);
+        let (size, horizontal) = if h >= 2 && w == h * h {
+            (h, true)
+        } else if w >= 2 && h == w * w {
+            (w, false)
+        } else {
+            return None;
+        };
+        if size > MAX_SIZE || data.len() != w * h * 4 {
+            return None;
+        }
+        let mut table = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let (x, y) = if horizontal {
+                        (b * size + r, g)
+                    } else {
+                        (r, b * size + g)
+                    };
+                    let px = &data[(y * w + x) * 4..][..3];
+                    table.push([
+                        px[0] as f32 / 255.0,
+                        px[1] as f32 / 255.0,
+                        px[2] as f32 / 255.0,
+                    ]);
+                }
+            }
+        }
+        Some(Self {
+            size,
+            table,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+        })
+    }
+
+    /// 三线性插值查表 (输入各通道 0..1)
+    pub(crate) fn lookup(&self, color: [f32; 3]) -> [f32; 3] {
+        let n = self.size;
+        let max = (n - 1) as f32;
+        let mut base = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for c in 0..3 {
+            let range = self.domain_max[c] - self.domain_min[c];
+            let t = if range > 0.0 {
+                (color[c] - self.domain_min[c]) / range
+            } else {
+                0.0
+            };
+            let p = (t * max).clamp(0.0, max);
+            base[c] = (p.floor() as usize).min(n - 2);
+            frac[c] = p - base[c] as f32;
+        }
+        let at = |r: usize, g: usize, b: usize| self.table[(b * n + g) * n + r];
+        let mut out = [0.0; 3];
+        for (corner, weight) in (0..8).map(|i| {
+            let d = [i & 1, (i >> 1) & 1, (i >> 2) & 1];
+            let w: f32 = (0..3)
+                .map(|c| if d[c] == 1 { frac[c] } else { 1.0 - frac[c] })
+                .product();
+            (at(base[0] + d[0], base[1] + d[1], base[2] + d[2]), w)
+        }) {
+            for c in 0..3 {
+                out[c] += corner[c] * weight;
+            }
+        }
+        out
+    }
+
+    /// 将查找表应用到 RGBA8 像素，`amount` 为与原色的混合比例
+    pub(crate) fn apply(&self, data: &mut [u8], amount: f32) {
+        for px in data.chunks_exact_mut(4) {
+            let src = [
+                px[0] as f32 / 255.0,
+                px[1] as f32 / 255.0,
+                px[2] as f32 / 255.0,
+            ];
+            let graded = self.lookup(src);
+            for c in 0..3 {
+                let v = src[c] + (graded[c] - src[c]) * amount;
+                px[c] = (v * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 边长为 2 的反相 LUT
+    const INVERT_CUBE: &str = "# comment
+TITLE \"invert\"
+LUT_3D_SIZE 2
+1 1 1
+0 1 1
+1 0 1
+0 0 1
+1 1 0
+0 1 0
+1 0 0
+0 0 0
+";
+
+    #[test]
+    fn test_parse_cube() {
+        let lut = Lut3d::parse_cube(INVERT_CUBE).unwrap();
+        let mut data = [255, 0, 0, 200, 64, 128, 191, 255];
+        lut.apply(&mut data, 1.0);
+        assert_eq!(data, [0, 255, 255, 200, 191, 127, 64, 255]);
+        assert!(Lut3d::parse_cube("LUT_3D_SIZE 2\n0 0 0\n").is_none());
+        assert!(Lut3d::parse_cube("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").is_none());
+    }
+
+    #[test]
+    fn test_strip_identity() {
+        // 横向条带: 2 个 2x2 切片
+        let mut strip = Vec::new();
+        for g in 0..2u8 {
+            for b in 0..2u8 {
+                for r in 0..2u8 {
+                    strip.extend_from_slice(&[r * 255, g * 255, b * 255, 255]);
+                }
+            }
+        }
+        let lut = Lut3d::from_strip(&strip, 4, 2).unwrap();
+        let mut data = [10, 100, 250, 255];
+        lut.apply(&mut data, 1.0);
+        assert_eq!(data, [10, 100, 250, 255]);
+        assert!(Lut3d::from_strip(&strip, 8, 1).is_none());
+    }
+}
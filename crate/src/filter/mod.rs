@@ -0,0 +1,464 @@
+//! 图像滤镜模块
+//!
+//! 对整块 RGBA8 像素缓冲做后期处理: 高斯模糊、泛光、暗角、色差、CRT 扫描线、像素化、灰度与怀旧色调，
+//! 投影、描边、外发光等基于轮廓的效果，颜色矩阵与 3D LUT 调色，以及带抖动的调色板量化。
+//! 滤镜是带参数的值对象，由场景或精灵图按顺序保存并依次应用。
+//! 用于精灵图时，图像四周先按各滤镜的 `padding` 之和扩展透明边距，使效果不被裁掉。
+
+mod blur;
+mod color;
+mod effects;
+mod lut;
+mod silhouette;
+
+use std::f32::consts::TAU;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+
+use crate::image::decode_image;
+use crate::palette::{DitherMode, Palette};
+use color::ColorMatrix;
+use lut::Lut3d;
+
+/// 将非有限值替换为 0 并截断到 [min, max]
+fn param(value: f32, min: f32, max: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(min, max)
+    } else {
+        0.0f32.clamp(min, max)
+    }
+}
+
+/// 模糊标准差上限 (像素)
+const MAX_SIGMA: f32 = 256.0;
+
+/// 偏移与描边宽度上限 (像素)
+const MAX_OFFSET: f32 = 256.0;
+
+/// 滤镜类型与参数
+#[derive(Debug, Clone, PartialEq)]
+enum FilterKind {
+    /// 高斯模糊
+    Blur { sigma: f32 },
+    /// 泛光
+    Bloom {
+        threshold: f32,
+        intensity: f32,
+        sigma: f32,
+    },
+    /// 暗角
+    Vignette {
+        strength: f32,
+        radius: f32,
+        softness: f32,
+    },
+    /// 色差
+    ChromaticAberration { offset: f32 },
+    /// CRT 扫描线与桶形畸变
+    Crt { scanline: f32, curvature: f32 },
+    /// 像素化
+    Pixelate { size: u32 },
+    /// 灰度
+    Grayscale { amount: f32 },
+    /// 怀旧色调
+    Sepia { amount: f32 },
+    /// 投影
+    DropShadow {
+        offset_x: f32,
+        offset_y: f32,
+        sigma: f32,
+        color: [u8; 4],
+    },
+    /// 描边
+    Outline { width: f32, color: [u8; 4] },
+    /// 外发光
+    Glow {
+        sigma: f32,
+        strength: f32,
+        color: [u8; 4],
+    },
+    /// 颜色矩阵
+    ColorMatrix(ColorMatrix),
+    /// 3D LUT 调色 (查找表共享，复制滤镜时不复制表数据)
+    Lut { lut: Rc<Lut3d>, amount: f32 },
+    /// 调色板量化
+    Quantize {
+        palette: Rc<Palette>,
+        dither: DitherMode,
+    },
+}
+
+/// 滤镜
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    kind: FilterKind,
+}
+
+impl Filter {
+    /// 将滤镜应用到 RGBA8 像素缓冲 (原地)
+    pub(crate) fn apply(&self, data: &mut [u8], width: u32, height: u32) {
+        if width == 0 || height == 0 || data.len() != (width * height * 4) as usize {
+            return;
+        }
+        match self.kind {
+            FilterKind::Blur { sigma } => {
+                let mut pixels = blur::to_premultiplied(data);
+                blur::gaussian_blur(&mut pixels, width as usize, height as usize, sigma);
+                blur::from_premultiplied(&pixels, data);
+            }
+            FilterKind::Bloom {
+                threshold,
+                intensity,
+                sigma,
+            } => effects::bloom(data, width, height, threshold, intensity, sigma),
+            FilterKind::Vignette {
+                strength,
+                radius,
+                softness,
+            } => effects::vignette(data, width, height, strength, radius, softness),
+            FilterKind::ChromaticAberration { offset } => {
+                effects::chromatic_aberration(data, width, height, offset)
+            }
+            FilterKind::Crt {
+                scanline,
+                curvature,
+            } => effects::crt(data, width, height, scanline, curvature),
+            FilterKind::Pixelate { size } => effects::pixelate(data, width, height, size),
+            FilterKind::Grayscale { amount } => effects::grayscale(data, amount),
+            FilterKind::Sepia { amount } => effects::sepia(data, amount),
+            FilterKind::DropShadow {
+                offset_x,
+                offset_y,
+                sigma,
+                color,
+            } => silhouette::drop_shadow(data, width, height, (offset_x, offset_y), sigma, color),
+            FilterKind::Outline { width: w, color } => {
+                silhouette::outline(data, width, height, w, color)
+            }
+            FilterKind::Glow {
+                sigma,
+                strength,
+                color,
+            } => silhouette::glow(data, width, height, sigma, strength, color),
+            FilterKind::ColorMatrix(ref m) => color::apply_matrix(data, m),
+            FilterKind::Lut { ref lut, amount } => lut.apply(data, amount),
+            FilterKind::Quantize {
+                ref palette,
+                dither,
+            } => palette.quantize(data, width, height, dither),
+        }
+    }
+
+    /// 效果超出原图的最大距离 (像素)，精灵图四周按此扩展透明边距
+    pub(crate) fn padding(&self) -> u32 {
+        let spread = |sigma: f32| (sigma * 3.0).ceil() as u32;
+        match self.kind {
+            FilterKind::Blur { sigma } | FilterKind::Bloom { sigma, .. } => spread(sigma),
+            FilterKind::DropShadow {
+                offset_x,
+                offset_y,
+                sigma,
+                ..
+            } => offset_x.abs().max(offset_y.abs()).round() as u32 + spread(sigma),
+            FilterKind::Outline { width, .. } => width.ceil() as u32,
+            FilterKind::Glow { sigma, .. } => spread(sigma),
+            _ => 0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Filter {
+    /// 高斯模糊，`sigma` 为标准差 (像素)
+    pub fn blur(sigma: f32) -> Self {
+        Self {
+            kind: FilterKind::Blur {
+                sigma: param(sigma, 0.0, MAX_SIGMA),
+            },
+        }
+    }
+
+    /// 泛光
+    ///
+    /// 亮度超过 `threshold` (0..1) 的部分经 `sigma` 模糊后乘以 `intensity` 叠加回原图。
+    pub fn bloom(threshold: f32, intensity: f32, sigma: f32) -> Self {
+        Self {
+            kind: FilterKind::Bloom {
+                threshold: param(threshold, 0.0, 1.0),
+                intensity: param(intensity, 0.0, 16.0),
+                sigma: param(sigma, 0.0, MAX_SIGMA),
+            },
+        }
+    }
+
+    /// 暗角
+    ///
+    /// 距中心的距离按半对角线归一化 (角落为 1)，从 `radius` 开始在 `softness` 范围内
+    /// 逐渐变暗，最暗处亮度乘以 `1 - strength`。
+    pub fn vignette(strength: f32, radius: f32, softness: f32) -> Self {
+        Self {
+            kind: FilterKind::Vignette {
+                strength: param(strength, 0.0, 1.0),
+                radius: param(radius, 0.0, 2.0),
+                softness: param(softness, 0.0, 2.0),
+            },
+        }
+    }
+
+    /// 色差，画面边缘红、蓝通道分别向外、向内偏移 `offset` 像素
+    pub fn chromatic_aberration(offset: f32) -> Self {
+        Self {
+            kind: FilterKind::ChromaticAberration {
+                offset: param(offset, -MAX_OFFSET, MAX_OFFSET),
+            },
+        }
+    }
+
+    /// CRT 效果
+    ///
+    /// `scanline` (0..1) 为奇数行变暗的比例，`curvature` (0..1) 为桶形畸变强度 (0 表示不弯曲)。
+    pub fn crt(scanline: f32, curvature: f32) -> Self {
+        Self {
+            kind: FilterKind::Crt {
+                scanline: param(scanline, 0.0, 1.0),
+                curvature: param(curvature, 0.0, 1.0),
+            },
+        }
+    }
+
+    /// 像素化，`size` 为块边长 (像素)
+    pub fn pixelate(size: u32) -> Self {
+        Self {
+            kind: FilterKind::Pixelate { size: size.max(1) },
+        }
+    }
+
+    /// 灰度，`amount` (0..1) 为与原色的混合比例
+    pub fn grayscale(amount: f32) -> Self {
+        Self {
+            kind: FilterKind::Grayscale {
+                amount: param(amount, 0.0, 1.0),
+            },
+        }
+    }
+
+    /// 怀旧色调，`amount` (0..1) 为与原色的混合比例
+    pub fn sepia(amount: f32) -> Self {
+        Self {
+            kind: FilterKind::Sepia {
+                amount: param(amount, 0.0, 1.0),
+            },
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Filter {
+    /// 投影
+    ///
+    /// 轮廓偏移 (`offset_x`, `offset_y`) 像素、按 `sigma` 模糊并以给定颜色绘制在原图之下。
+    pub fn drop_shadow(
+        offset_x: f32,
+        offset_y: f32,
+        sigma: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) -> Self {
+        Self {
+            kind: FilterKind::DropShadow {
+                offset_x: param(offset_x, -MAX_OFFSET, MAX_OFFSET),
+                offset_y: param(offset_y, -MAX_OFFSET, MAX_OFFSET),
+                sigma: param(sigma, 0.0, MAX_SIGMA),
+                color: [r, g, b, a],
+            },
+        }
+    }
+
+    /// 描边，轮廓向外扩展 `width` 像素
+    pub fn outline(width: f32, r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            kind: FilterKind::Outline {
+                width: param(width, 0.0, MAX_OFFSET),
+                color: [r, g, b, a],
+            },
+        }
+    }
+
+    /// 外发光
+    ///
+    /// 轮廓按 `sigma` 模糊后乘以 `strength` (1 为原始强度) 并以给定颜色绘制在原图之下。
+    pub fn glow(sigma: f32, strength: f32, r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            kind: FilterKind::Glow {
+                sigma: param(sigma, 0.0, MAX_SIGMA),
+                strength: param(strength, 0.0, 16.0),
+                color: [r, g, b, a],
+            },
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Filter {
+    /// 自定义 4x5 颜色矩阵 (行主序 20 个数，作用于 0..1 的非预乘 RGBA，第 5 列为偏移)
+    ///
+    /// 数量不为 20 或含非有限值时返回 None。
+    pub fn color_matrix(values: &[f32]) -> Option<Filter> {
+        let m: ColorMatrix = values.try_into().ok()?;
+        m.iter().all(|v| v.is_finite()).then_some(Self {
+            kind: FilterKind::ColorMatrix(m),
+        })
+    }
+
+    /// 亮度，RGB 乘以 `amount` (1 为不变)
+    pub fn brightness(amount: f32) -> Self {
+        Self::matrix(color::brightness(param(amount, 0.0, 16.0)))
+    }
+
+    /// 对比度，以中灰为中心缩放 (1 为不变，0 为全灰)
+    pub fn contrast(amount: f32) -> Self {
+        Self::matrix(color::contrast(param(amount, 0.0, 16.0)))
+    }
+
+    /// 饱和度 (1 为不变，0 为灰度，大于 1 增强)
+    pub fn saturate(amount: f32) -> Self {
+        Self::matrix(color::saturation(param(amount, 0.0, 16.0)))
+    }
+
+    /// 色相旋转 (弧度)
+    pub fn hue_rotate(angle: f32) -> Self {
+        Self::matrix(color::hue_rotate(param(angle, -TAU, TAU)))
+    }
+
+    /// 反相，`amount` (0..1) 为与原色的混合比例
+    pub fn invert(amount: f32) -> Self {
+        Self::matrix(color::invert(param(amount, 0.0, 1.0)))
+    }
+
+    /// 从 `.cube` 文本创建 3D LUT 调色滤镜，`amount` (0..1) 为与原色的混合比例
+    ///
+    /// 仅支持 `LUT_3D_SIZE`，格式错误时返回 None。
+    pub fn lut_from_cube(text: &str, amount: f32) -> Option<Filter> {
+        Some(Self::lut(Lut3d::parse_cube(text)?, amount))
+    }
+
+    /// 从 LUT 条带图像 (PNG 等编码数据) 创建 3D LUT 调色滤镜
+    ///
+    /// 图像须为 N 个 N×N 切片横向 (N² × N) 或纵向 (N × N²) 排列，否则返回 None。
+    pub fn lut_from_image(bytes: &[u8], amount: f32) -> Option<Filter> {
+        let image = decode_image(bytes)?;
+        Some(Self::lut(
+            Lut3d::from_strip(&image.data, image.width, image.height)?,
+            amount,
+        ))
+    }
+}
+
+#[wasm_bindgen]
+impl Filter {
+    /// 调色板量化 (通常作为场景的最后一个滤镜，也可用于单个精灵图)
+    ///
+    /// `dither`: 0 = 不抖动, 1 = Bayer 2x2, 2 = Bayer 4x4, 3 = Bayer 8x8,
+    /// 4 = Floyd–Steinberg, 5 = Atkinson。
+    pub fn quantize(palette: &Palette, dither: u8) -> Self {
+        Self {
+            kind: FilterKind::Quantize {
+                palette: Rc::new(palette.clone()),
+                dither: DitherMode::from_u8(dither),
+            },
+        }
+    }
+}
+
+impl Filter {
+    fn matrix(m: ColorMatrix) -> Self {
+        Self {
+            kind: FilterKind::ColorMatrix(m),
+        }
+    }
+
+    fn lut(lut: Lut3d, amount: f32) -> Self {
+        Self {
+            kind: FilterKind::Lut {
+                lut: Rc::new(lut),
+                amount: param(amount, 0.0, 1.0),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameters_are_sanitized() {
+        assert_eq!(Filter::blur(f32::NAN), Filter::blur(0.0));
+        assert_eq!(Filter::grayscale(3.0), Filter::grayscale(1.0));
+        assert_eq!(Filter::pixelate(0), Filter::pixelate(1));
+    }
+
+    #[test]
+    fn test_apply_blur() {
+        let mut data = vec![0u8; 5 * 4];
+        data[8..12].copy_from_slice(&[255, 255, 255, 255]);
+        Filter::blur(1.0).apply(&mut data, 5, 1);
+        assert!(data[11] < 255);
+        assert!(data[7] > 0 && data[15] > 0);
+        // 尺寸不匹配时不做处理
+        let mut data = vec![7u8; 8];
+        Filter::blur(1.0).apply(&mut data, 5, 1);
+        assert_eq!(data, vec![7u8; 8]);
+    }
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(Filter::blur(2.0).padding(), 6);
+        assert_eq!(
+            Filter::drop_shadow(3.0, -4.0, 1.0, 0, 0, 0, 255).padding(),
+            7
+        );
+        assert_eq!(Filter::outline(1.5, 0, 0, 0, 255).padding(), 2);
+        assert_eq!(Filter::grayscale(1.0).padding(), 0);
+    }
+
+    #[test]
+    fn test_color_grading() {
+        assert!(Filter::color_matrix(&[0.0; 19]).is_none());
+        let mut m = [0.0; 20];
+        // 交换红蓝通道
+        (m[2], m[6], m[10], m[18]) = (1.0, 1.0, 1.0, 1.0);
+        let mut data = vec![255, 128, 0, 255];
+        Filter::color_matrix(&m).unwrap().apply(&mut data, 1, 1);
+        assert_eq!(data, vec![0, 128, 255, 255]);
+
+        let mut strip = Vec::new();
+        for g in 0..2u8 {
+            for b in 0..2u8 {
+                for r in 0..2u8 {
+                    strip.extend_from_slice(&[255 - r * 255, 255 - g * 255, 255 - b * 255, 255]);
+                }
+            }
+        }
+        let png = crate::image::encode_png(&strip, 4, 2, crate::image::DEFAULT_COMPRESSION);
+        let invert = Filter::lut_from_image(&png, 0.5).unwrap();
+        let mut data = vec![255, 0, 0, 255];
+        invert.apply(&mut data, 1, 1);
+        assert_eq!(data, vec![128, 128, 128, 255]);
+        assert!(Filter::lut_from_image(&png[..10], 1.0).is_none());
+    }
+
+    #[test]
+    fn test_quantize() {
+        let palette = Palette::from_colors(&[0, 0, 0, 255, 255, 255, 255, 255]);
+        let mut data = [128, 128, 128, 255].repeat(4);
+        Filter::quantize(&palette, 1).apply(&mut data, 2, 2);
+        // 2x2 Bayer: 中灰被抖动为两黑两白
+        let whites = data.chunks_exact(4).filter(|px| px[0] == 255).count();
+        assert_eq!(whites, 2);
+    }
+}
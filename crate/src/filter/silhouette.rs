@@ -0,0 +1,300 @@
+//! 基于轮廓的效果
+//!
+//! 投影、描边与外发光都由图像的 Alpha 轮廓生成一层单色图层，再把原图叠加在该图层之上。
+//! 效果会超出原图范围，调用方需预先在四周留出足够的透明边距 (见 `Filter::padding`)。
+
+use super::blur::{from_premultiplied, gaussian_blur, to_premultiplied, Pixel};
+
+/// 按覆盖率将非预乘颜色转换为预乘像素
+fn tinted(color: [u8; 4], coverage: f32) -> Pixel {
+    let a = color[3] as f32 / 255.0 * coverage;
+    [
+        color[0] as f32 / 255.0 * a,
+        color[1] as f32 / 255.0 * a,
+        color[2] as f32 / 255.0 * a,
+        a,
+    ]
+}
+
+/// 读取 (x, y) 处的 Alpha (0..1)，越界时为 0
+fn alpha_at(data: &[u8], width: usize, height: usize, x: isize, y: isize) -> f32 {
+    if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+        return 0.0;
+    }
+    data[(y as usize * width + x as usize) * 4 + 3] as f32 / 255.0
+}
+
+/// 将原图叠加到效果图层之上并写回
+fn composite_over(data: &mut [u8], layer: &mut [Pixel]) {
+    let src = to_premultiplied(data);
+    for (l, s) in layer.iter_mut().zip(&src) {
+        let inv = 1.0 - s[3];
+        for c in 0..4 {
+            l[c] = s[c] + l[c] * inv;
+        }
+    }
+    from_premultiplied(layer, data);
+}
+
+/// 投影: 轮廓偏移 (dx, dy) 后模糊并着色
+pub(super) fn drop_shadow(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    offset: (f32, f32),
+    sigma: f32,
+    color: [u8; 4],
+) {
+    let (w, h) = (width as usize, height as usize);
+    let (ox, oy) = (offset.0.round() as isize, offset.1.round() as isize);
+    let mut layer = vec![[0.0; 4]; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let a = alpha_at(data, w, h, x as isize - ox, y as isize - oy);
+            layer[y * w + x] = tinted(color, a);
+        }
+    }
+    gaussian_blur(&mut layer, w, h, sigma);
+    composite_over(data, &mut layer);
+}
+
+/// 描边时区分的 Alpha 层数上限 (超出时按此量化)
+const OUTLINE_LEVELS: usize = 16;
+
+/// 距离变换中表示"无种子"的平方距离
+const FAR: f32 = 1e20;
+
+/// 一维平方距离变换 (Felzenszwalb-Huttenlocher 抛物线下包络)
+///
+/// `f` 为各点的初始平方距离，结果写回 `f`。
+/// `v`、`z`、`d` 为临时缓冲，长度分别不小于 n、n + 1、n。
+fn distance_1d(f: &mut [f32], v: &mut [usize], z: &mut [f32], d: &mut [f32]) {
+    let n = f.len();
+    let parabola = |f: &[f32], p: usize| f[p] + (p * p) as f32;
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+    for q in 1..n {
+        // z[0] 为负无穷，循环必然终止
+        let mut s = (parabola(f, q) - parabola(f, v[k])) / (2 * (q - v[k])) as f32;
+        while s <= z[k] {
+            k -= 1;
+            s = (parabola(f, q) - parabola(f, v[k])) / (2 * (q - v[k])) as f32;
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::INFINITY;
+    }
+    k = 0;
+    for (q, out) in d[..n].iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let offset = q.abs_diff(v[k]);
+        *out = (offset * offset) as f32 + f[v[k]];
+    }
+    f.copy_from_slice(&d[..n]);
+}
+
+/// 二维欧氏平方距离变换 (先逐列再逐行，代价与像素数成正比)
+fn distance_2d(grid: &mut [f32], w: usize, h: usize) {
+    let n = w.max(h);
+    let (mut v, mut z, mut d) = (vec![0usize; n], vec![0.0f32; n + 1], vec![0.0f32; n]);
+    let mut column = vec![0.0f32; h];
+    for x in 0..w {
+        for (y, c) in column.iter_mut().enumerate() {
+            *c = grid[y * w + x];
+        }
+        distance_1d(&mut column, &mut v, &mut z, &mut d);
+        for (y, c) in column.iter().enumerate() {
+            grid[y * w + x] = *c;
+        }
+    }
+    for row in grid.chunks_exact_mut(w) {
+        distance_1d(row, &mut v, &mut z, &mut d);
+    }
+}
+
+/// 描边: 轮廓向外膨胀 `thickness` 像素 (圆形笔触，边缘抗锯齿)
+///
+/// 每个像素的覆盖率为 max(源 Alpha × 笔触覆盖率)，笔触覆盖率随到源像素的距离线性衰减。
+/// 按 Alpha 分层后每层做一次欧氏距离变换，代价与描边宽度无关；
+/// 不同 Alpha 超过 `OUTLINE_LEVELS` 种时先量化。
+pub(super) fn outline(data: &mut [u8], width: u32, height: u32, thickness: f32, color: [u8; 4]) {
+    let (w, h) = (width as usize, height as usize);
+    let mut present = [false; 256];
+    for px in data.chunks_exact(4) {
+        present[px[3] as usize] = true;
+    }
+    let distinct = present[1..].iter().filter(|&&p| p).count();
+    let quantize = |a: u8| -> u8 {
+        if distinct <= OUTLINE_LEVELS || a == 0 {
+            a
+        } else {
+            let step = 255.0 / OUTLINE_LEVELS as f32;
+            ((a as f32 / step).round().max(1.0) * step).round() as u8
+        }
+    };
+    let mut levels: Vec<u8> = (1..=255u8)
+        .filter(|&a| present[a as usize])
+        .map(quantize)
+        .collect();
+    levels.dedup();
+
+    // 每层: 到 Alpha 不低于该层的像素的距离，覆盖率为 层 Alpha × 笔触覆盖率
+    let mut coverage = vec![0.0f32; w * h];
+    let mut grid = vec![0.0f32; w * h];
+    for &level in &levels {
+        for (g, px) in grid.iter_mut().zip(data.chunks_exact(4)) {
+            *g = if quantize(px[3]) >= level { 0.0 } else { FAR };
+        }
+        distance_2d(&mut grid, w, h);
+        let alpha = level as f32 / 255.0;
+        for (c, &d2) in coverage.iter_mut().zip(&grid) {
+            let stroke = (thickness + 1.0 - d2.sqrt()).clamp(0.0, 1.0);
+            *c = c.max(alpha * stroke);
+        }
+    }
+    let mut layer: Vec<Pixel> = coverage.iter().map(|&a| tinted(color, a)).collect();
+    composite_over(data, &mut layer);
+}
+
+/// 外发光: 轮廓模糊后乘以 `strength` 并着色
+pub(super) fn glow(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    sigma: f32,
+    strength: f32,
+    color: [u8; 4],
+) {
+    let (w, h) = (width as usize, height as usize);
+    let mut layer: Vec<Pixel> = data
+        .chunks_exact(4)
+        .map(|px| tinted(color, px[3] as f32 / 255.0))
+        .collect();
+    gaussian_blur(&mut layer, w, h, sigma);
+    for p in layer.iter_mut() {
+        // 增强后 Alpha 超过 1 时整体缩放，保持颜色不变
+        let scale = strength.min(1.0 / p[3].max(f32::EPSILON));
+        for c in p.iter_mut() {
+            *c *= scale;
+        }
+    }
+    composite_over(data, &mut layer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 5x5 透明图像，中心一个不透明白色像素
+    fn dot() -> Vec<u8> {
+        let mut data = vec![0u8; 5 * 5 * 4];
+        data[(2 * 5 + 2) * 4..][..4].copy_from_slice(&[255, 255, 255, 255]);
+        data
+    }
+
+    fn pixel(data: &[u8], x: usize, y: usize) -> [u8; 4] {
+        data[(y * 5 + x) * 4..][..4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_drop_shadow_offset() {
+        let mut data = dot();
+        drop_shadow(&mut data, 5, 5, (1.0, 1.0), 0.0, [0, 0, 0, 128]);
+        assert_eq!(pixel(&data, 2, 2), [255, 255, 255, 255]);
+        assert_eq!(pixel(&data, 3, 3), [0, 0, 0, 128]);
+        assert_eq!(pixel(&data, 1, 1), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_outline_surrounds_shape() {
+        let mut data = dot();
+        outline(&mut data, 5, 5, 1.0, [255, 0, 0, 255]);
+        assert_eq!(pixel(&data, 2, 2), [255, 255, 255, 255]);
+        assert_eq!(pixel(&data, 2, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&data, 3, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(&data, 0, 0), [0, 0, 0, 0]);
+    }
+
+    /// 逐源像素取最大值的描边覆盖率 (与圆盘卷积等价的参考实现)
+    fn brute_outline(data: &[u8], w: usize, h: usize, thickness: f32) -> Vec<f32> {
+        let mut expected = vec![0.0f32; w * h];
+        for y in 0..h as isize {
+            for x in 0..w as isize {
+                for sy in 0..h as isize {
+                    for sx in 0..w as isize {
+                        let a = alpha_at(data, w, h, sx, sy);
+                        let d = (((x - sx).pow(2) + (y - sy).pow(2)) as f32).sqrt();
+                        let c = (thickness + 1.0 - d).clamp(0.0, 1.0) * a;
+                        let e = &mut expected[y as usize * w + x as usize];
+                        *e = e.max(c);
+                    }
+                }
+            }
+        }
+        expected
+    }
+
+    #[test]
+    fn test_outline_matches_disk_kernel() {
+        // 半透明与不透明像素混合时，与逐偏移取最大值的结果一致
+        let (w, h) = (9usize, 7usize);
+        let mut data = vec![0u8; w * h * 4];
+        data[(3 * w + 3) * 4 + 3] = 255;
+        data[(3 * w + 5) * 4 + 3] = 100;
+        let expected = brute_outline(&data, w, h, 2.5);
+        outline(&mut data, w as u32, h as u32, 2.5, [0, 0, 0, 255]);
+        for (px, e) in data.chunks_exact(4).zip(&expected) {
+            assert!((px[3] as f32 - e * 255.0).abs() <= 1.0);
+        }
+
+        // Alpha 种类过多时量化，误差不超过半层
+        let (w, h) = (30usize, 5usize);
+        let mut data = vec![0u8; w * h * 4];
+        for x in 2..27 {
+            data[(2 * w + x) * 4 + 3] = (x * 10) as u8;
+        }
+        let expected = brute_outline(&data, w, h, 1.5);
+        outline(&mut data, w as u32, h as u32, 1.5, [0, 0, 0, 255]);
+        for (y, row) in data
+            .chunks_exact(w * 4)
+            .enumerate()
+            .filter(|&(y, _)| y != 2)
+        {
+            for (px, e) in row.chunks_exact(4).zip(&expected[y * w..]) {
+                assert!((px[3] as f32 - e * 255.0).abs() <= 9.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_outline_max_width_is_fast() {
+        // 4x4 精灵图按最大描边宽度扩展为 516x516
+        let size = 4 + 2 * 256;
+        let mut data = vec![0u8; size * size * 4];
+        for y in 256..260 {
+            for x in 256..260 {
+                data[(y * size + x) * 4..][..4].copy_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+        let start = std::time::Instant::now();
+        outline(&mut data, size as u32, size as u32, 256.0, [255, 0, 0, 255]);
+        assert!(start.elapsed().as_secs_f32() < 5.0);
+        assert_eq!(data[(258 * size + 2) * 4 + 3], 255);
+        assert_eq!(data[3], 0);
+    }
+
+    #[test]
+    fn test_glow_fades_outward() {
+        let mut data = dot();
+        glow(&mut data, 5, 5, 2.0, 4.0, [0, 255, 0, 255]);
+        assert_eq!(pixel(&data, 2, 2), [255, 255, 255, 255]);
+        let (near, far) = (pixel(&data, 3, 2), pixel(&data, 4, 2));
+        assert_eq!(&near[..3], &[0, 255, 0]);
+        assert!(near[3] > far[3] && far[3] > 0);
+    }
+}
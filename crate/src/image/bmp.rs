@@ -0,0 +1,429 @@
+//! BMP 编解码
+//!
+//! 解码支持 1/4/8 位调色板、16/24/32 位真彩色、位域掩码以及 RLE4/RLE8 压缩；
+//! 编码输出带 Alpha 掩码的 32 位 BITMAPV4HEADER 位图。
+
+use super::{fix_zero_alpha, Image};
+
+/// 文件头长度
+const FILE_HEADER_SIZE: usize = 14;
+
+/// BITMAPV4HEADER 长度
+const V4_HEADER_SIZE: usize = 108;
+
+const BI_RGB: u32 = 0;
+const BI_RLE8: u32 = 1;
+const BI_RLE4: u32 = 2;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+fn read_u16(bytes: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(pos..pos + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(pos..pos + 4)?.try_into().ok()?,
+    ))
+}
+
+/// 位域掩码通道
+#[derive(Clone, Copy)]
+struct Channel {
+    mask: u32,
+    shift: u32,
+    bits: u32,
+}
+
+impl Channel {
+    fn new(mask: u32) -> Self {
+        if mask == 0 {
+            return Self {
+                mask: 0,
+                shift: 0,
+                bits: 0,
+            };
+        }
+        Self {
+            mask,
+            shift: mask.trailing_zeros(),
+            bits: (mask >> mask.trailing_zeros()).trailing_ones(),
+        }
+    }
+
+    /// 提取通道值并缩放到 8 位
+    fn extract(&self, value: u32) -> Option<u8> {
+        if self.bits == 0 {
+            return None;
+        }
+        let v = (value & self.mask) >> self.shift;
+        let max = (1u64 << self.bits) - 1;
+        Some((v as u64 * 255 / max) as u8)
+    }
+}
+
+/// 解码 RLE4 / RLE8 压缩数据为调色板索引 (自底向上行序)
+fn decode_rle(data: &[u8], width: usize, height: usize, four_bit: bool) -> Option<Vec<u8>> {
+    let mut indices = vec![0u8; width * height];
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut pos = 0;
+
+    let mut put = |x: &mut usize, y: usize, v: u8| {
+        if *x < width && y < height {
+            indices[y * width + *x] = v;
+        }
+        *x += 1;
+    };
+
+    while pos + 1 < data.len() {
+        let count = data[pos] as usize;
+        let value = data[pos + 1];
+        pos += 2;
+
+        if count > 0 {
+            // 编码模式: 重复 count 个像素
+            for i in 0..count {
+                let v = if four_bit {
+                    if i % 2 == 0 {
+                        value >> 4
+                    } else {
+                        value & 0x0F
+                    }
+                } else {
+                    value
+                };
+                put(&mut x, y, v);
+            }
+            continue;
+        }
+
+        match value {
+            0 => {
+                x = 0;
+                y += 1;
+            }
+            1 => break,
+            2 => {
+                x += *data.get(pos)? as usize;
+                y += *data.get(pos + 1)? as usize;
+                pos += 2;
+            }
+            n => {
+                // 绝对模式: n 个未压缩像素，按 16 位对齐
+                let n = n as usize;
+                let byte_len = if four_bit { n.div_ceil(2) } else { n };
+                let run = data.get(pos..pos + byte_len)?;
+                for i in 0..n {
+                    let v = if four_bit {
+                        if i % 2 == 0 {
+                            run[i / 2] >> 4
+                        } else {
+                            run[i / 2] & 0x0F
+                        }
+                    } else {
+                        run[i]
+                    };
+                    put(&mut x, y, v);
+                }
+                pos += byte_len + (byte_len & 1);
+            }
+        }
+    }
+
+    Some(indices)
+}
+
+/// 解码 BMP 字节为 RGBA8 图像
+///
+/// # Returns
+/// 解码结果，文件损坏或格式不支持时返回 None
+pub fn decode_bmp(bytes: &[u8]) -> Option<Image> {
+    if bytes.len() < FILE_HEADER_SIZE + 12 || &bytes[0..2] != b"BM" {
+        return None;
+    }
+    let data_offset = read_u32(bytes, 10)? as usize;
+    let header_size = read_u32(bytes, 14)? as usize;
+
+    let (width, raw_height, bpp, compression, palette_entry, colors_used);
+    if header_size == 12 {
+        // BITMAPCOREHEADER (OS/2)
+        width = read_u16(bytes, 18)? as i32;
+        raw_height = read_u16(bytes, 20)? as i16 as i32;
+        bpp = read_u16(bytes, 24)?;
+        compression = BI_RGB;
+        palette_entry = 3;
+        colors_used = 0;
+    } else if header_size >= 40 {
+        width = read_u32(bytes, 18)? as i32;
+        raw_height = read_u32(bytes, 22)? as i32;
+        bpp = read_u16(bytes, 28)?;
+        compression = read_u32(bytes, 30)?;
+        palette_entry = 4;
+        colors_used = read_u32(bytes, 46)? as usize;
+    } else {
+        return None;
+    }
+
+    let top_down = raw_height < 0;
+    let width = u32::try_from(width).ok()?;
+    let height = raw_height.unsigned_abs();
+    let mut image = Image::new(width, height)?;
+    let (w, h) = (width as usize, height as usize);
+
+    // 位域掩码: V3+ 头部内含掩码，40 字节头部的掩码紧跟其后
+    let mut masks_end = FILE_HEADER_SIZE + header_size;
+    let masks = match compression {
+        BI_BITFIELDS | BI_ALPHABITFIELDS => {
+            let base = FILE_HEADER_SIZE + 40;
+            let alpha = if header_size >= 56 || compression == BI_ALPHABITFIELDS {
+                read_u32(bytes, base + 12)?
+            } else {
+                0
+            };
+            if header_size == 40 {
+                masks_end += if compression == BI_ALPHABITFIELDS {
+                    16
+                } else {
+                    12
+                };
+            }
+            Some([
+                read_u32(bytes, base)?,
+                read_u32(bytes, base + 4)?,
+                read_u32(bytes, base + 8)?,
+                alpha,
+            ])
+        }
+        _ if bpp == 16 => Some([0x7C00, 0x03E0, 0x001F, 0]),
+        _ if header_size >= 56 && bpp == 32 => {
+            // V4/V5 头部即使为 BI_RGB 也可能携带有效 Alpha 掩码
+            let alpha = read_u32(bytes, FILE_HEADER_SIZE + 52)?;
+            Some([0x00FF_0000, 0x0000_FF00, 0x0000_00FF, alpha])
+        }
+        _ => None,
+    };
+
+    // 调色板
+    let mut palette = Vec::new();
+    if bpp <= 8 {
+        let count = if colors_used > 0 {
+            colors_used.min(256)
+        } else {
+            1 << bpp
+        };
+        for i in 0..count {
+            let p = masks_end + i * palette_entry;
+            match bytes.get(p..p + 3) {
+                Some(c) => palette.push([c[2], c[1], c[0], 255]),
+                None => break,
+            }
+        }
+    }
+    let lookup = |index: u8| {
+        palette
+            .get(index as usize)
+            .copied()
+            .unwrap_or([0, 0, 0, 255])
+    };
+
+    let pixels = bytes.get(data_offset..)?;
+    let row_of = |y: usize| if top_down { y } else { h - 1 - y };
+
+    match compression {
+        BI_RLE8 | BI_RLE4 => {
+            let indices = decode_rle(pixels, w, h, compression == BI_RLE4)?;
+            for (i, &index) in indices.iter().enumerate() {
+                let y = row_of(i / w);
+                let dst = (y * w + i % w) * 4;
+                image.data[dst..dst + 4].copy_from_slice(&lookup(index));
+            }
+        }
+        BI_RGB | BI_BITFIELDS | BI_ALPHABITFIELDS => {
+            let stride = (w * bpp as usize).div_ceil(32) * 4;
+            let channels = masks.map(|m| m.map(Channel::new));
+            let mut has_alpha = channels.is_some_and(|c| c[3].bits > 0);
+
+            for file_row in 0..h {
+                let row = pixels.get(file_row * stride..file_row * stride + stride)?;
+                let y = row_of(file_row);
+                for x in 0..w {
+                    let color = match (bpp, channels) {
+                        (1 | 2 | 4 | 8, _) => {
+                            let bit = x * bpp as usize;
+                            let shift = 8 - bpp as usize - bit % 8;
+                            lookup((row[bit / 8] >> shift) & ((1u16 << bpp) - 1) as u8)
+                        }
+                        (24, _) => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 255],
+                        (16, Some(c)) | (32, Some(c)) => {
+                            let value = if bpp == 16 {
+                                read_u16(row, x * 2)? as u32
+                            } else {
+                                read_u32(row, x * 4)?
+                            };
+                            [
+                                c[0].extract(value).unwrap_or(0),
+                                c[1].extract(value).unwrap_or(0),
+                                c[2].extract(value).unwrap_or(0),
+                                c[3].extract(value).unwrap_or(255),
+                            ]
+                        }
+                        (32, None) => {
+                            has_alpha = true;
+                            [row[x * 4 + 2], row[x * 4 + 1], row[x * 4], row[x * 4 + 3]]
+                        }
+                        _ => return None,
+                    };
+                    let dst = (y * w + x) * 4;
+                    image.data[dst..dst + 4].copy_from_slice(&color);
+                }
+            }
+
+            if has_alpha {
+                fix_zero_alpha(&mut image.data);
+            }
+        }
+        _ => return None,
+    }
+
+    Some(image)
+}
+
+/// 将 RGBA8 像素编码为 32 位 BMP (BITMAPV4HEADER + BI_BITFIELDS，自底向上)
+///
+/// # Returns
+/// 完整的 BMP 文件字节
+pub fn encode_bmp(data: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let pixel_bytes = width as usize * height as usize * 4;
+    let data_offset = FILE_HEADER_SIZE + V4_HEADER_SIZE;
+    let file_size = data_offset + pixel_bytes;
+
+    let mut out = Vec::with_capacity(file_size);
+    // BITMAPFILEHEADER
+    out.extend_from_slice(b"BM");
+    out.extend_from_slice(&(file_size as u32).to_le_bytes());
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    out.extend_from_slice(&(data_offset as u32).to_le_bytes());
+
+    // BITMAPV4HEADER
+    out.extend_from_slice(&(V4_HEADER_SIZE as u32).to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&(height as i32).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(&BI_BITFIELDS.to_le_bytes());
+    out.extend_from_slice(&(pixel_bytes as u32).to_le_bytes());
+    // 分辨率 2835 像素/米 (72 DPI)
+    out.extend_from_slice(&2835u32.to_le_bytes());
+    out.extend_from_slice(&2835u32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    // R/G/B/A 掩码
+    for mask in [0x00FF_0000u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+        out.extend_from_slice(&mask.to_le_bytes());
+    }
+    // 颜色空间 LCS_sRGB，端点与伽马未使用
+    out.extend_from_slice(b"BGRs");
+    out.resize(data_offset, 0);
+
+    for y in (0..height as usize).rev() {
+        for x in 0..width as usize {
+            let src = (y * width as usize + x) * 4;
+            match data.get(src..src + 4) {
+                Some(px) => out.extend_from_slice(&[px[2], px[1], px[0], px[3]]),
+                None => out.extend_from_slice(&[0, 0, 0, 0]),
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 组装 BITMAPINFOHEADER (40 字节) 位图
+    fn build_bmp(
+        width: i32,
+        height: i32,
+        bpp: u16,
+        compression: u32,
+        palette: &[[u8; 4]],
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        let offset = (FILE_HEADER_SIZE + 40 + palette.len() * 4) as u32;
+        let mut out = b"BM".to_vec();
+        out.extend_from_slice(&(offset + pixels.len() as u32).to_le_bytes());
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&offset.to_le_bytes());
+        out.extend_from_slice(&40u32.to_le_bytes());
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&bpp.to_le_bytes());
+        out.extend_from_slice(&compression.to_le_bytes());
+        out.extend_from_slice(&[0u8; 12]);
+        out.extend_from_slice(&(palette.len() as u32).to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        for c in palette {
+            out.extend_from_slice(c);
+        }
+        out.extend_from_slice(pixels);
+        out
+    }
+
+    #[test]
+    fn test_decode_24bit_bottom_up() {
+        // 2x2，每行 6 字节 + 2 字节填充；第一行存储的是图像底部
+        let pixels = [
+            0, 0, 255, 0, 255, 0, 0, 0, // 底行: 红, 绿
+            255, 0, 0, 255, 255, 255, 0, 0, // 顶行: 蓝, 白
+        ];
+        let image = decode_bmp(&build_bmp(2, 2, 24, BI_RGB, &[], &pixels)).unwrap();
+        assert_eq!(
+            image.data,
+            vec![0, 0, 255, 255, 255, 255, 255, 255, 255, 0, 0, 255, 0, 255, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_decode_1bit_top_down() {
+        let palette = [[0, 0, 0, 0], [255, 255, 255, 0]];
+        let pixels = [0b1010_0000, 0, 0, 0];
+        let image = decode_bmp(&build_bmp(3, -1, 1, BI_RGB, &palette, &pixels)).unwrap();
+        assert_eq!(
+            image.data,
+            vec![255, 255, 255, 255, 0, 0, 0, 255, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn test_decode_rle8() {
+        let palette = [[0, 0, 255, 0], [0, 255, 0, 0]];
+        // 行 0: 3 个索引 1；行结束；行 1: 绝对模式 [0, 1, 0]；位图结束
+        let pixels = [3, 1, 0, 0, 0, 3, 0, 1, 0, 0, 0, 1];
+        let image = decode_bmp(&build_bmp(3, 2, 8, BI_RLE8, &palette, &pixels)).unwrap();
+        let reds: Vec<u8> = image.data.chunks_exact(4).map(|px| px[0]).collect();
+        // 顶行来自文件的第二行
+        assert_eq!(reds, vec![255, 0, 255, 0, 0, 0]);
+        assert_eq!(image.data[13], 255);
+    }
+
+    #[test]
+    fn test_decode_32bit_zero_alpha_is_opaque() {
+        let pixels = [1, 2, 3, 0];
+        let image = decode_bmp(&build_bmp(1, 1, 32, BI_RGB, &[], &pixels)).unwrap();
+        assert_eq!(image.data, vec![3, 2, 1, 255]);
+    }
+
+    #[test]
+    fn test_encode_header() {
+        let bytes = encode_bmp(&[9, 8, 7, 6], 1, 1);
+        assert_eq!(bytes.len(), FILE_HEADER_SIZE + V4_HEADER_SIZE + 4);
+        assert_eq!(read_u32(&bytes, 30), Some(BI_BITFIELDS));
+        assert_eq!(&bytes[bytes.len() - 4..], &[7, 8, 9, 6]);
+        assert!(decode_bmp(&bytes[..20]).is_none());
+    }
+}
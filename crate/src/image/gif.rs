@@ -0,0 +1,570 @@
+//! GIF 解码
+//!
+//! 支持 GIF87a / GIF89a：LZW 解压、全局与局部调色板、透明色、隔行扫描、
+//! 帧处置方式 (保留 / 清除为背景 / 恢复上一帧) 以及 NETSCAPE 循环次数。
+//! 每一帧都会合成为完整画布尺寸的 RGBA8 图像。
+
+use super::{pixel_len, Image};
+
+/// LZW 最大码长
+const MAX_CODE_BITS: u32 = 12;
+
+/// 延迟不超过该值 (单位 1/100 秒) 的帧按浏览器惯例视为 100 毫秒
+const MIN_DELAY_CS: u16 = 1;
+
+/// 未指定或过小延迟时使用的帧时长 (毫秒)
+const DEFAULT_DELAY_MS: u32 = 100;
+
+/// 所有帧合成结果的总字节数上限 (每帧都是完整画布，超出后不再解码后续帧)
+const MAX_TOTAL_BYTES: usize = 256 * 1024 * 1024;
+
+/// 动画中的一帧
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// 合成后的 RGBA 像素数据 (画布尺寸)
+    pub data: Vec<u8>,
+    /// 帧持续时间 (毫秒)
+    pub delay_ms: u32,
+}
+
+/// 解码后的动画图像
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimatedImage {
+    /// 画布宽度
+    pub width: u32,
+    /// 画布高度
+    pub height: u32,
+    /// 按播放顺序排列的帧
+    pub frames: Vec<Frame>,
+    /// 播放次数 (0 表示无限循环)
+    pub loop_count: u32,
+}
+
+/// 帧处置方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Disposal {
+    /// 保留当前帧内容
+    Keep,
+    /// 将帧区域清除为透明
+    Background,
+    /// 恢复到绘制此帧之前的画布
+    Previous,
+}
+
+/// 图形控制扩展的参数 (作用于下一幅图像)
+#[derive(Clone, Copy)]
+struct GraphicControl {
+    disposal: Disposal,
+    transparent: Option<u8>,
+    delay_cs: u16,
+}
+
+impl Default for GraphicControl {
+    fn default() -> Self {
+        Self {
+            disposal: Disposal::Keep,
+            transparent: None,
+            delay_cs: 0,
+        }
+    }
+}
+
+/// 字节读取游标
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Option<u8> {
+        let v = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        Some(v)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    /// 读取调色板 (RGB 三元组)
+    fn palette(&mut self, entries: usize) -> Option<Vec<[u8; 3]>> {
+        Some(
+            self.take(entries * 3)?
+                .chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect(),
+        )
+    }
+
+    /// 读取以 0 长度块结尾的数据子块序列，拼接为连续数据
+    fn sub_blocks(&mut self) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        loop {
+            let len = self.u8()? as usize;
+            if len == 0 {
+                return Some(out);
+            }
+            out.extend_from_slice(self.take(len)?);
+        }
+    }
+}
+
+/// LZW 解压为调色板索引
+///
+/// 数据提前结束或出现非法码时返回已解出的部分，由调用方补齐。
+fn lzw_decode(data: &[u8], min_code_size: u8, expected: usize) -> Vec<u8> {
+    let min_code_size = min_code_size.clamp(2, 11) as u32;
+    let clear_code = 1u32 << min_code_size;
+    let end_code = clear_code + 1;
+
+    // 码表: 每项为 (前缀码, 末尾字节)，前 clear_code 项为单字节
+    let mut prefixes: Vec<u16> = Vec::with_capacity(1 << MAX_CODE_BITS);
+    let mut suffixes: Vec<u8> = Vec::with_capacity(1 << MAX_CODE_BITS);
+    let mut firsts: Vec<u8> = Vec::with_capacity(1 << MAX_CODE_BITS);
+    let reset = |prefixes: &mut Vec<u16>, suffixes: &mut Vec<u8>, firsts: &mut Vec<u8>| {
+        prefixes.clear();
+        suffixes.clear();
+        firsts.clear();
+        for i in 0..clear_code + 2 {
+            prefixes.push(u16::MAX);
+            suffixes.push(i as u8);
+            firsts.push(i as u8);
+        }
+    };
+    reset(&mut prefixes, &mut suffixes, &mut firsts);
+
+    let mut out = Vec::with_capacity(expected);
+    let mut code_bits = min_code_size + 1;
+    let mut prev: Option<u32> = None;
+    let mut bit_buf = 0u32;
+    let mut bit_count = 0u32;
+    let mut stack = Vec::new();
+
+    let mut bytes = data.iter();
+    loop {
+        while bit_count < code_bits {
+            match bytes.next() {
+                Some(&b) => {
+                    bit_buf |= (b as u32) << bit_count;
+                    bit_count += 8;
+                }
+                None => return out,
+            }
+        }
+        let code = bit_buf & ((1 << code_bits) - 1);
+        bit_buf >>= code_bits;
+        bit_count -= code_bits;
+
+        if code == clear_code {
+            reset(&mut prefixes, &mut suffixes, &mut firsts);
+            code_bits = min_code_size + 1;
+            prev = None;
+            continue;
+        }
+        if code == end_code {
+            return out;
+        }
+
+        let table_len = prefixes.len() as u32;
+        let Some(prev_code) = prev else {
+            if code >= clear_code {
+                return out;
+            }
+            out.push(code as u8);
+            prev = Some(code);
+            continue;
+        };
+
+        // KwKwK 情况: 码尚未入表，值为上一串 + 上一串首字节
+        let first = if code < table_len {
+            firsts[code as usize]
+        } else if code == table_len {
+            firsts[prev_code as usize]
+        } else {
+            return out;
+        };
+
+        if table_len < (1 << MAX_CODE_BITS) {
+            prefixes.push(prev_code as u16);
+            suffixes.push(first);
+            firsts.push(firsts[prev_code as usize]);
+            if prefixes.len() as u32 == (1 << code_bits) && code_bits < MAX_CODE_BITS {
+                code_bits += 1;
+            }
+        }
+
+        // 沿前缀链回溯输出字符串
+        let mut c = code;
+        stack.clear();
+        while c != u16::MAX as u32 {
+            stack.push(suffixes[c as usize]);
+            c = prefixes[c as usize] as u32;
+        }
+        out.extend(stack.iter().rev());
+        prev = Some(code);
+
+        if out.len() >= expected {
+            return out;
+        }
+    }
+}
+
+/// 隔行扫描第 `row` 个存储行对应的实际行号
+fn deinterlace_row(row: usize, height: usize) -> usize {
+    let pass1 = height.div_ceil(8);
+    let pass2 = (height + 3) / 8;
+    let pass3 = (height + 1) / 4;
+    if row < pass1 {
+        row * 8
+    } else if row < pass1 + pass2 {
+        (row - pass1) * 8 + 4
+    } else if row < pass1 + pass2 + pass3 {
+        (row - pass1 - pass2) * 4 + 2
+    } else {
+        (row - pass1 - pass2 - pass3) * 2 + 1
+    }
+}
+
+/// 解码 GIF 字节为动画帧序列
+///
+/// # Returns
+/// 解码结果，文件损坏或不含任何图像时返回 None
+pub fn decode_gif(bytes: &[u8]) -> Option<AnimatedImage> {
+    if !bytes.starts_with(b"GIF87a") && !bytes.starts_with(b"GIF89a") {
+        return None;
+    }
+    let mut r = Reader { bytes, pos: 6 };
+
+    let width = r.u16()? as u32;
+    let height = r.u16()? as u32;
+    let packed = r.u8()?;
+    let _background = r.u8()?;
+    let _aspect = r.u8()?;
+    let global_palette = if packed & 0x80 != 0 {
+        Some(r.palette(2 << (packed & 0x07))?)
+    } else {
+        None
+    };
+
+    let len = pixel_len(width, height)?;
+    let (cw, ch) = (width as usize, height as usize);
+    let mut canvas = vec![0u8; len];
+    let mut frames = Vec::new();
+    let mut control = GraphicControl::default();
+    let mut loop_count = 1;
+
+    loop {
+        match r.u8() {
+            Some(0x21) => {
+                let label = r.u8()?;
+                let data = r.sub_blocks()?;
+                match label {
+                    0xF9 if data.len() >= 4 => {
+                        control.disposal = match (data[0] >> 2) & 0x07 {
+                            2 => Disposal::Background,
+                            3 => Disposal::Previous,
+                            _ => Disposal::Keep,
+                        };
+                        control.transparent = (data[0] & 0x01 != 0).then_some(data[3]);
+                        control.delay_cs = u16::from_le_bytes([data[1], data[2]]);
+                    }
+                    0xFF if data.starts_with(b"NETSCAPE2.0") && data.len() >= 14 => {
+                        // 子块: 0x01 + 循环次数 (0 表示无限)
+                        let repeats = u16::from_le_bytes([data[12], data[13]]) as u32;
+                        loop_count = if repeats == 0 { 0 } else { repeats + 1 };
+                    }
+                    _ => {}
+                }
+            }
+            Some(0x2C) => {
+                let left = r.u16()? as usize;
+                let top = r.u16()? as usize;
+                let fw = r.u16()? as usize;
+                let fh = r.u16()? as usize;
+                let packed = r.u8()?;
+                let local_palette = if packed & 0x80 != 0 {
+                    Some(r.palette(2 << (packed & 0x07))?)
+                } else {
+                    None
+                };
+                let interlaced = packed & 0x40 != 0;
+                let min_code_size = r.u8()?;
+                let data = r.sub_blocks()?;
+                // 帧矩形大于画布视为损坏 (避免按描述符分配巨量索引)，
+                // 帧数过多时同样停止，保留已解出的帧
+                if fw > cw || fh > ch || (frames.len() + 1) * len > MAX_TOTAL_BYTES {
+                    break;
+                }
+
+                let palette = local_palette.as_ref().or(global_palette.as_ref())?;
+                let mut indices = lzw_decode(&data, min_code_size, fw * fh);
+                indices.resize(fw * fh, control.transparent.unwrap_or(0));
+
+                let saved = (control.disposal == Disposal::Previous).then(|| canvas.clone());
+
+                for row in 0..fh {
+                    let y = top
+                        + if interlaced {
+                            deinterlace_row(row, fh)
+                        } else {
+                            row
+                        };
+                    if y >= ch {
+                        continue;
+                    }
+                    for col in 0..fw {
+                        let x = left + col;
+                        if x >= cw {
+                            continue;
+                        }
+                        let index = indices[row * fw + col];
+                        if Some(index) == control.transparent {
+                            continue;
+                        }
+                        let Some(rgb) = palette.get(index as usize) else {
+                            continue;
+                        };
+                        let dst = (y * cw + x) * 4;
+                        canvas[dst..dst + 3].copy_from_slice(rgb);
+                        canvas[dst + 3] = 255;
+                    }
+                }
+
+                let delay_ms = if control.delay_cs <= MIN_DELAY_CS {
+                    DEFAULT_DELAY_MS
+                } else {
+                    control.delay_cs as u32 * 10
+                };
+                frames.push(Frame {
+                    data: canvas.clone(),
+                    delay_ms,
+                });
+
+                match control.disposal {
+                    Disposal::Keep => {}
+                    Disposal::Background => {
+                        for y in top..(top + fh).min(ch) {
+                            for x in left..(left + fw).min(cw) {
+                                let dst = (y * cw + x) * 4;
+                                canvas[dst..dst + 4].fill(0);
+                            }
+                        }
+                    }
+                    Disposal::Previous => {
+                        if let Some(saved) = saved {
+                            canvas = saved;
+                        }
+                    }
+                }
+                control = GraphicControl::default();
+            }
+            // 文件尾或截断: 保留已解出的帧
+            Some(0x3B) | None => break,
+            Some(_) => break,
+        }
+    }
+
+    if frames.is_empty() {
+        return None;
+    }
+
+    Some(AnimatedImage {
+        width,
+        height,
+        frames,
+        loop_count,
+    })
+}
+
+/// 解码 GIF 的第一帧为静态图像
+pub fn decode_gif_first_frame(bytes: &[u8]) -> Option<Image> {
+    let gif = decode_gif(bytes)?;
+    let frame = gif.frames.into_iter().next()?;
+    Some(Image {
+        width: gif.width,
+        height: gif.height,
+        data: frame.data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 最简 LZW 编码 (每个像素输出一个码，定期清表)，用于构造测试数据
+    fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1u32 << min_code_size;
+        let code_bits = min_code_size as u32 + 1;
+        let mut out = Vec::new();
+        let mut buf = 0u32;
+        let mut count = 0u32;
+        let mut emit = |code: u32, out: &mut Vec<u8>| {
+            buf |= code << count;
+            count += code_bits;
+            while count >= 8 {
+                out.push(buf as u8);
+                buf >>= 8;
+                count -= 8;
+            }
+        };
+        // 每写一个码都会向表中加一项，码长增长前清表以保持固定码长
+        let limit = (1 << code_bits) - clear - 2;
+        for (i, &v) in indices.iter().enumerate() {
+            if (i as u32).is_multiple_of(limit) {
+                emit(clear, &mut out);
+            }
+            emit(v as u32, &mut out);
+        }
+        emit(clear + 1, &mut out);
+        if count > 0 {
+            out.push(buf as u8);
+        }
+        out
+    }
+
+    /// 组装 GIF: 2 色全局调色板 (红、绿)，帧参数为 (处置, 透明色, 延迟, 矩形, 索引)
+    #[allow(clippy::type_complexity)]
+    fn build_gif(
+        width: u16,
+        height: u16,
+        loops: Option<u16>,
+        frames: &[(u8, Option<u8>, u16, [u16; 4], Vec<u8>)],
+    ) -> Vec<u8> {
+        let mut out = b"GIF89a".to_vec();
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.extend_from_slice(&[0x80, 0, 0]);
+        out.extend_from_slice(&[255, 0, 0, 0, 255, 0]);
+        if let Some(n) = loops {
+            out.extend_from_slice(&[0x21, 0xFF, 11]);
+            out.extend_from_slice(b"NETSCAPE2.0");
+            out.extend_from_slice(&[3, 1]);
+            out.extend_from_slice(&n.to_le_bytes());
+            out.push(0);
+        }
+        for (disposal, transparent, delay, rect, indices) in frames {
+            let flags = (disposal << 2) | transparent.is_some() as u8;
+            out.extend_from_slice(&[0x21, 0xF9, 4, flags]);
+            out.extend_from_slice(&delay.to_le_bytes());
+            out.extend_from_slice(&[transparent.unwrap_or(0), 0]);
+            out.push(0x2C);
+            for v in rect {
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            out.push(0);
+            out.push(2);
+            let data = lzw_encode(indices, 2);
+            for chunk in data.chunks(255) {
+                out.push(chunk.len() as u8);
+                out.extend_from_slice(chunk);
+            }
+            out.push(0);
+        }
+        out.push(0x3B);
+        out
+    }
+
+    fn reds(data: &[u8]) -> Vec<u8> {
+        data.chunks_exact(4).map(|px| px[0]).collect()
+    }
+
+    #[test]
+    fn test_lzw_roundtrip() {
+        let indices: Vec<u8> = (0..100).map(|i| (i % 3) as u8).collect();
+        let data = lzw_encode(&indices, 2);
+        assert_eq!(lzw_decode(&data, 2, indices.len()), indices);
+    }
+
+    #[test]
+    fn test_lzw_repeated_strings() {
+        // 手工构造: clear, 1, 6, end。码 6 尚未入表 (KwKwK)，应解为 "11"
+        // min_code_size = 2 → clear = 4, end = 5，码长 3 位
+        let codes = [4u32, 1, 6, 5];
+        let mut buf = 0u32;
+        for (i, c) in codes.iter().enumerate() {
+            buf |= c << (i * 3);
+        }
+        let data = buf.to_le_bytes();
+        assert_eq!(lzw_decode(&data, 2, 16), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_decode_frames_and_delays() {
+        let gif = build_gif(
+            2,
+            1,
+            Some(0),
+            &[
+                (1, None, 5, [0, 0, 2, 1], vec![0, 0]),
+                (1, Some(0), 0, [0, 0, 2, 1], vec![0, 1]),
+            ],
+        );
+        let anim = decode_gif(&gif).unwrap();
+        assert_eq!((anim.width, anim.height), (2, 1));
+        assert_eq!(anim.loop_count, 0);
+        assert_eq!(anim.frames.len(), 2);
+        assert_eq!(anim.frames[0].delay_ms, 50);
+        assert_eq!(anim.frames[1].delay_ms, DEFAULT_DELAY_MS);
+        // 第二帧第一个像素透明，保留第一帧的红色
+        assert_eq!(anim.frames[1].data, vec![255, 0, 0, 255, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_disposal_background_and_previous() {
+        let gif = build_gif(
+            3,
+            1,
+            None,
+            &[
+                // 帧 0: 整行红色，处置为清除
+                (2, None, 10, [0, 0, 3, 1], vec![0, 0, 0]),
+                // 帧 1: 中间绿色，处置为恢复
+                (3, None, 10, [1, 0, 1, 1], vec![1]),
+                // 帧 2: 右侧红色
+                (1, None, 10, [2, 0, 1, 1], vec![0]),
+            ],
+        );
+        let anim = decode_gif(&gif).unwrap();
+        assert_eq!(anim.loop_count, 1);
+        assert_eq!(reds(&anim.frames[0].data), vec![255, 255, 255]);
+        // 帧 0 清除后只剩中间绿色
+        assert_eq!(anim.frames[1].data[3], 0);
+        assert_eq!(anim.frames[1].data[4..8], [0, 255, 0, 255]);
+        // 帧 1 恢复为清除后的透明画布，再画右侧红色
+        assert_eq!(anim.frames[2].data[4..8], [0, 0, 0, 0]);
+        assert_eq!(anim.frames[2].data[8..12], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_deinterlace_rows() {
+        let rows: Vec<usize> = (0..10).map(|r| deinterlace_row(r, 10)).collect();
+        assert_eq!(rows, vec![0, 8, 4, 2, 6, 1, 3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(decode_gif(b"GIF89a").is_none());
+        assert!(decode_gif(b"PNG").is_none());
+        // 无图像帧
+        assert!(decode_gif(&build_gif(1, 1, None, &[])).is_none());
+    }
+
+    #[test]
+    fn test_oversized_frame_rect() {
+        // 1x1 画布上声明 65535x65535 的帧: 不分配，直接视为损坏
+        let huge = (1, None, 10, [0, 0, u16::MAX, u16::MAX], vec![0]);
+        assert!(decode_gif(&build_gif(1, 1, None, std::slice::from_ref(&huge))).is_none());
+
+        // 之前已解出的帧保留
+        let ok = (1, None, 10, [0, 0, 1, 1], vec![1]);
+        let anim = decode_gif(&build_gif(1, 1, None, &[ok, huge])).unwrap();
+        assert_eq!(anim.frames.len(), 1);
+        assert_eq!(anim.frames[0].data, vec![0, 255, 0, 255]);
+    }
+}
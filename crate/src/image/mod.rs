@@ -0,0 +1,242 @@
+//! 图像编解码模块
+//!
+//! 提供精灵图与场景像素数据的图像格式编码/解码支持。
+//! 所有解码器统一输出 RGBA8 像素，所有编码器统一接收 RGBA8 像素。
+
+mod bmp;
+mod gif;
+mod png;
+mod qoi;
+mod tga;
+
+pub use bmp::{decode_bmp, encode_bmp};
+pub use gif::{decode_gif, decode_gif_first_frame};
+pub use png::{decode_png, encode_png, DEFAULT_COMPRESSION};
+pub use qoi::{decode_qoi, encode_qoi};
+pub use tga::{decode_tga, encode_tga};
+
+/// 解码后的图像 (RGBA8)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// 宽度
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+    /// RGBA 像素数据，每个像素 4 字节
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    /// 创建全透明图像
+    ///
+    /// 尺寸为 0 或像素总数溢出时返回 None。
+    pub fn new(width: u32, height: u32) -> Option<Self> {
+        let len = pixel_len(width, height)?;
+        Some(Self {
+            width,
+            height,
+            data: vec![0u8; len],
+        })
+    }
+}
+
+/// 单张图像允许的最大边长 (防止恶意文件导致巨量内存分配)
+pub const MAX_DIMENSION: u32 = 16384;
+
+/// 计算 RGBA 像素数据长度，尺寸非法时返回 None
+pub fn pixel_len(width: u32, height: u32) -> Option<usize> {
+    if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return None;
+    }
+    (width as usize)
+        .checked_mul(height as usize)?
+        .checked_mul(4)
+}
+
+/// 若所有像素 Alpha 均为 0，则视为不带透明通道，全部置为不透明
+///
+/// 部分 BMP / TGA 写入器会在 32 位图像中填充无意义的 0 Alpha。
+pub(crate) fn fix_zero_alpha(data: &mut [u8]) {
+    if data.chunks_exact(4).all(|px| px[3] == 0) {
+        for px in data.chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+    }
+}
+
+/// 非预乘 Alpha 的 source-over 混合
+pub fn blend_over(dst: &mut [u8], src: [u8; 4]) {
+    let sa = src[3] as u32;
+    if sa == 0 {
+        return;
+    }
+    if sa == 255 {
+        dst.copy_from_slice(&src);
+        return;
+    }
+    let da = dst[3] as u32;
+    // out_a = sa + da * (1 - sa)，以 255 为单位
+    let out_a = sa * 255 + da * (255 - sa);
+    if out_a == 0 {
+        return;
+    }
+    for i in 0..3 {
+        let c = src[i] as u32 * sa * 255 + dst[i] as u32 * da * (255 - sa);
+        dst[i] = (c / out_a) as u8;
+    }
+    dst[3] = (out_a / 255) as u8;
+}
+
+/// 图像格式枚举
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageFormat {
+    /// PNG (无损，压缩率高)
+    #[default]
+    Png,
+    /// QOI (无损，编解码速度快)
+    Qoi,
+    /// BMP (未压缩位图)
+    Bmp,
+    /// TGA (Truevision，可选 RLE 压缩)
+    Tga,
+}
+
+impl ImageFormat {
+    /// 从 u8 值创建图像格式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ImageFormat::Png,
+            1 => ImageFormat::Qoi,
+            2 => ImageFormat::Bmp,
+            3 => ImageFormat::Tga,
+            _ => ImageFormat::Png,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            ImageFormat::Png => 0,
+            ImageFormat::Qoi => 1,
+            ImageFormat::Bmp => 2,
+            ImageFormat::Tga => 3,
+        }
+    }
+
+    /// 根据文件头识别图像格式
+    ///
+    /// TGA 没有固定魔数，只在其他格式均不匹配时按文件尾签名或头部合法性判断。
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(b"qoif") {
+            Some(ImageFormat::Qoi)
+        } else if bytes.starts_with(b"BM") {
+            Some(ImageFormat::Bmp)
+        } else if tga::looks_like_tga(bytes) {
+            Some(ImageFormat::Tga)
+        } else {
+            None
+        }
+    }
+}
+
+/// 自动识别格式并解码图像
+///
+/// GIF 文件只解码第一帧，动画请使用 `decode_gif`。
+pub fn decode_image(bytes: &[u8]) -> Option<Image> {
+    if bytes.starts_with(b"GIF8") {
+        return decode_gif_first_frame(bytes);
+    }
+    match ImageFormat::detect(bytes)? {
+        ImageFormat::Png => decode_png(bytes),
+        ImageFormat::Qoi => decode_qoi(bytes),
+        ImageFormat::Bmp => decode_bmp(bytes),
+        ImageFormat::Tga => decode_tga(bytes),
+    }
+}
+
+/// 按指定格式编码 RGBA8 像素
+///
+/// `level` 对 PNG 为 0-10 的压缩等级；对 TGA 大于 0 时启用 RLE；其余格式忽略。
+pub fn encode_image(
+    format: ImageFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    level: u8,
+) -> Vec<u8> {
+    match format {
+        ImageFormat::Png => encode_png(data, width, height, level),
+        ImageFormat::Qoi => encode_qoi(data, width, height),
+        ImageFormat::Bmp => encode_bmp(data, width, height),
+        ImageFormat::Tga => encode_tga(data, width, height, level > 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_pixels() -> (Vec<u8>, u32, u32) {
+        let (w, h) = (7u32, 5u32);
+        let mut data = Vec::new();
+        for y in 0..h {
+            for x in 0..w {
+                data.extend_from_slice(&[(x * 30) as u8, (y * 50) as u8, 128, (x * y * 8) as u8]);
+            }
+        }
+        (data, w, h)
+    }
+
+    #[test]
+    fn test_format_conversion() {
+        for v in 0..4 {
+            assert_eq!(ImageFormat::from_u8(v).to_u8(), v);
+        }
+        assert_eq!(ImageFormat::from_u8(99), ImageFormat::Png);
+    }
+
+    #[test]
+    fn test_roundtrip_all_formats() {
+        let (data, w, h) = test_pixels();
+        for format in [
+            ImageFormat::Png,
+            ImageFormat::Qoi,
+            ImageFormat::Bmp,
+            ImageFormat::Tga,
+        ] {
+            for level in [0, 6] {
+                let bytes = encode_image(format, &data, w, h, level);
+                assert_eq!(ImageFormat::detect(&bytes), Some(format));
+                let image = decode_image(&bytes).unwrap();
+                assert_eq!((image.width, image.height), (w, h));
+                assert_eq!(image.data, data, "{:?} level {}", format, level);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_unknown() {
+        assert_eq!(ImageFormat::detect(b"GIF89a"), None);
+        assert!(decode_image(&[]).is_none());
+    }
+
+    #[test]
+    fn test_pixel_len_limits() {
+        assert_eq!(pixel_len(2, 3), Some(24));
+        assert_eq!(pixel_len(0, 3), None);
+        assert_eq!(pixel_len(MAX_DIMENSION + 1, 1), None);
+    }
+
+    #[test]
+    fn test_blend_over() {
+        let mut dst = [0u8, 0, 255, 255];
+        blend_over(&mut dst, [255, 0, 0, 128]);
+        assert!(dst[0] > 120 && dst[0] < 135);
+        assert_eq!(dst[3], 255);
+        let mut empty = [0u8; 4];
+        blend_over(&mut empty, [10, 20, 30, 40]);
+        assert_eq!(empty, [10, 20, 30, 40]);
+    }
+}
@@ -0,0 +1,678 @@
+//! PNG 编解码
+//!
+//! 编码输出 8 位真彩色 + Alpha；解码支持全部标准颜色类型、位深与 Adam7 隔行。
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+use super::Image;
+
+/// PNG 文件签名
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// 默认压缩等级
+pub const DEFAULT_COMPRESSION: u8 = 6;
+
+/// 最大压缩等级
+const MAX_COMPRESSION: u8 = 10;
+
+/// CRC32 查找表 (多项式 0xEDB88320)
+const CRC_TABLE: [u32; 256] = build_crc_table();
+
+const fn build_crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+/// 计算 CRC32 校验值
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &b in bytes {
+        crc = CRC_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+/// 写入一个 PNG 数据块 (长度 + 类型 + 数据 + CRC)
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let crc_start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[crc_start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Paeth 预测器
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// 对单行应用指定滤波器，结果写入 `out`
+fn filter_row(filter: u8, row: &[u8], prev: &[u8], out: &mut [u8]) {
+    const BPP: usize = 4;
+    for i in 0..row.len() {
+        let a = if i >= BPP { row[i - BPP] } else { 0 };
+        let b = prev[i];
+        let c = if i >= BPP { prev[i - BPP] } else { 0 };
+        out[i] = match filter {
+            1 => row[i].wrapping_sub(a),
+            2 => row[i].wrapping_sub(b),
+            3 => row[i].wrapping_sub(((a as u16 + b as u16) / 2) as u8),
+            4 => row[i].wrapping_sub(paeth(a, b, c)),
+            _ => row[i],
+        };
+    }
+}
+
+/// 滤波结果的代价估计 (有符号字节绝对值之和，越小越容易压缩)
+fn filter_cost(filtered: &[u8]) -> u32 {
+    filtered
+        .iter()
+        .map(|&v| (v as i8).unsigned_abs() as u32)
+        .sum()
+}
+
+/// 生成带滤波类型前缀的扫描线数据
+///
+/// 压缩等级为 0 时不做滤波；否则逐行选择代价最小的滤波器。
+fn filter_scanlines(data: &[u8], width: u32, height: u32, level: u8) -> Vec<u8> {
+    let stride = width as usize * 4;
+    let mut raw = Vec::with_capacity((stride + 1) * height as usize);
+    let zero_row = vec![0u8; stride];
+    let mut best = vec![0u8; stride];
+    let mut candidate = vec![0u8; stride];
+
+    for y in 0..height as usize {
+        let row = &data[y * stride..(y + 1) * stride];
+        let prev = if y > 0 {
+            &data[(y - 1) * stride..y * stride]
+        } else {
+            &zero_row[..]
+        };
+
+        if level == 0 {
+            raw.push(0);
+            raw.extend_from_slice(row);
+            continue;
+        }
+
+        let mut best_filter = 0u8;
+        let mut best_cost = u32::MAX;
+        for filter in 0..5u8 {
+            filter_row(filter, row, prev, &mut candidate);
+            let cost = filter_cost(&candidate);
+            if cost < best_cost {
+                best_cost = cost;
+                best_filter = filter;
+                std::mem::swap(&mut best, &mut candidate);
+            }
+        }
+
+        raw.push(best_filter);
+        raw.extend_from_slice(&best);
+    }
+
+    raw
+}
+
+/// 将 RGBA8 像素编码为 PNG
+///
+/// # Arguments
+/// * `data` - RGBA 像素数据，长度至少为 `width * height * 4`
+/// * `width` - 图像宽度
+/// * `height` - 图像高度
+/// * `level` - 压缩等级 (0-10，0 为不压缩，超出范围时取 10)
+///
+/// # Returns
+/// 完整的 PNG 文件字节
+pub fn encode_png(data: &[u8], width: u32, height: u32, level: u8) -> Vec<u8> {
+    let level = level.min(MAX_COMPRESSION);
+    let pixel_len = width as usize * height as usize * 4;
+    let pixels = &data[..pixel_len.min(data.len())];
+
+    // 数据不足时以透明像素补齐
+    let padded;
+    let pixels = if pixels.len() < pixel_len {
+        let mut buf = pixels.to_vec();
+        buf.resize(pixel_len, 0);
+        padded = buf;
+        &padded[..]
+    } else {
+        pixels
+    };
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&PNG_SIGNATURE);
+
+    // IHDR: 宽、高、位深 8、颜色类型 6 (RGBA)、压缩/滤波/隔行均为 0
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+    write_chunk(&mut out, b"IHDR", &ihdr);
+
+    let raw = filter_scanlines(pixels, width, height, level);
+    let compressed = compress_to_vec_zlib(&raw, level);
+    write_chunk(&mut out, b"IDAT", &compressed);
+
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Adam7 隔行扫描的 7 个子图: (起始 x, 起始 y, x 步长, y 步长)
+const ADAM7_PASSES: [(u32, u32, u32, u32); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// 非隔行扫描: 单遍逐像素
+const SINGLE_PASS: [(u32, u32, u32, u32); 1] = [(0, 0, 1, 1)];
+
+/// PNG 头部信息
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    interlaced: bool,
+}
+
+impl Header {
+    /// 每像素的通道数
+    fn channels(&self) -> usize {
+        match self.color_type {
+            0 | 3 => 1,
+            2 => 3,
+            4 => 2,
+            _ => 4,
+        }
+    }
+
+    /// 每像素的位数
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.bit_depth as usize
+    }
+
+    /// 扫描遍 (起点 x, 起点 y, 步长 x, 步长 y)
+    fn passes(&self) -> &'static [(u32, u32, u32, u32)] {
+        if self.interlaced {
+            &ADAM7_PASSES
+        } else {
+            &SINGLE_PASS
+        }
+    }
+
+    /// 某一遍的 (宽, 高, 每行字节数)，该遍为空时返回 None
+    fn pass_size(&self, (x0, y0, dx, dy): (u32, u32, u32, u32)) -> Option<(usize, usize, usize)> {
+        if x0 >= self.width || y0 >= self.height {
+            return None;
+        }
+        let pass_w = (self.width - x0).div_ceil(dx) as usize;
+        let pass_h = (self.height - y0).div_ceil(dy) as usize;
+        Some((pass_w, pass_h, (pass_w * self.bits_per_pixel()).div_ceil(8)))
+    }
+
+    /// 解压后 (含每行滤波类型字节) 的扫描线数据总长度
+    fn filtered_len(&self) -> Option<usize> {
+        self.passes()
+            .iter()
+            .filter_map(|&pass| self.pass_size(pass))
+            .try_fold(0usize, |total, (_, pass_h, stride)| {
+                total.checked_add(pass_h.checked_mul(stride + 1)?)
+            })
+    }
+
+    /// 校验颜色类型与位深的组合是否合法
+    fn is_valid(&self) -> bool {
+        matches!(
+            (self.color_type, self.bit_depth),
+            (0, 1 | 2 | 4 | 8 | 16) | (3, 1 | 2 | 4 | 8) | (2 | 4 | 6, 8 | 16)
+        )
+    }
+}
+
+/// 调色板与透明信息
+struct Palette {
+    /// 调色板颜色 (RGBA)
+    colors: Vec<[u8; 4]>,
+    /// 灰度 / RGB 图像的透明色键 (原始采样值)
+    color_key: Option<[u16; 3]>,
+}
+
+/// 对单行进行逆滤波 (原地)
+fn unfilter_row(filter: u8, row: &mut [u8], prev: &[u8], bpp: usize) -> Option<()> {
+    match filter {
+        0 => {}
+        1 => {
+            for i in bpp..row.len() {
+                row[i] = row[i].wrapping_add(row[i - bpp]);
+            }
+        }
+        2 => {
+            for i in 0..row.len() {
+                row[i] = row[i].wrapping_add(prev[i]);
+            }
+        }
+        3 => {
+            for i in 0..row.len() {
+                let a = if i >= bpp { row[i - bpp] } else { 0 };
+                row[i] = row[i].wrapping_add(((a as u16 + prev[i] as u16) / 2) as u8);
+            }
+        }
+        4 => {
+            for i in 0..row.len() {
+                let a = if i >= bpp { row[i - bpp] } else { 0 };
+                let c = if i >= bpp { prev[i - bpp] } else { 0 };
+                row[i] = row[i].wrapping_add(paeth(a, prev[i], c));
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// 读取行内第 `index` 个采样值 (按位深解包，16 位为大端)
+fn read_sample(row: &[u8], index: usize, depth: u8) -> u16 {
+    match depth {
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        8 => row[index] as u16,
+        _ => {
+            let bit = index * depth as usize;
+            let byte = row[bit / 8];
+            let shift = 8 - depth as usize - (bit % 8);
+            ((byte >> shift) & ((1u8 << depth) - 1)) as u16
+        }
+    }
+}
+
+/// 将采样值缩放到 8 位
+fn to_u8(value: u16, depth: u8) -> u8 {
+    match depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        _ => (value as u32 * 255 / ((1u32 << depth) - 1)) as u8,
+    }
+}
+
+/// 将一行已逆滤波的数据转换为 RGBA 像素
+fn row_pixel(header: &Header, palette: &Palette, row: &[u8], x: usize) -> [u8; 4] {
+    let depth = header.bit_depth;
+    match header.color_type {
+        0 => {
+            let g = read_sample(row, x, depth);
+            let a = match palette.color_key {
+                Some(key) if key[0] == g => 0,
+                _ => 255,
+            };
+            let v = to_u8(g, depth);
+            [v, v, v, a]
+        }
+        2 => {
+            let r = read_sample(row, x * 3, depth);
+            let g = read_sample(row, x * 3 + 1, depth);
+            let b = read_sample(row, x * 3 + 2, depth);
+            let a = match palette.color_key {
+                Some(key) if key == [r, g, b] => 0,
+                _ => 255,
+            };
+            [to_u8(r, depth), to_u8(g, depth), to_u8(b, depth), a]
+        }
+        3 => {
+            let index = read_sample(row, x, depth) as usize;
+            palette.colors.get(index).copied().unwrap_or([0, 0, 0, 255])
+        }
+        4 => {
+            let v = to_u8(read_sample(row, x * 2, depth), depth);
+            let a = to_u8(read_sample(row, x * 2 + 1, depth), depth);
+            [v, v, v, a]
+        }
+        _ => [
+            to_u8(read_sample(row, x * 4, depth), depth),
+            to_u8(read_sample(row, x * 4 + 1, depth), depth),
+            to_u8(read_sample(row, x * 4 + 2, depth), depth),
+            to_u8(read_sample(row, x * 4 + 3, depth), depth),
+        ],
+    }
+}
+
+/// 解码 PNG 字节为 RGBA8 图像
+///
+/// # Returns
+/// 解码结果，文件损坏或格式不支持时返回 None
+pub fn decode_png(bytes: &[u8]) -> Option<Image> {
+    if bytes.len() < 8 || bytes[..8] != PNG_SIGNATURE {
+        return None;
+    }
+
+    let mut header: Option<Header> = None;
+    let mut palette = Palette {
+        colors: Vec::new(),
+        color_key: None,
+    };
+    let mut idat = Vec::new();
+
+    let mut pos = 8;
+    while pos + 12 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+        let kind = &bytes[pos + 4..pos + 8];
+        let data = bytes.get(pos + 8..pos + 8 + len)?;
+        pos += 12 + len;
+
+        match kind {
+            b"IHDR" if data.len() >= 13 => {
+                header = Some(Header {
+                    width: u32::from_be_bytes(data[0..4].try_into().ok()?),
+                    height: u32::from_be_bytes(data[4..8].try_into().ok()?),
+                    bit_depth: data[8],
+                    color_type: data[9],
+                    interlaced: data[12] == 1,
+                });
+            }
+            b"PLTE" => {
+                palette.colors = data
+                    .chunks_exact(3)
+                    .map(|c| [c[0], c[1], c[2], 255])
+                    .collect();
+            }
+            b"tRNS" => match header.as_ref()?.color_type {
+                3 => {
+                    for (color, &a) in palette.colors.iter_mut().zip(data) {
+                        color[3] = a;
+                    }
+                }
+                0 if data.len() >= 2 => {
+                    let g = u16::from_be_bytes([data[0], data[1]]);
+                    palette.color_key = Some([g, g, g]);
+                }
+                2 if data.len() >= 6 => {
+                    palette.color_key = Some([
+                        u16::from_be_bytes([data[0], data[1]]),
+                        u16::from_be_bytes([data[2], data[3]]),
+                        u16::from_be_bytes([data[4], data[5]]),
+                    ]);
+                }
+                _ => {}
+            },
+            b"IDAT" => idat.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header?;
+    if !header.is_valid() {
+        return None;
+    }
+    // 尺寸经 pixel_len 校验后再计算扫描线长度，解压输出不得超过该长度
+    let mut image = Image::new(header.width, header.height)?;
+    let raw = decompress_to_vec_zlib_with_limit(&idat, header.filtered_len()?).ok()?;
+    let bpp = header.bits_per_pixel().div_ceil(8);
+
+    let mut offset = 0;
+    for &pass in header.passes() {
+        let (x0, y0, dx, dy) = pass;
+        let Some((pass_w, pass_h, stride)) = header.pass_size(pass) else {
+            continue;
+        };
+
+        let mut prev = vec![0u8; stride];
+        let mut row = vec![0u8; stride];
+        for py in 0..pass_h {
+            let filter = *raw.get(offset)?;
+            row.copy_from_slice(raw.get(offset + 1..offset + 1 + stride)?);
+            offset += 1 + stride;
+            unfilter_row(filter, &mut row, &prev, bpp)?;
+
+            let y = y0 as usize + py * dy as usize;
+            for px in 0..pass_w {
+                let x = x0 as usize + px * dx as usize;
+                let color = row_pixel(&header, &palette, &row, px);
+                let dst = (y * header.width as usize + x) * 4;
+                image.data[dst..dst + 4].copy_from_slice(&color);
+            }
+            std::mem::swap(&mut prev, &mut row);
+        }
+    }
+
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+    /// 解析 PNG 数据块列表 (类型, 数据)，同时校验 CRC
+    fn read_chunks(png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert_eq!(&png[..8], &PNG_SIGNATURE);
+        let mut chunks = Vec::new();
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let kind: [u8; 4] = png[pos + 4..pos + 8].try_into().unwrap();
+            let data = png[pos + 8..pos + 8 + len].to_vec();
+            let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32(&png[pos + 4..pos + 8 + len]));
+            chunks.push((kind, data));
+            pos += 12 + len;
+        }
+        chunks
+    }
+
+    #[test]
+    fn test_crc32() {
+        // 标准测试向量
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_encode_structure() {
+        let data = vec![255u8; 3 * 2 * 4];
+        let png = encode_png(&data, 3, 2, DEFAULT_COMPRESSION);
+        let chunks = read_chunks(&png);
+
+        let kinds: Vec<&[u8; 4]> = chunks.iter().map(|(k, _)| k).collect();
+        assert_eq!(kinds, vec![b"IHDR", b"IDAT", b"IEND"]);
+
+        let ihdr = &chunks[0].1;
+        assert_eq!(&ihdr[0..4], &3u32.to_be_bytes());
+        assert_eq!(&ihdr[4..8], &2u32.to_be_bytes());
+        assert_eq!(&ihdr[8..], &[8, 6, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_uncompressed_scanlines() {
+        let data: Vec<u8> = (0..2 * 2 * 4).map(|v| v as u8).collect();
+        let png = encode_png(&data, 2, 2, 0);
+        let chunks = read_chunks(&png);
+        let raw = decompress_to_vec_zlib(&chunks[1].1).unwrap();
+
+        // 每行 = 滤波类型 0 + 8 字节像素
+        assert_eq!(raw.len(), 2 * (1 + 8));
+        assert_eq!(raw[0], 0);
+        assert_eq!(&raw[1..9], &data[0..8]);
+        assert_eq!(raw[9], 0);
+        assert_eq!(&raw[10..18], &data[8..16]);
+    }
+
+    #[test]
+    fn test_filtered_scanlines_roundtrip() {
+        // 渐变图像，滤波器选择后应能无损还原
+        let (w, h) = (5u32, 4u32);
+        let data: Vec<u8> = (0..w * h * 4).map(|v| (v * 7 % 251) as u8).collect();
+        let png = encode_png(&data, w, h, 9);
+        let chunks = read_chunks(&png);
+        let raw = decompress_to_vec_zlib(&chunks[1].1).unwrap();
+
+        let stride = (w * 4) as usize;
+        let mut prev = vec![0u8; stride];
+        let mut decoded = Vec::new();
+        for y in 0..h as usize {
+            let filter = raw[y * (stride + 1)];
+            let line = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+            let mut row = vec![0u8; stride];
+            for i in 0..stride {
+                let a = if i >= 4 { row[i - 4] } else { 0 };
+                let b = prev[i];
+                let c = if i >= 4 { prev[i - 4] } else { 0 };
+                row[i] = match filter {
+                    1 => line[i].wrapping_add(a),
+                    2 => line[i].wrapping_add(b),
+                    3 => line[i].wrapping_add(((a as u16 + b as u16) / 2) as u8),
+                    4 => line[i].wrapping_add(paeth(a, b, c)),
+                    _ => line[i],
+                };
+            }
+            decoded.extend_from_slice(&row);
+            prev = row;
+        }
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_short_data_is_padded() {
+        let png = encode_png(&[1, 2, 3, 4], 2, 1, 0);
+        let chunks = read_chunks(&png);
+        let raw = decompress_to_vec_zlib(&chunks[1].1).unwrap();
+        assert_eq!(raw, vec![0, 1, 2, 3, 4, 0, 0, 0, 0]);
+    }
+
+    /// 组装最小 PNG 文件
+    fn build_png(ihdr: [u8; 13], extra: &[(&[u8; 4], Vec<u8>)], raw: &[u8]) -> Vec<u8> {
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &ihdr);
+        for (kind, data) in extra {
+            write_chunk(&mut out, kind, data);
+        }
+        write_chunk(&mut out, b"IDAT", &compress_to_vec_zlib(raw, 6));
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    fn ihdr(w: u32, h: u32, depth: u8, color_type: u8, interlace: u8) -> [u8; 13] {
+        let mut out = [0u8; 13];
+        out[0..4].copy_from_slice(&w.to_be_bytes());
+        out[4..8].copy_from_slice(&h.to_be_bytes());
+        out[8] = depth;
+        out[9] = color_type;
+        out[12] = interlace;
+        out
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        let data: Vec<u8> = (0..6 * 3 * 4).map(|v| (v * 13 % 256) as u8).collect();
+        let image = decode_png(&encode_png(&data, 6, 3, 6)).unwrap();
+        assert_eq!((image.width, image.height), (6, 3));
+        assert_eq!(image.data, data);
+    }
+
+    #[test]
+    fn test_decode_palette_with_transparency() {
+        // 2 位调色板，4 个像素: 索引 0,1,2,3
+        let palette = vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 9, 9, 9];
+        let png = build_png(
+            ihdr(4, 1, 2, 3, 0),
+            &[(b"PLTE", palette), (b"tRNS", vec![255, 128])],
+            &[0, 0b00_01_10_11],
+        );
+        let image = decode_png(&png).unwrap();
+        assert_eq!(
+            image.data,
+            vec![255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 255, 9, 9, 9, 255]
+        );
+    }
+
+    #[test]
+    fn test_decode_gray16_with_color_key() {
+        let png = build_png(
+            ihdr(2, 1, 16, 0, 0),
+            &[(b"tRNS", vec![0x12, 0x34])],
+            &[0, 0x12, 0x34, 0xFF, 0xFF],
+        );
+        let image = decode_png(&png).unwrap();
+        assert_eq!(image.data, vec![0x12, 0x12, 0x12, 0, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_decode_adam7() {
+        // 3x3 8 位灰度隔行图像，像素值 = y * 3 + x
+        // 子图顺序: (0,0) | 空 | 空 | (2,0) | (0,2),(2,2) | (1,0),(1,2) | (0,1),(1,1),(2,1)
+        let raw = [
+            0, 0, // pass 1
+            0, 2, // pass 4
+            0, 6, 8, // pass 5
+            0, 1, 0, 7, // pass 6 (两行)
+            0, 3, 4, 5, // pass 7
+        ];
+        let image = decode_png(&build_png(ihdr(3, 3, 8, 0, 1), &[], &raw)).unwrap();
+        let grays: Vec<u8> = image.data.chunks_exact(4).map(|px| px[0]).collect();
+        assert_eq!(grays, (0..9).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(decode_png(b"not a png").is_none());
+        // 非法位深
+        assert!(decode_png(&build_png(ihdr(1, 1, 3, 2, 0), &[], &[0, 0])).is_none());
+        // 数据不足
+        assert!(decode_png(&build_png(ihdr(4, 4, 8, 6, 0), &[], &[0, 1, 2])).is_none());
+    }
+
+    #[test]
+    fn test_decode_limits_decompressed_size() {
+        // 1x1 灰度图像只需 2 字节扫描线，1 MiB 的 IDAT 数据解压到上限即失败
+        let bomb = vec![0u8; 1 << 20];
+        assert!(decode_png(&build_png(ihdr(1, 1, 8, 0, 0), &[], &bomb)).is_none());
+        assert_eq!(ihdr_header(3, 3, 8, 0, true).filtered_len(), Some(15));
+        assert_eq!(ihdr_header(5, 2, 16, 6, false).filtered_len(), Some(82));
+    }
+
+    fn ihdr_header(
+        width: u32,
+        height: u32,
+        bit_depth: u8,
+        color_type: u8,
+        interlaced: bool,
+    ) -> Header {
+        Header {
+            width,
+            height,
+            bit_depth,
+            color_type,
+            interlaced,
+        }
+    }
+}
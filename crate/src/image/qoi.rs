@@ -0,0 +1,212 @@
+//! QOI 编解码
+//!
+//! Quite OK Image 格式：无损、单遍、解码速度远快于 PNG，适合大尺寸关卡背景。
+
+use super::{pixel_len, Image};
+
+/// 文件头长度
+const HEADER_SIZE: usize = 14;
+
+/// 文件尾标记
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xC0;
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const OP_MASK: u8 = 0xC0;
+
+/// 颜色哈希 (用于 64 项索引表)
+fn hash(px: [u8; 4]) -> usize {
+    (px[0] as usize * 3 + px[1] as usize * 5 + px[2] as usize * 7 + px[3] as usize * 11) % 64
+}
+
+/// 解码 QOI 字节为 RGBA8 图像
+///
+/// # Returns
+/// 解码结果，文件损坏时返回 None
+pub fn decode_qoi(bytes: &[u8]) -> Option<Image> {
+    if bytes.len() < HEADER_SIZE || &bytes[0..4] != b"qoif" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[4..8].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[8..12].try_into().ok()?);
+    let channels = bytes[12];
+    if channels != 3 && channels != 4 {
+        return None;
+    }
+
+    let len = pixel_len(width, height)?;
+    let mut data = Vec::with_capacity(len);
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0u8, 0, 0, 255];
+    let mut pos = HEADER_SIZE;
+    let mut run = 0u32;
+
+    while data.len() < len {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let b1 = *bytes.get(pos)?;
+            pos += 1;
+
+            if b1 == OP_RGB {
+                px[0..3].copy_from_slice(bytes.get(pos..pos + 3)?);
+                pos += 3;
+            } else if b1 == OP_RGBA {
+                px.copy_from_slice(bytes.get(pos..pos + 4)?);
+                pos += 4;
+            } else {
+                match b1 & OP_MASK {
+                    OP_INDEX => px = index[b1 as usize],
+                    OP_DIFF => {
+                        px[0] = px[0].wrapping_add(((b1 >> 4) & 0x03).wrapping_sub(2));
+                        px[1] = px[1].wrapping_add(((b1 >> 2) & 0x03).wrapping_sub(2));
+                        px[2] = px[2].wrapping_add((b1 & 0x03).wrapping_sub(2));
+                    }
+                    OP_LUMA => {
+                        let b2 = *bytes.get(pos)?;
+                        pos += 1;
+                        let vg = (b1 & 0x3F).wrapping_sub(32);
+                        px[0] =
+                            px[0].wrapping_add(vg.wrapping_sub(8).wrapping_add((b2 >> 4) & 0x0F));
+                        px[1] = px[1].wrapping_add(vg);
+                        px[2] = px[2].wrapping_add(vg.wrapping_sub(8).wrapping_add(b2 & 0x0F));
+                    }
+                    _ => run = (b1 & 0x3F) as u32,
+                }
+            }
+            index[hash(px)] = px;
+        }
+        data.extend_from_slice(&px);
+    }
+
+    Some(Image {
+        width,
+        height,
+        data,
+    })
+}
+
+/// 将 RGBA8 像素编码为 QOI (4 通道，sRGB)
+///
+/// # Returns
+/// 完整的 QOI 文件字节
+pub fn encode_qoi(data: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let count = width as usize * height as usize;
+    let mut out = Vec::with_capacity(HEADER_SIZE + count + END_MARKER.len());
+    out.extend_from_slice(b"qoif");
+    out.extend_from_slice(&width.to_be_bytes());
+    out.extend_from_slice(&height.to_be_bytes());
+    out.push(4);
+    out.push(0);
+
+    let mut index = [[0u8; 4]; 64];
+    let mut prev = [0u8, 0, 0, 255];
+    let mut run = 0u8;
+
+    for i in 0..count {
+        let px = match data.get(i * 4..i * 4 + 4) {
+            Some(p) => [p[0], p[1], p[2], p[3]],
+            None => [0, 0, 0, 0],
+        };
+
+        if px == prev {
+            run += 1;
+            if run == 62 || i == count - 1 {
+                out.push(OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+
+        if run > 0 {
+            out.push(OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        let slot = hash(px);
+        if index[slot] == px {
+            out.push(OP_INDEX | slot as u8);
+        } else {
+            index[slot] = px;
+            if px[3] == prev[3] {
+                let vr = px[0].wrapping_sub(prev[0]) as i8;
+                let vg = px[1].wrapping_sub(prev[1]) as i8;
+                let vb = px[2].wrapping_sub(prev[2]) as i8;
+                let vg_r = vr.wrapping_sub(vg);
+                let vg_b = vb.wrapping_sub(vg);
+
+                if (-2..=1).contains(&vr) && (-2..=1).contains(&vg) && (-2..=1).contains(&vb) {
+                    out.push(
+                        OP_DIFF
+                            | (((vr + 2) as u8) << 4)
+                            | (((vg + 2) as u8) << 2)
+                            | ((vb + 2) as u8),
+                    );
+                } else if (-8..=7).contains(&vg_r)
+                    && (-32..=31).contains(&vg)
+                    && (-8..=7).contains(&vg_b)
+                {
+                    out.push(OP_LUMA | ((vg + 32) as u8));
+                    out.push((((vg_r + 8) as u8) << 4) | ((vg_b + 8) as u8));
+                } else {
+                    out.extend_from_slice(&[OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+        prev = px;
+    }
+
+    out.extend_from_slice(&END_MARKER);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_runs_and_diffs() {
+        // 包含长游程 (>62)、小差值、亮度差值与 Alpha 变化
+        let mut data = Vec::new();
+        for _ in 0..70 {
+            data.extend_from_slice(&[10, 20, 30, 255]);
+        }
+        data.extend_from_slice(&[11, 19, 31, 255]); // DIFF
+        data.extend_from_slice(&[30, 40, 50, 255]); // LUMA
+        data.extend_from_slice(&[200, 0, 90, 255]); // RGB
+        data.extend_from_slice(&[200, 0, 90, 7]); // RGBA
+        data.extend_from_slice(&[10, 20, 30, 255]); // INDEX
+        let count = data.len() as u32 / 4;
+
+        let bytes = encode_qoi(&data, count, 1);
+        let image = decode_qoi(&bytes).unwrap();
+        assert_eq!(image.data, data);
+        // 游程编码应显著小于原始数据
+        assert!(bytes.len() < data.len() / 4);
+    }
+
+    #[test]
+    fn test_decode_rgb_channels() {
+        // 手写 3 通道文件: 1 个 OP_RGB 像素
+        let mut bytes = b"qoif".to_vec();
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&[3, 0, OP_RGB, 1, 2, 3]);
+        bytes.extend_from_slice(&END_MARKER);
+        let image = decode_qoi(&bytes).unwrap();
+        assert_eq!(image.data, vec![1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let bytes = encode_qoi(&[1, 2, 3, 4, 5, 6, 7, 8], 2, 1);
+        assert!(decode_qoi(&bytes[..HEADER_SIZE + 2]).is_none());
+        assert!(decode_qoi(b"qoif").is_none());
+    }
+}
@@ -0,0 +1,327 @@
+//! TGA 编解码
+//!
+//! 解码支持调色板 / 真彩色 / 灰度图像 (类型 1/2/3 及其 RLE 变体 9/10/11)、
+//! 8/15/16/24/32 位像素与四种扫描原点；编码输出 32 位左上原点图像，可选 RLE。
+
+use super::{fix_zero_alpha, Image};
+
+/// 文件头长度
+const HEADER_SIZE: usize = 18;
+
+/// TGA 2.0 文件尾签名
+const FOOTER_SIGNATURE: &[u8; 18] = b"TRUEVISION-XFILE.\0";
+
+/// 描述符: 原点在顶部
+const DESC_TOP: u8 = 0x20;
+
+/// 描述符: 原点在右侧
+const DESC_RIGHT: u8 = 0x10;
+
+/// RLE 数据包最大像素数
+const MAX_PACKET: usize = 128;
+
+/// 判断字节流是否可能为 TGA 文件
+pub fn looks_like_tga(bytes: &[u8]) -> bool {
+    if bytes.len() < HEADER_SIZE {
+        return false;
+    }
+    bytes.ends_with(FOOTER_SIGNATURE) || valid_header(bytes)
+}
+
+/// 检查文件头中的类型、位深与尺寸是否受支持 (`bytes` 至少包含完整文件头)
+fn valid_header(bytes: &[u8]) -> bool {
+    let color_map_type = bytes[1];
+    let image_type = bytes[2];
+    let map_depth = bytes[7];
+    let width = u16::from_le_bytes([bytes[12], bytes[13]]);
+    let height = u16::from_le_bytes([bytes[14], bytes[15]]);
+    let depth = bytes[16];
+    color_map_type <= 1
+        && (color_map_type == 0 || matches!(map_depth, 15 | 16 | 24 | 32))
+        && matches!(image_type, 1 | 2 | 3 | 9 | 10 | 11)
+        && matches!(depth, 8 | 15 | 16 | 24 | 32)
+        && width > 0
+        && height > 0
+}
+
+/// 按位深解析单个像素 (小端 BGR(A) 顺序)
+fn read_pixel(src: &[u8], depth: u8, alpha_bits: u8) -> [u8; 4] {
+    match depth {
+        8 => [src[0], src[0], src[0], 255],
+        15 | 16 => {
+            let v = u16::from_le_bytes([src[0], src[1]]);
+            let expand = |c: u16| ((c as u32 * 255) / 31) as u8;
+            let a = if depth == 16 && alpha_bits > 0 && v & 0x8000 == 0 {
+                0
+            } else {
+                255
+            };
+            [
+                expand((v >> 10) & 0x1F),
+                expand((v >> 5) & 0x1F),
+                expand(v & 0x1F),
+                a,
+            ]
+        }
+        24 => [src[2], src[1], src[0], 255],
+        _ => [src[2], src[1], src[0], src[3]],
+    }
+}
+
+/// 解码 TGA 字节为 RGBA8 图像
+///
+/// # Returns
+/// 解码结果，文件损坏或格式不支持时返回 None
+pub fn decode_tga(bytes: &[u8]) -> Option<Image> {
+    // 带文件尾的文件同样需要校验文件头，否则位深为 0 或颜色表项过短时无法解析
+    if bytes.len() < HEADER_SIZE || !valid_header(bytes) {
+        return None;
+    }
+    let id_len = bytes[0] as usize;
+    let color_map_type = bytes[1];
+    let image_type = bytes[2];
+    let map_first = u16::from_le_bytes([bytes[3], bytes[4]]) as usize;
+    let map_len = u16::from_le_bytes([bytes[5], bytes[6]]) as usize;
+    let map_depth = bytes[7];
+    let width = u16::from_le_bytes([bytes[12], bytes[13]]) as u32;
+    let height = u16::from_le_bytes([bytes[14], bytes[15]]) as u32;
+    let depth = bytes[16];
+    let descriptor = bytes[17];
+    let alpha_bits = descriptor & 0x0F;
+
+    let mut image = Image::new(width, height)?;
+    let mut pos = HEADER_SIZE + id_len;
+
+    // 颜色映射表
+    let mut palette = Vec::new();
+    if color_map_type == 1 {
+        let entry = (map_depth as usize).div_ceil(8);
+        for i in 0..map_len {
+            let src = bytes.get(pos + i * entry..pos + (i + 1) * entry)?;
+            palette.push(read_pixel(src, map_depth, alpha_bits));
+        }
+        pos += map_len * entry;
+    }
+
+    let is_mapped = image_type & 0x07 == 1;
+    if is_mapped && (palette.is_empty() || depth != 8) {
+        return None;
+    }
+    let pixel_size = (depth as usize).div_ceil(8);
+    let decode = |src: &[u8]| -> [u8; 4] {
+        if is_mapped {
+            let index = (src[0] as usize).wrapping_sub(map_first);
+            palette.get(index).copied().unwrap_or([0, 0, 0, 255])
+        } else {
+            read_pixel(src, depth, alpha_bits)
+        }
+    };
+
+    // 先按文件顺序解出所有像素
+    let count = (width * height) as usize;
+    let mut pixels: Vec<[u8; 4]> = Vec::with_capacity(count);
+    if image_type >= 9 {
+        while pixels.len() < count {
+            let packet = *bytes.get(pos)?;
+            pos += 1;
+            let n = (packet & 0x7F) as usize + 1;
+            if packet & 0x80 != 0 {
+                let color = decode(bytes.get(pos..pos + pixel_size)?);
+                pos += pixel_size;
+                pixels.extend(std::iter::repeat_n(color, n.min(count - pixels.len())));
+            } else {
+                for _ in 0..n.min(count - pixels.len()) {
+                    pixels.push(decode(bytes.get(pos..pos + pixel_size)?));
+                    pos += pixel_size;
+                }
+            }
+        }
+    } else {
+        let raw = bytes.get(pos..pos + count * pixel_size)?;
+        pixels.extend(raw.chunks_exact(pixel_size).map(decode));
+    }
+
+    // 按原点方向写入 (默认原点在左下)
+    let (w, h) = (width as usize, height as usize);
+    for (i, color) in pixels.iter().enumerate() {
+        let (fx, fy) = (i % w, i / w);
+        let x = if descriptor & DESC_RIGHT != 0 {
+            w - 1 - fx
+        } else {
+            fx
+        };
+        let y = if descriptor & DESC_TOP != 0 {
+            fy
+        } else {
+            h - 1 - fy
+        };
+        let dst = (y * w + x) * 4;
+        image.data[dst..dst + 4].copy_from_slice(color);
+    }
+
+    if depth == 32 && alpha_bits == 0 {
+        fix_zero_alpha(&mut image.data);
+    }
+
+    Some(image)
+}
+
+/// 将 RGBA8 像素编码为 32 位 TGA (左上原点，8 位 Alpha)
+///
+/// # Arguments
+/// * `rle` - 是否启用 RLE 压缩 (类型 10)，否则输出未压缩图像 (类型 2)
+///
+/// # Returns
+/// 完整的 TGA 文件字节 (含 TGA 2.0 文件尾)
+pub fn encode_tga(data: &[u8], width: u32, height: u32, rle: bool) -> Vec<u8> {
+    let width = width.min(u16::MAX as u32);
+    let height = height.min(u16::MAX as u32);
+    let count = (width * height) as usize;
+
+    let mut out = Vec::with_capacity(HEADER_SIZE + count * 4 + 26);
+    out.extend_from_slice(&[0, 0, if rle { 10 } else { 2 }]);
+    out.extend_from_slice(&[0, 0, 0, 0, 0]);
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    out.extend_from_slice(&(width as u16).to_le_bytes());
+    out.extend_from_slice(&(height as u16).to_le_bytes());
+    out.push(32);
+    out.push(DESC_TOP | 8);
+
+    let pixel = |i: usize| -> [u8; 4] {
+        match data.get(i * 4..i * 4 + 4) {
+            Some(px) => [px[2], px[1], px[0], px[3]],
+            None => [0, 0, 0, 0],
+        }
+    };
+
+    if rle {
+        let mut i = 0;
+        while i < count {
+            // 统计从 i 开始的重复像素数
+            let mut run = 1;
+            while i + run < count && run < MAX_PACKET && pixel(i + run) == pixel(i) {
+                run += 1;
+            }
+            if run > 1 {
+                out.push(0x80 | (run - 1) as u8);
+                out.extend_from_slice(&pixel(i));
+                i += run;
+                continue;
+            }
+
+            // 原始数据包: 延伸到下一段重复之前
+            let mut raw = 1;
+            while i + raw < count
+                && raw < MAX_PACKET
+                && !(i + raw + 1 < count && pixel(i + raw) == pixel(i + raw + 1))
+            {
+                raw += 1;
+            }
+            out.push((raw - 1) as u8);
+            for j in i..i + raw {
+                out.extend_from_slice(&pixel(j));
+            }
+            i += raw;
+        }
+    } else {
+        for i in 0..count {
+            out.extend_from_slice(&pixel(i));
+        }
+    }
+
+    // TGA 2.0 文件尾: 扩展区偏移 + 开发者目录偏移 + 签名
+    out.extend_from_slice(&[0u8; 8]);
+    out.extend_from_slice(FOOTER_SIGNATURE);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(image_type: u8, width: u16, height: u16, depth: u8, descriptor: u8) -> Vec<u8> {
+        let mut out = vec![0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.push(depth);
+        out.push(descriptor);
+        out
+    }
+
+    #[test]
+    fn test_decode_24bit_bottom_left() {
+        let mut bytes = header(2, 1, 2, 24, 0);
+        bytes.extend_from_slice(&[255, 0, 0, 0, 0, 255]); // 底: 蓝, 顶: 红
+        let image = decode_tga(&bytes).unwrap();
+        assert_eq!(image.data, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_decode_rle_gray() {
+        let mut bytes = header(11, 4, 1, 8, DESC_TOP);
+        bytes.extend_from_slice(&[0x82, 50, 0x00, 200]);
+        let image = decode_tga(&bytes).unwrap();
+        let grays: Vec<u8> = image.data.chunks_exact(4).map(|px| px[0]).collect();
+        assert_eq!(grays, vec![50, 50, 50, 200]);
+    }
+
+    #[test]
+    fn test_decode_color_mapped_16bit_palette() {
+        let mut bytes = header(1, 2, 1, 8, DESC_TOP);
+        bytes[1] = 1;
+        bytes[5] = 2; // 2 项
+        bytes[7] = 16; // 16 位调色板项
+        bytes[17] |= 1; // 1 位 Alpha
+        bytes.extend_from_slice(&0x7C00u16.to_le_bytes()); // 红，Alpha 位为 0
+        bytes.extend_from_slice(&0x83E0u16.to_le_bytes()); // 绿，不透明
+        bytes.extend_from_slice(&[1, 0]);
+        let image = decode_tga(&bytes).unwrap();
+        assert_eq!(image.data, vec![0, 255, 0, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rle_encode_is_compact() {
+        let data = vec![7u8; 300 * 4];
+        let rle = encode_tga(&data, 300, 1, true);
+        let raw = encode_tga(&data, 300, 1, false);
+        assert!(rle.len() < raw.len() / 10);
+        assert_eq!(decode_tga(&rle).unwrap().data, data);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let bytes = header(2, 4, 4, 32, 0);
+        assert!(decode_tga(&bytes).is_none());
+        assert!(!looks_like_tga(&[0, 0, 5]));
+    }
+
+    #[test]
+    fn test_malformed_headers() {
+        // 带文件尾但位深为 0
+        let mut bytes = header(2, 2, 2, 0, 0);
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(FOOTER_SIGNATURE);
+        assert!(looks_like_tga(&bytes));
+        assert!(decode_tga(&bytes).is_none());
+
+        // 颜色表项只有 1~2 字节却按 32 位读取
+        for map_depth in [1, 8, 12] {
+            let mut bytes = header(1, 1, 1, 8, 0);
+            bytes[1] = 1;
+            bytes[5] = 1;
+            bytes[7] = map_depth;
+            bytes.extend_from_slice(&[0; 8]);
+            bytes.extend_from_slice(FOOTER_SIGNATURE);
+            assert!(decode_tga(&bytes).is_none());
+        }
+
+        // 不支持的图像类型与位深
+        let mut bytes = header(5, 1, 1, 32, 0);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(FOOTER_SIGNATURE);
+        assert!(decode_tga(&bytes).is_none());
+        let mut bytes = header(2, 1, 1, 7, 0);
+        bytes.extend_from_slice(&[0; 4]);
+        assert!(decode_tga(&bytes).is_none());
+    }
+}
@@ -1,9 +1,22 @@
 use wasm_bindgen::prelude::*;
 
 mod core;
+mod filter;
+mod image;
+mod json;
 mod math;
-
-pub use core::World;
+mod palette;
+mod raster;
+mod svg;
+mod text;
+mod tiled;
+mod xml;
+
+pub use core::{SamplingMethod, World};
+pub use filter::Filter;
+pub use math::Matrix3x3;
+pub use palette::Palette;
+pub use raster::{Gradient, Path, StrokeStyle};
 
 /// 像素缓冲区 - 存储 RGBA 数据
 /// 
@@ -76,6 +89,7 @@ impl PixelBuffer {
     }
 
     /// 填充矩形区域
+    #[allow(clippy::too_many_arguments)]
     pub fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, r: u8, g: u8, b: u8, a: u8) {
         for dy in 0..h {
             for dx in 0..w {
@@ -42,6 +42,19 @@ impl Matrix3x3 {
         }
     }
 
+    /// 从仿射变换系数创建矩阵
+    ///
+    /// 变换为 x' = a·x + b·y + tx, y' = c·x + d·y + ty。
+    pub fn affine(a: f32, b: f32, c: f32, d: f32, tx: f32, ty: f32) -> Self {
+        Self {
+            data: [
+                a,   b,   tx,
+                c,   d,   ty,
+                0.0, 0.0, 1.0,
+            ],
+        }
+    }
+
     /// 创建旋转矩阵 (绕原点旋转)
     ///
     /// # Arguments
@@ -194,6 +207,14 @@ mod tests {
         assert!(approx_eq(y, 1.0));
     }
 
+    #[test]
+    fn test_affine() {
+        let m = Matrix3x3::affine(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        let (x, y) = m.transform_point(1.0, 1.0);
+        assert!(approx_eq(x, 8.0));
+        assert!(approx_eq(y, 13.0));
+    }
+
     #[test]
     fn test_scale() {
         let m = Matrix3x3::scale(2.0, 3.0);
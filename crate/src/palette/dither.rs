@@ -0,0 +1,210 @@
+//! 抖动
+//!
+//! 有序抖动按像素位置从 Bayer 矩阵取阈值偏移颜色后再匹配调色板，结果稳定、适合动画；
+//! 误差扩散 (Floyd–Steinberg、Atkinson) 把每个像素的量化误差按权重分给尚未处理的邻居。
+//! Atkinson 只扩散 3/4 的误差，对比度更高，常用于单色与电子墨水屏。
+
+use super::Palette;
+
+/// 抖动方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DitherMode {
+    /// 不抖动 (直接取最接近的颜色)
+    #[default]
+    None,
+    /// 2x2 Bayer 有序抖动
+    Bayer2,
+    /// 4x4 Bayer 有序抖动
+    Bayer4,
+    /// 8x8 Bayer 有序抖动
+    Bayer8,
+    /// Floyd–Steinberg 误差扩散
+    FloydSteinberg,
+    /// Atkinson 误差扩散
+    Atkinson,
+}
+
+impl DitherMode {
+    /// 从 u8 值创建抖动方式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => DitherMode::None,
+            1 => DitherMode::Bayer2,
+            2 => DitherMode::Bayer4,
+            3 => DitherMode::Bayer8,
+            4 => DitherMode::FloydSteinberg,
+            5 => DitherMode::Atkinson,
+            _ => DitherMode::None,
+        }
+    }
+}
+
+/// Floyd–Steinberg 扩散核 (dx, dy, 权重)
+const FLOYD_STEINBERG: [(isize, usize, f32); 4] = [
+    (1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    (0, 1, 5.0 / 16.0),
+    (1, 1, 1.0 / 16.0),
+];
+
+/// Atkinson 扩散核
+const ATKINSON: [(isize, usize, f32); 6] = [
+    (1, 0, 1.0 / 8.0),
+    (2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    (0, 1, 1.0 / 8.0),
+    (1, 1, 1.0 / 8.0),
+    (0, 2, 1.0 / 8.0),
+];
+
+/// 生成 n×n Bayer 矩阵 (n 为 2 的幂)，元素为 0..n²
+fn bayer(n: usize) -> Vec<u32> {
+    let mut m = vec![0u32];
+    let mut size = 1;
+    while size < n {
+        let next = size * 2;
+        let mut grown = vec![0u32; next * next];
+        for y in 0..size {
+            for x in 0..size {
+                let v = m[y * size + x] * 4;
+                grown[y * next + x] = v;
+                grown[y * next + x + size] = v + 2;
+                grown[(y + size) * next + x] = v + 3;
+                grown[(y + size) * next + x + size] = v + 1;
+            }
+        }
+        m = grown;
+        size = next;
+    }
+    m
+}
+
+/// 将像素量化到调色板
+pub(super) fn quantize(
+    palette: &Palette,
+    data: &mut [u8],
+    width: usize,
+    height: usize,
+    mode: DitherMode,
+) {
+    match mode {
+        DitherMode::None => ordered(palette, data, width, 1),
+        DitherMode::Bayer2 => ordered(palette, data, width, 2),
+        DitherMode::Bayer4 => ordered(palette, data, width, 4),
+        DitherMode::Bayer8 => ordered(palette, data, width, 8),
+        DitherMode::FloydSteinberg => diffuse(palette, data, width, height, &FLOYD_STEINBERG),
+        DitherMode::Atkinson => diffuse(palette, data, width, height, &ATKINSON),
+    }
+}
+
+/// 写入调色板颜色的 RGB (保留 Alpha)
+fn write(palette: &Palette, px: &mut [u8], rgb: [f32; 3]) -> [f32; 3] {
+    let Some(i) = palette.nearest(rgb) else {
+        return rgb;
+    };
+    let c = palette.entries()[i];
+    px[..3].copy_from_slice(&c[..3]);
+    [c[0] as f32, c[1] as f32, c[2] as f32]
+}
+
+/// 有序抖动 (n = 1 时不抖动)
+fn ordered(palette: &Palette, data: &mut [u8], width: usize, n: usize) {
+    let matrix = bayer(n);
+    // 阈值幅度约为调色板在每个通道上的平均间距
+    let spread = 255.0 / ((palette.entries().len() as f32).cbrt() - 1.0).max(1.0);
+    let levels = (n * n) as f32;
+    for (i, px) in data.chunks_exact_mut(4).enumerate() {
+        if px[3] == 0 {
+            continue;
+        }
+        let (x, y) = (i % width, i / width);
+        let t = if n > 1 {
+            ((matrix[(y % n) * n + x % n] as f32 + 0.5) / levels - 0.5) * spread
+        } else {
+            0.0
+        };
+        let rgb = [px[0] as f32 + t, px[1] as f32 + t, px[2] as f32 + t];
+        write(palette, px, rgb);
+    }
+}
+
+/// 误差扩散
+fn diffuse(
+    palette: &Palette,
+    data: &mut [u8],
+    width: usize,
+    height: usize,
+    kernel: &[(isize, usize, f32)],
+) {
+    let mut error = vec![[0.0f32; 3]; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let px = &mut data[i * 4..i * 4 + 4];
+            if px[3] == 0 {
+                continue;
+            }
+            let rgb: [f32; 3] =
+                std::array::from_fn(|k| (px[k] as f32 + error[i][k]).clamp(0.0, 255.0));
+            let chosen = write(palette, px, rgb);
+            for &(dx, dy, weight) in kernel {
+                let (nx, ny) = (x as isize + dx, y + dy);
+                if nx < 0 || nx as usize >= width || ny >= height {
+                    continue;
+                }
+                let e = &mut error[ny * width + nx as usize];
+                for k in 0..3 {
+                    e[k] += (rgb[k] - chosen[k]) * weight;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn black_and_white() -> Palette {
+        Palette::from_colors(&[0, 0, 0, 255, 255, 255, 255, 255])
+    }
+
+    /// 量化一块纯色灰，返回白色像素所占比例
+    fn white_ratio(mode: DitherMode, gray: u8) -> f32 {
+        let (w, h) = (16, 16);
+        let mut data = [gray, gray, gray, 255].repeat(w * h);
+        quantize(&black_and_white(), &mut data, w, h, mode);
+        data.chunks_exact(4).filter(|px| px[0] == 255).count() as f32 / (w * h) as f32
+    }
+
+    #[test]
+    fn test_mode_conversion() {
+        assert_eq!(DitherMode::from_u8(5), DitherMode::Atkinson);
+        assert_eq!(DitherMode::from_u8(42), DitherMode::None);
+    }
+
+    #[test]
+    fn test_bayer_matrix() {
+        assert_eq!(bayer(2), vec![0, 2, 3, 1]);
+        let mut m = bayer(4);
+        m.sort_unstable();
+        assert_eq!(m, (0..16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_dithering_preserves_average() {
+        assert_eq!(white_ratio(DitherMode::None, 100), 0.0);
+        for mode in [
+            DitherMode::Bayer2,
+            DitherMode::Bayer4,
+            DitherMode::Bayer8,
+            DitherMode::FloydSteinberg,
+        ] {
+            let ratio = white_ratio(mode, 64);
+            assert!((ratio - 0.25).abs() < 0.05, "{mode:?}: {ratio}");
+        }
+        // Atkinson 丢弃部分误差，暗部更暗但仍有白点
+        let ratio = white_ratio(DitherMode::Atkinson, 64);
+        assert!(ratio > 0.0 && ratio < 0.25);
+    }
+}
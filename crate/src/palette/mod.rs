@@ -0,0 +1,141 @@
+//! 调色板模块
+//!
+//! 固定调色板、调色板生成 (中位切分 / 八叉树) 与带抖动的颜色量化。
+//! 量化只匹配 RGB，像素原有的 Alpha 保持不变，全透明像素不参与量化与误差扩散。
+
+mod dither;
+mod quantize;
+
+pub use dither::DitherMode;
+
+use wasm_bindgen::prelude::*;
+
+/// 调色板最大颜色数
+pub const MAX_COLORS: usize = 256;
+
+/// 调色板
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Palette {
+    /// RGBA 颜色
+    colors: Vec<[u8; 4]>,
+}
+
+impl Palette {
+    /// 获取全部颜色
+    pub(crate) fn entries(&self) -> &[[u8; 4]] {
+        &self.colors
+    }
+
+    /// 查找与 RGB 最接近的颜色序号 (欧氏距离)，调色板为空时返回 None
+    pub(crate) fn nearest(&self, rgb: [f32; 3]) -> Option<usize> {
+        let mut best = None;
+        let mut best_dist = f32::MAX;
+        for (i, c) in self.colors.iter().enumerate() {
+            let d: f32 = (0..3)
+                .map(|k| {
+                    let diff = rgb[k] - c[k] as f32;
+                    diff * diff
+                })
+                .sum();
+            if d < best_dist {
+                best_dist = d;
+                best = Some(i);
+            }
+        }
+        best
+    }
+
+    /// 将 RGBA8 像素量化到调色板 (原地)
+    pub(crate) fn quantize(&self, data: &mut [u8], width: u32, height: u32, dither: DitherMode) {
+        if self.colors.is_empty() || data.len() != (width * height * 4) as usize {
+            return;
+        }
+        dither::quantize(self, data, width as usize, height as usize, dither);
+    }
+}
+
+#[wasm_bindgen]
+impl Palette {
+    /// 创建空调色板
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从 RGBA 数据创建调色板 (每 4 字节一个颜色，超过 256 个时截断)
+    pub fn from_colors(data: &[u8]) -> Self {
+        Self {
+            colors: data
+                .chunks_exact(4)
+                .take(MAX_COLORS)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect(),
+        }
+    }
+
+    /// 从 RGBA 像素生成最多 `count` 个颜色的调色板
+    ///
+    /// `method`: 0 = 中位切分 (median-cut), 1 = 八叉树 (octree)。全透明像素被忽略。
+    pub fn from_pixels(data: &[u8], count: u32, method: u8) -> Self {
+        let count = (count as usize).clamp(1, MAX_COLORS);
+        let colors = match method {
+            1 => quantize::octree(data, count),
+            _ => quantize::median_cut(data, count),
+        };
+        Self { colors }
+    }
+
+    /// 添加颜色 (已满 256 个时忽略)
+    pub fn add_color(&mut self, r: u8, g: u8, b: u8, a: u8) {
+        if self.colors.len() < MAX_COLORS {
+            self.colors.push([r, g, b, a]);
+        }
+    }
+
+    /// 设置指定序号的颜色
+    pub fn set_color(&mut self, index: u32, r: u8, g: u8, b: u8, a: u8) {
+        if let Some(c) = self.colors.get_mut(index as usize) {
+            *c = [r, g, b, a];
+        }
+    }
+
+    /// 获取颜色数量
+    pub fn len(&self) -> u32 {
+        self.colors.len() as u32
+    }
+
+    /// 检查调色板是否为空
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// 获取全部颜色 (RGBA，每 4 字节一个)
+    pub fn colors(&self) -> Vec<u8> {
+        self.colors.concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_basics() {
+        let mut palette = Palette::from_colors(&[0, 0, 0, 255, 255, 255, 255, 255, 9]);
+        assert_eq!(palette.len(), 2);
+        palette.add_color(255, 0, 0, 255);
+        palette.set_color(0, 0, 0, 64, 255);
+        assert_eq!(palette.colors()[..4], [0, 0, 64, 255]);
+        assert_eq!(palette.nearest([200.0, 30.0, 30.0]), Some(2));
+        assert_eq!(Palette::new().nearest([0.0; 3]), None);
+    }
+
+    #[test]
+    fn test_quantize_keeps_alpha() {
+        let palette = Palette::from_colors(&[0, 0, 0, 255, 255, 255, 255, 255]);
+        let mut data = vec![200, 210, 190, 128, 30, 20, 40, 255, 90, 90, 90, 0];
+        palette.quantize(&mut data, 3, 1, DitherMode::None);
+        assert_eq!(data, vec![255, 255, 255, 128, 0, 0, 0, 255, 90, 90, 90, 0]);
+    }
+}
@@ -0,0 +1,215 @@
+//! 调色板生成
+//!
+//! 中位切分: 反复选择颜色范围最大的盒子，沿范围最大的通道在像素数的中位处切分，
+//! 每个盒子取像素平均色。八叉树: 按 RGB 各位逐层插入，叶子过多时从最深层合并节点。
+//! 两者都先统计不同颜色的出现次数，避免对每个像素重复处理。
+
+use std::collections::HashMap;
+
+/// 统计不透明 (Alpha > 0) 像素中每种 RGB 的出现次数
+fn histogram(data: &[u8]) -> Vec<([u8; 3], u64)> {
+    let mut counts: HashMap<[u8; 3], u64> = HashMap::new();
+    for px in data.chunks_exact(4).filter(|px| px[3] > 0) {
+        *counts.entry([px[0], px[1], px[2]]).or_default() += 1;
+    }
+    let mut colors: Vec<_> = counts.into_iter().collect();
+    // HashMap 遍历顺序不固定，排序使结果可复现
+    colors.sort_unstable();
+    colors
+}
+
+/// 按权重求平均色
+fn average(colors: &[([u8; 3], u64)]) -> [u8; 4] {
+    let mut sum = [0u64; 3];
+    let mut total = 0;
+    for (c, n) in colors {
+        for k in 0..3 {
+            sum[k] += c[k] as u64 * n;
+        }
+        total += n;
+    }
+    let total = total.max(1);
+    [
+        ((sum[0] + total / 2) / total) as u8,
+        ((sum[1] + total / 2) / total) as u8,
+        ((sum[2] + total / 2) / total) as u8,
+        255,
+    ]
+}
+
+/// 盒子内范围最大的通道及其范围
+fn widest_channel(colors: &[([u8; 3], u64)]) -> (usize, u8) {
+    (0..3)
+        .map(|k| {
+            let min = colors.iter().map(|(c, _)| c[k]).min().unwrap_or(0);
+            let max = colors.iter().map(|(c, _)| c[k]).max().unwrap_or(0);
+            (k, max - min)
+        })
+        .max_by_key(|&(k, range)| (range, std::cmp::Reverse(k)))
+        .unwrap_or((0, 0))
+}
+
+/// 中位切分
+pub(super) fn median_cut(data: &[u8], count: usize) -> Vec<[u8; 4]> {
+    let colors = histogram(data);
+    if colors.is_empty() {
+        return Vec::new();
+    }
+    let mut boxes = vec![colors];
+    while boxes.len() < count {
+        let Some((i, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (i, widest_channel(b)))
+            .max_by_key(|&(i, (_, range))| (range, std::cmp::Reverse(i)))
+            .map(|(i, (channel, _))| (i, channel))
+        else {
+            break;
+        };
+        let mut b = boxes.swap_remove(i);
+        b.sort_unstable_by_key(|(c, _)| c[channel]);
+        let half = b.iter().map(|(_, n)| n).sum::<u64>() / 2;
+        let mut acc = 0;
+        let mut split = 1;
+        for (j, (_, n)) in b.iter().enumerate() {
+            acc += n;
+            if acc >= half {
+                split = (j + 1).clamp(1, b.len() - 1);
+                break;
+            }
+        }
+        let rest = b.split_off(split);
+        boxes.push(b);
+        boxes.push(rest);
+    }
+    boxes.iter().map(|b| average(b)).collect()
+}
+
+/// 八叉树节点
+#[derive(Default)]
+struct Node {
+    children: [Option<usize>; 8],
+    sum: [u64; 3],
+    count: u64,
+    leaf: bool,
+}
+
+/// 八叉树深度
+const DEPTH: usize = 8;
+
+/// 八叉树量化
+pub(super) fn octree(data: &[u8], count: usize) -> Vec<[u8; 4]> {
+    let mut nodes = vec![Node::default()];
+    // 每层可合并的内部节点
+    let mut levels: Vec<Vec<usize>> = vec![Vec::new(); DEPTH];
+    let mut leaves = 0;
+    for (c, n) in histogram(data) {
+        let mut node = 0;
+        for level in 0..DEPTH {
+            let shift = 7 - level;
+            let child = (((c[0] >> shift) & 1) << 2
+                | ((c[1] >> shift) & 1) << 1
+                | ((c[2] >> shift) & 1)) as usize;
+            node = match nodes[node].children[child] {
+                Some(next) => next,
+                None => {
+                    let next = nodes.len();
+                    nodes.push(Node {
+                        leaf: level == DEPTH - 1,
+                        ..Default::default()
+                    });
+                    nodes[node].children[child] = Some(next);
+                    if level == DEPTH - 1 {
+                        leaves += 1;
+                    } else {
+                        levels[level + 1].push(next);
+                    }
+                    next
+                }
+            };
+        }
+        let leaf = &mut nodes[node];
+        for (sum, v) in leaf.sum.iter_mut().zip(c) {
+            *sum += v as u64 * n;
+        }
+        leaf.count += n;
+    }
+
+    // 从最深层开始合并，直到叶子数不超过目标 (必要时合并到根节点)
+    levels[0].push(0);
+    let mut level = DEPTH - 1;
+    while leaves > count {
+        let Some(idx) = levels[level].pop() else {
+            if level == 0 {
+                break;
+            }
+            level -= 1;
+            continue;
+        };
+        let children = std::mem::take(&mut nodes[idx].children);
+        let mut merged = 0;
+        for c in children.into_iter().flatten() {
+            let (sum, n) = (nodes[c].sum, nodes[c].count);
+            let node = &mut nodes[idx];
+            for (total, v) in node.sum.iter_mut().zip(sum) {
+                *total += v;
+            }
+            node.count += n;
+            nodes[c].count = 0;
+            merged += 1;
+        }
+        nodes[idx].leaf = true;
+        leaves = leaves + 1 - merged;
+    }
+
+    nodes
+        .iter()
+        .filter(|n| n.leaf && n.count > 0)
+        .map(|n| {
+            let half = n.count / 2;
+            [
+                ((n.sum[0] + half) / n.count) as u8,
+                ((n.sum[1] + half) / n.count) as u8,
+                ((n.sum[2] + half) / n.count) as u8,
+                255,
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 红、绿、蓝三种颜色各若干像素，附带透明像素
+    fn pixels() -> Vec<u8> {
+        let mut data = Vec::new();
+        for _ in 0..4 {
+            data.extend_from_slice(&[250, 0, 0, 255, 0, 240, 10, 255, 0, 0, 255, 255]);
+            data.extend_from_slice(&[255, 10, 0, 255, 12, 34, 56, 0]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_median_cut() {
+        let palette = median_cut(&pixels(), 3);
+        assert_eq!(palette.len(), 3);
+        assert!(palette.contains(&[0, 240, 10, 255]));
+        assert!(palette.contains(&[0, 0, 255, 255]));
+        assert!(palette.contains(&[253, 5, 0, 255]));
+        assert_eq!(median_cut(&pixels(), 16).len(), 4);
+        assert!(median_cut(&[1, 2, 3, 0], 4).is_empty());
+    }
+
+    #[test]
+    fn test_octree() {
+        let palette = octree(&pixels(), 3);
+        assert!(palette.len() <= 3 && !palette.is_empty());
+        assert_eq!(octree(&pixels(), 16).len(), 4);
+        assert_eq!(octree(&pixels(), 1).len(), 1);
+        let gray = octree(&[100, 100, 100, 255], 4);
+        assert_eq!(gray, vec![[100, 100, 100, 255]]);
+    }
+}
@@ -0,0 +1,578 @@
+//! 像素缓冲区矢量绘制
+//!
+//! 为 `PixelBuffer` 提供抗锯齿的直线、折线、多边形、圆 / 椭圆、圆弧、圆角矩形
+//! 以及任意路径的填充与描边，填充可使用纯色或渐变。
+//! 覆盖率乘以颜色 Alpha 后按源覆盖 (source-over) 混合到缓冲区中。
+
+use wasm_bindgen::prelude::*;
+
+use super::gradient::Gradient;
+use super::path::Path;
+use super::rasterizer::{FillRule, Mask, Point, Rasterizer};
+use super::shapes::{ellipse, orient, rounded_rect};
+use super::stroke::{stroke_arc, stroke_polyline, LineCap, LineJoin, StrokeStyle};
+use crate::image::blend_over;
+use crate::PixelBuffer;
+
+/// 将覆盖率遮罩以纯色混合到 RGBA 像素数据中
+pub fn blend_mask(data: &mut [u8], width: u32, mask: &Mask, color: [u8; 4]) {
+    let mw = mask.width as usize;
+    if mw == 0 {
+        return;
+    }
+    for (row, coverage) in mask.data.chunks_exact(mw).enumerate() {
+        let start = ((mask.y as usize + row) * width as usize + mask.x as usize) * 4;
+        let Some(line) = data.get_mut(start..start + mw * 4) else {
+            return;
+        };
+        for (px, &c) in line.chunks_exact_mut(4).zip(coverage) {
+            let alpha = (c * color[3] as f32 + 0.5) as u8;
+            if alpha > 0 {
+                blend_over(px, [color[0], color[1], color[2], alpha]);
+            }
+        }
+    }
+}
+
+/// 将覆盖率遮罩以渐变混合到 RGBA 像素数据中 (渐变在像素中心取色)
+pub fn blend_mask_gradient(data: &mut [u8], width: u32, mask: &Mask, gradient: &Gradient) {
+    let mw = mask.width as usize;
+    if mw == 0 {
+        return;
+    }
+    let shader = gradient.shader();
+    for (row, coverage) in mask.data.chunks_exact(mw).enumerate() {
+        let y = mask.y as usize + row;
+        let start = (y * width as usize + mask.x as usize) * 4;
+        let Some(line) = data.get_mut(start..start + mw * 4) else {
+            return;
+        };
+        for (col, (px, &c)) in line.chunks_exact_mut(4).zip(coverage).enumerate() {
+            if c <= 0.0 {
+                continue;
+            }
+            let x = (mask.x as usize + col) as f32 + 0.5;
+            let color = shader.color_at(x, y as f32 + 0.5);
+            let alpha = (c * color[3] as f32 + 0.5) as u8;
+            if alpha > 0 {
+                blend_over(px, [color[0], color[1], color[2], alpha]);
+            }
+        }
+    }
+}
+
+/// 按填充规则以纯色填充路径到 RGBA 像素数据中
+pub fn fill_path_into(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    path: &Path,
+    rule: FillRule,
+    color: [u8; 4],
+) {
+    let mut raster = Rasterizer::new(width, height);
+    for subpath in path.flatten() {
+        raster.contour(&subpath.points);
+    }
+    blend_mask(data, width, &raster.fill_mask(rule), color);
+}
+
+/// 按描边样式以纯色描边路径到 RGBA 像素数据中
+pub fn stroke_path_into(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    path: &Path,
+    style: &StrokeStyle,
+    color: [u8; 4],
+) {
+    let mut raster = Rasterizer::new(width, height);
+    for subpath in path.flatten() {
+        stroke_polyline(&mut raster, &subpath.points, subpath.closed, style);
+    }
+    blend_mask(data, width, &raster.fill_mask(FillRule::NonZero), color);
+}
+
+/// 将 [x0, y0, x1, y1, ...] 形式的坐标数组转换为点列
+fn to_points(coords: &[f32]) -> Vec<Point> {
+    coords
+        .chunks_exact(2)
+        .map(|c| Point::new(c[0], c[1]))
+        .collect()
+}
+
+/// 描边闭合轮廓: 外扩轮廓与反向内缩轮廓构成环形
+fn ring(raster: &mut Rasterizer, outer: Vec<Point>, inner: Option<Vec<Point>>) {
+    raster.contour(&orient(outer, true));
+    if let Some(inner) = inner {
+        raster.contour(&orient(inner, false));
+    }
+}
+
+impl PixelBuffer {
+    /// 创建与缓冲区等大的光栅化器
+    fn rasterizer(&self) -> Rasterizer {
+        Rasterizer::new(self.width, self.height)
+    }
+
+    /// 以纯色填充光栅化结果 (非零环绕规则)
+    fn paint(&mut self, raster: &Rasterizer, color: [u8; 4]) {
+        let mask = raster.fill_mask(FillRule::NonZero);
+        blend_mask(&mut self.data, self.width, &mask, color);
+    }
+
+    /// 以渐变填充光栅化结果
+    fn paint_gradient(&mut self, raster: &Rasterizer, rule: FillRule, gradient: &Gradient) {
+        let mask = raster.fill_mask(rule);
+        blend_mask_gradient(&mut self.data, self.width, &mask, gradient);
+    }
+}
+
+#[wasm_bindgen]
+impl PixelBuffer {
+    /// 绘制抗锯齿直线
+    ///
+    /// `cap`: 0 = 平头, 1 = 圆头, 2 = 方头。
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_line(
+        &mut self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        width: f32,
+        cap: u8,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        let mut raster = self.rasterizer();
+        let points = [Point::new(x0, y0), Point::new(x1, y1)];
+        let style = StrokeStyle::solid(width, LineCap::from_u8(cap), LineJoin::Round);
+        stroke_polyline(&mut raster, &points, false, &style);
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 绘制折线 (连接处为圆角)
+    ///
+    /// `points` 为 [x0, y0, x1, y1, ...]，`cap` 同 `stroke_line`。
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_polyline(
+        &mut self,
+        points: &[f32],
+        width: f32,
+        cap: u8,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        let mut raster = self.rasterizer();
+        let style = StrokeStyle::solid(width, LineCap::from_u8(cap), LineJoin::Round);
+        stroke_polyline(&mut raster, &to_points(points), false, &style);
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 填充多边形 (非零环绕规则)
+    pub fn fill_polygon(&mut self, points: &[f32], r: u8, g: u8, b: u8, a: u8) {
+        let mut raster = self.rasterizer();
+        raster.contour(&to_points(points));
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 描边多边形 (闭合，连接处为圆角)
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_polygon(&mut self, points: &[f32], width: f32, r: u8, g: u8, b: u8, a: u8) {
+        let mut raster = self.rasterizer();
+        let style = StrokeStyle::solid(width, LineCap::Butt, LineJoin::Round);
+        stroke_polyline(&mut raster, &to_points(points), true, &style);
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 填充圆角矩形 (半径为 0 时为普通矩形)
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+        let mut raster = self.rasterizer();
+        raster.contour(&rounded_rect(x, y, w, h, radius));
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 描边圆角矩形 (线宽以轮廓为中心)
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        width: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        let hw = width / 2.0;
+        if hw <= 0.0 || w < 0.0 || h < 0.0 {
+            return;
+        }
+        let mut raster = self.rasterizer();
+        let radius = radius.clamp(0.0, w.min(h) / 2.0);
+        let outer = rounded_rect(x - hw, y - hw, w + width, h + width, radius + hw);
+        let inner = (w > width && h > width)
+            .then(|| rounded_rect(x + hw, y + hw, w - width, h - width, radius - hw));
+        ring(&mut raster, outer, inner);
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 填充椭圆
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, r: u8, g: u8, b: u8, a: u8) {
+        if rx <= 0.0 || ry <= 0.0 {
+            return;
+        }
+        let mut raster = self.rasterizer();
+        raster.contour(&ellipse(Point::new(cx, cy), rx, ry));
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 描边椭圆 (线宽以轮廓为中心)
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_ellipse(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        rx: f32,
+        ry: f32,
+        width: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        let hw = width / 2.0;
+        if hw <= 0.0 || rx < 0.0 || ry < 0.0 {
+            return;
+        }
+        let c = Point::new(cx, cy);
+        let mut raster = self.rasterizer();
+        let inner = (rx > hw && ry > hw).then(|| ellipse(c, rx - hw, ry - hw));
+        ring(&mut raster, ellipse(c, rx + hw, ry + hw), inner);
+        self.paint(&raster, [r, g, b, a]);
+    }
+
+    /// 填充圆
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, r: u8, g: u8, b: u8, a: u8) {
+        self.fill_ellipse(cx, cy, radius, radius, r, g, b, a);
+    }
+
+    /// 描边圆
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_circle(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        width: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        self.stroke_ellipse(cx, cy, radius, radius, width, r, g, b, a);
+    }
+
+    /// 描边圆弧
+    ///
+    /// 角度为弧度，0 指向 +X，正方向为顺时针 (屏幕坐标)；`end < start` 时逆时针绘制。
+    /// `cap` 同 `stroke_line`。
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_arc(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        width: f32,
+        cap: u8,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) {
+        let mut raster = self.rasterizer();
+        stroke_arc(
+            &mut raster,
+            Point::new(cx, cy),
+            radius,
+            start_angle,
+            end_angle,
+            width,
+            LineCap::from_u8(cap),
+        );
+        self.paint(&raster, [r, g, b, a]);
+    }
+}
+
+#[wasm_bindgen]
+impl PixelBuffer {
+    /// 填充路径 (未闭合的子路径自动闭合)
+    ///
+    /// `fill_rule`: 0 = 非零环绕, 1 = 奇偶。
+    pub fn fill_path(&mut self, path: &Path, fill_rule: u8, r: u8, g: u8, b: u8, a: u8) {
+        let rule = FillRule::from_u8(fill_rule);
+        fill_path_into(
+            &mut self.data,
+            self.width,
+            self.height,
+            path,
+            rule,
+            [r, g, b, a],
+        );
+    }
+
+    /// 按描边样式描边路径
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, r: u8, g: u8, b: u8, a: u8) {
+        stroke_path_into(
+            &mut self.data,
+            self.width,
+            self.height,
+            path,
+            style,
+            [r, g, b, a],
+        );
+    }
+}
+
+#[wasm_bindgen]
+impl PixelBuffer {
+    /// 以渐变覆盖整个缓冲区 (不混合，与 `clear` 相同)
+    pub fn fill_gradient(&mut self, gradient: &Gradient) {
+        gradient.fill_into(&mut self.data, self.width);
+    }
+
+    /// 以渐变填充多边形 (非零环绕规则)
+    pub fn fill_polygon_gradient(&mut self, points: &[f32], gradient: &Gradient) {
+        let mut raster = self.rasterizer();
+        raster.contour(&to_points(points));
+        self.paint_gradient(&raster, FillRule::NonZero, gradient);
+    }
+
+    /// 以渐变填充圆角矩形 (半径为 0 时为普通矩形)
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_rounded_rect_gradient(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        gradient: &Gradient,
+    ) {
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+        let mut raster = self.rasterizer();
+        raster.contour(&rounded_rect(x, y, w, h, radius));
+        self.paint_gradient(&raster, FillRule::NonZero, gradient);
+    }
+
+    /// 以渐变填充椭圆
+    pub fn fill_ellipse_gradient(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        rx: f32,
+        ry: f32,
+        gradient: &Gradient,
+    ) {
+        if rx <= 0.0 || ry <= 0.0 {
+            return;
+        }
+        let mut raster = self.rasterizer();
+        raster.contour(&ellipse(Point::new(cx, cy), rx, ry));
+        self.paint_gradient(&raster, FillRule::NonZero, gradient);
+    }
+
+    /// 以渐变填充圆
+    pub fn fill_circle_gradient(&mut self, cx: f32, cy: f32, radius: f32, gradient: &Gradient) {
+        self.fill_ellipse_gradient(cx, cy, radius, radius, gradient);
+    }
+
+    /// 以渐变填充路径 (`fill_rule` 同 `fill_path`)
+    pub fn fill_path_gradient(&mut self, path: &Path, fill_rule: u8, gradient: &Gradient) {
+        let mut raster = self.rasterizer();
+        for subpath in path.flatten() {
+            raster.contour(&subpath.points);
+        }
+        self.paint_gradient(&raster, FillRule::from_u8(fill_rule), gradient);
+    }
+
+    /// 以渐变描边路径
+    pub fn stroke_path_gradient(&mut self, path: &Path, style: &StrokeStyle, gradient: &Gradient) {
+        let mut raster = self.rasterizer();
+        for subpath in path.flatten() {
+            stroke_polyline(&mut raster, &subpath.points, subpath.closed, style);
+        }
+        self.paint_gradient(&raster, FillRule::NonZero, gradient);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(buf: &PixelBuffer, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * buf.width + x) * 4) as usize;
+        buf.data[i..i + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_fill_circle_blends_with_alpha() {
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.clear(0, 0, 255, 255);
+        buf.fill_circle(10.0, 10.0, 5.0, 255, 0, 0, 128);
+        let center = pixel(&buf, 10, 10);
+        assert_eq!(center[3], 255);
+        assert!(center[0] > 120 && center[0] < 136);
+        assert_eq!(pixel(&buf, 1, 1), [0, 0, 255, 255]);
+
+        // 边缘像素为部分覆盖
+        let edge = pixel(&buf, 14, 10);
+        assert!(edge[0] > 0 && edge[0] < center[0]);
+    }
+
+    #[test]
+    fn test_stroke_line_aa() {
+        let mut buf = PixelBuffer::new(20, 10);
+        buf.stroke_line(2.0, 5.0, 18.0, 5.0, 1.0, 0, 255, 255, 255, 255);
+        // 线宽 1、中心在像素边界 y=5: 上下两行各半覆盖
+        let upper = pixel(&buf, 10, 4)[3];
+        let lower = pixel(&buf, 10, 5)[3];
+        assert!((upper as i32 - 128).abs() <= 1);
+        assert!((lower as i32 - 128).abs() <= 1);
+        assert_eq!(pixel(&buf, 10, 2)[3], 0);
+    }
+
+    #[test]
+    fn test_rounded_rect_fill_and_stroke() {
+        let mut buf = PixelBuffer::new(30, 30);
+        buf.fill_rounded_rect(5.0, 5.0, 20.0, 20.0, 6.0, 0, 255, 0, 255);
+        assert_eq!(pixel(&buf, 15, 15), [0, 255, 0, 255]);
+        assert_eq!(pixel(&buf, 5, 5)[3], 0);
+
+        let mut buf = PixelBuffer::new(30, 30);
+        buf.stroke_rounded_rect(5.0, 5.0, 20.0, 20.0, 4.0, 2.0, 0, 255, 0, 255);
+        assert_eq!(pixel(&buf, 15, 15)[3], 0);
+        assert_eq!(pixel(&buf, 15, 5)[3], 255);
+    }
+
+    #[test]
+    fn test_polygon_and_arc() {
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.fill_polygon(&[2.0, 2.0, 18.0, 2.0, 2.0, 18.0], 255, 0, 0, 255);
+        assert_eq!(pixel(&buf, 4, 4)[3], 255);
+        assert_eq!(pixel(&buf, 16, 16)[3], 0);
+
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.stroke_arc(
+            10.0,
+            10.0,
+            6.0,
+            0.0,
+            std::f32::consts::PI,
+            4.0,
+            0,
+            255,
+            0,
+            0,
+            255,
+        );
+        assert_eq!(pixel(&buf, 10, 15)[3], 255);
+        assert_eq!(pixel(&buf, 10, 4)[3], 0);
+    }
+
+    #[test]
+    fn test_fill_path_rules() {
+        // 两个同向嵌套方框
+        let mut path = Path::new();
+        for (x0, x1) in [(2.0, 18.0), (6.0, 14.0)] {
+            path.move_to(x0, x0);
+            path.line_to(x1, x0);
+            path.line_to(x1, x1);
+            path.line_to(x0, x1);
+            path.close();
+        }
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.fill_path(&path, 0, 255, 0, 0, 255);
+        assert_eq!(pixel(&buf, 10, 10)[3], 255);
+
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.fill_path(&path, 1, 255, 0, 0, 255);
+        assert_eq!(pixel(&buf, 10, 10)[3], 0);
+        assert_eq!(pixel(&buf, 3, 10)[3], 255);
+    }
+
+    #[test]
+    fn test_stroke_path_curve() {
+        let mut path = Path::new();
+        path.move_to(2.0, 18.0);
+        path.quad_to(10.0, -6.0, 18.0, 18.0);
+        let mut style = StrokeStyle::new(2.0);
+        style.set_line_cap(1);
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.stroke_path(&path, &style, 0, 0, 255, 255);
+        // 曲线顶点位于 (10, 6)
+        assert_eq!(pixel(&buf, 10, 6)[3], 255);
+        assert_eq!(pixel(&buf, 10, 12)[3], 0);
+        assert!(pixel(&buf, 2, 18)[3] > 0);
+    }
+
+    #[test]
+    fn test_stroke_ellipse_ring() {
+        let mut buf = PixelBuffer::new(30, 20);
+        buf.stroke_ellipse(15.0, 10.0, 10.0, 6.0, 4.0, 255, 255, 255, 255);
+        assert_eq!(pixel(&buf, 15, 10)[3], 0);
+        assert_eq!(pixel(&buf, 24, 10)[3], 255);
+        buf.stroke_polygon(&[1.0, 1.0, 5.0, 1.0, 5.0, 5.0], 1.0, 255, 0, 0, 255);
+        buf.stroke_polyline(&[1.0, 18.0, 28.0, 18.0], 2.0, 1, 255, 0, 0, 255);
+        assert_eq!(pixel(&buf, 14, 18)[0], 255);
+    }
+
+    #[test]
+    fn test_gradient_fills() {
+        let mut g = Gradient::linear(0.0, 0.0, 20.0, 0.0);
+        g.add_color_stop(0.0, 255, 0, 0, 255);
+        g.add_color_stop(1.0, 0, 0, 255, 255);
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.fill_gradient(&g);
+        assert_eq!(pixel(&buf, 0, 0)[0], 249);
+        assert_eq!(pixel(&buf, 19, 19)[2], 249);
+
+        let mut buf = PixelBuffer::new(20, 20);
+        buf.fill_circle_gradient(10.0, 10.0, 6.0, &g);
+        assert_eq!(pixel(&buf, 10, 0)[3], 0);
+        let c = pixel(&buf, 5, 10);
+        assert!(c[0] > c[2] && c[3] == 255);
+        let c = pixel(&buf, 14, 10);
+        assert!(c[2] > c[0] && c[3] == 255);
+    }
+}
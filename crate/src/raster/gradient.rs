@@ -0,0 +1,336 @@
+//! 渐变
+//!
+//! 线性、径向与锥形渐变，支持任意数量的色标与 pad / repeat / reflect 扩展方式。
+//! 色标之间在预乘 Alpha 空间插值 (与 CSS 一致，过渡到透明时不会发灰)。
+//! 着色时先生成 256 级颜色查找表，再按像素中心计算渐变参数 t 查表。
+
+use std::f32::consts::TAU;
+
+use wasm_bindgen::prelude::*;
+
+/// 查找表级数
+const LUT_SIZE: usize = 256;
+
+/// 渐变范围之外的扩展方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpreadMode {
+    /// 延伸端点颜色
+    #[default]
+    Pad,
+    /// 重复
+    Repeat,
+    /// 镜像重复
+    Reflect,
+}
+
+impl SpreadMode {
+    /// 从 u8 值创建扩展方式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => SpreadMode::Pad,
+            1 => SpreadMode::Repeat,
+            2 => SpreadMode::Reflect,
+            _ => SpreadMode::Pad,
+        }
+    }
+
+    /// 将渐变参数映射到 [0, 1]
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            SpreadMode::Pad => t.clamp(0.0, 1.0),
+            SpreadMode::Repeat => t.rem_euclid(1.0),
+            SpreadMode::Reflect => {
+                let m = t.rem_euclid(2.0);
+                if m > 1.0 {
+                    2.0 - m
+                } else {
+                    m
+                }
+            }
+        }
+    }
+}
+
+/// 渐变几何
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GradientKind {
+    /// 线性: 起点 → 终点
+    Linear { x0: f32, y0: f32, x1: f32, y1: f32 },
+    /// 径向: 同心圆，内半径处 t = 0，外半径处 t = 1
+    Radial { cx: f32, cy: f32, r0: f32, r1: f32 },
+    /// 锥形: 绕中心顺时针一周 t 从 0 到 1
+    Conic { cx: f32, cy: f32, start: f32 },
+}
+
+/// 渐变
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    kind: GradientKind,
+    /// 色标 (按偏移排序，偏移相同的色标保持添加顺序以形成硬边)
+    stops: Vec<(f32, [u8; 4])>,
+    spread: SpreadMode,
+}
+
+impl Gradient {
+    fn with_kind(kind: GradientKind) -> Self {
+        Self {
+            kind,
+            stops: Vec::new(),
+            spread: SpreadMode::Pad,
+        }
+    }
+
+    /// 计算点 (x, y) 处未经扩展的渐变参数
+    fn parameter(&self, x: f32, y: f32) -> f32 {
+        match self.kind {
+            GradientKind::Linear { x0, y0, x1, y1 } => {
+                let (dx, dy) = (x1 - x0, y1 - y0);
+                let len_sq = dx * dx + dy * dy;
+                if len_sq <= f32::EPSILON {
+                    return 0.0;
+                }
+                ((x - x0) * dx + (y - y0) * dy) / len_sq
+            }
+            GradientKind::Radial { cx, cy, r0, r1 } => {
+                let d = ((x - cx) * (x - cx) + (y - cy) * (y - cy)).sqrt();
+                if (r1 - r0).abs() <= f32::EPSILON {
+                    return if d < r0 { 0.0 } else { 1.0 };
+                }
+                (d - r0) / (r1 - r0)
+            }
+            GradientKind::Conic { cx, cy, start } => {
+                ((y - cy).atan2(x - cx) - start).rem_euclid(TAU) / TAU
+            }
+        }
+    }
+
+    /// 计算偏移 t (已在 [0, 1] 内) 处的颜色，色标之间用 `mix` 插值
+    fn color_at_offset(&self, t: f32, mix: Interpolate) -> [u8; 4] {
+        let Some(&(first_offset, first)) = self.stops.first() else {
+            return [0, 0, 0, 0];
+        };
+        if t <= first_offset {
+            return first;
+        }
+        for pair in self.stops.windows(2) {
+            let ((o0, c0), (o1, c1)) = (pair[0], pair[1]);
+            if t <= o1 {
+                let f = if o1 > o0 { (t - o0) / (o1 - o0) } else { 1.0 };
+                return mix(c0, c1, f);
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+
+    /// 生成着色器 (预计算颜色查找表)
+    pub fn shader(&self) -> GradientShader<'_> {
+        self.shader_with(interpolate_premultiplied)
+    }
+
+    /// 生成使用指定色标插值方式的着色器
+    pub(crate) fn shader_with(&self, mix: Interpolate) -> GradientShader<'_> {
+        let lut = (0..LUT_SIZE)
+            .map(|i| self.color_at_offset(i as f32 / (LUT_SIZE - 1) as f32, mix))
+            .collect();
+        GradientShader {
+            gradient: self,
+            lut,
+        }
+    }
+
+    /// 以渐变覆盖整个 RGBA 像素数据 (不混合)
+    pub fn fill_into(&self, data: &mut [u8], width: u32) {
+        self.fill_into_with(data, width, interpolate_premultiplied);
+    }
+
+    /// 以渐变覆盖整个 RGBA 像素数据，色标之间用 `mix` 插值
+    pub(crate) fn fill_into_with(&self, data: &mut [u8], width: u32, mix: Interpolate) {
+        if width == 0 {
+            return;
+        }
+        let shader = self.shader_with(mix);
+        for (y, row) in data.chunks_exact_mut(width as usize * 4).enumerate() {
+            for (x, px) in row.chunks_exact_mut(4).enumerate() {
+                px.copy_from_slice(&shader.color_at(x as f32 + 0.5, y as f32 + 0.5));
+            }
+        }
+    }
+}
+
+/// 色标颜色插值函数 (c0, c1, 权重)
+pub(crate) type Interpolate = fn([u8; 4], [u8; 4], f32) -> [u8; 4];
+
+/// 在预乘 Alpha 空间插值两种颜色，返回非预乘结果
+fn interpolate_premultiplied(c0: [u8; 4], c1: [u8; 4], f: f32) -> [u8; 4] {
+    let (a0, a1) = (c0[3] as f32 / 255.0, c1[3] as f32 / 255.0);
+    let alpha = a0 + (a1 - a0) * f;
+    if alpha <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+    let mut out = [0u8; 4];
+    for i in 0..3 {
+        let v = (c0[i] as f32 * a0 + (c1[i] as f32 * a1 - c0[i] as f32 * a0) * f) / alpha;
+        out[i] = (v + 0.5).clamp(0.0, 255.0) as u8;
+    }
+    out[3] = (alpha * 255.0 + 0.5) as u8;
+    out
+}
+
+/// 渐变着色器 (带颜色查找表)
+pub struct GradientShader<'a> {
+    gradient: &'a Gradient,
+    lut: Vec<[u8; 4]>,
+}
+
+impl GradientShader<'_> {
+    /// 计算点 (x, y) 处的颜色
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        let t = self.gradient.parameter(x, y);
+        let t = if t.is_finite() {
+            self.gradient.spread.apply(t)
+        } else {
+            0.0
+        };
+        self.lut[(t * (LUT_SIZE - 1) as f32 + 0.5) as usize]
+    }
+}
+
+#[wasm_bindgen]
+impl Gradient {
+    /// 创建线性渐变 (从 (x0, y0) 到 (x1, y1))
+    pub fn linear(x0: f32, y0: f32, x1: f32, y1: f32) -> Self {
+        Self::with_kind(GradientKind::Linear { x0, y0, x1, y1 })
+    }
+
+    /// 创建径向渐变
+    ///
+    /// 以 (cx, cy) 为圆心，`inner_radius` 处为第一个色标，`outer_radius` 处为最后一个色标。
+    pub fn radial(cx: f32, cy: f32, inner_radius: f32, outer_radius: f32) -> Self {
+        Self::with_kind(GradientKind::Radial {
+            cx,
+            cy,
+            r0: inner_radius.max(0.0),
+            r1: outer_radius.max(0.0),
+        })
+    }
+
+    /// 创建锥形渐变
+    ///
+    /// 以 (cx, cy) 为中心，从 `start_angle` (弧度，0 指向 +X) 开始顺时针 (屏幕坐标) 一周。
+    pub fn conic(cx: f32, cy: f32, start_angle: f32) -> Self {
+        Self::with_kind(GradientKind::Conic {
+            cx,
+            cy,
+            start: start_angle,
+        })
+    }
+
+    /// 添加色标
+    ///
+    /// `offset` 截断到 [0, 1]；偏移相同的色标按添加顺序形成硬边。非有限值时忽略。
+    pub fn add_color_stop(&mut self, offset: f32, r: u8, g: u8, b: u8, a: u8) {
+        if !offset.is_finite() {
+            return;
+        }
+        let offset = offset.clamp(0.0, 1.0);
+        let pos = self.stops.partition_point(|(o, _)| *o <= offset);
+        self.stops.insert(pos, (offset, [r, g, b, a]));
+    }
+
+    /// 清除全部色标
+    pub fn clear_color_stops(&mut self) {
+        self.stops.clear();
+    }
+
+    /// 获取色标数量
+    pub fn color_stop_count(&self) -> usize {
+        self.stops.len()
+    }
+
+    /// 设置扩展方式
+    ///
+    /// `spread`: 0 = 延伸 (pad), 1 = 重复 (repeat), 2 = 镜像 (reflect)。
+    pub fn set_spread(&mut self, spread: u8) {
+        self.spread = SpreadMode::from_u8(spread);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn black_to_white(mut g: Gradient) -> Gradient {
+        g.add_color_stop(0.0, 0, 0, 0, 255);
+        g.add_color_stop(1.0, 255, 255, 255, 255);
+        g
+    }
+
+    #[test]
+    fn test_linear_stops() {
+        let mut g = Gradient::linear(0.0, 0.0, 100.0, 0.0);
+        g.add_color_stop(1.0, 0, 0, 255, 255);
+        g.add_color_stop(0.0, 255, 0, 0, 255);
+        g.add_color_stop(0.5, 0, 255, 0, 255);
+        let s = g.shader();
+        assert_eq!(s.color_at(0.0, 5.0), [255, 0, 0, 255]);
+        // 查找表量化误差不超过 1/255
+        let mid = s.color_at(50.0, 5.0);
+        assert!(mid[0] == 0 && mid[1] >= 252 && mid[2] <= 3);
+        assert_eq!(s.color_at(100.0, 5.0), [0, 0, 255, 255]);
+        let mid = s.color_at(25.0, 0.0);
+        assert!((mid[0] as i32 - 128).abs() <= 2 && (mid[1] as i32 - 128).abs() <= 2);
+    }
+
+    #[test]
+    fn test_spread_modes() {
+        let mut g = black_to_white(Gradient::linear(0.0, 0.0, 10.0, 0.0));
+        assert_eq!(g.shader().color_at(15.0, 0.0)[0], 255);
+        g.set_spread(1);
+        assert!((g.shader().color_at(12.5, 0.0)[0] as i32 - 64).abs() <= 2);
+        g.set_spread(2);
+        assert!((g.shader().color_at(12.5, 0.0)[0] as i32 - 191).abs() <= 2);
+        assert_eq!(g.shader().color_at(-10.0, 0.0)[0], 255);
+    }
+
+    #[test]
+    fn test_radial_and_conic() {
+        let radial = black_to_white(Gradient::radial(10.0, 10.0, 2.0, 6.0));
+        let s = radial.shader();
+        assert_eq!(s.color_at(11.0, 10.0)[0], 0);
+        assert!((s.color_at(14.0, 10.0)[0] as i32 - 128).abs() <= 2);
+        assert_eq!(s.color_at(10.0, 20.0)[0], 255);
+
+        let conic = black_to_white(Gradient::conic(0.0, 0.0, 0.0));
+        let s = conic.shader();
+        // 顺时针 (屏幕坐标 +Y 向下) 四分之一周
+        assert!((s.color_at(0.0, 5.0)[0] as i32 - 64).abs() <= 2);
+        assert!((s.color_at(-5.0, 0.01)[0] as i32 - 128).abs() <= 2);
+    }
+
+    #[test]
+    fn test_hard_stop_and_transparent_interpolation() {
+        let mut g = Gradient::linear(0.0, 0.0, 10.0, 0.0);
+        g.add_color_stop(0.5, 255, 0, 0, 255);
+        g.add_color_stop(0.5, 0, 0, 255, 255);
+        let s = g.shader();
+        assert_eq!(s.color_at(4.0, 0.0), [255, 0, 0, 255]);
+        assert_eq!(s.color_at(6.0, 0.0), [0, 0, 255, 255]);
+
+        // 预乘插值: 红色渐隐到透明黑时保持红色色相
+        let mut fade = Gradient::linear(0.0, 0.0, 10.0, 0.0);
+        fade.add_color_stop(0.0, 255, 0, 0, 255);
+        fade.add_color_stop(1.0, 0, 0, 0, 0);
+        let c = fade.shader().color_at(5.0, 0.0);
+        assert_eq!(c[0], 255);
+        assert!((c[3] as i32 - 128).abs() <= 2);
+    }
+
+    #[test]
+    fn test_empty_gradient_is_transparent() {
+        let g = Gradient::linear(0.0, 0.0, 1.0, 0.0);
+        let mut data = vec![255u8; 4 * 4];
+        g.fill_into(&mut data, 2);
+        assert!(data.iter().all(|&v| v == 0));
+    }
+}
@@ -0,0 +1,17 @@
+//! 矢量光栅化模块
+//!
+//! 将直线与贝塞尔曲线组成的轮廓转换为抗锯齿覆盖率，供字形与矢量图形绘制使用，
+//! 并为 `PixelBuffer` 提供基本图形与任意路径的抗锯齿填充、描边，以及渐变着色。
+
+mod draw;
+mod gradient;
+mod path;
+mod rasterizer;
+mod shapes;
+mod stroke;
+
+pub use draw::{fill_path_into, stroke_path_into};
+pub use gradient::Gradient;
+pub use path::Path;
+pub use rasterizer::{FillRule, Point, Rasterizer};
+pub use stroke::{LineCap, LineJoin, StrokeStyle};
@@ -0,0 +1,260 @@
+//! 矢量路径
+//!
+//! 与 Canvas 2D 路径一致的命令式构建接口 (move_to / line_to / quad_to / cubic_to / close)，
+//! 绘制前按容差将曲线展平为折线子路径。
+
+use wasm_bindgen::prelude::*;
+
+use super::rasterizer::{flatten_cubic, flatten_quad, Point};
+use crate::math::Matrix3x3;
+
+/// 默认展平容差 (像素)
+const DEFAULT_PATH_TOLERANCE: f32 = 0.1;
+
+/// 最小展平容差 (像素)
+const MIN_PATH_TOLERANCE: f32 = 0.01;
+
+/// 路径命令
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    MoveTo(Point),
+    LineTo(Point),
+    QuadTo(Point, Point),
+    CubicTo(Point, Point, Point),
+    Close,
+}
+
+/// 展平后的子路径
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subpath {
+    /// 折线顶点
+    pub points: Vec<Point>,
+    /// 是否已闭合
+    pub closed: bool,
+}
+
+/// 矢量路径
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    commands: Vec<Command>,
+    tolerance: f32,
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl Path {
+    /// 创建空路径
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            tolerance: DEFAULT_PATH_TOLERANCE,
+        }
+    }
+
+    /// 开始新的子路径
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.commands.push(Command::MoveTo(Point::new(x, y)));
+    }
+
+    /// 直线连接到 (x, y)
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.commands.push(Command::LineTo(Point::new(x, y)));
+    }
+
+    /// 二次贝塞尔曲线，控制点 (cx, cy)，终点 (x, y)
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.commands
+            .push(Command::QuadTo(Point::new(cx, cy), Point::new(x, y)));
+    }
+
+    /// 三次贝塞尔曲线，控制点 (c1x, c1y)、(c2x, c2y)，终点 (x, y)
+    pub fn cubic_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.commands.push(Command::CubicTo(
+            Point::new(c1x, c1y),
+            Point::new(c2x, c2y),
+            Point::new(x, y),
+        ));
+    }
+
+    /// 闭合当前子路径
+    pub fn close(&mut self) {
+        self.commands.push(Command::Close);
+    }
+
+    /// 清空路径
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// 路径是否为空
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// 设置曲线展平容差 (像素，越小越精细，最小 0.01)
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        if tolerance.is_finite() {
+            self.tolerance = tolerance.max(MIN_PATH_TOLERANCE);
+        }
+    }
+
+    /// 获取曲线展平容差
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+}
+
+impl Path {
+    /// 对路径所有点应用仿射变换 (贝塞尔曲线在仿射变换下保持形状)
+    pub fn transform(&mut self, matrix: &Matrix3x3) {
+        let map = |p: Point| {
+            let (x, y) = matrix.transform_point(p.x, p.y);
+            Point::new(x, y)
+        };
+        for command in &mut self.commands {
+            *command = match *command {
+                Command::MoveTo(p) => Command::MoveTo(map(p)),
+                Command::LineTo(p) => Command::LineTo(map(p)),
+                Command::QuadTo(c, p) => Command::QuadTo(map(c), map(p)),
+                Command::CubicTo(c1, c2, p) => Command::CubicTo(map(c1), map(c2), map(p)),
+                Command::Close => Command::Close,
+            };
+        }
+    }
+
+    /// 将路径展平为折线子路径
+    ///
+    /// 没有当前点时，绘制命令以其第一个点开始新子路径；闭合后的下一条命令
+    /// 从该子路径起点继续。仅含一个点的子路径被丢弃。
+    pub fn flatten(&self) -> Vec<Subpath> {
+        let mut subpaths = Vec::new();
+        let mut points: Vec<Point> = Vec::new();
+
+        let finish = |points: &mut Vec<Point>, closed: bool, out: &mut Vec<Subpath>| {
+            if points.len() > 1 {
+                out.push(Subpath {
+                    points: std::mem::take(points),
+                    closed,
+                });
+            } else {
+                points.clear();
+            }
+        };
+
+        for &command in &self.commands {
+            if let Command::MoveTo(p) = command {
+                finish(&mut points, false, &mut subpaths);
+                points.push(p);
+                continue;
+            }
+            if command == Command::Close {
+                if let Some(&start) = points.first() {
+                    finish(&mut points, true, &mut subpaths);
+                    points.push(start);
+                }
+                continue;
+            }
+
+            let start = match command {
+                Command::LineTo(p) | Command::QuadTo(p, _) | Command::CubicTo(p, _, _) => p,
+                _ => unreachable!(),
+            };
+            let current = match points.last() {
+                Some(&p) => p,
+                None => {
+                    points.push(start);
+                    if let Command::LineTo(_) = command {
+                        continue;
+                    }
+                    start
+                }
+            };
+            match command {
+                Command::LineTo(p) => points.push(p),
+                Command::QuadTo(c, p) => flatten_quad(current, c, p, self.tolerance, &mut points),
+                Command::CubicTo(c1, c2, p) => {
+                    flatten_cubic(current, c1, c2, p, self.tolerance, &mut points)
+                }
+                _ => {}
+            }
+        }
+        finish(&mut points, false, &mut subpaths);
+        subpaths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subpaths() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.line_to(10.0, 10.0);
+        path.close();
+        path.line_to(0.0, 10.0);
+        path.move_to(50.0, 50.0);
+
+        let subpaths = path.flatten();
+        assert_eq!(subpaths.len(), 2);
+        assert!(subpaths[0].closed);
+        assert_eq!(subpaths[0].points.len(), 3);
+        // 闭合后从起点继续
+        assert!(!subpaths[1].closed);
+        assert_eq!(
+            subpaths[1].points,
+            vec![Point::new(0.0, 0.0), Point::new(0.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn test_implicit_start() {
+        let mut path = Path::new();
+        path.line_to(1.0, 2.0);
+        path.line_to(3.0, 4.0);
+        let subpaths = path.flatten();
+        assert_eq!(subpaths[0].points[0], Point::new(1.0, 2.0));
+        assert_eq!(subpaths[0].points.len(), 2);
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut path = Path::new();
+        path.move_to(1.0, 0.0);
+        path.quad_to(1.0, 1.0, 0.0, 1.0);
+        path.transform(&Matrix3x3::translation(10.0, 20.0));
+        let points = &path.flatten()[0].points;
+        assert_eq!(points[0], Point::new(11.0, 20.0));
+        assert_eq!(*points.last().unwrap(), Point::new(10.0, 21.0));
+    }
+
+    #[test]
+    fn test_tolerance_controls_segments() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.cubic_to(0.0, 100.0, 100.0, 100.0, 100.0, 0.0);
+        let fine = path.flatten()[0].points.len();
+        path.set_tolerance(5.0);
+        let coarse = path.flatten()[0].points.len();
+        assert!(coarse < fine);
+        assert_eq!(
+            *path.flatten()[0].points.last().unwrap(),
+            Point::new(100.0, 0.0)
+        );
+
+        path.set_tolerance(0.0);
+        assert_eq!(path.tolerance(), MIN_PATH_TOLERANCE);
+        path.clear();
+        assert!(path.is_empty());
+        assert!(path.flatten().is_empty());
+    }
+}
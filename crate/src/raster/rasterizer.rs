@@ -0,0 +1,467 @@
+//! 扫描线光栅化器
+//!
+//! 每个像素行取若干条子扫描线，求出与各边的交点后按环绕数与填充规则填充区间，
+//! 区间两端按小数坐标计算水平覆盖，从而得到平滑的抗锯齿边缘。
+
+/// 每个像素行的子扫描线数
+const SUBSAMPLES: usize = 16;
+
+/// 默认曲线展平容差 (像素)
+const DEFAULT_TOLERANCE: f32 = 0.1;
+
+/// 二维点
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn lerp(self, other: Point, t: f32) -> Point {
+        Point::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+}
+
+/// 展平二次贝塞尔曲线，将除起点外的各点追加到 `out`
+///
+/// 分段数使每段弦线与曲线的最大偏差不超过 `tolerance`。
+pub fn flatten_quad(p0: Point, p1: Point, p2: Point, tolerance: f32, out: &mut Vec<Point>) {
+    let dd = (p0.x - 2.0 * p1.x + p2.x).hypot(p0.y - 2.0 * p1.y + p2.y);
+    let n = ((dd / (4.0 * tolerance)).sqrt().ceil() as usize).clamp(1, 256);
+    for i in 1..=n {
+        let t = i as f32 / n as f32;
+        out.push(p0.lerp(p1, t).lerp(p1.lerp(p2, t), t));
+    }
+}
+
+/// 展平三次贝塞尔曲线，将除起点外的各点追加到 `out`
+pub fn flatten_cubic(
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    tolerance: f32,
+    out: &mut Vec<Point>,
+) {
+    let dd1 = (p0.x - 2.0 * p1.x + p2.x).hypot(p0.y - 2.0 * p1.y + p2.y);
+    let dd2 = (p1.x - 2.0 * p2.x + p3.x).hypot(p1.y - 2.0 * p2.y + p3.y);
+    let n = ((0.75 * dd1.max(dd2) / tolerance).sqrt().ceil() as usize).clamp(1, 256);
+    for i in 1..=n {
+        let t = i as f32 / n as f32;
+        let a = p0.lerp(p1, t);
+        let b = p1.lerp(p2, t);
+        let c = p2.lerp(p3, t);
+        out.push(a.lerp(b, t).lerp(b.lerp(c, t), t));
+    }
+}
+
+/// 填充规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// 非零环绕: 环绕数不为 0 的区域被填充
+    #[default]
+    NonZero,
+    /// 奇偶: 被奇数条边包围的区域被填充
+    EvenOdd,
+}
+
+impl FillRule {
+    /// 从 u8 值创建填充规则
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => FillRule::NonZero,
+            1 => FillRule::EvenOdd,
+            _ => FillRule::NonZero,
+        }
+    }
+
+    /// 环绕数对应的位置是否在内部
+    fn contains(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// 覆盖率遮罩 (仅包含轮廓包围盒与输出区域相交的部分)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mask {
+    /// 左上角 X
+    pub x: u32,
+    /// 左上角 Y
+    pub y: u32,
+    /// 宽度
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+    /// 覆盖率 (0.0 ~ 1.0，行优先)
+    pub data: Vec<f32>,
+}
+
+/// 有向边 (y0 < y1)
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    x0: f32,
+    y0: f32,
+    y1: f32,
+    /// dx / dy
+    slope: f32,
+    /// 原始方向: 向下为 1，向上为 -1
+    winding: i32,
+}
+
+/// 扫描线光栅化器
+pub struct Rasterizer {
+    width: u32,
+    height: u32,
+    edges: Vec<Edge>,
+}
+
+impl Rasterizer {
+    /// 创建指定输出尺寸的光栅化器
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            edges: Vec::new(),
+        }
+    }
+
+    /// 添加一条直线边
+    pub fn line(&mut self, p0: Point, p1: Point) {
+        if p0.y == p1.y || !(p0.y.is_finite() && p1.y.is_finite()) {
+            return;
+        }
+        let (top, bottom, winding) = if p0.y < p1.y {
+            (p0, p1, 1)
+        } else {
+            (p1, p0, -1)
+        };
+        self.edges.push(Edge {
+            x0: top.x,
+            y0: top.y,
+            y1: bottom.y,
+            slope: (bottom.x - top.x) / (bottom.y - top.y),
+            winding,
+        });
+    }
+
+    /// 添加闭合多边形轮廓
+    pub fn contour(&mut self, points: &[Point]) {
+        if points.len() < 2 {
+            return;
+        }
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1]);
+        }
+        self.line(points[points.len() - 1], points[0]);
+    }
+
+    /// 添加二次贝塞尔曲线 (按默认容差展平为直线段)
+    pub fn quad(&mut self, p0: Point, p1: Point, p2: Point) {
+        let mut points = Vec::new();
+        flatten_quad(p0, p1, p2, DEFAULT_TOLERANCE, &mut points);
+        self.polyline(p0, &points);
+    }
+
+    /// 添加三次贝塞尔曲线 (按默认容差展平为直线段)
+    pub fn cubic(&mut self, p0: Point, p1: Point, p2: Point, p3: Point) {
+        let mut points = Vec::new();
+        flatten_cubic(p0, p1, p2, p3, DEFAULT_TOLERANCE, &mut points);
+        self.polyline(p0, &points);
+    }
+
+    /// 从 `start` 依次连接各点
+    fn polyline(&mut self, start: Point, points: &[Point]) {
+        let mut prev = start;
+        for &next in points {
+            self.line(prev, next);
+            prev = next;
+        }
+    }
+
+    /// 输出尺寸 (宽, 高)
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// 按填充规则填充，返回整个输出区域的每像素覆盖率 (0.0 ~ 1.0，行优先)
+    pub fn fill(&self, rule: FillRule) -> Vec<f32> {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut coverage = vec![0.0f32; w * h];
+        let mask = self.fill_mask(rule);
+        let (mx, mw) = (mask.x as usize, mask.width as usize);
+        for (row, src) in mask.data.chunks_exact(mw.max(1)).enumerate() {
+            let dst = (mask.y as usize + row) * w + mx;
+            coverage[dst..dst + mw].copy_from_slice(src);
+        }
+        coverage
+    }
+
+    /// 轮廓包围盒与输出区域的交集 (x0, y0, x1, y1)，为空时返回 None
+    fn bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+        let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for e in &self.edges {
+            let x1 = e.x0 + (e.y1 - e.y0) * e.slope;
+            min_x = min_x.min(e.x0).min(x1);
+            max_x = max_x.max(e.x0).max(x1);
+            min_y = min_y.min(e.y0);
+            max_y = max_y.max(e.y1);
+        }
+        let clamp = |v: f32, limit: u32| (v.max(0.0) as u32).min(limit);
+        let x0 = clamp(min_x.floor(), self.width);
+        let x1 = clamp(max_x.ceil(), self.width);
+        let y0 = clamp(min_y.floor(), self.height);
+        let y1 = clamp(max_y.ceil(), self.height);
+        (x0 < x1 && y0 < y1).then_some((x0, y0, x1, y1))
+    }
+
+    /// 按填充规则填充，只计算轮廓包围盒内的覆盖率
+    pub fn fill_mask(&self, rule: FillRule) -> Mask {
+        let Some((x0, y0, x1, y1)) = self.bounds() else {
+            return Mask::default();
+        };
+        let (w, h) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        let origin = x0 as f32;
+        let mut coverage = vec![0.0f32; w * h];
+
+        let mut edges = self.edges.clone();
+        edges.sort_by(|a, b| a.y0.total_cmp(&b.y0));
+
+        let weight = 1.0 / SUBSAMPLES as f32;
+        let mut active: Vec<Edge> = Vec::new();
+        let mut next_edge = 0;
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        // 整像素区间的差分累加
+        let mut spans = vec![0.0f32; w + 1];
+
+        for row in 0..h {
+            let row_top = (y0 as usize + row) as f32;
+            let row_bottom = row_top + 1.0;
+
+            while next_edge < edges.len() && edges[next_edge].y0 < row_bottom {
+                active.push(edges[next_edge]);
+                next_edge += 1;
+            }
+            active.retain(|e| e.y1 > row_top);
+            if active.is_empty() {
+                if next_edge >= edges.len() {
+                    break;
+                }
+                continue;
+            }
+
+            let line = &mut coverage[row * w..(row + 1) * w];
+            spans.fill(0.0);
+
+            for s in 0..SUBSAMPLES {
+                let y = row_top + (s as f32 + 0.5) * weight;
+                crossings.clear();
+                for e in &active {
+                    if y >= e.y0 && y < e.y1 {
+                        crossings.push((e.x0 + (y - e.y0) * e.slope - origin, e.winding));
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+                    if rule.contains(winding) {
+                        add_span(line, &mut spans, pair[0].0, pair[1].0, weight);
+                    }
+                }
+            }
+
+            let mut acc = 0.0;
+            for (c, d) in line.iter_mut().zip(&spans) {
+                acc += d;
+                *c = (*c + acc).min(1.0);
+            }
+        }
+
+        Mask {
+            x: x0,
+            y: y0,
+            width: w as u32,
+            height: h as u32,
+            data: coverage,
+        }
+    }
+}
+
+/// 将 [x0, x1) 区间以给定权重累加到一行覆盖率中
+///
+/// 两端的部分像素直接写入 `line`，中间的整像素写入差分数组 `spans`。
+fn add_span(line: &mut [f32], spans: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let w = line.len() as f32;
+    let x0 = x0.clamp(0.0, w);
+    let x1 = x1.clamp(0.0, w);
+    if x1 <= x0 {
+        return;
+    }
+
+    let i0 = x0 as usize;
+    let i1 = x1 as usize;
+    if i0 == i1 {
+        line[i0] += (x1 - x0) * weight;
+        return;
+    }
+
+    line[i0] += (i0 as f32 + 1.0 - x0) * weight;
+    if i1 > i0 + 1 {
+        spans[i0 + 1] += weight;
+        spans[i1] -= weight;
+    }
+    if i1 < line.len() {
+        line[i1] += (x1 - i1 as f32) * weight;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(r: &mut Rasterizer, x0: f32, y0: f32, x1: f32, y1: f32) {
+        let (a, b, c, d) = (
+            Point::new(x0, y0),
+            Point::new(x1, y0),
+            Point::new(x1, y1),
+            Point::new(x0, y1),
+        );
+        r.line(a, b);
+        r.line(b, c);
+        r.line(c, d);
+        r.line(d, a);
+    }
+
+    #[test]
+    fn test_fill_rect_exact() {
+        let mut r = Rasterizer::new(4, 4);
+        rect(&mut r, 1.0, 1.0, 3.0, 3.0);
+        let cov = r.fill(FillRule::NonZero);
+        assert_eq!(cov[5], 1.0);
+        assert_eq!(cov[6], 1.0);
+        assert_eq!(cov[0], 0.0);
+        assert_eq!(cov[15], 0.0);
+    }
+
+    #[test]
+    fn test_partial_coverage() {
+        let mut r = Rasterizer::new(2, 1);
+        rect(&mut r, 0.5, 0.0, 1.25, 1.0);
+        let cov = r.fill(FillRule::NonZero);
+        assert!((cov[0] - 0.5).abs() < 1e-4);
+        assert!((cov[1] - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_nonzero_overlap_and_hole() {
+        // 同向重叠: 仍为满覆盖
+        let mut r = Rasterizer::new(4, 1);
+        rect(&mut r, 0.0, 0.0, 3.0, 1.0);
+        rect(&mut r, 1.0, 0.0, 4.0, 1.0);
+        assert_eq!(r.fill(FillRule::NonZero), vec![1.0; 4]);
+
+        // 反向内轮廓: 形成空洞
+        let mut r = Rasterizer::new(4, 1);
+        rect(&mut r, 0.0, 0.0, 4.0, 1.0);
+        rect(&mut r, 3.0, 0.0, 1.0, 1.0);
+        assert_eq!(r.fill(FillRule::NonZero), vec![1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_even_odd() {
+        // 同向嵌套: 非零规则全部填充，奇偶规则内部镂空
+        let mut r = Rasterizer::new(4, 1);
+        rect(&mut r, 0.0, 0.0, 4.0, 1.0);
+        rect(&mut r, 1.0, 0.0, 3.0, 1.0);
+        assert_eq!(r.fill(FillRule::NonZero), vec![1.0; 4]);
+        assert_eq!(r.fill(FillRule::EvenOdd), vec![1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(FillRule::from_u8(1), FillRule::EvenOdd);
+    }
+
+    #[test]
+    fn test_curve_area() {
+        // 用 4 段三次曲线近似半径 10 的圆
+        let k = 0.552_284_8 * 10.0;
+        let c = Point::new(12.0, 12.0);
+        let mut r = Rasterizer::new(24, 24);
+        let pts = [
+            (
+                Point::new(c.x + 10.0, c.y),
+                Point::new(c.x, c.y + 10.0),
+                (0.0, k),
+                (k, 0.0),
+            ),
+            (
+                Point::new(c.x, c.y + 10.0),
+                Point::new(c.x - 10.0, c.y),
+                (-k, 0.0),
+                (0.0, k),
+            ),
+            (
+                Point::new(c.x - 10.0, c.y),
+                Point::new(c.x, c.y - 10.0),
+                (0.0, -k),
+                (-k, 0.0),
+            ),
+            (
+                Point::new(c.x, c.y - 10.0),
+                Point::new(c.x + 10.0, c.y),
+                (k, 0.0),
+                (0.0, -k),
+            ),
+        ];
+        for (a, b, da, db) in pts {
+            r.cubic(
+                a,
+                Point::new(a.x + da.0, a.y + da.1),
+                Point::new(b.x + db.0, b.y + db.1),
+                b,
+            );
+        }
+        // 展平弦线向内偏离不超过容差，面积损失不超过 周长 × 容差
+        let area: f32 = r.fill(FillRule::NonZero).iter().sum();
+        let circle = std::f32::consts::PI * 100.0;
+        assert!(area <= circle + 0.5);
+        assert!(area >= circle - 2.0 * std::f32::consts::PI * 10.0 * DEFAULT_TOLERANCE);
+    }
+
+    #[test]
+    fn test_mask_bounds() {
+        let mut r = Rasterizer::new(100, 100);
+        r.contour(&[
+            Point::new(10.5, 20.0),
+            Point::new(14.0, 20.0),
+            Point::new(14.0, 23.0),
+        ]);
+        let mask = r.fill_mask(FillRule::NonZero);
+        assert_eq!((mask.x, mask.y, mask.width, mask.height), (10, 20, 4, 3));
+        assert_eq!(mask.data.len(), 12);
+        assert_eq!(r.fill(FillRule::NonZero).len(), 100 * 100);
+        assert_eq!(
+            Rasterizer::new(10, 10)
+                .fill_mask(FillRule::NonZero)
+                .data
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_clipped_outside() {
+        let mut r = Rasterizer::new(2, 2);
+        rect(&mut r, -5.0, -5.0, 10.0, 10.0);
+        assert_eq!(r.fill(FillRule::NonZero), vec![1.0; 4]);
+    }
+}
@@ -0,0 +1,131 @@
+//! 基本图形轮廓
+//!
+//! 将圆弧、椭圆与圆角矩形展平为多边形点列，角度单位为弧度，
+//! 屏幕坐标系下 (y 轴向下) 正角度为顺时针方向。
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use super::rasterizer::Point;
+
+/// 圆弧展平容差 (像素)
+pub const ARC_TOLERANCE: f32 = 0.05;
+
+/// 多边形有向面积 (屏幕坐标系下顺时针为正)
+pub fn signed_area(points: &[Point]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+/// 使轮廓方向为顺时针 (`clockwise` 为 true) 或逆时针
+pub fn orient(mut points: Vec<Point>, clockwise: bool) -> Vec<Point> {
+    if (signed_area(&points) >= 0.0) != clockwise {
+        points.reverse();
+    }
+    points
+}
+
+/// 展平半径为 `radius`、扫过 `sweep` 弧度的圆弧所需线段数
+fn segment_count(radius: f32, sweep: f32) -> usize {
+    if radius <= ARC_TOLERANCE {
+        return (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    }
+    let step = 2.0 * (1.0 - ARC_TOLERANCE / radius).acos();
+    ((sweep.abs() / step).ceil() as usize).clamp(1, 1024)
+}
+
+/// 椭圆弧上的点 (含起点与终点)
+///
+/// `end < start` 时逆时针绘制。中间顶点沿径向略微外扩，使折线围成的面积与真实弧线一致，
+/// 避免小半径圆形因弦线内缩而显得偏小；非整圆时端点保持在弧线上以便与相邻线段衔接。
+pub fn arc_points(center: Point, rx: f32, ry: f32, start: f32, end: f32) -> Vec<Point> {
+    let sweep = end - start;
+    let n = segment_count(rx.max(ry), sweep);
+    let step = (sweep / n as f32).abs();
+    let scale = if step > f32::EPSILON {
+        (step / step.sin()).sqrt()
+    } else {
+        1.0
+    };
+    let full = sweep.abs() >= TAU;
+    (0..=n)
+        .map(|i| {
+            let angle = start + sweep * i as f32 / n as f32;
+            let k = if full || (i > 0 && i < n) { scale } else { 1.0 };
+            Point::new(
+                center.x + rx * k * angle.cos(),
+                center.y + ry * k * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// 椭圆轮廓 (顺时针)
+pub fn ellipse(center: Point, rx: f32, ry: f32) -> Vec<Point> {
+    let mut points = arc_points(center, rx, ry, 0.0, TAU);
+    points.pop();
+    points
+}
+
+/// 圆角矩形轮廓 (顺时针)，圆角半径不超过短边的一半
+pub fn rounded_rect(x: f32, y: f32, w: f32, h: f32, radius: f32) -> Vec<Point> {
+    let r = radius.clamp(0.0, w.min(h) / 2.0);
+    if r <= 0.0 {
+        return vec![
+            Point::new(x, y),
+            Point::new(x + w, y),
+            Point::new(x + w, y + h),
+            Point::new(x, y + h),
+        ];
+    }
+
+    let corners = [
+        (Point::new(x + w - r, y + r), -FRAC_PI_2),
+        (Point::new(x + w - r, y + h - r), 0.0),
+        (Point::new(x + r, y + h - r), FRAC_PI_2),
+        (Point::new(x + r, y + r), PI),
+    ];
+    corners
+        .iter()
+        .flat_map(|&(c, start)| arc_points(c, r, r, start, start + FRAC_PI_2))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ellipse_area_and_orientation() {
+        let pts = ellipse(Point::new(0.0, 0.0), 20.0, 10.0);
+        let area = signed_area(&pts);
+        assert!(area > 0.0);
+        assert!((area - PI * 200.0).abs() < 2.0);
+        assert!(signed_area(&orient(pts, false)) < 0.0);
+    }
+
+    #[test]
+    fn test_rounded_rect() {
+        let square = rounded_rect(0.0, 0.0, 10.0, 4.0, 0.0);
+        assert_eq!(signed_area(&square), 40.0);
+
+        // 半径被限制为 2: 面积 = 40 - (4 - π) × 4
+        let pill = rounded_rect(0.0, 0.0, 10.0, 4.0, 5.0);
+        let expected = 40.0 - (4.0 - PI) * 4.0;
+        assert!((signed_area(&pill) - expected).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_arc_direction() {
+        let cw = arc_points(Point::new(0.0, 0.0), 1.0, 1.0, 0.0, FRAC_PI_2);
+        assert_eq!(cw[0], Point::new(1.0, 0.0));
+        assert!((cw.last().unwrap().y - 1.0).abs() < 1e-6);
+        let ccw = arc_points(Point::new(0.0, 0.0), 1.0, 1.0, 0.0, -FRAC_PI_2);
+        assert!((ccw.last().unwrap().y + 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,713 @@
+//! 描边
+//!
+//! 将折线按线宽扩展为若干顺时针多边形 (线段矩形、连接与端点线帽)，
+//! 在非零环绕规则下叠加即为完整描边。虚线先将折线切分为开放折线再逐段描边。
+
+use std::f32::consts::PI;
+
+use wasm_bindgen::prelude::*;
+
+use super::rasterizer::{Point, Rasterizer};
+use super::shapes::{arc_points, ellipse, orient};
+
+/// 线帽样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    /// 平头 (止于端点)
+    #[default]
+    Butt,
+    /// 圆头
+    Round,
+    /// 方头 (延伸半个线宽)
+    Square,
+}
+
+impl LineCap {
+    /// 从 u8 值创建线帽样式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LineCap::Butt,
+            1 => LineCap::Round,
+            2 => LineCap::Square,
+            _ => LineCap::Butt,
+        }
+    }
+}
+
+/// 连接样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    /// 斜接 (超过斜接限制时退化为斜角)
+    #[default]
+    Miter,
+    /// 圆角
+    Round,
+    /// 斜角
+    Bevel,
+}
+
+impl LineJoin {
+    /// 从 u8 值创建连接样式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LineJoin::Miter,
+            1 => LineJoin::Round,
+            2 => LineJoin::Bevel,
+            _ => LineJoin::Miter,
+        }
+    }
+}
+
+/// 默认斜接限制 (与 Canvas 2D 一致)
+const DEFAULT_MITER_LIMIT: f32 = 10.0;
+
+/// 描边样式
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    /// 线宽
+    width: f32,
+    /// 线帽
+    cap: LineCap,
+    /// 连接
+    join: LineJoin,
+    /// 斜接限制 (斜接长度与线宽之比的上限)
+    miter_limit: f32,
+    /// 虚线模式 (实线段与间隔交替，为空时为实线)
+    dash: Vec<f32>,
+    /// 虚线起始偏移
+    dash_offset: f32,
+}
+
+impl StrokeStyle {
+    /// 创建无虚线的描边样式
+    pub fn solid(width: f32, cap: LineCap, join: LineJoin) -> Self {
+        Self {
+            width,
+            cap,
+            join,
+            miter_limit: DEFAULT_MITER_LIMIT,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl StrokeStyle {
+    /// 创建描边样式 (平头、斜接、实线)
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: f32) -> Self {
+        Self::solid(width, LineCap::Butt, LineJoin::Miter)
+    }
+
+    /// 设置线宽
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width;
+    }
+
+    /// 设置线帽: 0 = 平头, 1 = 圆头, 2 = 方头
+    pub fn set_line_cap(&mut self, cap: u8) {
+        self.cap = LineCap::from_u8(cap);
+    }
+
+    /// 设置连接: 0 = 斜接, 1 = 圆角, 2 = 斜角
+    pub fn set_line_join(&mut self, join: u8) {
+        self.join = LineJoin::from_u8(join);
+    }
+
+    /// 设置斜接限制 (小于 1 或非有限值时忽略)
+    pub fn set_miter_limit(&mut self, limit: f32) {
+        if limit.is_finite() && limit >= 1.0 {
+            self.miter_limit = limit;
+        }
+    }
+
+    /// 设置虚线模式
+    ///
+    /// `segments` 为交替的实线段与间隔长度，奇数个时重复一次；空数组表示实线。
+    /// 含负数或非有限值时忽略。
+    pub fn set_line_dash(&mut self, segments: &[f32], offset: f32) {
+        if segments.iter().any(|v| !v.is_finite() || *v < 0.0) || !offset.is_finite() {
+            return;
+        }
+        self.dash = if segments.iter().sum::<f32>() > 0.0 {
+            segments.to_vec()
+        } else {
+            Vec::new()
+        };
+        if self.dash.len() % 2 == 1 {
+            self.dash.extend_from_slice(segments);
+        }
+        self.dash_offset = offset;
+    }
+}
+
+/// 单位方向向量，长度为 0 时返回 None
+fn direction(a: Point, b: Point) -> Option<Point> {
+    let len = (b.x - a.x).hypot(b.y - a.y);
+    (len > f32::EPSILON).then(|| Point::new((b.x - a.x) / len, (b.y - a.y) / len))
+}
+
+/// 添加沿 a→b、半宽为 `hw` 的矩形
+fn segment(raster: &mut Rasterizer, a: Point, b: Point, hw: f32) {
+    let Some(d) = direction(a, b) else {
+        return;
+    };
+    let n = Point::new(-d.y * hw, d.x * hw);
+    let quad = vec![
+        Point::new(a.x + n.x, a.y + n.y),
+        Point::new(b.x + n.x, b.y + n.y),
+        Point::new(b.x - n.x, b.y - n.y),
+        Point::new(a.x - n.x, a.y - n.y),
+    ];
+    raster.contour(&orient(quad, true));
+}
+
+/// 在端点 `p` 处添加线帽，`d` 为指向线外的单位方向
+fn cap(raster: &mut Rasterizer, p: Point, d: Point, hw: f32, style: LineCap) {
+    match style {
+        LineCap::Butt => {}
+        LineCap::Round => raster.contour(&ellipse(p, hw, hw)),
+        LineCap::Square => {
+            let end = Point::new(p.x + d.x * hw, p.y + d.y * hw);
+            segment(raster, p, end, hw);
+        }
+    }
+}
+
+/// 在顶点 `v` 处添加 prev→v→next 的连接
+fn join(raster: &mut Rasterizer, prev: Point, v: Point, next: Point, hw: f32, style: &StrokeStyle) {
+    let (Some(d0), Some(d1)) = (direction(prev, v), direction(v, next)) else {
+        return;
+    };
+    let cross = d0.x * d1.y - d0.y * d1.x;
+    let dot = d0.x * d1.x + d0.y * d1.y;
+    if cross.abs() < 1e-6 && dot > 0.0 {
+        return;
+    }
+
+    if style.join == LineJoin::Round {
+        raster.contour(&ellipse(v, hw, hw));
+        return;
+    }
+
+    // 外侧单位法线
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let u0 = Point::new(-d0.y * side, d0.x * side);
+    let u1 = Point::new(-d1.y * side, d1.x * side);
+    let a = Point::new(v.x + u0.x * hw, v.y + u0.y * hw);
+    let b = Point::new(v.x + u1.x * hw, v.y + u1.y * hw);
+
+    let sum = Point::new(u0.x + u1.x, u0.y + u1.y);
+    let sum_len = sum.x.hypot(sum.y);
+    // 斜接长度与线宽之比 = 1 / cos(θ/2) = 2 / |u0 + u1|
+    if style.join == LineJoin::Miter && sum_len > f32::EPSILON && 2.0 / sum_len <= style.miter_limit
+    {
+        let k = 2.0 * hw / (sum_len * sum_len);
+        let tip = Point::new(v.x + sum.x * k, v.y + sum.y * k);
+        raster.contour(&orient(vec![v, a, tip, b], true));
+    } else {
+        raster.contour(&orient(vec![v, a, b], true));
+    }
+}
+
+/// 描边单条无虚线的折线
+fn stroke_solid(raster: &mut Rasterizer, points: &[Point], closed: bool, style: &StrokeStyle) {
+    let hw = style.width / 2.0;
+
+    let mut pts: Vec<Point> = Vec::with_capacity(points.len());
+    for &p in points {
+        if pts.last() != Some(&p) {
+            pts.push(p);
+        }
+    }
+    if closed && pts.len() > 1 && pts.first() == pts.last() {
+        pts.pop();
+    }
+
+    match pts.len() {
+        0 => return,
+        1 => {
+            // 零长度线段: 仅圆头 / 方头可见
+            let p = pts[0];
+            match style.cap {
+                LineCap::Butt => {}
+                LineCap::Round => raster.contour(&ellipse(p, hw, hw)),
+                LineCap::Square => segment(
+                    raster,
+                    Point::new(p.x - hw, p.y),
+                    Point::new(p.x + hw, p.y),
+                    hw,
+                ),
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let n = pts.len();
+    let segments = if closed { n } else { n - 1 };
+    for i in 0..segments {
+        segment(raster, pts[i], pts[(i + 1) % n], hw);
+    }
+
+    if closed {
+        for i in 0..n {
+            join(
+                raster,
+                pts[(i + n - 1) % n],
+                pts[i],
+                pts[(i + 1) % n],
+                hw,
+                style,
+            );
+        }
+    } else {
+        for i in 1..n - 1 {
+            join(raster, pts[i - 1], pts[i], pts[i + 1], hw, style);
+        }
+        if let Some(d) = direction(pts[1], pts[0]) {
+            cap(raster, pts[0], d, hw, style.cap);
+        }
+        if let Some(d) = direction(pts[n - 2], pts[n - 1]) {
+            cap(raster, pts[n - 1], d, hw, style.cap);
+        }
+    }
+}
+
+/// 单条折线切分出的虚线段数上限，超出部分不再绘制
+const MAX_DASHES: usize = 1 << 16;
+
+/// 线段 a→b 落在矩形 [左, 上, 右, 下] 内的参数区间 (Liang-Barsky 裁剪)
+fn clip_segment(a: Point, b: Point, rect: [f32; 4]) -> Option<(f32, f32)> {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for (p, q) in [
+        (-dx, a.x - rect[0]),
+        (dx, rect[2] - a.x),
+        (-dy, a.y - rect[1]),
+        (dy, rect[3] - a.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    (t0 < t1).then_some((t0, t1))
+}
+
+/// 按虚线模式将折线切分为若干段开放折线
+///
+/// 虚线边界由沿折线的累计距离 (f64) 直接计算，不随段数累积误差；
+/// 完全落在 `bounds` 之外的部分被跳过，切分出的段数不超过 `MAX_DASHES`。
+fn dash_polyline(
+    points: &[Point],
+    closed: bool,
+    dash: &[f32],
+    offset: f32,
+    bounds: [f32; 4],
+) -> Vec<Vec<Point>> {
+    let total: f64 = dash.iter().map(|&v| v as f64).sum();
+    let mut pieces = Vec::new();
+    if points.len() < 2 || total <= 0.0 {
+        return pieces;
+    }
+    // starts[i] 为第 i 段在一个周期内的起点
+    let starts: Vec<f64> = dash
+        .iter()
+        .scan(0.0f64, |acc, &v| {
+            let start = *acc;
+            *acc += v as f64;
+            Some(start)
+        })
+        .collect();
+    let phase = (offset as f64).rem_euclid(total);
+
+    let n = points.len();
+    let segments = if closed { n } else { n - 1 };
+    let mut current: Vec<Point> = Vec::new();
+    let mut dashes = 0;
+    // 线段起点沿折线的累计距离
+    let mut distance = 0.0f64;
+    for i in 0..segments {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let len = ((b.x - a.x) as f64).hypot((b.y - a.y) as f64);
+        let seg_start = distance;
+        distance += len;
+        let Some((t0, t1)) = clip_segment(a, b, bounds).filter(|_| len > 0.0) else {
+            if len > 0.0 && current.len() > 1 {
+                pieces.push(std::mem::take(&mut current));
+            }
+            current.clear();
+            continue;
+        };
+        if t0 > 0.0 {
+            // 从可见区域外进入，之前的段已结束
+            if current.len() > 1 {
+                pieces.push(std::mem::take(&mut current));
+            }
+            current.clear();
+        }
+        let point_at = |d: f64| a.lerp(b, ((d - seg_start) / len) as f32);
+        let (from, to) = (seg_start + t0 as f64 * len, seg_start + t1 as f64 * len);
+
+        // 定位 from 处所在的虚线段
+        let u = from + phase;
+        let mut period = (u / total).floor();
+        let within = u - period * total;
+        let mut index = starts.iter().rposition(|&s| s <= within).unwrap_or(0);
+        let mut on = index % 2 == 0;
+        if !on {
+            if current.len() > 1 {
+                pieces.push(std::mem::take(&mut current));
+            }
+            current.clear();
+        } else if current.is_empty() {
+            current.push(point_at(from));
+        }
+        loop {
+            let end = if index + 1 < dash.len() {
+                starts[index + 1]
+            } else {
+                total
+            };
+            let boundary = period * total + end - phase;
+            if boundary >= to {
+                break;
+            }
+            dashes += 1;
+            if dashes > MAX_DASHES {
+                if on && current.len() > 1 {
+                    pieces.push(current);
+                }
+                return pieces;
+            }
+            current.push(point_at(boundary));
+            if on {
+                pieces.push(std::mem::take(&mut current));
+            }
+            on = !on;
+            index += 1;
+            if index == dash.len() {
+                index = 0;
+                period += 1.0;
+            }
+        }
+        if on {
+            current.push(point_at(to));
+        } else {
+            current.clear();
+        }
+        if t1 < 1.0 {
+            if current.len() > 1 {
+                pieces.push(std::mem::take(&mut current));
+            }
+            current.clear();
+        }
+    }
+    if current.len() > 1 {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// 描边折线
+///
+/// # Arguments
+/// * `points` - 折线顶点
+/// * `closed` - 是否闭合 (闭合且无虚线时忽略线帽)
+/// * `style` - 线宽、线帽、连接与虚线样式
+pub fn stroke_polyline(
+    raster: &mut Rasterizer,
+    points: &[Point],
+    closed: bool,
+    style: &StrokeStyle,
+) {
+    let hw = style.width / 2.0;
+    if hw <= 0.0 || !hw.is_finite() {
+        return;
+    }
+
+    if style.dash.is_empty() {
+        stroke_solid(raster, points, closed, style);
+    } else {
+        // 连接与线帽可能超出线段的范围
+        let margin = hw * style.miter_limit.max(std::f32::consts::SQRT_2) + 1.0;
+        let (width, height) = raster.size();
+        let bounds = [
+            -margin,
+            -margin,
+            width as f32 + margin,
+            height as f32 + margin,
+        ];
+        for piece in dash_polyline(points, closed, &style.dash, style.dash_offset, bounds) {
+            stroke_solid(raster, &piece, false, style);
+        }
+    }
+}
+
+/// 描边圆弧
+///
+/// 弧身直接由内外两条同心弧围成，扫过角度达到整圆时不绘制线帽。
+pub fn stroke_arc(
+    raster: &mut Rasterizer,
+    center: Point,
+    radius: f32,
+    start: f32,
+    end: f32,
+    width: f32,
+    line_cap: LineCap,
+) {
+    let hw = width / 2.0;
+    if hw <= 0.0 || !hw.is_finite() || radius < 0.0 || start == end {
+        return;
+    }
+
+    let outer_r = radius + hw;
+    let inner_r = (radius - hw).max(0.0);
+    if (end - start).abs() >= 2.0 * PI {
+        raster.contour(&ellipse(center, outer_r, outer_r));
+        if inner_r > 0.0 {
+            raster.contour(&orient(ellipse(center, inner_r, inner_r), false));
+        }
+        return;
+    }
+
+    let mut body = arc_points(center, outer_r, outer_r, start, end);
+    let inner = arc_points(center, inner_r, inner_r, start, end);
+    body.extend(inner.into_iter().rev());
+    raster.contour(&orient(body, true));
+
+    // 端点切线方向 (沿绘制方向)
+    let sign = (end - start).signum();
+    let point_at = |a: f32| Point::new(center.x + radius * a.cos(), center.y + radius * a.sin());
+    let tangent = |a: f32| Point::new(-a.sin() * sign, a.cos() * sign);
+    let t0 = tangent(start);
+    cap(
+        raster,
+        point_at(start),
+        Point::new(-t0.x, -t0.y),
+        hw,
+        line_cap,
+    );
+    cap(raster, point_at(end), tangent(end), hw, line_cap);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::FillRule;
+
+    const UNBOUNDED: [f32; 4] = [f32::MIN, f32::MIN, f32::MAX, f32::MAX];
+
+    fn area(raster: &Rasterizer) -> f32 {
+        raster.fill(FillRule::NonZero).iter().sum()
+    }
+
+    #[test]
+    fn test_line_caps() {
+        let line = |cap| {
+            let mut r = Rasterizer::new(40, 20);
+            stroke_polyline(
+                &mut r,
+                &[Point::new(10.0, 10.0), Point::new(30.0, 10.0)],
+                false,
+                &StrokeStyle::solid(4.0, cap, LineJoin::Miter),
+            );
+            area(&r)
+        };
+        assert!((line(LineCap::Butt) - 80.0).abs() < 0.01);
+        assert!((line(LineCap::Square) - 96.0).abs() < 0.01);
+        let round = line(LineCap::Round);
+        assert!((round - (80.0 + 4.0 * PI)).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_polyline_join_has_no_gap() {
+        let pts = [
+            Point::new(5.0, 5.0),
+            Point::new(30.0, 5.0),
+            Point::new(30.0, 30.0),
+        ];
+        let join = |join| {
+            let mut r = Rasterizer::new(40, 40);
+            stroke_polyline(
+                &mut r,
+                &pts,
+                false,
+                &StrokeStyle::solid(4.0, LineCap::Butt, join),
+            );
+            r.fill(FillRule::NonZero)
+        };
+
+        // 外侧拐角 (30..32, 3..5): 斜接填满、圆角大部分、斜角一半
+        let corner =
+            |cov: &[f32]| cov[3 * 40 + 30] + cov[3 * 40 + 31] + cov[4 * 40 + 30] + cov[4 * 40 + 31];
+        let miter = join(LineJoin::Miter);
+        assert!((corner(&miter) - 4.0).abs() < 0.01);
+        let round = corner(&join(LineJoin::Round));
+        assert!((round - PI).abs() < 0.1);
+        assert!((corner(&join(LineJoin::Bevel)) - 2.0).abs() < 0.01);
+        assert_eq!(miter[20 * 40 + 20], 0.0);
+    }
+
+    #[test]
+    fn test_miter_limit_falls_back_to_bevel() {
+        // 约 11° 的尖角: 斜接比约 10.2
+        let pts = [
+            Point::new(2.0, 20.0),
+            Point::new(60.0, 20.0),
+            Point::new(2.0, 31.5),
+        ];
+        let area_with = |limit: f32| {
+            let mut style = StrokeStyle::new(2.0);
+            style.set_miter_limit(limit);
+            let mut r = Rasterizer::new(80, 40);
+            stroke_polyline(&mut r, &pts, false, &style);
+            area(&r)
+        };
+        assert!(area_with(20.0) > area_with(10.0) + 3.0);
+    }
+
+    #[test]
+    fn test_dashes() {
+        let mut style = StrokeStyle::new(2.0);
+        style.set_line_dash(&[4.0, 2.0], 0.0);
+        let pieces = dash_polyline(
+            &[Point::new(0.0, 0.0), Point::new(14.0, 0.0)],
+            false,
+            &style.dash,
+            style.dash_offset,
+            UNBOUNDED,
+        );
+        let xs: Vec<(f32, f32)> = pieces.iter().map(|p| (p[0].x, p[p.len() - 1].x)).collect();
+        assert_eq!(xs, vec![(0.0, 4.0), (6.0, 10.0), (12.0, 14.0)]);
+
+        // 偏移与奇数个模式
+        style.set_line_dash(&[3.0], 1.0);
+        assert_eq!(style.dash, vec![3.0, 3.0]);
+        let pieces = dash_polyline(
+            &[Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+            false,
+            &style.dash,
+            style.dash_offset,
+            UNBOUNDED,
+        );
+        assert_eq!(pieces[0][1].x, 2.0);
+        assert_eq!(pieces[1][0].x, 5.0);
+
+        // 非法模式被忽略
+        style.set_line_dash(&[1.0, -1.0], 0.0);
+        assert_eq!(style.dash, vec![3.0, 3.0]);
+        style.set_line_dash(&[], 0.0);
+        assert!(style.dash.is_empty());
+    }
+
+    #[test]
+    fn test_dashes_clipped_and_capped() {
+        // 裁剪到可见区域后虚线相位保持不变
+        let pieces = dash_polyline(
+            &[Point::new(-100.0, 1.0), Point::new(14.0, 1.0)],
+            false,
+            &[4.0, 2.0],
+            0.0,
+            [1.0, 0.0, 20.0, 20.0],
+        );
+        let xs: Vec<(f32, f32)> = pieces.iter().map(|p| (p[0].x, p[p.len() - 1].x)).collect();
+        assert_eq!(xs, vec![(2.0, 6.0), (8.0, 12.0)]);
+        let outside = [Point::new(-50.0, -50.0), Point::new(-10.0, -50.0)];
+        assert!(
+            dash_polyline(&outside, false, &[1.0, 1.0], 0.0, [0.0, 0.0, 20.0, 20.0]).is_empty()
+        );
+
+        // 极短的虚线在长线段上既不会死循环，段数也有上限
+        let long = [Point::new(0.0, 0.0), Point::new(1.0e5, 1.0e5)];
+        let pieces = dash_polyline(&long, false, &[1e-3, 1e-3], 0.0, UNBOUNDED);
+        assert!(pieces.len() <= MAX_DASHES);
+        let mut style = StrokeStyle::new(2.0);
+        style.set_line_dash(&[1e-3, 1e-3], 0.0);
+        let mut r = Rasterizer::new(16, 16);
+        stroke_polyline(&mut r, &long, false, &style);
+        assert!(area(&r) > 0.0);
+    }
+
+    #[test]
+    fn test_dashed_stroke_across_corner() {
+        let mut style = StrokeStyle::solid(2.0, LineCap::Butt, LineJoin::Miter);
+        style.set_line_dash(&[5.0, 5.0], 0.0);
+        let mut r = Rasterizer::new(30, 30);
+        let pts = [
+            Point::new(2.0, 5.0),
+            Point::new(20.0, 5.0),
+            Point::new(20.0, 25.0),
+        ];
+        stroke_polyline(&mut r, &pts, false, &style);
+        let cov = r.fill(FillRule::NonZero);
+        assert_eq!(cov[4 * 30 + 3], 1.0);
+        assert_eq!(cov[4 * 30 + 9], 0.0);
+        assert_eq!(cov[4 * 30 + 13], 1.0);
+        // 间隔跨过拐角，第三段虚线位于竖直段 y = 7..12
+        assert_eq!(cov[9 * 30 + 19], 1.0);
+        assert_eq!(cov[14 * 30 + 19], 0.0);
+    }
+
+    #[test]
+    fn test_closed_polyline_ring() {
+        let mut r = Rasterizer::new(30, 30);
+        let square = [
+            Point::new(5.0, 5.0),
+            Point::new(25.0, 5.0),
+            Point::new(25.0, 25.0),
+            Point::new(5.0, 25.0),
+        ];
+        stroke_polyline(&mut r, &square, true, &StrokeStyle::new(2.0));
+        let cov = r.fill(FillRule::NonZero);
+        assert_eq!(cov[15 * 30 + 15], 0.0);
+        assert_eq!(cov[15 * 30 + 5], 1.0);
+        assert_eq!(cov[5 * 30 + 15], 1.0);
+    }
+
+    #[test]
+    fn test_stroke_arc() {
+        // 半圆环: 面积 = π (R² - r²) / 2
+        let mut r = Rasterizer::new(40, 40);
+        stroke_arc(
+            &mut r,
+            Point::new(20.0, 20.0),
+            10.0,
+            0.0,
+            PI,
+            4.0,
+            LineCap::Butt,
+        );
+        let expected = PI * (144.0 - 64.0) / 2.0;
+        assert!((area(&r) - expected).abs() < 2.0);
+
+        // 整圆环中心为空
+        let mut r = Rasterizer::new(40, 40);
+        stroke_arc(
+            &mut r,
+            Point::new(20.0, 20.0),
+            10.0,
+            0.0,
+            2.0 * PI,
+            4.0,
+            LineCap::Round,
+        );
+        assert_eq!(r.fill(FillRule::NonZero)[20 * 40 + 20], 0.0);
+    }
+
+    #[test]
+    fn test_style_from_u8() {
+        assert_eq!(LineCap::from_u8(1), LineCap::Round);
+        assert_eq!(LineCap::from_u8(7), LineCap::Butt);
+        assert_eq!(LineJoin::from_u8(2), LineJoin::Bevel);
+        assert_eq!(LineJoin::from_u8(9), LineJoin::Miter);
+    }
+}
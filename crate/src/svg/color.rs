@@ -0,0 +1,188 @@
+//! SVG 颜色与画笔解析
+
+/// 填充 / 描边画笔
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paint {
+    /// 不绘制
+    None,
+    /// 纯色 (RGBA)
+    Color([u8; 4]),
+}
+
+/// 常用 CSS 颜色名
+const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("black", [0, 0, 0]),
+    ("white", [255, 255, 255]),
+    ("red", [255, 0, 0]),
+    ("lime", [0, 255, 0]),
+    ("green", [0, 128, 0]),
+    ("blue", [0, 0, 255]),
+    ("yellow", [255, 255, 0]),
+    ("cyan", [0, 255, 255]),
+    ("aqua", [0, 255, 255]),
+    ("magenta", [255, 0, 255]),
+    ("fuchsia", [255, 0, 255]),
+    ("gray", [128, 128, 128]),
+    ("grey", [128, 128, 128]),
+    ("silver", [192, 192, 192]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgrey", [211, 211, 211]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgrey", [169, 169, 169]),
+    ("maroon", [128, 0, 0]),
+    ("olive", [128, 128, 0]),
+    ("navy", [0, 0, 128]),
+    ("purple", [128, 0, 128]),
+    ("teal", [0, 128, 128]),
+    ("orange", [255, 165, 0]),
+    ("pink", [255, 192, 203]),
+    ("brown", [165, 42, 42]),
+    ("gold", [255, 215, 0]),
+    ("indigo", [75, 0, 130]),
+    ("violet", [238, 130, 238]),
+    ("crimson", [220, 20, 60]),
+    ("tomato", [255, 99, 71]),
+    ("coral", [255, 127, 80]),
+    ("salmon", [250, 128, 114]),
+    ("skyblue", [135, 206, 235]),
+    ("steelblue", [70, 130, 180]),
+    ("royalblue", [65, 105, 225]),
+    ("dodgerblue", [30, 144, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkred", [139, 0, 0]),
+    ("forestgreen", [34, 139, 34]),
+    ("limegreen", [50, 205, 50]),
+    ("whitesmoke", [245, 245, 245]),
+    ("gainsboro", [220, 220, 220]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+];
+
+/// 解析十六进制颜色 (#rgb / #rgba / #rrggbb / #rrggbbaa)
+fn parse_hex(hex: &str) -> Option<[u8; 4]> {
+    let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+    let pair = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match hex.len() {
+        3 | 4 => {
+            let mut c = [255u8; 4];
+            for (i, v) in c.iter_mut().take(hex.len()).enumerate() {
+                *v = digit(i)? * 17;
+            }
+            Some(c)
+        }
+        6 | 8 => {
+            let mut c = [255u8; 4];
+            for (i, v) in c.iter_mut().take(hex.len() / 2).enumerate() {
+                *v = pair(i * 2)?;
+            }
+            Some(c)
+        }
+        _ => None,
+    }
+}
+
+/// 解析 rgb() / rgba() 函数 (分量可为数值或百分比，Alpha 为 0~1 或百分比)
+fn parse_rgb_function(args: &str) -> Option<[u8; 4]> {
+    let parts: Vec<&str> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.len() != 3 && parts.len() != 4 {
+        return None;
+    }
+    let channel = |p: &str| -> Option<u8> {
+        let v = match p.strip_suffix('%') {
+            Some(pct) => pct.parse::<f32>().ok()? * 2.55,
+            None => p.parse::<f32>().ok()?,
+        };
+        Some(v.round().clamp(0.0, 255.0) as u8)
+    };
+    let alpha = match parts.get(3) {
+        Some(p) => {
+            let v = match p.strip_suffix('%') {
+                Some(pct) => pct.parse::<f32>().ok()? / 100.0,
+                None => p.parse::<f32>().ok()?,
+            };
+            (v.clamp(0.0, 1.0) * 255.0).round() as u8
+        }
+        None => 255,
+    };
+    Some([
+        channel(parts[0])?,
+        channel(parts[1])?,
+        channel(parts[2])?,
+        alpha,
+    ])
+}
+
+/// 解析颜色值
+pub fn parse_color(value: &str) -> Option<[u8; 4]> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    let lower = value.to_ascii_lowercase();
+    if let Some(args) = lower
+        .strip_prefix("rgba(")
+        .or_else(|| lower.strip_prefix("rgb("))
+    {
+        return parse_rgb_function(args.strip_suffix(')')?);
+    }
+    if lower == "transparent" {
+        return Some([0, 0, 0, 0]);
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| *name == lower)
+        .map(|(_, [r, g, b])| [*r, *g, *b, 255])
+}
+
+/// 解析 fill / stroke 属性值
+///
+/// `url(#id)` 引用的渐变与图案不受支持，使用其后的备用颜色，无备用颜色时不绘制。
+/// `currentColor` 视为黑色。无法识别的值返回 None (保持继承值)。
+pub fn parse_paint(value: &str) -> Option<Paint> {
+    let value = value.trim();
+    if value == "none" {
+        return Some(Paint::None);
+    }
+    if value == "currentColor" {
+        return Some(Paint::Color([0, 0, 0, 255]));
+    }
+    if let Some(rest) = value.strip_prefix("url(") {
+        let fallback = rest.split_once(')').map_or("", |(_, f)| f.trim());
+        return Some(parse_paint(fallback).unwrap_or(Paint::None));
+    }
+    parse_color(value).map(Paint::Color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_and_functions() {
+        assert_eq!(parse_color("#f00"), Some([255, 0, 0, 255]));
+        assert_eq!(parse_color("#0f08"), Some([0, 255, 0, 136]));
+        assert_eq!(parse_color("#123456"), Some([0x12, 0x34, 0x56, 255]));
+        assert_eq!(parse_color("rgb(10, 20, 30)"), Some([10, 20, 30, 255]));
+        assert_eq!(parse_color("rgba(100%,0%,0%,0.5)"), Some([255, 0, 0, 128]));
+        assert_eq!(parse_color("Navy"), Some([0, 0, 128, 255]));
+        assert_eq!(parse_color("#12"), None);
+        assert_eq!(parse_color("nocolor"), None);
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(parse_paint("none"), Some(Paint::None));
+        assert_eq!(
+            parse_paint("url(#g) red"),
+            Some(Paint::Color([255, 0, 0, 255]))
+        );
+        assert_eq!(parse_paint("url(#g)"), Some(Paint::None));
+        assert_eq!(parse_paint("inherit"), None);
+    }
+}
@@ -0,0 +1,663 @@
+//! SVG 文档模型
+//!
+//! 将 XML 元素树展开为带变换与样式的形状列表，按需以任意尺寸栅格化。
+//! 支持 path、rect、circle、ellipse、line、polyline、polygon 以及带变换的 g 分组，
+//! 纯色填充与描边、填充规则、线帽 / 连接 / 虚线和各类不透明度。
+//!
+//! 分组不透明度按乘法下放到各子形状 (不做离屏合成)，子形状重叠时与浏览器略有差异。
+
+use super::color::{parse_paint, Paint};
+use super::path_data::{parse_path_data, NumberScanner};
+use crate::image::{Image, MAX_DIMENSION};
+use crate::math::Matrix3x3;
+use crate::raster::{
+    fill_path_into, stroke_path_into, FillRule, LineCap, LineJoin, Path, StrokeStyle,
+};
+use crate::xml::{parse_xml, Element};
+
+/// 未指定宽高且无 viewBox 时的默认尺寸
+const DEFAULT_SIZE: f32 = 100.0;
+
+/// 圆弧的三次贝塞尔近似控制点系数
+const KAPPA: f32 = 0.552_284_8;
+
+/// 可继承的绘制样式
+#[derive(Debug, Clone)]
+struct Style {
+    fill: Paint,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: Paint,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f32,
+    dash: Vec<f32>,
+    dash_offset: f32,
+    /// 累积的元素不透明度 (opacity)
+    opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Paint::Color([0, 0, 0, 255]),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: Paint::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            miter_limit: 4.0,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// 展开后的单个形状
+#[derive(Debug, Clone)]
+struct Shape {
+    /// 用户坐标下的路径
+    path: Path,
+    /// 用户坐标 → 文档视口坐标
+    transform: Matrix3x3,
+    /// 最终填充颜色 (已乘入不透明度)
+    fill: Option<[u8; 4]>,
+    fill_rule: FillRule,
+    /// 最终描边颜色 (已乘入不透明度)
+    stroke: Option<[u8; 4]>,
+    stroke_width: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f32,
+    dash: Vec<f32>,
+    dash_offset: f32,
+}
+
+/// 解析后的 SVG 文档
+#[derive(Debug, Clone)]
+pub struct SvgDocument {
+    /// 固有宽度 (像素)
+    pub width: f32,
+    /// 固有高度 (像素)
+    pub height: f32,
+    shapes: Vec<Shape>,
+}
+
+/// 将画笔与不透明度合成为最终颜色，完全透明时返回 None
+fn resolve_paint(paint: Paint, opacity: f32) -> Option<[u8; 4]> {
+    match paint {
+        Paint::None => None,
+        Paint::Color([r, g, b, a]) => {
+            let alpha = (a as f32 * opacity.clamp(0.0, 1.0) + 0.5) as u8;
+            (alpha > 0).then_some([r, g, b, alpha])
+        }
+    }
+}
+
+/// 解析长度 (支持绝对单位与百分比，百分比相对 `reference`)
+fn parse_length(value: &str, reference: f32) -> Option<f32> {
+    let value = value.trim();
+    let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%');
+    let unit = &value[number.len()..];
+    let number: f32 = number.trim().parse().ok()?;
+    let factor = match unit.trim() {
+        "" | "px" => 1.0,
+        "%" => reference / 100.0,
+        "pt" => 4.0 / 3.0,
+        "pc" => 16.0,
+        "mm" => 96.0 / 25.4,
+        "cm" => 96.0 / 2.54,
+        "in" => 96.0,
+        "em" => 16.0,
+        "ex" => 8.0,
+        _ => return None,
+    };
+    let length = number * factor;
+    length.is_finite().then_some(length)
+}
+
+/// 解析不透明度 (数值或百分比)
+fn parse_opacity(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let v = match value.strip_suffix('%') {
+        Some(pct) => pct.trim().parse::<f32>().ok()? / 100.0,
+        None => value.parse::<f32>().ok()?,
+    };
+    v.is_finite().then_some(v.clamp(0.0, 1.0))
+}
+
+/// 解析 transform 属性 (变换列表从左到右组合)
+fn parse_transform(value: &str) -> Matrix3x3 {
+    let mut result = Matrix3x3::identity();
+    let mut rest = value;
+    while let Some(open) = rest.find('(') {
+        let name = rest[..open].trim_matches(|c: char| c.is_whitespace() || c == ',');
+        let Some(close) = rest[open..].find(')') else {
+            break;
+        };
+        let args = NumberScanner::new(&rest[open + 1..open + close]).numbers();
+        rest = &rest[open + close + 1..];
+        let arg = |i: usize| args.get(i).copied();
+        let t = match (name, args.len()) {
+            ("matrix", 6) => {
+                Matrix3x3::affine(args[0], args[2], args[1], args[3], args[4], args[5])
+            }
+            ("translate", 1 | 2) => Matrix3x3::translation(args[0], arg(1).unwrap_or(0.0)),
+            ("scale", 1 | 2) => Matrix3x3::scale(args[0], arg(1).unwrap_or(args[0])),
+            ("rotate", 1) => Matrix3x3::rotation(args[0].to_radians()),
+            ("rotate", 3) => Matrix3x3::translation(args[1], args[2])
+                .multiply(&Matrix3x3::rotation(args[0].to_radians()))
+                .multiply(&Matrix3x3::translation(-args[1], -args[2])),
+            ("skewX", 1) => Matrix3x3::affine(1.0, args[0].to_radians().tan(), 0.0, 1.0, 0.0, 0.0),
+            ("skewY", 1) => Matrix3x3::affine(1.0, 0.0, args[0].to_radians().tan(), 1.0, 0.0, 0.0),
+            // 无法识别的变换使整个属性无效
+            _ => return Matrix3x3::identity(),
+        };
+        result = result.multiply(&t);
+    }
+    result
+}
+
+/// 收集元素的表现属性 (style 属性中的声明优先于同名属性)
+fn properties(element: &Element) -> Vec<(&str, &str)> {
+    let mut props: Vec<(&str, &str)> = element
+        .attributes
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    if let Some(style) = element.attr("style") {
+        props.extend(style.split(';').filter_map(|decl| {
+            let (k, v) = decl.split_once(':')?;
+            Some((k.trim(), v.trim()))
+        }));
+    }
+    props
+}
+
+impl Style {
+    /// 应用单个表现属性，无法识别的值保持继承值
+    fn apply(&mut self, name: &str, value: &str, diagonal: f32) {
+        let value = value.trim_end_matches("!important").trim();
+        match name {
+            "fill" => {
+                if let Some(paint) = parse_paint(value) {
+                    self.fill = paint;
+                }
+            }
+            "stroke" => {
+                if let Some(paint) = parse_paint(value) {
+                    self.stroke = paint;
+                }
+            }
+            "fill-opacity" => self.fill_opacity = parse_opacity(value).unwrap_or(self.fill_opacity),
+            "stroke-opacity" => {
+                self.stroke_opacity = parse_opacity(value).unwrap_or(self.stroke_opacity)
+            }
+            "opacity" => self.opacity *= parse_opacity(value).unwrap_or(1.0),
+            "fill-rule" => match value {
+                "nonzero" => self.fill_rule = FillRule::NonZero,
+                "evenodd" => self.fill_rule = FillRule::EvenOdd,
+                _ => {}
+            },
+            "stroke-width" => {
+                if let Some(w) = parse_length(value, diagonal).filter(|w| *w >= 0.0) {
+                    self.stroke_width = w;
+                }
+            }
+            "stroke-linecap" => match value {
+                "butt" => self.line_cap = LineCap::Butt,
+                "round" => self.line_cap = LineCap::Round,
+                "square" => self.line_cap = LineCap::Square,
+                _ => {}
+            },
+            "stroke-linejoin" => match value {
+                "miter" | "miter-clip" => self.line_join = LineJoin::Miter,
+                "round" => self.line_join = LineJoin::Round,
+                "bevel" => self.line_join = LineJoin::Bevel,
+                _ => {}
+            },
+            "stroke-miterlimit" => {
+                if let Some(limit) = value.parse::<f32>().ok().filter(|l| *l >= 1.0) {
+                    self.miter_limit = limit;
+                }
+            }
+            "stroke-dasharray" => {
+                self.dash = if value == "none" {
+                    Vec::new()
+                } else {
+                    value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| parse_length(s, diagonal))
+                        .collect::<Option<Vec<f32>>>()
+                        .unwrap_or_default()
+                };
+            }
+            "stroke-dashoffset" => {
+                self.dash_offset = parse_length(value, diagonal).unwrap_or(self.dash_offset)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 构建圆角矩形路径 (圆角以三次贝塞尔近似)
+fn rect_path(x: f32, y: f32, w: f32, h: f32, rx: f32, ry: f32) -> Path {
+    let mut path = Path::new();
+    if rx <= 0.0 || ry <= 0.0 {
+        path.move_to(x, y);
+        path.line_to(x + w, y);
+        path.line_to(x + w, y + h);
+        path.line_to(x, y + h);
+        path.close();
+        return path;
+    }
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    let (r, b) = (x + w, y + h);
+    path.move_to(x + rx, y);
+    path.line_to(r - rx, y);
+    path.cubic_to(r - rx + kx, y, r, y + ry - ky, r, y + ry);
+    path.line_to(r, b - ry);
+    path.cubic_to(r, b - ry + ky, r - rx + kx, b, r - rx, b);
+    path.line_to(x + rx, b);
+    path.cubic_to(x + rx - kx, b, x, b - ry + ky, x, b - ry);
+    path.line_to(x, y + ry);
+    path.cubic_to(x, y + ry - ky, x + rx - kx, y, x + rx, y);
+    path.close();
+    path
+}
+
+/// 构建椭圆路径 (四段三次贝塞尔)
+fn ellipse_path(cx: f32, cy: f32, rx: f32, ry: f32) -> Path {
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    let mut path = Path::new();
+    path.move_to(cx + rx, cy);
+    path.cubic_to(cx + rx, cy + ky, cx + kx, cy + ry, cx, cy + ry);
+    path.cubic_to(cx - kx, cy + ry, cx - rx, cy + ky, cx - rx, cy);
+    path.cubic_to(cx - rx, cy - ky, cx - kx, cy - ry, cx, cy - ry);
+    path.cubic_to(cx + kx, cy - ry, cx + rx, cy - ky, cx + rx, cy);
+    path.close();
+    path
+}
+
+/// 构建折线 / 多边形路径
+fn points_path(points: &str, closed: bool) -> Option<Path> {
+    let coords = NumberScanner::new(points).numbers();
+    let mut pairs = coords.chunks_exact(2);
+    let first = pairs.next()?;
+    let mut path = Path::new();
+    path.move_to(first[0], first[1]);
+    for p in pairs {
+        path.line_to(p[0], p[1]);
+    }
+    if closed {
+        path.close();
+    }
+    Some(path)
+}
+
+/// 展开元素树时的上下文
+struct Builder {
+    /// 当前视口尺寸 (用于百分比长度)
+    viewport: (f32, f32),
+    shapes: Vec<Shape>,
+}
+
+impl Builder {
+    fn length(&self, element: &Element, name: &str, reference: f32) -> f32 {
+        element
+            .attr(name)
+            .and_then(|v| parse_length(v, reference))
+            .unwrap_or(0.0)
+    }
+
+    fn diagonal(&self) -> f32 {
+        let (w, h) = self.viewport;
+        ((w * w + h * h) / 2.0).sqrt()
+    }
+
+    /// 构建基本形状的路径，不支持或无效的元素返回 None
+    fn shape_path(&self, element: &Element) -> Option<Path> {
+        let (vw, vh) = self.viewport;
+        let diag = self.diagonal();
+        match element.name.as_str() {
+            "path" => Some(parse_path_data(element.attr("d")?)),
+            "rect" => {
+                let w = self.length(element, "width", vw);
+                let h = self.length(element, "height", vh);
+                if w <= 0.0 || h <= 0.0 {
+                    return None;
+                }
+                let rx = element.attr("rx").and_then(|v| parse_length(v, vw));
+                let ry = element.attr("ry").and_then(|v| parse_length(v, vh));
+                let (rx, ry) = match (rx, ry) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (None, None) => (0.0, 0.0),
+                };
+                Some(rect_path(
+                    self.length(element, "x", vw),
+                    self.length(element, "y", vh),
+                    w,
+                    h,
+                    rx.clamp(0.0, w / 2.0),
+                    ry.clamp(0.0, h / 2.0),
+                ))
+            }
+            "circle" => {
+                let r = self.length(element, "r", diag);
+                (r > 0.0).then(|| {
+                    ellipse_path(
+                        self.length(element, "cx", vw),
+                        self.length(element, "cy", vh),
+                        r,
+                        r,
+                    )
+                })
+            }
+            "ellipse" => {
+                let rx = self.length(element, "rx", vw);
+                let ry = self.length(element, "ry", vh);
+                (rx > 0.0 && ry > 0.0).then(|| {
+                    ellipse_path(
+                        self.length(element, "cx", vw),
+                        self.length(element, "cy", vh),
+                        rx,
+                        ry,
+                    )
+                })
+            }
+            "line" => {
+                let mut path = Path::new();
+                path.move_to(
+                    self.length(element, "x1", vw),
+                    self.length(element, "y1", vh),
+                );
+                path.line_to(
+                    self.length(element, "x2", vw),
+                    self.length(element, "y2", vh),
+                );
+                Some(path)
+            }
+            "polyline" => points_path(element.attr("points")?, false),
+            "polygon" => points_path(element.attr("points")?, true),
+            _ => None,
+        }
+    }
+
+    /// 递归展开元素
+    fn visit(&mut self, element: &Element, parent: &Style, ctm: &Matrix3x3, depth: usize) {
+        if depth > 64 {
+            return;
+        }
+        let props = properties(element);
+        if props.iter().any(|(k, v)| {
+            (*k == "display" && *v == "none") || (*k == "visibility" && *v == "hidden")
+        }) {
+            return;
+        }
+        let mut style = parent.clone();
+        let diagonal = self.diagonal();
+        for (name, value) in &props {
+            style.apply(name, value, diagonal);
+        }
+        let ctm = match element.attr("transform") {
+            Some(t) => ctm.multiply(&parse_transform(t)),
+            None => *ctm,
+        };
+
+        match element.name.as_str() {
+            "svg" | "g" | "a" | "switch" => {
+                for child in &element.children {
+                    self.visit(child, &style, &ctm, depth + 1);
+                }
+            }
+            _ => {
+                let Some(path) = self.shape_path(element) else {
+                    return;
+                };
+                let fill = resolve_paint(style.fill, style.fill_opacity * style.opacity);
+                let stroke = resolve_paint(style.stroke, style.stroke_opacity * style.opacity)
+                    .filter(|_| style.stroke_width > 0.0);
+                if fill.is_none() && stroke.is_none() {
+                    return;
+                }
+                self.shapes.push(Shape {
+                    path,
+                    transform: ctm,
+                    fill,
+                    fill_rule: style.fill_rule,
+                    stroke,
+                    stroke_width: style.stroke_width,
+                    line_cap: style.line_cap,
+                    line_join: style.line_join,
+                    miter_limit: style.miter_limit,
+                    dash: style.dash.clone(),
+                    dash_offset: style.dash_offset,
+                });
+            }
+        }
+    }
+}
+
+/// 计算 viewBox 到视口的映射 (preserveAspectRatio)
+fn view_box_transform(view_box: [f32; 4], width: f32, height: f32, aspect: &str) -> Matrix3x3 {
+    let [vx, vy, vw, vh] = view_box;
+    let mut sx = width / vw;
+    let mut sy = height / vh;
+    let mut parts = aspect.split_whitespace();
+    let align = parts.next().unwrap_or("xMidYMid");
+    if align != "none" {
+        let s = if parts.next() == Some("slice") {
+            sx.max(sy)
+        } else {
+            sx.min(sy)
+        };
+        sx = s;
+        sy = s;
+    }
+    let offset = |key_min: &str, key_max: &str, free: f32| {
+        if align.contains(key_min) {
+            0.0
+        } else if align.contains(key_max) {
+            free
+        } else {
+            free / 2.0
+        }
+    };
+    let tx = if align == "none" {
+        0.0
+    } else {
+        offset("xMin", "xMax", width - vw * sx)
+    };
+    let ty = if align == "none" {
+        0.0
+    } else {
+        offset("YMin", "YMax", height - vh * sy)
+    };
+    Matrix3x3::affine(sx, 0.0, 0.0, sy, tx - vx * sx, ty - vy * sy)
+}
+
+impl SvgDocument {
+    /// 解析 SVG 文本
+    ///
+    /// 根元素不是 svg 或 XML 格式错误时返回 None。
+    /// 未指定 width / height 时取 viewBox 尺寸，两者都没有时为 100×100。
+    pub fn parse(text: &str) -> Option<Self> {
+        let root = parse_xml(text)?;
+        if root.name != "svg" {
+            return None;
+        }
+        let view_box = root
+            .attr("viewBox")
+            .map(|v| NumberScanner::new(v).numbers())
+            .filter(|v| v.len() == 4 && v[2] > 0.0 && v[3] > 0.0)
+            .map(|v| [v[0], v[1], v[2], v[3]]);
+        let size = |name: &str, fallback: f32| {
+            root.attr(name)
+                .filter(|v| !v.trim().ends_with('%'))
+                .and_then(|v| parse_length(v, 0.0))
+                .filter(|v| *v > 0.0)
+                .unwrap_or(fallback)
+        };
+        let width = size("width", view_box.map_or(DEFAULT_SIZE, |v| v[2]));
+        let height = size("height", view_box.map_or(DEFAULT_SIZE, |v| v[3]));
+
+        let (viewport, user_size) = match view_box {
+            Some(vb) => (
+                view_box_transform(
+                    vb,
+                    width,
+                    height,
+                    root.attr("preserveAspectRatio").unwrap_or(""),
+                ),
+                (vb[2], vb[3]),
+            ),
+            None => (Matrix3x3::identity(), (width, height)),
+        };
+        let mut builder = Builder {
+            viewport: user_size,
+            shapes: Vec::new(),
+        };
+        // 根元素自身的 transform 不参与 (SVG 1.1)，其余表现属性照常继承
+        let mut root = root;
+        root.attributes.retain(|(k, _)| k != "transform");
+        builder.visit(&root, &Style::default(), &viewport, 0);
+        Some(Self {
+            width,
+            height,
+            shapes: builder.shapes,
+        })
+    }
+
+    /// 栅格化文档
+    ///
+    /// `transform` 将文档视口坐标 (0..width, 0..height) 映射到输出图像坐标，
+    /// 描边宽度与虚线长度按其平均缩放比例缩放。
+    /// 输出尺寸为 0 或过大时返回 None。
+    pub fn render(&self, transform: &Matrix3x3, out_width: u32, out_height: u32) -> Option<Image> {
+        if out_width > MAX_DIMENSION || out_height > MAX_DIMENSION {
+            return None;
+        }
+        let mut image = Image::new(out_width, out_height)?;
+        for shape in &self.shapes {
+            let total = transform.multiply(&shape.transform);
+            let m = total.data();
+            let scale = (m[0] * m[4] - m[1] * m[3]).abs().sqrt();
+            let mut path = shape.path.clone();
+            path.transform(&total);
+            if let Some(color) = shape.fill {
+                fill_path_into(
+                    &mut image.data,
+                    out_width,
+                    out_height,
+                    &path,
+                    shape.fill_rule,
+                    color,
+                );
+            }
+            if let Some(color) = shape.stroke {
+                let mut style =
+                    StrokeStyle::solid(shape.stroke_width * scale, shape.line_cap, shape.line_join);
+                style.set_miter_limit(shape.miter_limit);
+                let dash: Vec<f32> = shape.dash.iter().map(|d| d * scale).collect();
+                style.set_line_dash(&dash, shape.dash_offset * scale);
+                stroke_path_into(&mut image.data, out_width, out_height, &path, &style, color);
+            }
+        }
+        Some(image)
+    }
+
+    /// 以指定尺寸栅格化 (拉伸整个视口到输出尺寸)
+    pub fn render_to_size(&self, width: u32, height: u32) -> Option<Image> {
+        let transform = Matrix3x3::scale(width as f32 / self.width, height as f32 / self.height);
+        self.render(&transform, width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(image: &Image, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * image.width + x) * 4) as usize;
+        image.data[i..i + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let doc = SvgDocument::parse(r#"<svg viewBox="0 0 24 12"/>"#).unwrap();
+        assert_eq!((doc.width, doc.height), (24.0, 12.0));
+        let doc = SvgDocument::parse(r#"<svg width="2in" height="10mm"/>"#).unwrap();
+        assert_eq!(doc.width, 192.0);
+        assert!((doc.height - 37.795).abs() < 0.01);
+        let doc = SvgDocument::parse(r#"<svg width="100%"/>"#).unwrap();
+        assert_eq!((doc.width, doc.height), (100.0, 100.0));
+        assert!(SvgDocument::parse("<html/>").is_none());
+    }
+
+    #[test]
+    fn test_transform_list() {
+        let m = parse_transform("translate(10, 5) scale(2)");
+        let (x, y) = m.transform_point(1.0, 1.0);
+        assert!((x - 12.0).abs() < 1e-4 && (y - 7.0).abs() < 1e-4);
+        let m = parse_transform("rotate(90 10 10)");
+        let (x, y) = m.transform_point(20.0, 10.0);
+        assert!((x - 10.0).abs() < 1e-4 && (y - 20.0).abs() < 1e-4);
+        let m = parse_transform("matrix(1 0 0 1 3 4)");
+        assert_eq!(m.transform_point(0.0, 0.0), (3.0, 4.0));
+    }
+
+    #[test]
+    fn test_render_shapes() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+            <rect x="0" y="0" width="5" height="10" fill="#ff0000"/>
+            <g transform="translate(5 0)" style="fill: blue">
+                <circle cx="2.5" cy="5" r="2"/>
+            </g>
+        </svg>"##;
+        let doc = SvgDocument::parse(svg).unwrap();
+        let image = doc.render_to_size(20, 20).unwrap();
+        assert_eq!(pixel(&image, 2, 10), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 15, 10), [0, 0, 255, 255]);
+        assert_eq!(pixel(&image, 15, 1)[3], 0);
+    }
+
+    #[test]
+    fn test_viewbox_meet_centers() {
+        let svg = r#"<svg width="20" height="10" viewBox="0 0 10 10"><rect width="10" height="10"/></svg>"#;
+        let image = SvgDocument::parse(svg)
+            .unwrap()
+            .render_to_size(20, 10)
+            .unwrap();
+        assert_eq!(pixel(&image, 2, 5)[3], 0);
+        assert_eq!(pixel(&image, 10, 5)[3], 255);
+        assert_eq!(pixel(&image, 17, 5)[3], 0);
+    }
+
+    #[test]
+    fn test_stroke_and_opacity() {
+        let svg = r#"<svg width="20" height="20">
+            <g opacity="0.5"><line x1="0" y1="10" x2="20" y2="10" stroke="black" stroke-width="4"/></g>
+            <rect x="2" y="2" width="4" height="4" fill="none" stroke="none"/>
+        </svg>"#;
+        let doc = SvgDocument::parse(svg).unwrap();
+        assert_eq!(doc.shapes.len(), 1);
+        let image = doc.render_to_size(40, 40).unwrap();
+        // 线宽随缩放放大: 4 * 2 = 8 像素
+        assert_eq!(pixel(&image, 20, 17)[3], 128);
+        assert_eq!(pixel(&image, 20, 24)[3], 0);
+    }
+
+    #[test]
+    fn test_display_none_skipped() {
+        let svg =
+            r#"<svg width="4" height="4"><rect width="4" height="4" style="display:none"/></svg>"#;
+        assert!(SvgDocument::parse(svg).unwrap().shapes.is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+//! SVG 支持
+//!
+//! 解析常见 SVG 图标子集并栅格化为 RGBA 图像。
+//! 不支持文本、渐变、图案、滤镜、遮罩、CSS 样式表与 use 引用。
+
+mod color;
+mod document;
+mod path_data;
+
+pub use document::SvgDocument;
@@ -0,0 +1,423 @@
+//! SVG 路径数据解析
+//!
+//! 支持全部路径命令 (M L H V C S Q T A Z 及其相对形式)，椭圆弧转换为三次贝塞尔曲线。
+//! 遇到语法错误时停止解析并保留已解析的部分，与浏览器行为一致。
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::raster::Path;
+
+/// 数字扫描器 (处理 "1.5.5"、"-1-2"、"1e-3" 等紧凑写法)
+pub struct NumberScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NumberScanner<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    /// 跳过空白与逗号
+    fn skip_separators(&mut self) {
+        while self.pos < self.bytes.len()
+            && (self.bytes[self.pos].is_ascii_whitespace() || self.bytes[self.pos] == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// 查看下一个非分隔字符
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// 读取一个数字
+    pub fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.pos;
+        let at = |i: usize| self.bytes.get(i).copied().unwrap_or(0);
+        let mut i = self.pos;
+        if matches!(at(i), b'+' | b'-') {
+            i += 1;
+        }
+        let mut digits = false;
+        while at(i).is_ascii_digit() {
+            i += 1;
+            digits = true;
+        }
+        if at(i) == b'.' {
+            i += 1;
+            while at(i).is_ascii_digit() {
+                i += 1;
+                digits = true;
+            }
+        }
+        if !digits {
+            return None;
+        }
+        if matches!(at(i), b'e' | b'E') {
+            let mut j = i + 1;
+            if matches!(at(j), b'+' | b'-') {
+                j += 1;
+            }
+            if at(j).is_ascii_digit() {
+                while at(j).is_ascii_digit() {
+                    j += 1;
+                }
+                i = j;
+            }
+        }
+        self.pos = i;
+        std::str::from_utf8(&self.bytes[start..i])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// 读取椭圆弧标志位 (单个 0 / 1，可与后续数字紧连)
+    fn flag(&mut self) -> Option<bool> {
+        match self.peek()? {
+            b'0' => {
+                self.pos += 1;
+                Some(false)
+            }
+            b'1' => {
+                self.pos += 1;
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    /// 读取剩余全部数字
+    pub fn numbers(&mut self) -> Vec<f32> {
+        std::iter::from_fn(|| self.number()).collect()
+    }
+}
+
+/// 将 SVG 椭圆弧 (端点参数化) 转换为三次贝塞尔曲线并追加到路径
+///
+/// 参见 SVG 规范附录 F.6.5 / F.6.6 的端点到中心参数化转换与半径修正。
+#[allow(clippy::too_many_arguments)]
+fn arc_to(
+    path: &mut Path,
+    from: (f32, f32),
+    rx: f32,
+    ry: f32,
+    rotation_deg: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: (f32, f32),
+) {
+    let (x1, y1) = from;
+    let (x2, y2) = to;
+    if (x1, y1) == (x2, y2) {
+        return;
+    }
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    if rx == 0.0 || ry == 0.0 {
+        path.line_to(x2, y2);
+        return;
+    }
+
+    let phi = rotation_deg.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let dx = (x1 - x2) / 2.0;
+    let dy = (y1 - y2) / 2.0;
+    let x1p = cos_phi * dx + sin_phi * dy;
+    let y1p = -sin_phi * dx + cos_phi * dy;
+
+    // 半径不足时等比放大
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        let k = lambda.sqrt();
+        rx *= k;
+        ry *= k;
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+    let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let cxp = coef * rx * y1p / ry;
+    let cyp = -coef * ry * x1p / rx;
+    let cx = cos_phi * cxp - sin_phi * cyp + (x1 + x2) / 2.0;
+    let cy = sin_phi * cxp + cos_phi * cyp + (y1 + y2) / 2.0;
+
+    let angle = |ux: f32, uy: f32, vx: f32, vy: f32| {
+        let a = (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+        if a.is_nan() {
+            0.0
+        } else {
+            a
+        }
+    };
+    let ux = (x1p - cxp) / rx;
+    let uy = (y1p - cyp) / ry;
+    let vx = (-x1p - cxp) / rx;
+    let vy = (-y1p - cyp) / ry;
+    let theta1 = angle(1.0, 0.0, ux, uy);
+    let mut delta = angle(ux, uy, vx, vy);
+    if !sweep && delta > 0.0 {
+        delta -= TAU;
+    } else if sweep && delta < 0.0 {
+        delta += TAU;
+    }
+
+    // 每段不超过 90°
+    let segments = (delta.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = delta / segments as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let point = |t: f32| {
+        let (sin_t, cos_t) = t.sin_cos();
+        let x = rx * cos_t;
+        let y = ry * sin_t;
+        (
+            cx + cos_phi * x - sin_phi * y,
+            cy + sin_phi * x + cos_phi * y,
+        )
+    };
+    let derivative = |t: f32| {
+        let (sin_t, cos_t) = t.sin_cos();
+        let x = -rx * sin_t;
+        let y = ry * cos_t;
+        (cos_phi * x - sin_phi * y, sin_phi * x + cos_phi * y)
+    };
+
+    let mut t = theta1;
+    for i in 0..segments {
+        let t2 = t + step;
+        let (p1, d1) = (point(t), derivative(t));
+        let (p2, d2) = (point(t2), derivative(t2));
+        // 最后一段精确落在终点
+        let end = if i + 1 == segments { (x2, y2) } else { p2 };
+        path.cubic_to(
+            p1.0 + k * d1.0,
+            p1.1 + k * d1.1,
+            p2.0 - k * d2.0,
+            p2.1 - k * d2.1,
+            end.0,
+            end.1,
+        );
+        t = t2;
+    }
+}
+
+/// 读取 n 个数字，不足时返回 None
+fn read(scanner: &mut NumberScanner, n: usize) -> Option<Vec<f32>> {
+    (0..n).map(|_| scanner.number()).collect()
+}
+
+/// 解析 `d` 属性为路径
+pub fn parse_path_data(data: &str) -> Path {
+    let mut path = Path::new();
+    let mut scanner = NumberScanner::new(data);
+
+    let mut current = (0.0f32, 0.0f32);
+    let mut start = (0.0f32, 0.0f32);
+    // 上一条曲线的第二个控制点 (用于 S / T 反射)
+    let mut last_cubic: Option<(f32, f32)> = None;
+    let mut last_quad: Option<(f32, f32)> = None;
+    let mut command = 0u8;
+
+    while let Some(next) = scanner.peek() {
+        if next.is_ascii_alphabetic() {
+            command = next;
+            scanner.pos += 1;
+        } else if command == 0 {
+            break;
+        } else if matches!(command, b'M' | b'm') {
+            // moveto 之后的隐式坐标对视为 lineto
+            command = if command == b'M' { b'L' } else { b'l' };
+        }
+
+        let relative = command.is_ascii_lowercase();
+        let (ox, oy) = if relative { current } else { (0.0, 0.0) };
+        let upper = command.to_ascii_uppercase();
+
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+        match upper {
+            b'Z' => {
+                path.close();
+                current = start;
+                // Z 不携带参数，防止被隐式重复
+                command = 0;
+            }
+            b'M' => {
+                let Some(v) = read(&mut scanner, 2) else {
+                    break;
+                };
+                current = (ox + v[0], oy + v[1]);
+                start = current;
+                path.move_to(current.0, current.1);
+            }
+            b'L' => {
+                let Some(v) = read(&mut scanner, 2) else {
+                    break;
+                };
+                current = (ox + v[0], oy + v[1]);
+                path.line_to(current.0, current.1);
+            }
+            b'H' => {
+                let Some(v) = read(&mut scanner, 1) else {
+                    break;
+                };
+                current.0 = ox + v[0];
+                path.line_to(current.0, current.1);
+            }
+            b'V' => {
+                let Some(v) = read(&mut scanner, 1) else {
+                    break;
+                };
+                current.1 = oy + v[0];
+                path.line_to(current.0, current.1);
+            }
+            b'C' | b'S' => {
+                let (c1, rest) = if upper == b'C' {
+                    let Some(v) = read(&mut scanner, 6) else {
+                        break;
+                    };
+                    ((ox + v[0], oy + v[1]), v[2..].to_vec())
+                } else {
+                    let Some(v) = read(&mut scanner, 4) else {
+                        break;
+                    };
+                    let c1 = match last_cubic {
+                        Some((x, y)) => (2.0 * current.0 - x, 2.0 * current.1 - y),
+                        None => current,
+                    };
+                    (c1, v)
+                };
+                let c2 = (ox + rest[0], oy + rest[1]);
+                current = (ox + rest[2], oy + rest[3]);
+                path.cubic_to(c1.0, c1.1, c2.0, c2.1, current.0, current.1);
+                cubic_ctrl = Some(c2);
+            }
+            b'Q' | b'T' => {
+                let (c, end) = if upper == b'Q' {
+                    let Some(v) = read(&mut scanner, 4) else {
+                        break;
+                    };
+                    ((ox + v[0], oy + v[1]), (ox + v[2], oy + v[3]))
+                } else {
+                    let Some(v) = read(&mut scanner, 2) else {
+                        break;
+                    };
+                    let c = match last_quad {
+                        Some((x, y)) => (2.0 * current.0 - x, 2.0 * current.1 - y),
+                        None => current,
+                    };
+                    (c, (ox + v[0], oy + v[1]))
+                };
+                current = end;
+                path.quad_to(c.0, c.1, current.0, current.1);
+                quad_ctrl = Some(c);
+            }
+            b'A' => {
+                let Some(radii) = read(&mut scanner, 3) else {
+                    break;
+                };
+                let (Some(large_arc), Some(sweep)) = (scanner.flag(), scanner.flag()) else {
+                    break;
+                };
+                let Some(v) = read(&mut scanner, 2) else {
+                    break;
+                };
+                let to = (ox + v[0], oy + v[1]);
+                arc_to(
+                    &mut path, current, radii[0], radii[1], radii[2], large_arc, sweep, to,
+                );
+                current = to;
+            }
+            _ => break,
+        }
+        last_cubic = cubic_ctrl;
+        last_quad = quad_ctrl;
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::Point;
+
+    fn points(path: &Path) -> Vec<Point> {
+        path.flatten().into_iter().flat_map(|s| s.points).collect()
+    }
+
+    #[test]
+    fn test_scanner_compact_numbers() {
+        let mut s = NumberScanner::new("1.5.5-2e1,3 -.5e-1");
+        assert_eq!(s.numbers(), vec![1.5, 0.5, -20.0, 3.0, -0.05]);
+    }
+
+    #[test]
+    fn test_lines_and_relative() {
+        let path = parse_path_data("M10 10 h5 v5 l-5 0 z m 1 1 L 20 20");
+        let subpaths = path.flatten();
+        assert_eq!(subpaths.len(), 2);
+        assert!(subpaths[0].closed);
+        assert_eq!(
+            subpaths[0].points,
+            vec![
+                Point::new(10.0, 10.0),
+                Point::new(15.0, 10.0),
+                Point::new(15.0, 15.0),
+                Point::new(10.0, 15.0)
+            ]
+        );
+        // z 后的相对 m 以子路径起点为基准
+        assert_eq!(subpaths[1].points[0], Point::new(11.0, 11.0));
+    }
+
+    #[test]
+    fn test_implicit_lineto_after_moveto() {
+        let pts = points(&parse_path_data("m0 0 10 0 0 10"));
+        assert_eq!(
+            pts,
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 10.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_smooth_curves_end_points() {
+        let pts = points(&parse_path_data(
+            "M0 0 C 0 10 10 10 10 0 S 20 -10 20 0 Q 25 5 30 0 T 40 0",
+        ));
+        assert_eq!(*pts.last().unwrap(), Point::new(40.0, 0.0));
+        assert!(pts.contains(&Point::new(20.0, 0.0)));
+    }
+
+    #[test]
+    fn test_arc_half_circle() {
+        // 从 (0,0) 到 (20,0) 的半圆，sweep=1 顺时针 (屏幕坐标向下凸出前经过 y<0)
+        let pts = points(&parse_path_data("M0 0 A10 10 0 0 1 20 0"));
+        assert_eq!(*pts.last().unwrap(), Point::new(20.0, 0.0));
+        let top = pts.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        assert!((top + 10.0).abs() < 0.05);
+
+        // 紧连的标志位写法
+        let compact = points(&parse_path_data("M0 0a10 10 0 0120 0"));
+        assert_eq!(compact.len(), pts.len());
+    }
+
+    #[test]
+    fn test_error_keeps_prefix() {
+        let pts = points(&parse_path_data("M0 0 L10 0 L 5 X 1 2"));
+        assert_eq!(pts, vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]);
+    }
+}
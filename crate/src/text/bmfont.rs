@@ -0,0 +1,447 @@
+//! AngelCode BMFont 位图字体
+//!
+//! 解析文本格式与二进制格式 (版本 3) 的 `.fnt` 描述文件，
+//! 并将排版结果从页面图像中逐字符拷贝合成为 RGBA 位图。
+
+use std::collections::HashMap;
+
+use super::layout::{FontMetrics, TextLayout};
+use crate::image::{blend_over, Image};
+
+/// 通道掩码: 字形使用全部通道 (彩色字形)
+const CHANNEL_ALL: u8 = 15;
+
+/// 单个字形在页面图像中的位置与度量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BitmapGlyph {
+    /// 页面内 X
+    pub x: u16,
+    /// 页面内 Y
+    pub y: u16,
+    /// 宽度
+    pub width: u16,
+    /// 高度
+    pub height: u16,
+    /// 绘制时的 X 偏移
+    pub xoffset: i16,
+    /// 绘制时相对行顶的 Y 偏移
+    pub yoffset: i16,
+    /// 水平前进量
+    pub xadvance: i16,
+    /// 所在页面
+    pub page: u8,
+    /// 所在通道 (1=B, 2=G, 4=R, 8=A, 15=全部)
+    pub channel: u8,
+}
+
+/// 位图字体描述
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BitmapFont {
+    /// 字号
+    pub size: i16,
+    /// 行高
+    pub line_height: u16,
+    /// 基线距行顶的距离
+    pub base: u16,
+    /// 页面图像文件名
+    pub pages: Vec<String>,
+    /// 字形表
+    pub glyphs: HashMap<u32, BitmapGlyph>,
+    /// 字距调整表
+    pub kernings: HashMap<(u32, u32), i16>,
+}
+
+impl BitmapFont {
+    /// 自动识别文本 / 二进制格式并解析
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"BMF") {
+            Self::parse_binary(bytes)
+        } else {
+            Self::parse_text(std::str::from_utf8(bytes).ok()?)
+        }
+    }
+
+    /// 解析文本格式
+    pub fn parse_text(text: &str) -> Option<Self> {
+        let mut font = BitmapFont::default();
+        let mut has_common = false;
+        let mut page_count = None;
+
+        for line in text.lines() {
+            let (tag, attrs) = split_attributes(line);
+            let get = |key: &str| -> i64 {
+                attrs
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .and_then(|(_, v)| v.parse().ok())
+                    .unwrap_or(0)
+            };
+
+            match tag {
+                "info" => font.size = get("size") as i16,
+                "common" => {
+                    font.line_height = get("lineHeight") as u16;
+                    font.base = get("base") as u16;
+                    has_common = true;
+                    if attrs.iter().any(|(k, _)| *k == "pages") {
+                        page_count = Some(get("pages"));
+                    }
+                }
+                "page" => {
+                    // 字形的页码为 u8，超出范围或不小于声明的页数视为无效文件
+                    let id = get("id");
+                    if !(0..=u8::MAX as i64).contains(&id) || page_count.is_some_and(|n| id >= n) {
+                        return None;
+                    }
+                    let id = id as usize;
+                    let file = attrs
+                        .iter()
+                        .find(|(k, _)| *k == "file")
+                        .map(|(_, v)| v.to_string())
+                        .unwrap_or_default();
+                    if font.pages.len() <= id {
+                        font.pages.resize(id + 1, String::new());
+                    }
+                    font.pages[id] = file;
+                }
+                "char" => {
+                    let has_channel = attrs.iter().any(|(k, _)| *k == "chnl");
+                    font.glyphs.insert(
+                        get("id") as u32,
+                        BitmapGlyph {
+                            x: get("x") as u16,
+                            y: get("y") as u16,
+                            width: get("width") as u16,
+                            height: get("height") as u16,
+                            xoffset: get("xoffset") as i16,
+                            yoffset: get("yoffset") as i16,
+                            xadvance: get("xadvance") as i16,
+                            page: get("page") as u8,
+                            channel: if has_channel {
+                                get("chnl") as u8
+                            } else {
+                                CHANNEL_ALL
+                            },
+                        },
+                    );
+                }
+                "kerning" => {
+                    font.kernings.insert(
+                        (get("first") as u32, get("second") as u32),
+                        get("amount") as i16,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        has_common.then_some(font)
+    }
+
+    /// 解析二进制格式 (版本 3)
+    pub fn parse_binary(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 4 || &bytes[0..3] != b"BMF" || bytes[3] != 3 {
+            return None;
+        }
+        let u16_at = |b: &[u8], p: usize| u16::from_le_bytes([b[p], b[p + 1]]);
+        let u32_at = |b: &[u8], p: usize| u32::from_le_bytes([b[p], b[p + 1], b[p + 2], b[p + 3]]);
+
+        let mut font = BitmapFont::default();
+        let mut has_common = false;
+        let mut pos = 4;
+
+        while pos + 5 <= bytes.len() {
+            let kind = bytes[pos];
+            let size = u32_at(bytes, pos + 1) as usize;
+            let block = bytes.get(pos + 5..pos + 5 + size)?;
+            pos += 5 + size;
+
+            match kind {
+                1 if block.len() >= 2 => font.size = u16_at(block, 0) as i16,
+                2 if block.len() >= 4 => {
+                    font.line_height = u16_at(block, 0);
+                    font.base = u16_at(block, 2);
+                    has_common = true;
+                }
+                3 => {
+                    font.pages = block
+                        .split(|&b| b == 0)
+                        .filter(|name| !name.is_empty())
+                        .map(|name| String::from_utf8_lossy(name).into_owned())
+                        .collect();
+                }
+                4 => {
+                    for c in block.chunks_exact(20) {
+                        font.glyphs.insert(
+                            u32_at(c, 0),
+                            BitmapGlyph {
+                                x: u16_at(c, 4),
+                                y: u16_at(c, 6),
+                                width: u16_at(c, 8),
+                                height: u16_at(c, 10),
+                                xoffset: u16_at(c, 12) as i16,
+                                yoffset: u16_at(c, 14) as i16,
+                                xadvance: u16_at(c, 16) as i16,
+                                page: c[18],
+                                channel: c[19],
+                            },
+                        );
+                    }
+                }
+                5 => {
+                    for k in block.chunks_exact(10) {
+                        font.kernings
+                            .insert((u32_at(k, 0), u32_at(k, 4)), u16_at(k, 8) as i16);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        has_common.then_some(font)
+    }
+
+    /// 查找字形，缺失时回退到 '?'
+    fn glyph(&self, ch: char) -> Option<&BitmapGlyph> {
+        self.glyphs
+            .get(&(ch as u32))
+            .or_else(|| self.glyphs.get(&('?' as u32)))
+    }
+
+    /// 将排版结果合成为 RGBA 位图
+    ///
+    /// # Arguments
+    /// * `layout` - 排版结果
+    /// * `pages` - 页面图像 (按页面序号，缺失的页面跳过)
+    /// * `color` - 文字颜色，与字形像素相乘
+    ///
+    /// 文本块超出位图尺寸上限时返回 None。
+    pub fn render(
+        &self,
+        layout: &TextLayout,
+        pages: &[Option<Image>],
+        color: [u8; 4],
+    ) -> Option<Image> {
+        let width = (layout.width.ceil() as u32).max(1);
+        let height = (layout.height.ceil() as u32).max(1);
+        let mut out = Image::new(width, height)?;
+
+        for placed in &layout.glyphs {
+            let Some(glyph) = self.glyph(placed.ch) else {
+                continue;
+            };
+            let Some(Some(page)) = pages.get(glyph.page as usize) else {
+                continue;
+            };
+
+            let dst_x0 = placed.x.round() as i32 + glyph.xoffset as i32;
+            let dst_y0 = (placed.line * self.line_height as u32) as i32 + glyph.yoffset as i32;
+
+            for gy in 0..glyph.height as i32 {
+                let (sy, dy) = (glyph.y as i32 + gy, dst_y0 + gy);
+                if sy >= page.height as i32 || dy < 0 || dy >= height as i32 {
+                    continue;
+                }
+                for gx in 0..glyph.width as i32 {
+                    let (sx, dx) = (glyph.x as i32 + gx, dst_x0 + gx);
+                    if sx >= page.width as i32 || dx < 0 || dx >= width as i32 {
+                        continue;
+                    }
+                    let src = ((sy as u32 * page.width + sx as u32) * 4) as usize;
+                    let texel = &page.data[src..src + 4];
+
+                    let (rgb, alpha) = match glyph.channel {
+                        CHANNEL_ALL | 0 => ([texel[0], texel[1], texel[2]], texel[3]),
+                        // 单通道字形: 该通道即覆盖率
+                        c => {
+                            let v = match c {
+                                1 => texel[2],
+                                2 => texel[1],
+                                4 => texel[0],
+                                _ => texel[3],
+                            };
+                            ([255, 255, 255], v)
+                        }
+                    };
+
+                    let src_color = [
+                        (rgb[0] as u32 * color[0] as u32 / 255) as u8,
+                        (rgb[1] as u32 * color[1] as u32 / 255) as u8,
+                        (rgb[2] as u32 * color[2] as u32 / 255) as u8,
+                        (alpha as u32 * color[3] as u32 / 255) as u8,
+                    ];
+                    let dst = ((dy as u32 * width + dx as u32) * 4) as usize;
+                    blend_over(&mut out.data[dst..dst + 4], src_color);
+                }
+            }
+        }
+
+        Some(out)
+    }
+}
+
+impl FontMetrics for BitmapFont {
+    fn line_height(&self) -> f32 {
+        self.line_height as f32
+    }
+
+    fn advance(&self, ch: char) -> f32 {
+        self.glyph(ch).map_or(0.0, |g| g.xadvance as f32)
+    }
+
+    fn kerning(&self, left: char, right: char) -> f32 {
+        self.kernings
+            .get(&(left as u32, right as u32))
+            .map_or(0.0, |&k| k as f32)
+    }
+}
+
+/// 拆分一行文本格式描述为标签与 `key=value` 属性列表 (支持带空格的引号值)
+fn split_attributes(line: &str) -> (&str, Vec<(&str, &str)>) {
+    let line = line.trim();
+    let (tag, mut rest) = match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim_start()),
+        None => (line, ""),
+    };
+
+    let mut attrs = Vec::new();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let after = &rest[eq + 1..];
+        let (value, remain) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            }
+        } else {
+            match after.find(char::is_whitespace) {
+                Some(end) => (&after[..end], &after[end..]),
+                None => (after, ""),
+            }
+        };
+        attrs.push((key, value));
+        rest = remain.trim_start();
+    }
+
+    (tag, attrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::layout::{layout_text, TextAlign};
+
+    const FONT_TEXT: &str = r#"info face="Test Font" size=8 bold=0
+common lineHeight=10 base=8 scaleW=4 scaleH=2 pages=1 packed=0
+page id=0 file="test font_0.png"
+chars count=2
+char id=65 x=0 y=0 width=2 height=2 xoffset=0 yoffset=1 xadvance=3 page=0 chnl=15
+char id=66 x=2 y=0 width=2 height=2 xoffset=1 yoffset=0 xadvance=4 page=0 chnl=8
+kernings count=1
+kerning first=65 second=66 amount=-1
+"#;
+
+    /// 4x2 页面: 左半白色不透明，右半 Alpha 128
+    fn page() -> Image {
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend_from_slice(&[255, 255, 255, 255, 255, 255, 255, 255]);
+            data.extend_from_slice(&[0, 0, 0, 128, 0, 0, 0, 128]);
+        }
+        Image {
+            width: 4,
+            height: 2,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_parse_text_format() {
+        let font = BitmapFont::parse(FONT_TEXT.as_bytes()).unwrap();
+        assert_eq!(font.size, 8);
+        assert_eq!(font.line_height, 10);
+        assert_eq!(font.base, 8);
+        assert_eq!(font.pages, vec!["test font_0.png".to_string()]);
+        assert_eq!(font.glyphs[&65].xadvance, 3);
+        assert_eq!(font.glyphs[&66].channel, 8);
+        assert_eq!(font.kerning('A', 'B'), -1.0);
+    }
+
+    #[test]
+    fn test_invalid_page_ids() {
+        let font = |page: &str| {
+            BitmapFont::parse_text(&format!("common lineHeight=10 base=8 pages=2\n{page}\n"))
+        };
+        assert_eq!(font("page id=1 file=\"b.png\"").unwrap().pages.len(), 2);
+        assert!(font("page id=-1 file=\"a.png\"").is_none());
+        assert!(font("page id=2 file=\"a.png\"").is_none());
+        assert!(font("page id=4000000000 file=\"a.png\"").is_none());
+        // 未声明页数时以 u8 为上限
+        assert!(BitmapFont::parse_text("common lineHeight=10\npage id=300\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_binary_format() {
+        let mut bytes = b"BMF\x03".to_vec();
+        // common
+        bytes.push(2);
+        bytes.extend_from_slice(&15u32.to_le_bytes());
+        bytes.extend_from_slice(&12u16.to_le_bytes());
+        bytes.extend_from_slice(&9u16.to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 11]);
+        // pages
+        bytes.push(3);
+        bytes.extend_from_slice(&6u32.to_le_bytes());
+        bytes.extend_from_slice(b"a.png\0");
+        // chars
+        bytes.push(4);
+        bytes.extend_from_slice(&20u32.to_le_bytes());
+        bytes.extend_from_slice(&65u32.to_le_bytes());
+        for v in [1u16, 2, 3, 4, (-1i16) as u16, 5, 6] {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0, 15]);
+        // kerning
+        bytes.push(5);
+        bytes.extend_from_slice(&10u32.to_le_bytes());
+        bytes.extend_from_slice(&65u32.to_le_bytes());
+        bytes.extend_from_slice(&65u32.to_le_bytes());
+        bytes.extend_from_slice(&(-2i16).to_le_bytes());
+
+        let font = BitmapFont::parse(&bytes).unwrap();
+        assert_eq!(font.line_height, 12);
+        assert_eq!(font.base, 9);
+        assert_eq!(font.pages, vec!["a.png".to_string()]);
+        let glyph = font.glyphs[&65];
+        assert_eq!((glyph.x, glyph.y, glyph.width, glyph.height), (1, 2, 3, 4));
+        assert_eq!((glyph.xoffset, glyph.yoffset, glyph.xadvance), (-1, 5, 6));
+        assert_eq!(font.kerning('A', 'A'), -2.0);
+    }
+
+    #[test]
+    fn test_render_with_tint_and_channels() {
+        let font = BitmapFont::parse_text(FONT_TEXT).unwrap();
+        let layout = layout_text(&font, "AB", 0.0, TextAlign::Left);
+        // A 宽 3，字距 -1，B 从 x=2 开始，前进 4
+        assert_eq!(layout.width, 6.0);
+
+        let image = font
+            .render(&layout, &[Some(page())], [255, 0, 0, 255])
+            .unwrap();
+        assert_eq!((image.width, image.height), (6, 10));
+
+        // A 的像素 (0, 1): 白色 × 红色
+        let a = (image.width * 4) as usize;
+        assert_eq!(&image.data[a..a + 4], &[255, 0, 0, 255]);
+        // B 的像素 (3, 0): Alpha 通道字形，覆盖率 128
+        let b = (3 * 4) as usize;
+        assert_eq!(&image.data[b..b + 4], &[255, 0, 0, 128]);
+    }
+
+    #[test]
+    fn test_missing_glyph_falls_back() {
+        let font = BitmapFont::parse_text(FONT_TEXT).unwrap();
+        assert_eq!(font.advance('Z'), 0.0);
+        assert!(BitmapFont::parse(b"garbage").is_none());
+    }
+}
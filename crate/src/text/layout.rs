@@ -0,0 +1,304 @@
+//! 文字排版
+//!
+//! 与字体格式无关的单行 / 多行排版：字距调整、换行符、最大宽度自动换行
+//! (在空白处或中日韩字符之间断行) 以及左 / 中 / 右对齐。
+
+use crate::image::MAX_DIMENSION;
+
+/// 文字对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    /// 左对齐
+    #[default]
+    Left,
+    /// 居中
+    Center,
+    /// 右对齐
+    Right,
+}
+
+impl TextAlign {
+    /// 从 u8 值创建对齐方式
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => TextAlign::Left,
+            1 => TextAlign::Center,
+            2 => TextAlign::Right,
+            _ => TextAlign::Left,
+        }
+    }
+
+    /// 转换为 u8 值
+    pub fn to_u8(self) -> u8 {
+        match self {
+            TextAlign::Left => 0,
+            TextAlign::Center => 1,
+            TextAlign::Right => 2,
+        }
+    }
+}
+
+/// 排版所需的字体度量
+pub trait FontMetrics {
+    /// 行高 (像素)
+    fn line_height(&self) -> f32;
+
+    /// 字符的水平前进量 (像素)
+    fn advance(&self, ch: char) -> f32;
+
+    /// 字符对之间的字距调整 (像素，负值表示靠近)
+    fn kerning(&self, left: char, right: char) -> f32;
+}
+
+/// 排版后的单个字符
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedGlyph {
+    /// 字符
+    pub ch: char,
+    /// 笔位置 X (相对文本块左边缘)
+    pub x: f32,
+    /// 所在行号 (从 0 开始)
+    pub line: u32,
+}
+
+/// 排版结果
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextLayout {
+    /// 字符位置列表
+    pub glyphs: Vec<PlacedGlyph>,
+    /// 文本块宽度 (指定最大宽度时等于最大宽度)
+    pub width: f32,
+    /// 文本块高度 (行数 × 行高)
+    pub height: f32,
+    /// 行数
+    pub lines: u32,
+}
+
+/// 是否为可在任意字符间断行的中日韩字符
+fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x3040..=0x30FF   // 平假名、片假名
+        | 0x3100..=0x312F // 注音
+        | 0x3400..=0x4DBF // CJK 扩展 A
+        | 0x4E00..=0x9FFF // CJK 统一汉字
+        | 0xAC00..=0xD7AF // 韩文音节
+        | 0xF900..=0xFAFF // CJK 兼容汉字
+        | 0x3000..=0x303F // CJK 标点
+        | 0xFF00..=0xFFEF // 全角字符
+        | 0x20000..=0x2FFFF)
+}
+
+/// 计算一行字符的笔位置，返回 (各字符 X, 行宽)
+fn measure<F: FontMetrics>(font: &F, chars: &[char]) -> (Vec<f32>, f32) {
+    let mut xs = Vec::with_capacity(chars.len());
+    let mut x = 0.0;
+    for (i, &ch) in chars.iter().enumerate() {
+        if i > 0 {
+            x += font.kerning(chars[i - 1], ch);
+        }
+        xs.push(x);
+        x += font.advance(ch);
+    }
+    (xs, x)
+}
+
+/// 将一个段落 (不含换行符) 拆分为多行
+fn wrap_paragraph<F: FontMetrics>(font: &F, chars: &[char], max_width: f32) -> Vec<(usize, usize)> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+
+    if chars.is_empty() {
+        lines.push((0, 0));
+        return lines;
+    }
+
+    while start < chars.len() {
+        let mut x = 0.0;
+        let mut i = start;
+        let mut last_break = None;
+
+        while i < chars.len() {
+            let ch = chars[i];
+            let kern = if i > start {
+                font.kerning(chars[i - 1], ch)
+            } else {
+                0.0
+            };
+            let next_x = x + kern + font.advance(ch);
+            if max_width > 0.0 && next_x > max_width && i > start && !ch.is_whitespace() {
+                break;
+            }
+            x = next_x;
+            if ch.is_whitespace() || is_cjk(ch) || chars.get(i + 1).is_some_and(|&c| is_cjk(c)) {
+                last_break = Some(i + 1);
+            }
+            i += 1;
+        }
+
+        let end = if i < chars.len() {
+            last_break.filter(|&b| b > start).unwrap_or(i)
+        } else {
+            chars.len()
+        };
+
+        // 去除行尾空白
+        let mut trimmed = end;
+        while trimmed > start && chars[trimmed - 1].is_whitespace() {
+            trimmed -= 1;
+        }
+        lines.push((start, trimmed));
+
+        // 下一行跳过行首空白
+        start = end;
+        while start < chars.len() && chars[start].is_whitespace() {
+            start += 1;
+        }
+    }
+
+    lines
+}
+
+/// 排版文本
+///
+/// # Arguments
+/// * `font` - 字体度量
+/// * `text` - 文本，`\n` 为强制换行
+/// * `max_width` - 最大行宽，小于等于 0 表示不自动换行
+/// * `align` - 对齐方式
+pub fn layout_text<F: FontMetrics>(
+    font: &F,
+    text: &str,
+    max_width: f32,
+    align: TextAlign,
+) -> TextLayout {
+    let mut lines: Vec<(Vec<char>, Vec<f32>, f32)> = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let chars: Vec<char> = paragraph.trim_end_matches('\r').chars().collect();
+        for (start, end) in wrap_paragraph(font, &chars, max_width) {
+            let line = chars[start..end].to_vec();
+            let (xs, width) = measure(font, &line);
+            lines.push((line, xs, width));
+        }
+    }
+
+    let widest = lines.iter().map(|(_, _, w)| *w).fold(0.0f32, f32::max);
+    // 文本块宽度不超过位图尺寸上限
+    let block_width = if max_width > 0.0 { max_width } else { widest }.min(MAX_DIMENSION as f32);
+
+    let mut glyphs = Vec::new();
+    for (line_idx, (chars, xs, width)) in lines.iter().enumerate() {
+        let offset = match align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => ((block_width - width) / 2.0).floor(),
+            TextAlign::Right => block_width - width,
+        };
+        for (&ch, &x) in chars.iter().zip(xs) {
+            glyphs.push(PlacedGlyph {
+                ch,
+                x: x + offset,
+                line: line_idx as u32,
+            });
+        }
+    }
+
+    TextLayout {
+        glyphs,
+        width: block_width,
+        height: lines.len() as f32 * font.line_height(),
+        lines: lines.len() as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 等宽测试字体: 每字符宽 10，"AV" 字距 -3，行高 20
+    struct MonoFont;
+
+    impl FontMetrics for MonoFont {
+        fn line_height(&self) -> f32 {
+            20.0
+        }
+
+        fn advance(&self, _ch: char) -> f32 {
+            10.0
+        }
+
+        fn kerning(&self, left: char, right: char) -> f32 {
+            if left == 'A' && right == 'V' {
+                -3.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    fn line_text(layout: &TextLayout, line: u32) -> String {
+        layout
+            .glyphs
+            .iter()
+            .filter(|g| g.line == line)
+            .map(|g| g.ch)
+            .collect()
+    }
+
+    #[test]
+    fn test_align_conversion() {
+        assert_eq!(TextAlign::from_u8(1), TextAlign::Center);
+        assert_eq!(TextAlign::from_u8(9), TextAlign::Left);
+        assert_eq!(TextAlign::Right.to_u8(), 2);
+    }
+
+    #[test]
+    fn test_kerning_and_newlines() {
+        let layout = layout_text(&MonoFont, "AV\nA", 0.0, TextAlign::Left);
+        assert_eq!(layout.lines, 2);
+        assert_eq!(layout.glyphs[1].x, 7.0);
+        assert_eq!(layout.width, 17.0);
+        assert_eq!(layout.height, 40.0);
+        assert_eq!(layout.glyphs[2].line, 1);
+    }
+
+    #[test]
+    fn test_word_wrap() {
+        let layout = layout_text(&MonoFont, "ab cd efgh", 50.0, TextAlign::Left);
+        assert_eq!(layout.lines, 2);
+        assert_eq!(line_text(&layout, 0), "ab cd");
+        assert_eq!(line_text(&layout, 1), "efgh");
+        assert_eq!(layout.width, 50.0);
+    }
+
+    #[test]
+    fn test_long_word_is_broken() {
+        let layout = layout_text(&MonoFont, "abcdefg", 30.0, TextAlign::Left);
+        assert_eq!(layout.lines, 3);
+        assert_eq!(line_text(&layout, 2), "g");
+    }
+
+    #[test]
+    fn test_cjk_breaks_between_chars() {
+        let layout = layout_text(&MonoFont, "你好世界", 25.0, TextAlign::Left);
+        assert_eq!(layout.lines, 2);
+        assert_eq!(line_text(&layout, 0), "你好");
+    }
+
+    #[test]
+    fn test_alignment() {
+        let center = layout_text(&MonoFont, "ab\nabcd", 0.0, TextAlign::Center);
+        assert_eq!(center.glyphs[0].x, 10.0);
+        let right = layout_text(&MonoFont, "ab", 50.0, TextAlign::Right);
+        assert_eq!(right.glyphs[0].x, 30.0);
+        let huge = layout_text(&MonoFont, "ab", 1e30, TextAlign::Left);
+        assert_eq!(huge.width, MAX_DIMENSION as f32);
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let layout = layout_text(&MonoFont, "", 0.0, TextAlign::Left);
+        assert_eq!(layout.lines, 1);
+        assert!(layout.glyphs.is_empty());
+        assert_eq!(layout.width, 0.0);
+    }
+}
@@ -0,0 +1,14 @@
+//! 文字模块
+//!
+//! 提供字体解析、排版与文字位图合成，由 World 的文字精灵使用。
+
+mod bmfont;
+mod layout;
+mod ttf;
+
+pub use bmfont::BitmapFont;
+pub use layout::{layout_text, TextAlign};
+pub use ttf::{clamp_size, OutlineFont};
+
+#[cfg(test)]
+pub(crate) use ttf::test_font;
@@ -0,0 +1,613 @@
+//! TrueType / OpenType 矢量字体
+//!
+//! 通过 ttf-parser 读取字形轮廓 (TrueType 二次曲线与 CFF 三次曲线)，
+//! 由内置扫描线光栅化器生成抗锯齿覆盖率，并按字号缓存到字形图集中。
+
+use std::collections::HashMap;
+
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+use super::layout::{FontMetrics, TextLayout};
+use crate::image::{blend_over, pixel_len, Image};
+use crate::raster::{FillRule, Point, Rasterizer};
+
+/// 最小字号 (像素)
+pub const MIN_FONT_SIZE: f32 = 1.0;
+
+/// 最大字号 (像素)
+pub const MAX_FONT_SIZE: f32 = 512.0;
+
+/// 图集最小宽度 (像素)
+const ATLAS_MIN_WIDTH: u32 = 256;
+
+/// 字形之间的留白 (像素)
+const ATLAS_PADDING: u32 = 1;
+
+/// 字号量化步长的倒数 (字号按 0.5 像素取整，避免缓动字号时每帧新建图集)
+const SIZE_STEPS: f32 = 2.0;
+
+/// 每个字体最多缓存的字号图集数 (超出时淘汰最久未使用的)
+const MAX_ATLASES: usize = 8;
+
+/// 单个图集的覆盖率数据上限 (字节)，写满时清空后重新填充
+const MAX_ATLAS_BYTES: usize = 4 << 20;
+
+/// 图集中的字形位置与度量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AtlasGlyph {
+    /// 图集内 X
+    pub x: u32,
+    /// 图集内 Y
+    pub y: u32,
+    /// 宽度 (空白字形为 0)
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+    /// 位图左边缘相对笔位置的偏移
+    pub left: i32,
+    /// 位图上边缘相对基线的高度 (向上为正)
+    pub top: i32,
+}
+
+/// 单一字号的字形图集 (8 位覆盖率，按行架式排布)
+///
+/// 数据超过 `MAX_ATLAS_BYTES` 时清空已缓存的字形重新开始；
+/// 比图集更宽的字形会加宽图集 (同样清空)，而不是被截断。
+#[derive(Debug, Clone, Default)]
+pub struct GlyphAtlas {
+    /// 图集宽度
+    pub width: u32,
+    /// 图集当前高度 (按需增长)
+    pub height: u32,
+    /// 覆盖率数据，每像素 1 字节
+    pub data: Vec<u8>,
+    /// 已缓存字形
+    glyphs: HashMap<u16, AtlasGlyph>,
+    /// 当前行起始 Y
+    shelf_y: u32,
+    /// 当前行已用宽度
+    shelf_x: u32,
+    /// 当前行高度
+    shelf_height: u32,
+}
+
+impl GlyphAtlas {
+    fn new(size: f32) -> Self {
+        Self {
+            width: ATLAS_MIN_WIDTH.max((size * 2.0).ceil() as u32 + ATLAS_PADDING),
+            ..Self::default()
+        }
+    }
+
+    /// 已缓存的字形数量
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// 清空已缓存的字形，宽度至少为 `width`
+    fn reset(&mut self, width: u32) {
+        *self = Self {
+            width: self.width.max(width),
+            ..Self::default()
+        };
+    }
+
+    /// 为 w×h 的位图分配位置，必要时换行并扩展图集高度，超出容量时清空图集
+    fn allocate(&mut self, w: u32, h: u32) -> (u32, u32) {
+        if w + ATLAS_PADDING > self.width {
+            self.reset(w + ATLAS_PADDING);
+        }
+        if self.shelf_x + w + ATLAS_PADDING > self.width {
+            self.shelf_y += self.shelf_height + ATLAS_PADDING;
+            self.shelf_x = 0;
+            self.shelf_height = 0;
+        }
+        // 写满时清空 (空图集总能放下单个字形)
+        let bottom = self.shelf_y + self.shelf_height.max(h);
+        if self.width as usize * bottom as usize > MAX_ATLAS_BYTES && !self.glyphs.is_empty() {
+            self.reset(0);
+        }
+        let pos = (self.shelf_x, self.shelf_y);
+        self.shelf_x += w + ATLAS_PADDING;
+        self.shelf_height = self.shelf_height.max(h);
+
+        let needed = self.shelf_y + self.shelf_height;
+        if needed > self.height {
+            // 按 2 的幂增长，但不超过容量上限 (单个字形本身更大时除外)
+            let cap = (MAX_ATLAS_BYTES / self.width as usize) as u32;
+            self.height = needed.next_power_of_two().min(cap.max(needed));
+            self.data
+                .resize(self.width as usize * self.height as usize, 0);
+        }
+        pos
+    }
+
+    /// 写入一个光栅化好的字形 (`coverage` 为 width×height 的覆盖率)
+    fn insert(&mut self, id: u16, entry: AtlasGlyph, coverage: &[u8]) -> AtlasGlyph {
+        let (x, y) = self.allocate(entry.width, entry.height);
+        let w = entry.width as usize;
+        for (row, src) in coverage.chunks_exact(w).enumerate() {
+            let dst = (y as usize + row) * self.width as usize + x as usize;
+            self.data[dst..dst + w].copy_from_slice(src);
+        }
+        let entry = AtlasGlyph { x, y, ..entry };
+        self.glyphs.insert(id, entry);
+        entry
+    }
+
+    /// 获取字形，未缓存时光栅化并写入图集
+    fn get_or_insert(&mut self, face: &Face, glyph: GlyphId, scale: f32) -> AtlasGlyph {
+        if let Some(&cached) = self.glyphs.get(&glyph.0) {
+            return cached;
+        }
+        match rasterize_glyph(face, glyph, scale) {
+            Some((entry, coverage)) => self.insert(glyph.0, entry, &coverage),
+            None => {
+                self.glyphs.insert(glyph.0, AtlasGlyph::default());
+                AtlasGlyph::default()
+            }
+        }
+    }
+}
+
+/// 将字体单位的轮廓转换到字形位图坐标系并送入光栅化器
+struct GlyphOutline {
+    raster: Rasterizer,
+    scale: f32,
+    /// 位图左边缘 (像素)
+    left: f32,
+    /// 位图上边缘 (像素，向上为正)
+    top: f32,
+    start: Point,
+    current: Point,
+}
+
+impl GlyphOutline {
+    fn map(&self, x: f32, y: f32) -> Point {
+        Point::new(x * self.scale - self.left, self.top - y * self.scale)
+    }
+}
+
+impl OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.map(x, y);
+        self.current = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.map(x, y);
+        self.raster.line(self.current, p);
+        self.current = p;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (c, p) = (self.map(x1, y1), self.map(x, y));
+        self.raster.quad(self.current, c, p);
+        self.current = p;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (c1, c2, p) = (self.map(x1, y1), self.map(x2, y2), self.map(x, y));
+        self.raster.cubic(self.current, c1, c2, p);
+        self.current = p;
+    }
+
+    fn close(&mut self) {
+        self.raster.line(self.current, self.start);
+        self.current = self.start;
+    }
+}
+
+/// 光栅化单个字形，返回度量与覆盖率 (空白字形返回 None)
+fn rasterize_glyph(face: &Face, glyph: GlyphId, scale: f32) -> Option<(AtlasGlyph, Vec<u8>)> {
+    let bbox = face.glyph_bounding_box(glyph)?;
+    let left = (bbox.x_min as f32 * scale).floor();
+    let right = (bbox.x_max as f32 * scale).ceil();
+    let top = (bbox.y_max as f32 * scale).ceil();
+    let bottom = (bbox.y_min as f32 * scale).floor();
+    let width = (right - left).max(1.0) as u32;
+    let height = (top - bottom).max(1.0) as u32;
+    // 字体中异常的包围盒不应导致巨大的分配
+    pixel_len(width, height)?;
+
+    let mut outline = GlyphOutline {
+        raster: Rasterizer::new(width, height),
+        scale,
+        left,
+        top,
+        start: Point::default(),
+        current: Point::default(),
+    };
+    face.outline_glyph(glyph, &mut outline)?;
+
+    let coverage = outline
+        .raster
+        .fill(FillRule::NonZero)
+        .iter()
+        .map(|&c| (c * 255.0 + 0.5) as u8)
+        .collect();
+    let entry = AtlasGlyph {
+        width,
+        height,
+        left: left as i32,
+        top: top as i32,
+        ..AtlasGlyph::default()
+    };
+    Some((entry, coverage))
+}
+
+/// 矢量字体 (TTF / OTF，字体集合取第一个字体)
+pub struct OutlineFont {
+    /// 字体文件数据 (已确认可以解析)
+    data: Vec<u8>,
+    /// 字号图集 (键为量化后的字号，按使用先后排列，最近使用的在末尾)
+    atlases: Vec<(u32, GlyphAtlas)>,
+}
+
+/// 指定字号下的字体度量
+pub struct SizedFont<'a> {
+    face: Face<'a>,
+    scale: f32,
+}
+
+impl OutlineFont {
+    /// 解析字体文件，格式不支持时返回 None
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        Face::parse(bytes, 0).ok()?;
+        Some(Self {
+            data: bytes.to_vec(),
+            atlases: Vec::new(),
+        })
+    }
+
+    /// 获取指定字号的度量
+    pub fn at_size(&self, size: f32) -> Option<SizedFont<'_>> {
+        sized(&self.data, clamp_size(size))
+    }
+
+    /// 获取指定字号的字形图集
+    pub fn atlas(&self, size: f32) -> Option<&GlyphAtlas> {
+        let key = size_key(clamp_size(size));
+        self.atlases
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, atlas)| atlas)
+    }
+
+    /// 将排版结果合成为 RGBA 位图
+    ///
+    /// 字形覆盖率与 `color` 的 Alpha 相乘；字号对应的图集按需填充。
+    ///
+    /// 文本块超出位图尺寸上限时返回 None。
+    pub fn render(&mut self, layout: &TextLayout, size: f32, color: [u8; 4]) -> Option<Image> {
+        let width = (layout.width.ceil() as u32).max(1);
+        let height = (layout.height.ceil() as u32).max(1);
+        let mut out = Image::new(width, height)?;
+
+        let size = clamp_size(size);
+        let sized = sized(&self.data, size)?;
+        let (line_height, ascent) = (sized.line_height(), sized.ascent());
+        let (face, scale) = (&sized.face, sized.scale);
+        let atlas = touch_atlas(&mut self.atlases, size);
+
+        for placed in &layout.glyphs {
+            let id = face.glyph_index(placed.ch).unwrap_or(GlyphId(0));
+            let glyph = atlas.get_or_insert(face, id, scale);
+            if glyph.width == 0 {
+                continue;
+            }
+            let baseline = (placed.line as f32 * line_height + ascent).round() as i32;
+            let dst_x0 = placed.x.round() as i32 + glyph.left;
+            let dst_y0 = baseline - glyph.top;
+
+            for gy in 0..glyph.height as i32 {
+                let dy = dst_y0 + gy;
+                if dy < 0 || dy >= height as i32 {
+                    continue;
+                }
+                for gx in 0..glyph.width as i32 {
+                    let dx = dst_x0 + gx;
+                    if dx < 0 || dx >= width as i32 {
+                        continue;
+                    }
+                    let src = (glyph.y as i32 + gy) as usize * atlas.width as usize
+                        + (glyph.x as i32 + gx) as usize;
+                    let cov = atlas.data[src];
+                    if cov == 0 {
+                        continue;
+                    }
+                    let alpha = (cov as u32 * color[3] as u32 / 255) as u8;
+                    let dst = ((dy as u32 * width + dx as u32) * 4) as usize;
+                    blend_over(
+                        &mut out.data[dst..dst + 4],
+                        [color[0], color[1], color[2], alpha],
+                    );
+                }
+            }
+        }
+
+        Some(out)
+    }
+}
+
+impl SizedFont<'_> {
+    /// 基线以上高度 (像素)
+    fn ascent(&self) -> f32 {
+        self.face.ascender() as f32 * self.scale
+    }
+
+    /// 字符对应的字形，缺失时使用 .notdef
+    fn glyph(&self, ch: char) -> GlyphId {
+        self.face.glyph_index(ch).unwrap_or(GlyphId(0))
+    }
+}
+
+impl FontMetrics for SizedFont<'_> {
+    fn line_height(&self) -> f32 {
+        let units = self.face.ascender() as f32 - self.face.descender() as f32
+            + self.face.line_gap() as f32;
+        (units * self.scale).round()
+    }
+
+    fn advance(&self, ch: char) -> f32 {
+        self.face.glyph_hor_advance(self.glyph(ch)).unwrap_or(0) as f32 * self.scale
+    }
+
+    fn kerning(&self, left: char, right: char) -> f32 {
+        let Some(kern) = self.face.tables().kern else {
+            return 0.0;
+        };
+        let (l, r) = (self.glyph(left), self.glyph(right));
+        let units: i32 = kern
+            .subtables
+            .into_iter()
+            .filter(|s| s.horizontal && !s.variable && !s.has_cross_stream)
+            .filter_map(|s| s.glyphs_kerning(l, r))
+            .map(i32::from)
+            .sum();
+        units as f32 * self.scale
+    }
+}
+
+/// 将字号限制在支持范围内并按 0.5 像素量化
+pub fn clamp_size(size: f32) -> f32 {
+    if size.is_finite() {
+        ((size * SIZE_STEPS).round() / SIZE_STEPS).clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+    } else {
+        MIN_FONT_SIZE
+    }
+}
+
+/// 解析字体数据 (每次使用时重新解析，解析只读取表目录，开销很小)
+fn sized(data: &[u8], size: f32) -> Option<SizedFont<'_>> {
+    let face = Face::parse(data, 0).ok()?;
+    let scale = size / face.units_per_em() as f32;
+    Some(SizedFont { face, scale })
+}
+
+/// 取出 (或新建) 字号图集并标记为最近使用，超出上限时淘汰最久未使用的
+fn touch_atlas(atlases: &mut Vec<(u32, GlyphAtlas)>, size: f32) -> &mut GlyphAtlas {
+    let key = size_key(size);
+    let entry = match atlases.iter().position(|(k, _)| *k == key) {
+        Some(i) => atlases.remove(i),
+        None => (key, GlyphAtlas::new(size)),
+    };
+    if atlases.len() >= MAX_ATLASES {
+        atlases.remove(0);
+    }
+    atlases.push(entry);
+    &mut atlases.last_mut().expect("just pushed").1
+}
+
+/// 量化后字号对应的图集键
+fn size_key(size: f32) -> u32 {
+    (size * SIZE_STEPS) as u32
+}
+
+/// 构造测试用最小 TrueType 字体
+///
+/// 1000 单位/em，上升 800、下降 -200；'A' 与 '你' 映射到同一个方块字形
+/// (x 100..500, y 0..700，前进量 600)，其余字符为空的 .notdef (前进量 500)。
+#[cfg(test)]
+pub(crate) fn test_font() -> Vec<u8> {
+    fn be16(out: &mut Vec<u8>, v: i32) {
+        out.extend_from_slice(&(v as u16).to_be_bytes());
+    }
+    fn be32(out: &mut Vec<u8>, v: u32) {
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+
+    // cmap: 格式 12
+    let mut cmap = Vec::new();
+    for v in [0, 1, 3, 10] {
+        be16(&mut cmap, v);
+    }
+    be32(&mut cmap, 12);
+    be16(&mut cmap, 12);
+    be16(&mut cmap, 0);
+    be32(&mut cmap, 16 + 2 * 12);
+    be32(&mut cmap, 0);
+    be32(&mut cmap, 2);
+    for code in [0x41u32, 0x4F60] {
+        be32(&mut cmap, code);
+        be32(&mut cmap, code);
+        be32(&mut cmap, 1);
+    }
+
+    // glyf: 字形 1 为单个矩形轮廓
+    let mut glyf = Vec::new();
+    for v in [1, 100, 0, 500, 700, 3, 0] {
+        be16(&mut glyf, v);
+    }
+    glyf.extend_from_slice(&[1, 1, 1, 1]);
+    for v in [100, 400, 0, -400, 0, 0, 700, 0] {
+        be16(&mut glyf, v);
+    }
+
+    let mut head = Vec::new();
+    be32(&mut head, 0x0001_0000);
+    be32(&mut head, 0x0001_0000);
+    be32(&mut head, 0);
+    be32(&mut head, 0x5F0F_3CF5);
+    be16(&mut head, 0);
+    be16(&mut head, 1000);
+    head.extend_from_slice(&[0u8; 16]);
+    for v in [0, 0, 500, 700, 0, 8, 2, 0, 0] {
+        be16(&mut head, v);
+    }
+
+    let mut hhea = Vec::new();
+    be32(&mut hhea, 0x0001_0000);
+    for v in [800, -200, 0, 600, 0, 0, 500, 1, 0, 0, 0, 0, 0, 0, 0, 2] {
+        be16(&mut hhea, v);
+    }
+
+    let mut hmtx = Vec::new();
+    for v in [500, 0, 600, 100] {
+        be16(&mut hmtx, v);
+    }
+
+    let mut loca = Vec::new();
+    for v in [0, 0, glyf.len() as i32 / 2] {
+        be16(&mut loca, v);
+    }
+
+    let mut maxp = Vec::new();
+    be32(&mut maxp, 0x0000_5000);
+    be16(&mut maxp, 2);
+
+    let tables: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"cmap", cmap),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+    ];
+
+    let mut out = Vec::new();
+    be32(&mut out, 0x0001_0000);
+    for v in [tables.len() as i32, 64, 2, 48] {
+        be16(&mut out, v);
+    }
+    let mut offset = 12 + 16 * tables.len();
+    let mut body = Vec::new();
+    for (tag, data) in &tables {
+        out.extend_from_slice(*tag);
+        be32(&mut out, 0);
+        be32(&mut out, offset as u32);
+        be32(&mut out, data.len() as u32);
+        body.extend_from_slice(data);
+        while body.len() % 4 != 0 {
+            body.push(0);
+        }
+        offset = 12 + 16 * tables.len() + body.len();
+    }
+    out.extend_from_slice(&body);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::layout::{layout_text, TextAlign};
+
+    #[test]
+    fn test_parse() {
+        assert!(OutlineFont::parse(&test_font()).is_some());
+        assert!(OutlineFont::parse(b"not a font").is_none());
+    }
+
+    #[test]
+    fn test_metrics() {
+        let font = OutlineFont::parse(&test_font()).unwrap();
+        let sized = font.at_size(10.0).unwrap();
+        assert_eq!(sized.line_height(), 10.0);
+        assert_eq!(sized.advance('A'), 6.0);
+        assert_eq!(sized.advance(' '), 5.0);
+        assert_eq!(sized.kerning('A', 'A'), 0.0);
+    }
+
+    #[test]
+    fn test_render_glyph_coverage() {
+        let mut font = OutlineFont::parse(&test_font()).unwrap();
+        let layout = layout_text(&font.at_size(10.0).unwrap(), "A", 0.0, TextAlign::Left);
+        let image = font.render(&layout, 10.0, [255, 0, 0, 255]).unwrap();
+        assert_eq!((image.width, image.height), (6, 10));
+
+        // 方块占据 x 1..5, y 1..8 (基线 y=8，顶部 7px)
+        let px = |x: u32, y: u32| &image.data[((y * 6 + x) * 4) as usize..][..4];
+        assert_eq!(px(2, 4), &[255, 0, 0, 255]);
+        assert_eq!(px(0, 4)[3], 0);
+        assert_eq!(px(2, 0)[3], 0);
+        assert_eq!(px(4, 7)[3], 255);
+        assert_eq!(px(2, 8)[3], 0);
+    }
+
+    #[test]
+    fn test_atlas_cached_per_size() {
+        let mut font = OutlineFont::parse(&test_font()).unwrap();
+        let layout = layout_text(&font.at_size(10.0).unwrap(), "A你A", 0.0, TextAlign::Left);
+        font.render(&layout, 10.0, [255; 4]);
+        // 'A' 与 '你' 共用同一字形
+        assert_eq!(font.atlas(10.0).unwrap().glyph_count(), 1);
+        assert!(font.atlas(20.0).is_none());
+
+        let layout = layout_text(&font.at_size(20.0).unwrap(), "A", 0.0, TextAlign::Left);
+        let image = font.render(&layout, 20.0, [255; 4]).unwrap();
+        assert_eq!((image.width, image.height), (12, 20));
+        assert!(font.atlas(20.0).is_some());
+    }
+
+    #[test]
+    fn test_clamp_size() {
+        assert_eq!(clamp_size(0.0), MIN_FONT_SIZE);
+        assert_eq!(clamp_size(f32::NAN), MIN_FONT_SIZE);
+        assert_eq!(clamp_size(4096.0), MAX_FONT_SIZE);
+        assert_eq!(clamp_size(10.3), 10.5);
+        assert_eq!(clamp_size(10.2), 10.0);
+    }
+
+    #[test]
+    fn test_atlas_quantized_and_evicted() {
+        let mut font = OutlineFont::parse(&test_font()).unwrap();
+        // 缓动字号: 相邻的小数字号共用图集，总数不超过上限
+        for step in 0..200 {
+            let size = 10.0 + step as f32 * 0.1;
+            let layout = layout_text(&font.at_size(size).unwrap(), "A", 0.0, TextAlign::Left);
+            font.render(&layout, size, [255; 4]);
+        }
+        assert_eq!(font.atlases.len(), MAX_ATLASES);
+        assert!(font.atlas(29.9).is_some());
+        assert!(font.atlas(10.0).is_none());
+        assert!(font.atlas(29.75).is_some());
+    }
+
+    #[test]
+    fn test_atlas_bounded_and_widened() {
+        let glyph = |width, height| AtlasGlyph {
+            width,
+            height,
+            ..AtlasGlyph::default()
+        };
+        // 大量大字形: 写满后清空重来，数据量不超过上限
+        let mut atlas = GlyphAtlas::new(100.0);
+        let coverage = vec![255u8; 200 * 200];
+        for id in 0..2000u16 {
+            let entry = atlas.insert(id, glyph(200, 200), &coverage);
+            assert!(atlas.data.len() <= MAX_ATLAS_BYTES);
+            let end = (entry.y + 199) as usize * atlas.width as usize + entry.x as usize + 199;
+            assert_eq!(atlas.data[end], 255);
+        }
+        assert!(atlas.glyph_count() < 2000);
+
+        // 比图集更宽的字形加宽图集而不是被截断
+        let mut atlas = GlyphAtlas::new(10.0);
+        let coverage = vec![7u8; 300 * 2];
+        let entry = atlas.insert(1, glyph(300, 2), &coverage);
+        assert_eq!(entry.width, 300);
+        assert!(atlas.width > 300);
+        assert_eq!(atlas.data[299], 7);
+    }
+}
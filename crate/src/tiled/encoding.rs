@@ -0,0 +1,173 @@
+//! 图层数据解码
+//!
+//! 支持 CSV、base64 以及 base64 + zlib / gzip 压缩的格子数据 (小端 u32 数组)。
+
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, decompress_to_vec_zlib_with_limit};
+
+/// 解码标准 base64 (忽略空白，允许省略末尾填充)
+pub fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| -> Option<u32> {
+        Some(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        } as u32)
+    };
+    let symbols: Vec<u8> = text.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    let symbols = symbols
+        .strip_suffix(b"==")
+        .or_else(|| symbols.strip_suffix(b"="))
+        .unwrap_or(&symbols);
+    if symbols.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(symbols.len() * 3 / 4);
+    for chunk in symbols.chunks(4) {
+        let mut bits = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            bits |= value(c)? << (18 - 6 * i);
+        }
+        let bytes = bits.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+/// 解压 gzip 数据 (跳过头部的可选字段后按原始 deflate 解压)
+///
+/// 输出超过 `limit` 字节时返回 None。
+fn decompress_gzip(data: &[u8], limit: usize) -> Option<Vec<u8>> {
+    if data.len() < 18 || data[0] != 0x1F || data[1] != 0x8B || data[2] != 8 {
+        return None;
+    }
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & 0x04 != 0 {
+        let extra = u16::from_le_bytes([*data.get(pos)?, *data.get(pos + 1)?]) as usize;
+        pos += 2 + extra;
+    }
+    // 文件名与注释均以 0 结尾
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            pos += data.get(pos..)?.iter().position(|&b| b == 0)? + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        pos += 2;
+    }
+    decompress_to_vec_with_limit(data.get(pos..)?, limit).ok()
+}
+
+/// 解码图层格子数据
+///
+/// `encoding` 为 "csv" 或 "base64"，`compression` 为空、"zlib" 或 "gzip"。
+/// 格子数与 `count` 不一致或编码不受支持时返回 None。
+pub fn decode_cells(
+    text: &str,
+    encoding: &str,
+    compression: &str,
+    count: usize,
+) -> Option<Vec<u32>> {
+    let cells: Vec<u32> = match encoding {
+        "csv" => text
+            .split(',')
+            .map(|v| v.trim().parse().ok())
+            .collect::<Option<_>>()?,
+        "base64" => {
+            let raw = decode_base64(text)?;
+            // 解压输出不超过格子数据应有的大小，防止压缩炸弹
+            let limit = count.checked_mul(4)?;
+            let bytes = match compression {
+                "" => raw,
+                "zlib" => decompress_to_vec_zlib_with_limit(&raw, limit).ok()?,
+                "gzip" => decompress_gzip(&raw, limit)?,
+                _ => return None,
+            };
+            bytes
+                .chunks_exact(4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        }
+        _ => return None,
+    };
+    (cells.len() == count).then_some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+
+    fn encode_base64(bytes: &[u8]) -> String {
+        const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let mut bits = 0u32;
+            for (i, &b) in chunk.iter().enumerate() {
+                bits |= (b as u32) << (16 - 8 * i);
+            }
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(TABLE[(bits >> (18 - 6 * i) & 63) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    fn cell_bytes(cells: &[u32]) -> Vec<u8> {
+        cells.iter().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(decode_base64("TWFu").unwrap(), b"Man");
+        assert_eq!(decode_base64("TWE=").unwrap(), b"Ma");
+        assert_eq!(decode_base64(" TQ ==\n").unwrap(), b"M");
+        assert!(decode_base64("T").is_none());
+        assert!(decode_base64("TW!u").is_none());
+    }
+
+    #[test]
+    fn test_decode_cells() {
+        let cells = [1, 0, 0x8000_0002, 7];
+        assert_eq!(
+            decode_cells("\n1,0,\n2147483650,7\n", "csv", "", 4).unwrap(),
+            cells
+        );
+        let raw = cell_bytes(&cells);
+        let plain = encode_base64(&raw);
+        assert_eq!(decode_cells(&plain, "base64", "", 4).unwrap(), cells);
+        let zlib = encode_base64(&compress_to_vec_zlib(&raw, 6));
+        assert_eq!(decode_cells(&zlib, "base64", "zlib", 4).unwrap(), cells);
+
+        let mut gzip = vec![0x1F, 0x8B, 8, 0x08, 0, 0, 0, 0, 0, 0xFF];
+        gzip.extend_from_slice(b"name\0");
+        gzip.extend_from_slice(&compress_to_vec(&raw, 6));
+        gzip.extend_from_slice(&[0; 8]);
+        let gzip = encode_base64(&gzip);
+        assert_eq!(decode_cells(&gzip, "base64", "gzip", 4).unwrap(), cells);
+
+        assert!(decode_cells(&plain, "base64", "zstd", 4).is_none());
+        assert!(decode_cells("1,2", "csv", "", 4).is_none());
+    }
+
+    #[test]
+    fn test_decompression_limit() {
+        // 1 MiB 的零压缩后很小，但远超 4 个格子的大小
+        let bomb = vec![0u8; 1 << 20];
+        let zlib = encode_base64(&compress_to_vec_zlib(&bomb, 6));
+        assert!(decode_cells(&zlib, "base64", "zlib", 4).is_none());
+
+        let mut gzip = vec![0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 0xFF];
+        gzip.extend_from_slice(&compress_to_vec(&bomb, 6));
+        gzip.extend_from_slice(&[0; 8]);
+        assert!(decode_cells(&encode_base64(&gzip), "base64", "gzip", 4).is_none());
+        assert!(decode_cells(&zlib, "base64", "zlib", usize::MAX).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+//! Tiled JSON 格式
+
+use super::encoding::decode_cells;
+use super::{
+    parse_hex_color, GroupState, ObjectShape, TileLayer, TiledMap, TiledObject, TilesetData,
+    TilesetRef,
+};
+use crate::json::{parse_json, Value};
+
+fn num(value: &Value, key: &str) -> Option<f64> {
+    value.get(key)?.as_f64()
+}
+
+fn uint(value: &Value, key: &str) -> Option<u32> {
+    num(value, key).filter(|n| *n >= 0.0).map(|n| n as u32)
+}
+
+fn float_or(value: &Value, key: &str, default: f32) -> f32 {
+    num(value, key).map_or(default, |n| n as f32)
+}
+
+fn string(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn items<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value.get(key).and_then(Value::as_array).unwrap_or_default()
+}
+
+pub(super) fn parse_map(text: &str) -> Option<TiledMap> {
+    let root = parse_json(text)?;
+    if root.get("orientation").and_then(Value::as_str) != Some("orthogonal")
+        || root.get("infinite").and_then(Value::as_bool) == Some(true)
+    {
+        return None;
+    }
+    let mut map = TiledMap {
+        columns: uint(&root, "width")?,
+        rows: uint(&root, "height")?,
+        tile_width: uint(&root, "tilewidth")?,
+        tile_height: uint(&root, "tileheight")?,
+        ..Default::default()
+    };
+    for tileset in items(&root, "tilesets") {
+        let source = tileset.get("source").and_then(Value::as_str);
+        map.tilesets.push(TilesetRef {
+            first_gid: uint(tileset, "firstgid")?,
+            source: source.map(str::to_string),
+            data: source.is_none().then(|| tileset_data(tileset)),
+        });
+    }
+    parse_layers(&root, GroupState::ROOT, &mut map)?;
+    Some(map)
+}
+
+pub(super) fn parse_tileset(text: &str) -> Option<TilesetData> {
+    let root = parse_json(text)?;
+    root.as_object()?;
+    Some(tileset_data(&root))
+}
+
+fn tileset_data(value: &Value) -> TilesetData {
+    let animations = items(value, "tiles")
+        .iter()
+        .filter_map(|tile| {
+            let frames: Vec<(u32, u32)> = items(tile, "animation")
+                .iter()
+                .filter_map(|f| Some((uint(f, "tileid")?, uint(f, "duration")?)))
+                .collect();
+            (!frames.is_empty()).then_some((uint(tile, "id")?, frames))
+        })
+        .collect();
+    TilesetData {
+        name: string(value, "name"),
+        tile_width: uint(value, "tilewidth").unwrap_or(0),
+        tile_height: uint(value, "tileheight").unwrap_or(0),
+        margin: uint(value, "margin").unwrap_or(0),
+        spacing: uint(value, "spacing").unwrap_or(0),
+        tile_count: uint(value, "tilecount").unwrap_or(0),
+        image: value
+            .get("image")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        transparent: value
+            .get("transparentcolor")
+            .and_then(Value::as_str)
+            .and_then(parse_hex_color),
+        animations,
+    }
+}
+
+/// 递归读取图层 (分组图层展开)
+fn parse_layers(parent: &Value, group: GroupState, map: &mut TiledMap) -> Option<()> {
+    for layer in items(parent, "layers") {
+        let state = group.nested(
+            float_or(layer, "offsetx", 0.0),
+            float_or(layer, "offsety", 0.0),
+            float_or(layer, "opacity", 1.0),
+            layer.get("visible").and_then(Value::as_bool) != Some(false),
+        );
+        match layer.get("type").and_then(Value::as_str) {
+            Some("tilelayer") => {
+                let count = (map.columns as usize).checked_mul(map.rows as usize)?;
+                map.layers.push(TileLayer {
+                    name: string(layer, "name"),
+                    cells: layer_cells(layer, count)?,
+                    opacity: state.opacity,
+                    visible: state.visible,
+                    offset_x: state.offset_x,
+                    offset_y: state.offset_y,
+                });
+            }
+            Some("objectgroup") => {
+                let name = string(layer, "name");
+                for object in items(layer, "objects") {
+                    map.objects.push(parse_object(object, &name, state));
+                }
+            }
+            Some("group") => parse_layers(layer, state, map)?,
+            _ => {}
+        }
+    }
+    Some(())
+}
+
+/// 读取图层格子 (数字数组，或 base64 字符串)
+fn layer_cells(layer: &Value, count: usize) -> Option<Vec<u32>> {
+    match layer.get("data")? {
+        Value::Array(values) => {
+            let cells: Vec<u32> = values
+                .iter()
+                .map(|v| v.as_f64().map(|n| n as u32))
+                .collect::<Option<_>>()?;
+            (cells.len() == count).then_some(cells)
+        }
+        Value::String(text) => decode_cells(
+            text,
+            layer.get("encoding").and_then(Value::as_str)?,
+            layer
+                .get("compression")
+                .and_then(Value::as_str)
+                .unwrap_or_default(),
+            count,
+        ),
+        _ => None,
+    }
+}
+
+/// 属性值统一转为字符串 (数字去掉多余的小数部分)
+fn property_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn parse_object(value: &Value, layer: &str, state: GroupState) -> TiledObject {
+    let gid = uint(value, "gid").unwrap_or(0);
+    let flag = |key: &str| value.get(key).and_then(Value::as_bool) == Some(true);
+    let points = |key: &str| -> Vec<[f32; 2]> {
+        items(value, key)
+            .iter()
+            .filter_map(|p| Some([num(p, "x")? as f32, num(p, "y")? as f32]))
+            .collect()
+    };
+    let (shape, points) = if gid != 0 {
+        (ObjectShape::Tile, Vec::new())
+    } else if flag("ellipse") {
+        (ObjectShape::Ellipse, Vec::new())
+    } else if flag("point") {
+        (ObjectShape::Point, Vec::new())
+    } else if value.get("polygon").is_some() {
+        (ObjectShape::Polygon, points("polygon"))
+    } else if value.get("polyline").is_some() {
+        (ObjectShape::Polyline, points("polyline"))
+    } else if value.get("text").is_some() {
+        (ObjectShape::Text, Vec::new())
+    } else {
+        (ObjectShape::Rectangle, Vec::new())
+    };
+    let class = match string(value, "type") {
+        class if class.is_empty() => string(value, "class"),
+        class => class,
+    };
+    TiledObject {
+        id: uint(value, "id").unwrap_or(0),
+        name: string(value, "name"),
+        class,
+        layer: layer.to_string(),
+        shape,
+        x: float_or(value, "x", 0.0) + state.offset_x,
+        y: float_or(value, "y", 0.0) + state.offset_y,
+        width: float_or(value, "width", 0.0),
+        height: float_or(value, "height", 0.0),
+        rotation: float_or(value, "rotation", 0.0),
+        gid,
+        visible: state.visible && value.get("visible").and_then(Value::as_bool) != Some(false),
+        points,
+        properties: items(value, "properties")
+            .iter()
+            .map(|p| {
+                let value = p.get("value").map(property_string).unwrap_or_default();
+                (string(p, "name"), value)
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,245 @@
+//! Tiled 地图解析
+//!
+//! 读取 Tiled 编辑器导出的正交地图 (TMX / JSON) 与外部瓦片集 (TSX / JSON)，
+//! 转换为与引擎无关的数据模型：多个瓦片图层 (分组图层展开，偏移与不透明度逐级累积)
+//! 与对象图层中的对象。图像图层被忽略，交错 / 等距 / 六边形与无限地图不受支持。
+
+mod encoding;
+mod json_map;
+mod tmx;
+
+/// Tiled 全局瓦片 ID 中的翻转标志 (水平、垂直、对角)
+const GID_FLIP_FLAGS: u32 = 0xE000_0000;
+/// Tiled 全局瓦片 ID 中的瓦片部分 (位 28 为六边形旋转标志，不使用)
+const GID_MASK: u32 = 0x0FFF_FFFF;
+
+/// 地图
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TiledMap {
+    /// 列数 (格子)
+    pub columns: u32,
+    /// 行数 (格子)
+    pub rows: u32,
+    /// 格子宽度 (像素)
+    pub tile_width: u32,
+    /// 格子高度 (像素)
+    pub tile_height: u32,
+    /// 引用的瓦片集 (按 first_gid 升序)
+    pub tilesets: Vec<TilesetRef>,
+    /// 瓦片图层 (按绘制顺序)
+    pub layers: Vec<TileLayer>,
+    /// 全部对象图层中的对象 (按出现顺序)
+    pub objects: Vec<TiledObject>,
+}
+
+/// 地图对瓦片集的引用
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TilesetRef {
+    /// 该瓦片集第一个瓦片的全局 ID
+    pub first_gid: u32,
+    /// 外部瓦片集文件路径 (内嵌瓦片集为 None)
+    pub source: Option<String>,
+    /// 瓦片集内容 (外部瓦片集在提供文件前为 None)
+    pub data: Option<TilesetData>,
+}
+
+/// 瓦片集内容
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TilesetData {
+    pub name: String,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// 纹理四周边距
+    pub margin: u32,
+    /// 瓦片间距
+    pub spacing: u32,
+    /// 瓦片总数
+    pub tile_count: u32,
+    /// 纹理文件路径 (图像集合类瓦片集为 None)
+    pub image: Option<String>,
+    /// 透明色键 (RGB)
+    pub transparent: Option<[u8; 3]>,
+    /// 动画瓦片: (瓦片索引, [(帧瓦片索引, 时长毫秒)])
+    pub animations: Vec<(u32, Vec<(u32, u32)>)>,
+}
+
+/// 瓦片图层
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TileLayer {
+    pub name: String,
+    /// 全局瓦片 ID (行优先，含翻转标志，0 为空)
+    pub cells: Vec<u32>,
+    /// 不透明度 (已乘上所在分组的不透明度)
+    pub opacity: f32,
+    /// 是否可见 (所在分组隐藏时同样隐藏)
+    pub visible: bool,
+    /// 像素偏移 (已累加所在分组的偏移)
+    pub offset_x: f32,
+    pub offset_y: f32,
+}
+
+/// 对象形状
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObjectShape {
+    #[default]
+    Rectangle,
+    Ellipse,
+    Point,
+    Polygon,
+    Polyline,
+    /// 瓦片对象 (gid 有效，坐标为左下角)
+    Tile,
+    Text,
+}
+
+impl ObjectShape {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            ObjectShape::Rectangle => 0,
+            ObjectShape::Ellipse => 1,
+            ObjectShape::Point => 2,
+            ObjectShape::Polygon => 3,
+            ObjectShape::Polyline => 4,
+            ObjectShape::Tile => 5,
+            ObjectShape::Text => 6,
+        }
+    }
+}
+
+/// 对象图层中的对象 (坐标为地图像素坐标，已累加图层偏移)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TiledObject {
+    pub id: u32,
+    pub name: String,
+    /// 类型 (Tiled 1.9 之后称为 class)
+    pub class: String,
+    /// 所在对象图层名
+    pub layer: String,
+    pub shape: ObjectShape,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// 顺时针旋转角度 (度)
+    pub rotation: f32,
+    /// 瓦片对象的全局瓦片 ID (含翻转标志)，其他对象为 0
+    pub gid: u32,
+    pub visible: bool,
+    /// 多边形 / 折线顶点 (相对于对象坐标)
+    pub points: Vec<[f32; 2]>,
+    /// 自定义属性 (值统一转为字符串)
+    pub properties: Vec<(String, String)>,
+}
+
+/// 父级分组图层累积的属性
+#[derive(Debug, Clone, Copy)]
+struct GroupState {
+    offset_x: f32,
+    offset_y: f32,
+    opacity: f32,
+    visible: bool,
+}
+
+impl GroupState {
+    const ROOT: GroupState = GroupState {
+        offset_x: 0.0,
+        offset_y: 0.0,
+        opacity: 1.0,
+        visible: true,
+    };
+
+    /// 进入子图层
+    fn nested(self, offset_x: f32, offset_y: f32, opacity: f32, visible: bool) -> GroupState {
+        GroupState {
+            offset_x: self.offset_x + offset_x,
+            offset_y: self.offset_y + offset_y,
+            opacity: self.opacity * opacity.clamp(0.0, 1.0),
+            visible: self.visible && visible,
+        }
+    }
+}
+
+impl TiledMap {
+    /// 解析地图 (按首个非空白字符自动识别 TMX 或 JSON)
+    pub fn parse(text: &str) -> Option<TiledMap> {
+        let mut map = if text.trim_start().starts_with('<') {
+            tmx::parse_map(text)?
+        } else {
+            json_map::parse_map(text)?
+        };
+        if map.columns == 0 || map.rows == 0 || map.tile_width == 0 || map.tile_height == 0 {
+            return None;
+        }
+        map.tilesets.sort_by_key(|t| t.first_gid);
+        Some(map)
+    }
+
+    /// 查找全局瓦片 ID 所属的瓦片集下标
+    pub fn tileset_for(&self, gid: u32) -> Option<usize> {
+        let gid = gid & GID_MASK;
+        if gid == 0 {
+            return None;
+        }
+        self.tilesets.iter().rposition(|t| t.first_gid <= gid)
+    }
+
+    /// 将全局瓦片 ID 转换为瓦片地图格子值 (瓦片集内索引 + 1，保留翻转标志)
+    pub fn local_cell(&self, gid: u32, tileset: usize) -> u32 {
+        let local = (gid & GID_MASK) - self.tilesets[tileset].first_gid;
+        (local + 1) | (gid & GID_FLIP_FLAGS)
+    }
+}
+
+impl TilesetData {
+    /// 解析外部瓦片集 (按首个非空白字符自动识别 TSX 或 JSON)
+    pub fn parse(text: &str) -> Option<TilesetData> {
+        let data = if text.trim_start().starts_with('<') {
+            tmx::parse_tileset(text)?
+        } else {
+            json_map::parse_tileset(text)?
+        };
+        (data.tile_width > 0 && data.tile_height > 0).then_some(data)
+    }
+}
+
+/// 解析 `#RRGGBB` / `RRGGBB` (以及带 alpha 的 `#AARRGGBB`) 颜色
+fn parse_hex_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().trim_start_matches('#');
+    let hex = match hex.len() {
+        6 => hex,
+        8 => &hex[2..],
+        _ => return None,
+    };
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gid_lookup() {
+        let map = TiledMap {
+            tilesets: vec![
+                TilesetRef {
+                    first_gid: 1,
+                    ..Default::default()
+                },
+                TilesetRef {
+                    first_gid: 5,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(map.tileset_for(0), None);
+        assert_eq!(map.tileset_for(4), Some(0));
+        assert_eq!(map.tileset_for(0x8000_0005), Some(1));
+        assert_eq!(map.local_cell(0x8000_0006, 1), 0x8000_0002);
+        // 六边形旋转标志被丢弃
+        assert_eq!(map.local_cell(0x1000_0002, 0), 2);
+        assert_eq!(parse_hex_color("#ff00ff"), Some([255, 0, 255]));
+        assert_eq!(parse_hex_color("80102030"), Some([0x10, 0x20, 0x30]));
+    }
+}
@@ -0,0 +1,216 @@
+//! TMX / TSX (XML) 格式
+
+use std::str::FromStr;
+
+use super::encoding::decode_cells;
+use super::{
+    parse_hex_color, GroupState, ObjectShape, TileLayer, TiledMap, TiledObject, TilesetData,
+    TilesetRef,
+};
+use crate::xml::{parse_xml, Element};
+
+/// 读取数值属性
+fn num<T: FromStr>(element: &Element, name: &str) -> Option<T> {
+    element.attr(name)?.trim().parse().ok()
+}
+
+/// 读取数值属性，缺省时取默认值
+fn num_or<T: FromStr>(element: &Element, name: &str, default: T) -> T {
+    num(element, name).unwrap_or(default)
+}
+
+fn text_attr(element: &Element, name: &str) -> String {
+    element.attr(name).unwrap_or_default().to_string()
+}
+
+fn child<'a>(element: &'a Element, name: &str) -> Option<&'a Element> {
+    element.children.iter().find(|c| c.name == name)
+}
+
+pub(super) fn parse_map(text: &str) -> Option<TiledMap> {
+    let root = parse_xml(text)?;
+    if root.name != "map"
+        || root.attr("orientation").is_some_and(|o| o != "orthogonal")
+        || root.attr("infinite") == Some("1")
+    {
+        return None;
+    }
+    let mut map = TiledMap {
+        columns: num(&root, "width")?,
+        rows: num(&root, "height")?,
+        tile_width: num(&root, "tilewidth")?,
+        tile_height: num(&root, "tileheight")?,
+        ..Default::default()
+    };
+    for element in &root.children {
+        if element.name == "tileset" {
+            let source = element.attr("source").map(str::to_string);
+            let data = match source {
+                Some(_) => None,
+                None => Some(tileset_data(element)),
+            };
+            map.tilesets.push(TilesetRef {
+                first_gid: num(element, "firstgid")?,
+                source,
+                data,
+            });
+        }
+    }
+    parse_layers(&root, GroupState::ROOT, &mut map)?;
+    Some(map)
+}
+
+pub(super) fn parse_tileset(text: &str) -> Option<TilesetData> {
+    let root = parse_xml(text)?;
+    (root.name == "tileset").then(|| tileset_data(&root))
+}
+
+fn tileset_data(element: &Element) -> TilesetData {
+    let image = child(element, "image");
+    let mut animations = Vec::new();
+    for tile in element.children.iter().filter(|c| c.name == "tile") {
+        let Some(animation) = child(tile, "animation") else {
+            continue;
+        };
+        let frames: Vec<(u32, u32)> = animation
+            .children
+            .iter()
+            .filter_map(|f| Some((num(f, "tileid")?, num(f, "duration")?)))
+            .collect();
+        if let (Some(id), false) = (num(tile, "id"), frames.is_empty()) {
+            animations.push((id, frames));
+        }
+    }
+    TilesetData {
+        name: text_attr(element, "name"),
+        tile_width: num_or(element, "tilewidth", 0),
+        tile_height: num_or(element, "tileheight", 0),
+        margin: num_or(element, "margin", 0),
+        spacing: num_or(element, "spacing", 0),
+        tile_count: num_or(element, "tilecount", 0),
+        image: image.and_then(|i| i.attr("source")).map(str::to_string),
+        transparent: image
+            .and_then(|i| i.attr("trans"))
+            .and_then(parse_hex_color),
+        animations,
+    }
+}
+
+/// 递归读取图层 (分组图层展开)
+fn parse_layers(parent: &Element, group: GroupState, map: &mut TiledMap) -> Option<()> {
+    for element in &parent.children {
+        let state = group.nested(
+            num_or(element, "offsetx", 0.0),
+            num_or(element, "offsety", 0.0),
+            num_or(element, "opacity", 1.0),
+            element.attr("visible") != Some("0"),
+        );
+        match element.name.as_str() {
+            "layer" => {
+                let count = (map.columns as usize).checked_mul(map.rows as usize)?;
+                map.layers.push(TileLayer {
+                    name: text_attr(element, "name"),
+                    cells: layer_cells(child(element, "data")?, count)?,
+                    opacity: state.opacity,
+                    visible: state.visible,
+                    offset_x: state.offset_x,
+                    offset_y: state.offset_y,
+                });
+            }
+            "objectgroup" => {
+                let layer = text_attr(element, "name");
+                for object in element.children.iter().filter(|c| c.name == "object") {
+                    map.objects.push(parse_object(object, &layer, state));
+                }
+            }
+            "group" => parse_layers(element, state, map)?,
+            _ => {}
+        }
+    }
+    Some(())
+}
+
+/// 读取图层格子 (CSV / base64，或旧版逐个 `<tile gid>` 元素)
+fn layer_cells(data: &Element, count: usize) -> Option<Vec<u32>> {
+    match data.attr("encoding") {
+        Some(encoding) => decode_cells(
+            &data.text,
+            encoding,
+            data.attr("compression").unwrap_or_default(),
+            count,
+        ),
+        None => {
+            let cells: Vec<u32> = data
+                .children
+                .iter()
+                .filter(|c| c.name == "tile")
+                .map(|c| num_or(c, "gid", 0))
+                .collect();
+            (cells.len() == count).then_some(cells)
+        }
+    }
+}
+
+fn parse_object(element: &Element, layer: &str, state: GroupState) -> TiledObject {
+    let gid: u32 = num_or(element, "gid", 0);
+    let mut object = TiledObject {
+        id: num_or(element, "id", 0),
+        name: text_attr(element, "name"),
+        class: element
+            .attr("type")
+            .or_else(|| element.attr("class"))
+            .unwrap_or_default()
+            .to_string(),
+        layer: layer.to_string(),
+        shape: if gid != 0 {
+            ObjectShape::Tile
+        } else {
+            ObjectShape::Rectangle
+        },
+        x: num_or(element, "x", 0.0) + state.offset_x,
+        y: num_or(element, "y", 0.0) + state.offset_y,
+        width: num_or(element, "width", 0.0),
+        height: num_or(element, "height", 0.0),
+        rotation: num_or(element, "rotation", 0.0),
+        gid,
+        visible: state.visible && element.attr("visible") != Some("0"),
+        ..Default::default()
+    };
+    for c in &element.children {
+        match c.name.as_str() {
+            "ellipse" => object.shape = ObjectShape::Ellipse,
+            "point" => object.shape = ObjectShape::Point,
+            "text" => object.shape = ObjectShape::Text,
+            "polygon" | "polyline" => {
+                object.shape = if c.name == "polygon" {
+                    ObjectShape::Polygon
+                } else {
+                    ObjectShape::Polyline
+                };
+                object.points = c
+                    .attr("points")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .filter_map(|p| {
+                        let (x, y) = p.split_once(',')?;
+                        Some([x.parse().ok()?, y.parse().ok()?])
+                    })
+                    .collect();
+            }
+            "properties" => {
+                // 多行字符串属性的值写在元素内容中
+                object.properties = c
+                    .children
+                    .iter()
+                    .filter(|p| p.name == "property")
+                    .map(|p| {
+                        let value = p.attr("value").map_or(p.text.as_str(), |v| v);
+                        (text_attr(p, "name"), value.to_string())
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    object
+}
@@ -0,0 +1,227 @@
+//! 最小 XML 解析
+//!
+//! 仅构建元素树 (名称、属性、子元素与文本内容)，忽略注释、处理指令与 DOCTYPE。
+//! 足以读取常见的 SVG 图标与 Tiled 地图文件。
+
+/// XML 元素
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Element {
+    /// 元素名 (去除命名空间前缀)
+    pub name: String,
+    /// 属性 (按出现顺序)
+    pub attributes: Vec<(String, String)>,
+    /// 子元素
+    pub children: Vec<Element>,
+    /// 文本内容 (含 CDATA，各段直接拼接)
+    pub text: String,
+}
+
+impl Element {
+    /// 获取属性值
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 去除命名空间前缀 (`svg:rect` → `rect`)，`xlink:href` 等属性保持原样
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// 解码预定义实体与数字字符引用
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 解析器状态
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// 跳过直到 `pattern` 之后，找不到时返回 None
+    fn skip_past(&mut self, pattern: &str) -> Option<()> {
+        let offset = self.rest().find(pattern)?;
+        self.pos += offset + pattern.len();
+        Some(())
+    }
+
+    /// 读取名称 (元素名或属性名)
+    fn name(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '/' | '>'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    /// 解析从 `<` 开始的元素 (含子元素)
+    fn element(&mut self, depth: usize) -> Option<Element> {
+        if depth > 256 {
+            return None;
+        }
+        self.pos += 1;
+        let name = self.name()?;
+        let mut element = Element {
+            name: local_name(name).to_string(),
+            ..Element::default()
+        };
+
+        // 属性
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Some(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                element.attributes.push((key.to_string(), String::new()));
+                continue;
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self.rest().chars().next()?;
+            if quote != '"' && quote != '\'' {
+                return None;
+            }
+            self.pos += 1;
+            let end = self.rest().find(quote)?;
+            let value = decode_entities(&self.rest()[..end]);
+            self.pos += end + 1;
+            element.attributes.push((key.to_string(), value));
+        }
+
+        // 内容
+        loop {
+            let offset = self.rest().find('<')?;
+            element
+                .text
+                .push_str(&decode_entities(&self.rest()[..offset]));
+            self.pos += offset;
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.skip_past(">")?;
+                return Some(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>")?;
+                element.text.push_str(&cdata[..end]);
+                self.pos += "<![CDATA[".len() + end + 3;
+            } else if rest.starts_with("<?") || rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                let child = self.element(depth + 1)?;
+                element.children.push(child);
+            }
+        }
+    }
+}
+
+/// 解析 XML 文本，返回根元素
+pub fn parse_xml(text: &str) -> Option<Element> {
+    let mut parser = Parser { text, pos: 0 };
+    loop {
+        let offset = parser.rest().find('<')?;
+        parser.pos += offset;
+        let rest = parser.rest();
+        if rest.starts_with("<!--") {
+            parser.skip_past("-->")?;
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            parser.skip_past(">")?;
+        } else {
+            return parser.element(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tree() {
+        let text = r#"<?xml version="1.0"?>
+            <!DOCTYPE svg>
+            <!-- 注释 -->
+            <svg:svg xmlns:svg="http://www.w3.org/2000/svg" width='10'>
+                <g id="a"><rect x="1"/>text<![CDATA[<rect/>]]></g>
+                <circle r="2" title="a &amp; b &#x41;"></circle>
+            </svg:svg>"#;
+        let root = parse_xml(text).unwrap();
+        assert_eq!(root.name, "svg");
+        assert_eq!(root.attr("width"), Some("10"));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].children[0].name, "rect");
+        assert_eq!(root.children[0].children.len(), 1);
+        assert_eq!(root.children[0].text, "text<rect/>");
+        assert_eq!(root.children[1].attr("title"), Some("a & b A"));
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(parse_xml("no markup").is_none());
+        assert!(parse_xml("<svg><g></svg").is_none());
+        assert!(parse_xml("<svg width=10/>").is_none());
+    }
+}